@@ -5,10 +5,20 @@ use libyobicash::crypto::mac::YMACCode;
 use libyobicash::crypto::key::YKey32;
 use libyobicash::crypto::elliptic::keys::*;
 use libyobicash::amount::*;
-use std::net::Ipv4Addr;
+use libyobicash::transaction::YTransaction as LibTransaction;
+use libyobicash::coinbase::YCoinbase as LibCoinbase;
+use std::net::{Ipv4Addr, SocketAddr};
+use std::sync::{Arc, Mutex};
+use std::mem;
 use errors::*;
 use store::*;
 use network::host::YHost;
+use network::client::YClient;
+use network::message::request::YRequest;
+use network::message::response::YResponse;
+use network::message::transaction::{YGetTxReq, YListTxAncestorsReq, YPushTxReq};
+use network::message::coinbase::YGetCbReq;
+use network::message::ping::YPingReq;
 use config::*;
 use models::*;
 use info::*;
@@ -20,59 +30,218 @@ pub struct YAPIStore<M, P: YStorage> {
 
 pub struct YAPI<M, P: YStorage> {
     pub config: YConfig,
+    pub key: YKey32,
+    pub secret_key: YSecretKey,
     pub store: YAPIStore<M, P>,
+    pub prices: Arc<Mutex<Vec<YPeerPrice>>>,
+    pub relays: Vec<LibTransaction>,
+    pub fetches: Vec<YDigest64>,
 }
 
-impl YAPI<YMemoryStore, YPersistentStore> {
-    pub fn new(config: YConfig, read_only: bool) -> YHResult<YAPI<YMemoryStore, YPersistentStore>> {
+impl YAPI<YMemoryStore, YEncryptedStore<YPersistentStore>> {
+    pub fn new(mut config: YConfig, read_only: bool) -> YHResult<YAPI<YMemoryStore, YEncryptedStore<YPersistentStore>>> {
         let mem_config = YMemoryConfig {
-            mode: YMemoryMode {
-                path: YConfig::db_path()?,
-                read_only: read_only,
-            }, 
+            read_only: read_only,
         };
         let mem_store = YMemoryStore::open(mem_config)?;
-        let per_config = YPersistentConfig {
-            mode: YPersistentMode::Persistent {
-                path: YConfig::db_path()?,
-                read_only: read_only,
+        // stores written before the kdf are keyed by a plain hash of the password
+        let key = match config.legacy_password {
+            Some(ref pswd) => Self::legacy_key(pswd),
+            None => config.master_key()?,
+        };
+        let store_key = match config.legacy_password {
+            Some(ref pswd) => Self::legacy_store_key(pswd),
+            None => Self::store_key(key),
+        };
+        let per_config = YEncryptedConfig {
+            key: store_key,
+            inner: YPersistentConfig {
+                mode: YPersistentMode::Persistent {
+                    path: YConfig::db_path()?,
+                    read_only: read_only,
+                },
             },
         };
-        let per_store = YPersistentStore::open(per_config)?;
+        let mut per_store = YEncryptedStore::open(per_config)?;
+        if !per_store.is_sealed()? {
+            if read_only {
+                if !YSchema::is_empty(&per_store)? {
+                    return Err(YHErrorKind::UnencryptedStore.into());
+                }
+            } else {
+                let bucks = YBucket::all()
+                    .iter()
+                    .map(|buck| buck.to_store_buck())
+                    .collect();
+                per_store.encrypt_plain(&bucks)?;
+            }
+        }
+        if read_only {
+            YSchema::check(&per_store)?;
+        } else {
+            YSchema::migrate(&mut per_store, key)?;
+        }
+        let secret_key = YNodeKey::load(&mut per_store, config.legacy_secret_key.clone(), read_only)?;
+        if !read_only && config.legacy_secret_key.is_some() {
+            config.legacy_secret_key = None;
+            config.write()?;
+        }
+        if YPrune::mode(&per_store)? == YStorageMode::Light {
+            config.light_mode = true;
+        }
         let store = YAPIStore {
             memory: mem_store,
             persistent: per_store,
         };
-        let api = YAPI::<YMemoryStore, YPersistentStore> {
+        let api = YAPI::<YMemoryStore, YEncryptedStore<YPersistentStore>> {
             config: config,
+            key: key,
+            secret_key: secret_key,
             store: store,
+            prices: Arc::new(Mutex::new(Vec::new())),
+            relays: Vec::new(),
+            fetches: Vec::new(),
         };
         Ok(api)
     }
 
-    pub fn new_temporary(config: YConfig) -> YHResult<YAPI<YMemoryStore, YPersistentStore>> {
-        let mem_config = YMemoryConfig {
-            mode: YMemoryMode {
-                path: YConfig::db_path()?,
-                read_only: false,
-            }, 
-        };
-        let mem_store = YMemoryStore::create(mem_config)?;
-        let per_config = YPersistentConfig {
-            mode: YPersistentMode::Temporary,
-        };
-        let per_store = YPersistentStore::create(per_config)?;
+    pub fn store_key(key: YKey32) -> YKey32 {
+        let mut seed = b"store".to_vec();
+        seed.extend(key.0.iter().cloned());
+        YKey32(YSHA256::hash(&seed).0)
+    }
+
+    pub fn legacy_key(pswd: &str) -> YKey32 {
+        YKey32(YSHA256::hash(pswd.as_bytes()).0)
+    }
+
+    pub fn legacy_store_key(pswd: &str) -> YKey32 {
+        let mut seed = b"store".to_vec();
+        seed.extend(pswd.as_bytes());
+        YKey32(YSHA256::hash(&seed).0)
+    }
+
+    // backups hold the raw sealed entries, so they stay encrypted on disk
+    pub fn backup_store(&self, path: &str) -> YHResult<u32> {
+        let mut bucks: Vec<YStoreBuck> = YBucket::all()
+            .iter()
+            .map(|buck| buck.to_store_buck())
+            .collect();
+        bucks.push(YEncryptedStore::<YPersistentStore>::check_buck());
+        let snapshot = YSnapshot::export(&self.store.persistent.inner, &bucks)?;
+        snapshot.write(path)?;
+        Ok(snapshot.entries.len() as u32)
+    }
+
+    pub fn restore_store(&mut self, path: &str) -> YHResult<u32> {
+        let snapshot = YSnapshot::read(path)?;
+        let check_buck = YEncryptedStore::<YMemoryStore>::check_buck();
+        for entry in snapshot.entries.iter() {
+            if entry.buck != check_buck && YBucket::from_bytes(&entry.buck)? == YBucket::Unknown {
+                return Err(YHErrorKind::InvalidValue.into());
+            }
+        }
+        if !YSchema::is_empty(&self.store.persistent)? {
+            return Err(YHErrorKind::AlreadyFound.into());
+        }
+
+        let bucks: Vec<YStoreBuck> = YBucket::all()
+            .iter()
+            .map(|buck| buck.to_store_buck())
+            .collect();
+        let inner = YMemoryStore::create(YMemoryConfig::default())?;
+        let mut staging = YEncryptedStore::new(self.store.persistent.key, inner);
+        snapshot.import(&mut staging.inner)?;
+        // backups taken before they were kept sealed hold plaintext entries
+        if staging.is_sealed()? {
+            staging.check_key()?;
+        } else {
+            staging.encrypt_plain(&bucks)?;
+        }
+        YSchema::migrate(&mut staging, self.get_key())?;
+        let snapshot = YSnapshot::export(&staging, &bucks)?;
+
+        let meta_buck = YBucket::Meta.to_store_buck();
+        for key in [YSchema::version_key(), YNodeKey::key()].iter() {
+            if self.store.persistent.lookup(&meta_buck, key)? {
+                self.store.persistent.delete(&meta_buck, key)?;
+            }
+        }
+        snapshot.import(&mut self.store.persistent)?;
+        // snapshots taken before the store held the node key keep the current one
+        if !YNodeKey::lookup(&self.store.persistent)? {
+            YNodeKey::set(&mut self.store.persistent, &self.secret_key)?;
+        }
+        self.secret_key = YNodeKey::get(&self.store.persistent)?;
+        Ok(snapshot.entries.len() as u32)
+    }
+
+    pub fn change_password(&mut self, pswd: &str) -> YHResult<()> {
+        let mut config = self.config.clone();
+        config.set_password(pswd)?;
+        let old_key = self.get_key();
+        let new_key = config.master_key()?;
+        let old_store_key = self.store.persistent.key;
+        let new_store_key = Self::store_key(new_key);
+        let bucks: Vec<YStoreBuck> = YBucket::all()
+            .iter()
+            .map(|buck| buck.to_store_buck())
+            .collect();
+        // the new verifier is on disk before the store is re-keyed, and only replaces the config after
+        config.write_tmp()?;
+        let res = atomically(&mut self.store.persistent, |store| {
+            let keys_count = YKeys::count(store)?;
+            let keys = YKeys::list(store, 0, keys_count)?;
+            let wallets_count = YWallet::count(store)?;
+            for wallet in YWallet::list(store, old_key, 0, wallets_count)? {
+                wallet.delete(store, old_key)?;
+                wallet.create(store, new_key)?;
+                let old_link = YWallet::enc_name(old_key, &wallet.name)?;
+                for mut keys in keys.iter().filter(|keys| keys.wallet == old_link).cloned() {
+                    keys.wallet = YWallet::enc_name(new_key, &wallet.name)?;
+                    keys.update(store)?;
+                }
+            }
+            YIndex::rebuild(store, new_key)?;
+            store.rotate(&bucks, new_store_key)
+        });
+        if let Err(err) = res {
+            self.store.persistent.key = old_store_key;
+            YConfig::discard_tmp()?;
+            return Err(err);
+        }
+        YConfig::commit_tmp()?;
+        self.config = config;
+        self.key = new_key;
+        Ok(())
+    }
+}
+
+impl YAPI<YMemoryStore, YMemoryStore> {
+    pub fn new_temporary(config: YConfig) -> YHResult<YAPI<YMemoryStore, YMemoryStore>> {
+        let mem_store = YMemoryStore::create(YMemoryConfig::default())?;
+        let mut per_store = YMemoryStore::create(YMemoryConfig::default())?;
+        let key = config.master_key()?;
+        YSchema::migrate(&mut per_store, key)?;
+        let secret_key = YNodeKey::load(&mut per_store, config.legacy_secret_key.clone(), false)?;
         let store = YAPIStore {
             memory: mem_store,
             persistent: per_store,
         };
-        let api = YAPI::<YMemoryStore, YPersistentStore> {
+        let api = YAPI::<YMemoryStore, YMemoryStore> {
             config: config,
+            key: key,
+            secret_key: secret_key,
             store: store,
+            prices: Arc::new(Mutex::new(Vec::new())),
+            relays: Vec::new(),
+            fetches: Vec::new(),
         };
         Ok(api)
     }
+}
 
+impl<M: YStorage, P: YStorage> YAPI<M, P> {
     pub fn close_store(&mut self) -> YHResult<()> {
         self.store.memory.close()?;
         self.store.persistent.close()?;
@@ -91,25 +260,118 @@ impl YAPI<YMemoryStore, YPersistentStore> {
         Ok(())
     }
 
+    pub fn migrate_store(&mut self) -> YHResult<u32> {
+        let key = self.get_key();
+        YSchema::migrate(&mut self.store.persistent, key)
+    }
+
+    pub fn reindex_store(&mut self) -> YHResult<()> {
+        let key = self.get_key();
+        YIndex::rebuild(&mut self.store.persistent, key)
+    }
+
+    pub fn check_store(&mut self, repair: bool) -> YHResult<YFsckReport> {
+        let key = self.get_key();
+        YFsck::run(&mut self.store.persistent, key, repair)
+    }
+
+    pub fn prune_store(&mut self) -> YHResult<YPruneReport> {
+        let key = self.get_key();
+        let retention = self.config.data_retention;
+        let report = YPrune::run(&mut self.store.persistent, key, retention)?;
+        self.config.light_mode = true;
+        Ok(report)
+    }
+
     pub fn check_password(&self, pswd: &str) -> YHResult<()> {
-        if pswd.to_string() != self.config.password {
-            return Err(YHErrorKind::InvalidPassword.into());
-        }
-        Ok(())
+        let key = self.config.kdf.verify(pswd, &self.config.verifier)?;
+        self.check_key(key)
+            .map_err(|_| YHErrorKind::InvalidPassword.into())
     }
 
     pub fn get_key(&self) -> YKey32 {
-        let pswd_seed = self.config.password.as_bytes();
-        YKey32(YSHA256::hash(&pswd_seed).0)
+        self.key
     }
 
     pub fn check_key(&self, key: YKey32) -> YHResult<()> {
-        if key != self.get_key() {
+        if !constant_time_eq(&key.0, &self.key.0) {
             return Err(YHErrorKind::InvalidKey.into());
         }
         Ok(())
     }
 
+    pub fn public_key(&self) -> YPublicKey {
+        self.secret_key.to_public()
+    }
+
+    pub fn price(&self) -> YHResult<YAmount> {
+        let price = YAmount::from_u64(self.config.price)?;
+        Ok(price)
+    }
+
+    // the peer prices are cached by the server in the background, the node price is always part of them
+    pub fn node_prices(&self) -> YHResult<Vec<YPeerPrice>> {
+        let mut prices = self.prices.lock().unwrap().clone();
+        prices.push(YPeerPrice::new(self.public_key(), &self.price()?));
+        Ok(prices)
+    }
+
+    pub fn fee_estimator(&self) -> YHResult<YFeeEstimator> {
+        let prices = self.node_prices()?
+            .iter()
+            .map(|price| price.price.clone())
+            .collect();
+        Ok(YFeeEstimator::new(&prices))
+    }
+
+    // the node confirming a transaction is only known after it has been relayed,
+    // so the fee goes to the peer asking the median price, or to this node without peers
+    pub fn fee_public_key(&self) -> YHResult<YPublicKey> {
+        match YPeerPrice::median(&self.node_prices()?) {
+            Some(price) => Ok(price.pk),
+            None => Ok(self.public_key()),
+        }
+    }
+
+    pub fn peer_hosts(&self) -> YHResult<Vec<YHost>> {
+        let count = YPeer::count_by_ip(&self.store.persistent)?;
+        let hosts = self.list_peers(0, count)?
+            .iter()
+            .map(|peer| peer.host.clone())
+            .filter(|host| *host != self.config.host)
+            .collect();
+        Ok(hosts)
+    }
+
+    // unreachable peers are skipped, this blocks on every peer so it runs off the job loop
+    pub fn fetch_peer_prices(hosts: &Vec<YHost>) -> Vec<YPeerPrice> {
+        hosts.iter()
+            .filter_map(|host| Self::peer_price(host.internal()).ok())
+            .collect()
+    }
+
+    fn peer_price(address: SocketAddr) -> YHResult<YPeerPrice> {
+        let mut client = YClient::new(Some(address), YClient::default_timeout())?;
+        let req = YRequest::Ping(YPingReq::new()?);
+        match client.send_request(&req)? {
+            YResponse::Ping(res) => Ok(YPeerPrice::new(res.public_key, &res.price)),
+            _ => Err(YHErrorKind::InvalidResponse.into()),
+        }
+    }
+
+    // the node key is paid the fees, so the node wallet has to track it
+    pub fn track_node_key(&mut self, wallet: &str) -> YHResult<()> {
+        let key = self.get_key();
+        if YKeys::lookup(&self.store.persistent, self.public_key())? {
+            return Ok(());
+        }
+        YKeys::from_wallet(self.secret_key, key, wallet)?.create(&mut self.store.persistent)
+    }
+
+    pub fn estimate_fee(&self, tx_size: u32, data_size: u32) -> YHResult<YAmount> {
+        Ok(self.fee_estimator()?.estimate(tx_size, data_size))
+    }
+
     pub fn put_peer(&mut self, host: YHost) -> YHResult<()> {
         let ip = host.ip()?;
         let peer = YPeer::new(host);
@@ -125,6 +387,10 @@ impl YAPI<YMemoryStore, YPersistentStore> {
         YPeer::list_by_ip(&self.store.persistent, skip, count)
     }
 
+    pub fn list_peers_from(&self, after: &Option<YStoreKey>, limit: u32) -> YHResult<(Vec<YPeer>, Option<YStoreKey>)> {
+        YPeer::list_by_ip_from(&self.store.persistent, after, limit)
+    }
+
     pub fn get_peer(&self, ip: Ipv4Addr) -> YHResult<YPeer> {
         YPeer::get(&self.store.persistent, ip)
     }
@@ -150,11 +416,51 @@ impl YAPI<YMemoryStore, YPersistentStore> {
         wallet.create(&mut self.store.persistent, key)
     }
 
+    pub fn wallet_mnemonic(&self, name: &str) -> YHResult<String> {
+        let key = self.get_key();
+        YWallet::get(&self.store.persistent, key, name)?.mnemonic()
+    }
+
+    pub fn restore_wallet(&mut self, name: &str, phrase: &str) -> YHResult<YWallet> {
+        let key = self.get_key();
+        YHD::restore(&mut self.store.persistent, key, name, phrase)
+    }
+
+    pub fn export_wallet(&self, name: &str, passphrase: &str) -> YHResult<YWalletExport> {
+        let key = self.get_key();
+        let wallet = YWallet::get(&self.store.persistent, key, name)?;
+        // the export gets its own salt, but keeps the cost chosen for the node password
+        let kdf = YKdfParams::new(self.config.kdf.log_n, self.config.kdf.r, self.config.kdf.p)?;
+        YWalletExport::with_kdf(&wallet, passphrase, kdf)
+    }
+
+    pub fn import_wallet(&mut self, export: &YWalletExport, passphrase: &str) -> YHResult<YWallet> {
+        let key = self.get_key();
+        let wallet = export.wallet(passphrase)?;
+        YWallet::import(&mut self.store.persistent, key, &wallet)
+    }
+
+    pub fn rescan_wallet(&mut self, name: &str) -> YHResult<u32> {
+        let key = self.get_key();
+        let mut wallet = YWallet::get(&self.store.persistent, key, name)?;
+        YHD::rescan(&mut self.store.persistent, key, &mut wallet)
+    }
+
+    pub fn lookup_wallet(&self, name: &str) -> YHResult<bool> {
+        let key = self.get_key();
+        YWallet::lookup(&self.store.persistent, key, name)
+    }
+
     pub fn list_wallets(&self, skip: u32, count: u32) -> YHResult<Vec<YWallet>> {
         let key = self.get_key();
         YWallet::list(&self.store.persistent, key, skip, count)
     }
 
+    pub fn list_wallets_from(&self, after: &Option<YStoreKey>, limit: u32) -> YHResult<(Vec<YWallet>, Option<YStoreKey>)> {
+        let key = self.get_key();
+        YWallet::list_from(&self.store.persistent, key, after, limit)
+    }
+
     pub fn get_wallet(&self, name: &str) -> YHResult<YWallet> {
         let key = self.get_key();
         YWallet::get(&self.store.persistent, key, name)
@@ -164,20 +470,40 @@ impl YAPI<YMemoryStore, YPersistentStore> {
         YData::list(&self.store.persistent, skip, count)
     }
 
+    pub fn list_data_from(&self, after: &Option<YStoreKey>, limit: u32) -> YHResult<(Vec<YData>, Option<YStoreKey>)> {
+        YData::list_from(&self.store.persistent, after, limit)
+    }
+
     pub fn list_data_by_wallet(&self, wallet_name: &str) -> YHResult<Vec<YData>> {
-        let ucoins = self.list_ucoins(wallet_name)?;
-        let mut data = Vec::new();
-        for ucoin in ucoins {
-            let d = self.get_data(ucoin.id, ucoin.tag.unwrap())?;
-            data.push(d);
-        }
-        Ok(data)
+        let key = self.get_key();
+        YIndex::list_wallet_data(&self.store.persistent, key, wallet_name)
+    }
+
+    pub fn list_data_by_wallet_from(&self, wallet_name: &str, after: &Option<YStoreKey>, limit: u32) -> YHResult<(Vec<YData>, Option<YStoreKey>)> {
+        let key = self.get_key();
+        YIndex::list_wallet_data_from(&self.store.persistent, key, wallet_name, after, limit)
     }
 
     pub fn get_data(&self, checksum: YDigest64, tag: YMACCode) -> YHResult<YData> {
         YData::get(&self.store.persistent, checksum, tag)
     }
 
+    pub fn get_data_by_checksum(&self, checksum: YDigest64) -> YHResult<YData> {
+        YData::get_by_checksum(&self.store.persistent, checksum)
+    }
+
+    pub fn list_transaction_data(&self, id: YDigest64) -> YHResult<Vec<YData>> {
+        let tx = self.get_transaction(id)?;
+        let mut data = Vec::new();
+        for output in tx.internal().outputs {
+            if let Some(_data) = output.data {
+                let d = self.get_data(_data.checksum, _data.tag)?;
+                data.push(d);
+            }
+        }
+        Ok(data)
+    }
+
     pub fn list_coins(&self, wallet: &str) -> YHResult<Vec<YCoin>> {
         let wallet = self.get_wallet(wallet)?;
         let mut coins = Vec::new();
@@ -186,6 +512,11 @@ impl YAPI<YMemoryStore, YPersistentStore> {
         Ok(coins)
     }
 
+    pub fn wallet_history(&self, wallet: &str) -> YHResult<YHistory> {
+        let key = self.get_key();
+        YHistory::build(&self.store.persistent, key, wallet)
+    }
+
     pub fn list_ucoins(&self, wallet: &str) -> YHResult<Vec<YCoin>> {
         let wallet = self.get_wallet(wallet)?;
         Ok(wallet.ucoins)
@@ -196,41 +527,241 @@ impl YAPI<YMemoryStore, YPersistentStore> {
         Ok(wallet.scoins)
     }
 
+    pub fn new_receive_key(&mut self, wallet: &str) -> YHResult<YPublicKey> {
+        let key = self.get_key();
+        YWallet::new_receive_key(&mut self.store.persistent, key, wallet)
+    }
+
+    pub fn list_receive_keys(&self, wallet: &str) -> YHResult<Vec<YPublicKey>> {
+        let key = self.get_key();
+        let keys = YKeys::list_by_wallet(&self.store.persistent, key, wallet)?
+            .iter()
+            .map(|keys| keys.pk)
+            .collect();
+        Ok(keys)
+    }
+
+    fn receive_transaction(&mut self, id: YDigest64) -> YHResult<u32> {
+        let key = self.get_key();
+        let utxos = YUTXO::list_by_tx(&self.store.persistent, id)?;
+        YWallet::receive(&mut self.store.persistent, key, &utxos)
+    }
+
+    fn receive_coinbase(&mut self, id: YDigest64) -> YHResult<u32> {
+        let key = self.get_key();
+        let utxos = YUTXO::list_by_cb(&self.store.persistent, id)?;
+        YWallet::receive(&mut self.store.persistent, key, &utxos)
+    }
+
     pub fn create_raw_transaction(&mut self, key: YKey32, wallet: &str, raw: &str, sks: &Vec<YSecretKey>) -> YHResult<YTransaction> {
-        YTransaction::create_raw(&mut self.store.persistent, key, wallet, raw, sks)
+        let tx = YTransaction::create_raw(&mut self.store.persistent, key, wallet, raw, sks)?;
+        self.receive_transaction(tx.0.id)?;
+        Ok(tx)
+    }
+
+    pub fn create_coin_transaction<C: YCoinSelector>(&mut self, key: YKey32, wallet: &str, to: YPublicKey, amount: YAmount, keep_data: bool, selector: &C, max_inputs: u32, fee: &YFee) -> YHResult<(YTransaction, Option<YTransaction>)> {
+        let (tx, fee_tx) = YTransaction::create_coins(&mut self.store.persistent, key, wallet, to, amount, keep_data, selector, max_inputs, fee)?;
+        self.receive_transaction(tx.0.id)?;
+        if let Some(ref fee_tx) = fee_tx {
+            self.receive_transaction(fee_tx.0.id)?;
+        }
+        Ok((tx, fee_tx))
+    }
+
+    pub fn create_data_transaction<C: YCoinSelector>(&mut self, key: YKey32, wallet: &str, to: YPublicKey, buf: &[u8], keep_data: bool, selector: &C, max_inputs: u32, fee: &YFee) -> YHResult<(YTransaction, Option<YTransaction>)> {
+        let (tx, fee_tx) = YTransaction::create_data(&mut self.store.persistent, key, wallet, to, buf, keep_data, selector, max_inputs, fee)?;
+        self.receive_transaction(tx.0.id)?;
+        if let Some(ref fee_tx) = fee_tx {
+            self.receive_transaction(fee_tx.0.id)?;
+        }
+        Ok((tx, fee_tx))
+    }
+
+    pub fn create_batch_transaction<C: YCoinSelector>(&mut self, key: YKey32, wallet: &str, payments: &Vec<(YPublicKey, YAmount)>, keep_data: bool, selector: &C, max_inputs: u32, fee: &YFee) -> YHResult<(Vec<YTransaction>, Option<YTransaction>)> {
+        let (txs, fee_tx) = YTransaction::create_batch(&mut self.store.persistent, key, wallet, payments, keep_data, selector, max_inputs, fee)?;
+        for tx in txs.iter() {
+            self.receive_transaction(tx.0.id)?;
+        }
+        if let Some(ref fee_tx) = fee_tx {
+            self.receive_transaction(fee_tx.0.id)?;
+        }
+        Ok((txs, fee_tx))
+    }
+
+    pub fn create_unsigned_transaction<C: YCoinSelector>(&mut self, key: YKey32, wallet: &str, to: YPublicKey, amount: YAmount, keep_data: bool, selector: &C, max_inputs: u32, fee: &YFee) -> YHResult<YUnsignedTransaction> {
+        YUnsignedTransaction::create(&mut self.store.persistent, key, wallet, to, amount, keep_data, selector, max_inputs, fee)
+    }
+
+    pub fn push_transaction(&mut self, tx: &LibTransaction) -> YHResult<YTransaction> {
+        let tx = YTransaction::push(&mut self.store.persistent, tx)?;
+        let key = self.get_key();
+        YWallet::spend(&mut self.store.persistent, key, &tx.internal())?;
+        YReservation::release(&mut self.store.persistent, &tx.internal())?;
+        self.receive_transaction(tx.0.id)?;
+        Ok(tx)
     }
 
-    pub fn create_coin_transaction(&mut self, key: YKey32, wallet: &str, to: YPublicKey, amount: YAmount, keep_data: bool) -> YHResult<YTransaction> {
-        YTransaction::create_coins(&mut self.store.persistent, key, wallet, to, amount, keep_data)
+    // relaying blocks on every peer, so the transactions are queued and the server relays them off the job loop
+    pub fn queue_relay(&mut self, tx: &LibTransaction) {
+        self.relays.push(tx.clone());
     }
 
-    pub fn create_data_transaction(&mut self, key: YKey32, wallet: &str, to: YPublicKey, buf: &[u8], keep_data: bool) -> YHResult<YTransaction> {
-        YTransaction::create_data(&mut self.store.persistent, key, wallet, to, buf, keep_data)
+    pub fn take_relays(&mut self) -> Vec<LibTransaction> {
+        mem::replace(&mut self.relays, Vec::new())
+    }
+
+    // peers already knowing the transaction refuse it, so the relay stops there
+    pub fn relay_transaction(hosts: &Vec<YHost>, tx: &LibTransaction) -> u32 {
+        hosts.iter()
+            .filter(|host| Self::push_transaction_to(host.internal(), tx).is_ok())
+            .count() as u32
+    }
+
+    fn push_transaction_to(address: SocketAddr, tx: &LibTransaction) -> YHResult<()> {
+        let mut client = YClient::new(Some(address), YClient::default_timeout())?;
+        let req = YRequest::PushTx(YPushTxReq::new(tx)?);
+        match client.send_request(&req)? {
+            YResponse::PushTx(res) => {
+                if res.tx_id != tx.id {
+                    return Err(YHErrorKind::InvalidResponse.into());
+                }
+                Ok(())
+            },
+            _ => Err(YHErrorKind::InvalidResponse.into()),
+        }
     }
 
     pub fn list_transactions(&self, skip: u32, count: u32) -> YHResult<Vec<YTransaction>> {
         YTransaction::list(&self.store.persistent, skip, count)
     }
 
+    pub fn list_transactions_from(&self, after: &Option<YStoreKey>, limit: u32) -> YHResult<(Vec<YTransaction>, Option<YStoreKey>)> {
+        YTransaction::list_from(&self.store.persistent, after, limit)
+    }
+
     pub fn list_transactions_by_wallet(&self, wallet_name: &str) -> YHResult<Vec<YTransaction>> {
-        let coins = self.list_coins(wallet_name)?;
+        let key = self.get_key();
+        let ids = YIndex::list_wallet_txs(&self.store.persistent, key, wallet_name)?;
         let mut transactions = Vec::new();
-        for coin in coins {
-            if coin.kind == YCoinKind::Transaction {
-                let transaction = self.get_transaction(coin.id)?;
-                transactions.push(transaction);
-            }
+        for id in ids {
+            transactions.push(self.get_transaction(id)?);
         }
         Ok(transactions)
     }
 
+    pub fn list_transactions_by_wallet_from(&self, wallet_name: &str, after: &Option<YStoreKey>, limit: u32) -> YHResult<(Vec<YTransaction>, Option<YStoreKey>)> {
+        let key = self.get_key();
+        let (ids, next) = YIndex::list_wallet_txs_from(&self.store.persistent, key, wallet_name, after, limit)?;
+        let mut transactions = Vec::new();
+        for id in ids {
+            transactions.push(self.get_transaction(id)?);
+        }
+        Ok((transactions, next))
+    }
+
     pub fn list_transaction_utxos(&self, id: YDigest64) -> YHResult<Vec<YUTXO>>{
         YUTXO::list_by_tx(&self.store.persistent, id)
     }
 
-    pub fn list_transaction_ancestors(&self, id: YDigest64) -> YHResult<(Vec<YTransaction>, Vec<YCoinbase>)>{
-        let tx = self.get_transaction(id)?;
-        tx.list_ancestors(&self.store.persistent)
+    // light stores answer with pruned and queue the ancestors, the server fetches them off the job loop
+    pub fn list_transaction_ancestors(&mut self, id: YDigest64) -> YHResult<(Vec<YTransaction>, Vec<YCoinbase>)>{
+        let res = self.get_transaction(id)
+            .and_then(|tx| tx.list_ancestors(&self.store.persistent));
+        if res.is_err() && self.config.light_mode {
+            self.queue_fetch(id);
+            return Err(YHErrorKind::Pruned.into());
+        }
+        res
+    }
+
+    pub fn queue_fetch(&mut self, id: YDigest64) {
+        if !self.fetches.contains(&id) {
+            self.fetches.push(id);
+        }
+    }
+
+    pub fn take_fetches(&mut self) -> Vec<YDigest64> {
+        mem::replace(&mut self.fetches, Vec::new())
+    }
+
+    // the first peer answering wins, this blocks on every peer so it runs off the job loop
+    pub fn fetch_transaction_ancestors(hosts: &Vec<YHost>, id: YDigest64) -> YHResult<(Vec<LibTransaction>, Vec<LibCoinbase>)> {
+        let mut res = Err(YHErrorKind::NotFound.into());
+        for host in hosts.iter() {
+            res = Self::fetch_transaction_ancestors_from(host.internal(), id);
+            if res.is_ok() {
+                break;
+            }
+        }
+        res
+    }
+
+    fn fetch_transaction_ancestors_from(address: SocketAddr, id: YDigest64) -> YHResult<(Vec<LibTransaction>, Vec<LibCoinbase>)> {
+        let mut client = YClient::new(Some(address), YClient::default_timeout())?;
+
+        let mut txs = Vec::new();
+        let req = YRequest::GetTx(YGetTxReq::new(id)?);
+        match client.send_request(&req)? {
+            YResponse::GetTx(res) => {
+                if res.tx.id != id {
+                    return Err(YHErrorKind::InvalidResponse.into());
+                }
+                txs.push(res.tx);
+            },
+            _ => {
+                return Err(YHErrorKind::InvalidResponse.into());
+            },
+        }
+
+        let req = YRequest::ListTxAncestors(YListTxAncestorsReq::new(id)?);
+        match client.send_request(&req)? {
+            YResponse::ListTxAncestors(res) => txs.extend(res.txs),
+            _ => {
+                return Err(YHErrorKind::InvalidResponse.into());
+            },
+        }
+
+        let mut cbs = Vec::new();
+        let mut cb_ids = Vec::new();
+        for tx in txs.iter() {
+            for input in tx.inputs.iter() {
+                if input.height == 0 && !cb_ids.contains(&input.id) {
+                    cb_ids.push(input.id);
+                }
+            }
+        }
+        for cb_id in cb_ids {
+            let req = YRequest::GetCb(YGetCbReq::new(cb_id)?);
+            match client.send_request(&req)? {
+                YResponse::GetCb(res) => {
+                    if res.cb.id != cb_id {
+                        return Err(YHErrorKind::InvalidResponse.into());
+                    }
+                    cbs.push(res.cb);
+                },
+                _ => {
+                    return Err(YHErrorKind::InvalidResponse.into());
+                },
+            }
+        }
+
+        Ok((txs, cbs))
+    }
+
+    pub fn store_ancestors(&mut self, txs: &Vec<LibTransaction>, cbs: &Vec<LibCoinbase>) -> YHResult<()> {
+        atomically(&mut self.store.persistent, |store| {
+            for tx in txs.iter() {
+                if !YTransaction::lookup(store, tx.id)? {
+                    YTransaction::new(tx)?.create(store)?;
+                }
+            }
+            for cb in cbs.iter() {
+                if !YCoinbase::lookup(store, cb.id)? {
+                    YCoinbase::new(cb)?.create(store)?;
+                }
+            }
+            Ok(())
+        })
     }
 
     pub fn get_transaction(&self, id: YDigest64) -> YHResult<YTransaction> {
@@ -256,20 +787,58 @@ impl YAPI<YMemoryStore, YPersistentStore> {
         YCoinbase::list(&self.store.persistent, skip, count)
     }
 
+    pub fn list_coinbases_from(&self, after: &Option<YStoreKey>, limit: u32) -> YHResult<(Vec<YCoinbase>, Option<YStoreKey>)> {
+        YCoinbase::list_from(&self.store.persistent, after, limit)
+    }
+
     pub fn list_coinbases_by_wallet(&self, wallet_name: &str) -> YHResult<Vec<YCoinbase>> {
-        let coins = self.list_coins(wallet_name)?;
+        let key = self.get_key();
+        let ids = YIndex::list_wallet_cbs(&self.store.persistent, key, wallet_name)?;
         let mut coinbases = Vec::new();
-        for coin in coins {
-            if coin.kind == YCoinKind::Coinbase {
-                let coinbase = self.get_coinbase(coin.id)?;
-                coinbases.push(coinbase);
-            }
+        for id in ids {
+            coinbases.push(self.get_coinbase(id)?);
         }
         Ok(coinbases)
     }
 
+    pub fn list_coinbases_by_wallet_from(&self, wallet_name: &str, after: &Option<YStoreKey>, limit: u32) -> YHResult<(Vec<YCoinbase>, Option<YStoreKey>)> {
+        let key = self.get_key();
+        let (ids, next) = YIndex::list_wallet_cbs_from(&self.store.persistent, key, wallet_name, after, limit)?;
+        let mut coinbases = Vec::new();
+        for id in ids {
+            coinbases.push(self.get_coinbase(id)?);
+        }
+        Ok((coinbases, next))
+    }
+
+    pub fn list_recipient_utxos(&self, recipient: &YPublicKey) -> YHResult<Vec<YUTXO>> {
+        YIndex::list_recipient_utxos(&self.store.persistent, recipient)
+    }
+
+    pub fn get_utxo_spender(&self, id: YDigest64, idx: u32) -> YHResult<Option<YDigest64>> {
+        YIndex::get_spender(&self.store.persistent, id, idx)
+    }
+
     pub fn list_coinbase_utxos(&self, id: YDigest64) -> YHResult<Vec<YUTXO>>{
-        YUTXO::list_by_tx(&self.store.persistent, id)
+        YUTXO::list_by_cb(&self.store.persistent, id)
+    }
+
+    pub fn list_utxos(&self, id: YDigest64) -> YHResult<Vec<YUTXO>> {
+        if YTransaction::lookup(&self.store.persistent, id)? {
+            self.list_transaction_utxos(id)
+        } else {
+            self.list_coinbase_utxos(id)
+        }
+    }
+
+    pub fn get_utxo(&self, id: YDigest64, idx: u32) -> YHResult<YUTXO> {
+        YUTXO::get(&self.store.persistent, id, idx)
+    }
+
+    pub fn push_coinbase(&mut self, cb: &LibCoinbase) -> YHResult<YCoinbase> {
+        let cb = YCoinbase::push(&mut self.store.persistent, cb)?;
+        self.receive_coinbase(cb.0.id)?;
+        Ok(cb)
     }
 
     pub fn mine(&mut self, wallet: &str, id: YDigest64, incr: u32, fee_pk: YPublicKey) -> YHResult<(YCoinbase, u32)> {
@@ -277,9 +846,19 @@ impl YAPI<YMemoryStore, YPersistentStore> {
         YCoinbase::mine(&mut self.store.persistent, key, wallet, id, incr, fee_pk)
     }
 
-    pub fn mine_genesys(&mut self, wallet: &str, incr: u32, fee_pk: YPublicKey) -> YHResult<((YCoinbase, YTransaction), u32)> {
+    pub fn mine_genesys(&mut self, wallet: &str, diff: u32, incr: u32, fee_pk: YPublicKey) -> YHResult<((YCoinbase, YTransaction), u32)> {
+        let key = self.get_key();
+        YCoinbase::mine_genesys(&mut self.store.persistent, key, wallet, diff, incr, fee_pk)
+    }
+
+    pub fn genesys_keys(&mut self, wallet: &str) -> YHResult<(YSecretKey, YPublicKey)> {
+        let key = self.get_key();
+        YCoinbase::genesys_keys(&mut self.store.persistent, key, wallet)
+    }
+
+    pub fn store_genesys(&mut self, wallet: &str, miner_sk: YSecretKey, cb: &LibCoinbase, tx: &LibTransaction) -> YHResult<(YCoinbase, YTransaction)> {
         let key = self.get_key();
-        YCoinbase::mine_genesys(&mut self.store.persistent, key, wallet, incr, fee_pk)
+        YCoinbase::store_genesys(&mut self.store.persistent, key, wallet, miner_sk, cb, tx)
     }
 
     pub fn info(&self) -> YHResult<YInfo> {
@@ -0,0 +1,415 @@
+use libyobicash::crypto::hash::digest::YDigest64;
+use libyobicash::crypto::elliptic::keys::YPublicKey;
+use libyobicash::amount::YAmount;
+use libyobicash::transaction::YTransaction as LibTransaction;
+use libyobicash::coinbase::YCoinbase as LibCoinbase;
+use structopt::StructOpt;
+use std::net::{SocketAddr, IpAddr};
+use std::path::Path;
+use std::fs::File;
+use std::io::prelude::*;
+use config::*;
+use models::hd::YHDKey;
+use models::payment::*;
+use models::selection::YCoinSelection;
+use models::unsigned::*;
+use models::export::YWalletExport;
+use models::history::*;
+use network::client::*;
+use network::message::*;
+use cli::client::opt::*;
+use cli::prompt::YPrompt;
+use utils::hex::*;
+use errors::*;
+
+pub struct YClientCli;
+
+impl YClientCli {
+    pub fn from_args() -> YHResult<()> {
+        let opt = YClientOpt::from_args();
+        match opt {
+            YClientOpt::Connect { host, port, verbose } => {
+                YClientCli::connect(host, port, verbose)
+            },
+            YClientOpt::Ping { host, port, verbose } => {
+                let req = YRequest::Ping(YPingReq::new()?);
+                YClientCli::send(host, port, verbose, &req)
+            },
+            YClientOpt::Info { host, port, verbose } => {
+                let req = YRequest::GetInfo(YGetInfoReq::new()?);
+                YClientCli::send(host, port, verbose, &req)
+            },
+            YClientOpt::Create { host, port, verbose, cmd } => {
+                let out = match cmd {
+                    CreateCommands::UnsignedTransaction { ref out, .. } => Some(out.clone()),
+                    _ => None,
+                };
+                let req = YClientCli::create_request(cmd)?;
+                match out {
+                    Some(out) => YClientCli::export_unsigned(host, port, verbose, &req, &out),
+                    None => YClientCli::send(host, port, verbose, &req),
+                }
+            },
+            YClientOpt::Push { host, port, verbose, cmd } => {
+                let req = YClientCli::push_request(cmd)?;
+                YClientCli::send(host, port, verbose, &req)
+            },
+            YClientOpt::Send { host, port, verbose, cmd } => {
+                let req = YClientCli::send_request(cmd)?;
+                YClientCli::send(host, port, verbose, &req)
+            },
+            YClientOpt::List { host, port, verbose, cmd } => {
+                let history = match cmd {
+                    ListCommands::History { ref name, ref format, ref out } => {
+                        Some((name.clone(), format.parse::<YHistoryFormat>()?, out.clone()))
+                    },
+                    _ => None,
+                };
+                let req = YClientCli::list_request(cmd)?;
+                match history {
+                    Some((name, format, out)) => YClientCli::export_history(host, port, verbose, &req, &name, format, out),
+                    None => YClientCli::send(host, port, verbose, &req),
+                }
+            },
+            YClientOpt::Get { host, port, verbose, cmd } => {
+                let req = YClientCli::get_request(cmd)?;
+                YClientCli::send(host, port, verbose, &req)
+            },
+            YClientOpt::Mine { difficulty, name, host, port, verbose } => {
+                let req = YRequest::Mine(YMineReq::new(&name, difficulty)?);
+                YClientCli::send(host, port, verbose, &req)
+            },
+            YClientOpt::Sign { input, out, wallet_file, verbose } => {
+                YClientCli::sign(&input, &out, wallet_file, verbose)
+            },
+        }
+    }
+
+    pub fn address(host: Option<String>, port: Option<u16>) -> YHResult<SocketAddr> {
+        let default_addr = if Path::new(&YConfig::path()?).exists() {
+            YConfig::read()?.control.internal()
+        } else {
+            YConfig::default_control().internal()
+        };
+        let ip = match host {
+            Some(host) => {
+                host.parse::<IpAddr>()
+                    .map_err(|_| YHErrorKind::InvalidIp)?
+            },
+            None => default_addr.ip(),
+        };
+        let port = port.unwrap_or(default_addr.port());
+        Ok(SocketAddr::new(ip, port))
+    }
+
+    pub fn connect(host: Option<String>, port: Option<u16>, verbose: bool) -> YHResult<()> {
+        let address = YClientCli::address(host, port)?;
+        let mut client = YClient::new(Some(address), YClient::default_timeout())?;
+        println!("connected to {:?}", address);
+        let req = YRequest::Ping(YPingReq::new()?);
+        if verbose {
+            println!("request: {}", String::from_utf8(req.to_json()?)?);
+        }
+        let res = client.send_request(&req)?;
+        YClientCli::print_response(&res)
+    }
+
+    pub fn request(host: Option<String>, port: Option<u16>, verbose: bool, req: &YRequest) -> YHResult<YResponse> {
+        let address = YClientCli::address(host, port)?;
+        if verbose {
+            println!("sending request to {:?}: {}", address, String::from_utf8(req.to_json()?)?);
+        }
+        let mut client = YClient::new(Some(address), YClient::default_timeout())?;
+        client.send_request(req)
+    }
+
+    pub fn send(host: Option<String>, port: Option<u16>, verbose: bool, req: &YRequest) -> YHResult<()> {
+        let res = YClientCli::request(host, port, verbose, req)?;
+        YClientCli::print_response(&res)
+    }
+
+    pub fn export_unsigned(host: Option<String>, port: Option<u16>, verbose: bool, req: &YRequest, out: &str) -> YHResult<()> {
+        let res = YClientCli::request(host, port, verbose, req)?;
+        match res {
+            YResponse::CreateUnsignedTx(res) => {
+                let unsigned = YUnsignedTransaction::from_bytes(&res.raw)?;
+                YClientCli::write_hex(out, &res.raw)?;
+                println!("{}", unsigned.to_json()?);
+                Ok(())
+            },
+            _ => {
+                YClientCli::print_response(&res)?;
+                Err(YHErrorKind::InvalidResponse.into())
+            },
+        }
+    }
+
+    pub fn export_history(host: Option<String>, port: Option<u16>, verbose: bool, req: &YRequest, name: &str, format: YHistoryFormat, out: Option<String>) -> YHResult<()> {
+        let res = YClientCli::request(host, port, verbose, req)?;
+        match res {
+            YResponse::ListHistory(res) => {
+                let history = YHistory::new(name, &res.entries).format(format)?;
+                match out {
+                    Some(out) => {
+                        let mut file = File::create(&out)?;
+                        file.write_all(history.as_bytes())?;
+                        println!("{} history entries written to {}", res.count, out);
+                    },
+                    None => println!("{}", history),
+                }
+                Ok(())
+            },
+            _ => {
+                YClientCli::print_response(&res)?;
+                Err(YHErrorKind::InvalidResponse.into())
+            },
+        }
+    }
+
+    // signing needs no node: the seed phrase or the wallet export never leaves the offline machine,
+    // the fee transaction spends the change of the signed one so it is pushed after it
+    pub fn sign(input: &str, out: &str, wallet_file: Option<String>, verbose: bool) -> YHResult<()> {
+        let hex = YClientCli::read_hex(None, Some(input.to_string()))?;
+        let unsigned = YUnsignedTransaction::from_bytes(&from_hex(&hex)?)?;
+        if verbose {
+            println!("signing: {}", unsigned.to_json()?);
+        }
+        let (tx, fee_tx) = match wallet_file {
+            Some(path) => {
+                let export = YWalletExport::read(&path)?;
+                let passphrase = YPrompt::read_secret("YOBICASH_WALLET_PASSPHRASE", "wallet passphrase: ")?;
+                unsigned.sign_with_wallet(&export.wallet(&passphrase)?)?
+            },
+            None => {
+                let phrase = YPrompt::read_secret("YOBICASH_MNEMONIC", "seed phrase: ")?;
+                unsigned.sign(&YHDKey::from_mnemonic(&phrase)?)?
+            },
+        };
+        YClientCli::write_hex(out, &tx.to_bytes()?)?;
+        println!("signed transaction {} written to {}", tx.id.to_hex(), out);
+        if let Some(fee_tx) = fee_tx {
+            let fee_out = format!("{}.fee", out);
+            YClientCli::write_hex(&fee_out, &fee_tx.to_bytes()?)?;
+            println!("signed fee transaction {} written to {}", fee_tx.id.to_hex(), fee_out);
+        }
+        Ok(())
+    }
+
+    pub fn print_response(res: &YResponse) -> YHResult<()> {
+        let res_json = String::from_utf8(res.to_json()?)?;
+        println!("{}", res_json);
+        Ok(())
+    }
+
+    pub fn create_request(cmd: CreateCommands) -> YHResult<YRequest> {
+        match cmd {
+            CreateCommands::Wallet { name } => {
+                Ok(YRequest::CreateWallet(YCreateWalletReq::new(&name)?))
+            },
+            CreateCommands::ReceiveKey { name } => {
+                Ok(YRequest::CreateReceiveKey(YCreateReceiveKeyReq::new(&name)?))
+            },
+            CreateCommands::UnsignedTransaction { amount, to, name, spend_data, fee, max_fee, selection, .. } => {
+                let to = YClientCli::parse_public_key(&to)?;
+                let amount = YAmount::from_u64(amount as u64)?;
+                let fee = YClientCli::parse_fee(fee)?;
+                let max_fee = YClientCli::parse_fee(max_fee)?;
+                let selection = YClientCli::parse_selection(selection)?;
+                Ok(YRequest::CreateUnsignedTx(YCreateUnsignedTxReq::new(&name, to, &amount, !spend_data, selection, &fee, &max_fee)?))
+            },
+        }
+    }
+
+    pub fn push_request(cmd: PushCommands) -> YHResult<YRequest> {
+        match cmd {
+            PushCommands::Transaction { raw, file } => {
+                let hex = YClientCli::read_hex(raw, file)?;
+                let tx = LibTransaction::from_hex(&hex)?;
+                Ok(YRequest::PushTx(YPushTxReq::new(&tx)?))
+            },
+            PushCommands::Coinbase { raw, file } => {
+                let hex = YClientCli::read_hex(raw, file)?;
+                let cb = LibCoinbase::from_hex(&hex)?;
+                Ok(YRequest::PushCb(YPushCbReq::new(&cb)?))
+            },
+        }
+    }
+
+    pub fn send_request(cmd: SendCommands) -> YHResult<YRequest> {
+        match cmd {
+            SendCommands::Data { raw, file, to, name, spend_data, fee, max_fee, selection } => {
+                let hex = YClientCli::read_hex(raw, file)?;
+                let data = from_hex(&hex)?;
+                let to = YClientCli::parse_public_key(&to)?;
+                let fee = YClientCli::parse_fee(fee)?;
+                let max_fee = YClientCli::parse_fee(max_fee)?;
+                let selection = YClientCli::parse_selection(selection)?;
+                let req = YSendDataReq::new(&name, to, &data, !spend_data, selection, &fee, &max_fee)?;
+                Ok(YRequest::SendData(req))
+            },
+            SendCommands::Coins { amount, to, name, spend_data, fee, max_fee, selection } => {
+                let to = YClientCli::parse_public_key(&to)?;
+                let amount = YAmount::from_u64(amount as u64)?;
+                let fee = YClientCli::parse_fee(fee)?;
+                let max_fee = YClientCli::parse_fee(max_fee)?;
+                let selection = YClientCli::parse_selection(selection)?;
+                let req = YSendCoinsReq::new(&name, to, &amount, !spend_data, selection, &fee, &max_fee)?;
+                Ok(YRequest::SendCoins(req))
+            },
+            SendCommands::Batch { file, name, fee, max_fee } => {
+                let payments = YClientCli::read_payments(&file)?;
+                let fee = YClientCli::parse_fee(fee)?;
+                let max_fee = YClientCli::parse_fee(max_fee)?;
+                let req = YSendBatchReq::new(&name, &payments, true, &fee, &max_fee)?;
+                Ok(YRequest::SendBatch(req))
+            },
+        }
+    }
+
+    pub fn list_request(cmd: ListCommands) -> YHResult<YRequest> {
+        match cmd {
+            ListCommands::Peers { cursor, limit } => {
+                let cursor = YClientCli::parse_cursor(cursor)?;
+                Ok(YRequest::ListPeers(YListPeersReq::new(&cursor, limit)?))
+            },
+            ListCommands::Wallets { cursor, limit } => {
+                let cursor = YClientCli::parse_cursor(cursor)?;
+                Ok(YRequest::ListWallets(YListWalletsReq::new(&cursor, limit)?))
+            },
+            ListCommands::Data { name, cursor, limit } => {
+                let cursor = YClientCli::parse_cursor(cursor)?;
+                Ok(YRequest::ListWalletData(YListWalletDataReq::new(&name, &cursor, limit)?))
+            },
+            ListCommands::Transactions { name, cursor, limit } => {
+                let cursor = YClientCli::parse_cursor(cursor)?;
+                Ok(YRequest::ListTxs(YListTxsReq::new(&name, &cursor, limit)?))
+            },
+            ListCommands::TxUTXOs { tx_id } |
+            ListCommands::CbUTXOs { tx_id } => {
+                let tx_id = YClientCli::parse_digest(&tx_id)?;
+                Ok(YRequest::ListUTXOs(YListUTXOsReq::new(tx_id)?))
+            },
+            ListCommands::Ancestors { tx_id } => {
+                let tx_id = YClientCli::parse_digest(&tx_id)?;
+                Ok(YRequest::ListTxAncestors(YListTxAncestorsReq::new(tx_id)?))
+            },
+            ListCommands::Coinbases { name, cursor, limit } => {
+                let cursor = YClientCli::parse_cursor(cursor)?;
+                Ok(YRequest::ListCbs(YListCbsReq::new(&name, &cursor, limit)?))
+            },
+            ListCommands::Coins { name } => {
+                Ok(YRequest::ListCoins(YListCoinsReq::new(&name, YCoinsKind::All)?))
+            },
+            ListCommands::Scoins { name } => {
+                Ok(YRequest::ListCoins(YListCoinsReq::new(&name, YCoinsKind::Spent)?))
+            },
+            ListCommands::Ucoins { name } => {
+                Ok(YRequest::ListCoins(YListCoinsReq::new(&name, YCoinsKind::Unspent)?))
+            },
+            ListCommands::History { name, .. } => {
+                Ok(YRequest::ListHistory(YListHistoryReq::new(&name)?))
+            },
+        }
+    }
+
+    pub fn get_request(cmd: GetCommands) -> YHResult<YRequest> {
+        match cmd {
+            GetCommands::Wallet { name } => {
+                Ok(YRequest::GetWallet(YGetWalletReq::new(&name)?))
+            },
+            GetCommands::Data { checksum, .. } => {
+                let checksum = YClientCli::parse_digest(&checksum)?;
+                Ok(YRequest::GetData(YGetDataReq::new(checksum)?))
+            },
+            GetCommands::Transaction { id, .. } => {
+                let tx_id = YClientCli::parse_digest(&id)?;
+                Ok(YRequest::GetTx(YGetTxReq::new(tx_id)?))
+            },
+            GetCommands::Coinbase { id, .. } => {
+                let cb_id = YClientCli::parse_digest(&id)?;
+                Ok(YRequest::GetCb(YGetCbReq::new(cb_id)?))
+            },
+            GetCommands::Coin { tx_id, idx } => {
+                let tx_id = YClientCli::parse_digest(&tx_id)?;
+                Ok(YRequest::GetUTXO(YGetUTXOReq::new(tx_id, idx)?))
+            },
+        }
+    }
+
+    pub fn read_hex(raw: Option<String>, file: Option<String>) -> YHResult<String> {
+        match (raw, file) {
+            (Some(raw), None) => Ok(raw.trim().to_string()),
+            (None, Some(path)) => {
+                let mut file = File::open(path)?;
+                let mut hex = String::new();
+                file.read_to_string(&mut hex)?;
+                Ok(hex.trim().to_string())
+            },
+            _ => Err(YHErrorKind::InvalidValue.into()),
+        }
+    }
+
+    // one payment per line as `public_key_hex,amount`, skipping empty lines and `#` comments
+    pub fn read_payments(path: &str) -> YHResult<Vec<YPayment>> {
+        let mut file = File::open(path)?;
+        let mut csv = String::new();
+        file.read_to_string(&mut csv)?;
+        let mut payments = Vec::new();
+        for line in csv.lines() {
+            let line = line.trim();
+            if line.is_empty() || line.starts_with("#") {
+                continue;
+            }
+            let fields: Vec<&str> = line.split(',').map(|field| field.trim()).collect();
+            if fields.len() != 2 {
+                return Err(YHErrorKind::ParsingFailure.into());
+            }
+            let to = YClientCli::parse_public_key(fields[0])?;
+            let amount = fields[1].parse::<u64>()
+                .map_err(|_| YHErrorKind::ParsingFailure)?;
+            let amount = YAmount::from_u64(amount)?;
+            payments.push(YPayment::new(to, &amount)?);
+        }
+        if payments.is_empty() {
+            return Err(YHErrorKind::InvalidLength.into());
+        }
+        Ok(payments)
+    }
+
+    pub fn write_hex(path: &str, buf: &[u8]) -> YHResult<()> {
+        let mut file = File::create(path)?;
+        file.write_all(to_hex(buf).as_bytes())?;
+        Ok(())
+    }
+
+    pub fn parse_digest(s: &str) -> YHResult<YDigest64> {
+        let buf = from_hex(s)?;
+        let digest = YDigest64::from_bytes(&buf)?;
+        Ok(digest)
+    }
+
+    pub fn parse_cursor(s: Option<String>) -> YHResult<Vec<u8>> {
+        match s {
+            Some(s) => from_hex(&s),
+            None => Ok(Vec::new()),
+        }
+    }
+
+    pub fn parse_fee(fee: Option<u32>) -> YHResult<YAmount> {
+        let fee = YAmount::from_u64(fee.unwrap_or(0) as u64)?;
+        Ok(fee)
+    }
+
+    pub fn parse_selection(selection: Option<String>) -> YHResult<YCoinSelection> {
+        match selection {
+            Some(selection) => selection.parse(),
+            None => Ok(YCoinSelection::default()),
+        }
+    }
+
+    pub fn parse_public_key(s: &str) -> YHResult<YPublicKey> {
+        let buf = from_hex(s)?;
+        let pk = YPublicKey::from_bytes(&buf)?;
+        Ok(pk)
+    }
+}
@@ -1,7 +1,9 @@
 pub mod server;
 pub mod node;
 pub mod client;
+pub mod prompt;
 
 pub use self::server::*;
 pub use self::node::*;
 pub use self::client::*;
+pub use self::prompt::*;
@@ -1,6 +1,22 @@
+use libc;
 use std::process::*;
+use std::path::{Path, PathBuf};
+use std::fs::{File, OpenOptions, remove_file};
+use std::io::prelude::*;
+use std::io::Error as IOError;
+use std::os::unix::io::AsRawFd;
+use std::sync::Arc;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::thread;
+use std::time::Duration;
 use config::*;
 use store::common::*;
+use network::server::*;
+use errors::*;
+
+pub const YNODE_POLL_MILLIS: u64 = 100;
+
+pub const YNODE_STOP_TIMEOUT_SECS: u64 = 30;
 
 #[derive(Clone, Eq, PartialEq, Debug)]
 pub enum YNodeStatus {
@@ -35,15 +51,182 @@ impl YNode {
         }
     }
 
-    pub fn start() {
-        unreachable!()
+    pub fn pid_path() -> YHResult<String> {
+        let mut path = PathBuf::new();
+        path.push(&YConfigDir::home()?);
+        path.push("yobicashd.pid");
+        let path_str = path
+            .to_str()
+            .unwrap()
+            .to_string();
+        Ok(path_str)
+    }
+
+    pub fn stop_path() -> YHResult<String> {
+        let mut path = PathBuf::new();
+        path.push(&YConfigDir::home()?);
+        path.push("yobicashd.stop");
+        let path_str = path
+            .to_str()
+            .unwrap()
+            .to_string();
+        Ok(path_str)
+    }
+
+    pub fn read_pid() -> YHResult<Option<u32>> {
+        let path = YNode::pid_path()?;
+        if !Path::new(&path).exists() {
+            return Ok(None);
+        }
+        let mut file = OpenOptions::new()
+            .read(true)
+            .open(path)?;
+        let mut pid_str = String::new();
+        file.read_to_string(&mut pid_str)?;
+        let pid = pid_str.trim()
+            .parse::<u32>()
+            .map_err(|_| YHErrorKind::ParsingFailure)?;
+        Ok(Some(pid))
+    }
+
+    // the lock lives as long as the returned file, a reused pid can't hold it
+    pub fn lock_pid(pid: u32) -> YHResult<File> {
+        YConfigDir::create_home()?;
+        let mut file = OpenOptions::new()
+            .read(true)
+            .write(true)
+            .create(true)
+            .open(YNode::pid_path()?)?;
+        if unsafe { libc::flock(file.as_raw_fd(), libc::LOCK_EX | libc::LOCK_NB) } != 0 {
+            return Err(YHErrorKind::AlreadyFound.into());
+        }
+        file.set_len(0)?;
+        file.write_all(pid.to_string().as_bytes())?;
+        file.flush()?;
+        Ok(file)
+    }
+
+    pub fn is_locked() -> YHResult<bool> {
+        let path = YNode::pid_path()?;
+        if !Path::new(&path).exists() {
+            return Ok(false);
+        }
+        let file = OpenOptions::new()
+            .read(true)
+            .open(path)?;
+        if unsafe { libc::flock(file.as_raw_fd(), libc::LOCK_SH | libc::LOCK_NB) } == 0 {
+            unsafe { libc::flock(file.as_raw_fd(), libc::LOCK_UN) };
+            return Ok(false);
+        }
+        let err = IOError::last_os_error();
+        match err.raw_os_error() {
+            Some(libc::EWOULDBLOCK) => Ok(true),
+            _ => Err(err.into()),
+        }
+    }
+
+    pub fn is_alive(pid: u32) -> bool {
+        if unsafe { libc::kill(pid as libc::pid_t, 0) } == 0 {
+            return true;
+        }
+        IOError::last_os_error().raw_os_error() == Some(libc::EPERM)
     }
 
-    pub fn status() {
-        unreachable!()
+    fn cleanup() -> YHResult<()> {
+        let pid_path = YNode::pid_path()?;
+        if Path::new(&pid_path).exists() {
+            remove_file(pid_path)?;
+        }
+        let stop_path = YNode::stop_path()?;
+        if Path::new(&stop_path).exists() {
+            remove_file(stop_path)?;
+        }
+        Ok(())
     }
 
-    pub fn stop() {
-        unreachable!()
+    pub fn start(&mut self, difficulty: Option<u32>, verbose: bool) -> YHResult<()> {
+        if YNode::status()? == YNodeStatus::Running {
+            return Err(YHErrorKind::AlreadyFound.into());
+        }
+        YNode::cleanup()?;
+
+        let server = YServer::new(self.config.clone(),
+                                  self.storage_kind.clone(),
+                                  self.storage_mode.clone(),
+                                  difficulty,
+                                  verbose)?;
+
+        let pid_file = YNode::lock_pid(::std::process::id())?;
+        self.status = YNodeStatus::Running;
+
+        let stop = Arc::new(AtomicBool::new(false));
+        let watcher_stop = stop.clone();
+        let stop_path = YNode::stop_path()?;
+
+        thread::spawn(move || {
+            while !watcher_stop.load(Ordering::SeqCst) {
+                if Path::new(&stop_path).exists() {
+                    watcher_stop.store(true, Ordering::SeqCst);
+                    break;
+                }
+                thread::sleep(Duration::from_millis(YNODE_POLL_MILLIS));
+            }
+        });
+
+        let res = server.run(stop.clone());
+        stop.store(true, Ordering::SeqCst);
+
+        YNode::cleanup()?;
+        drop(pid_file);
+        self.status = YNodeStatus::Stopped;
+
+        res
+    }
+
+    pub fn status() -> YHResult<YNodeStatus> {
+        match YNode::read_pid()? {
+            Some(pid) => {
+                if YNode::is_locked()? && YNode::is_alive(pid) {
+                    Ok(YNodeStatus::Running)
+                } else {
+                    Ok(YNodeStatus::Stopped)
+                }
+            },
+            None => {
+                if Path::new(&YConfig::db_path()?).exists() {
+                    Ok(YNodeStatus::Stopped)
+                } else {
+                    Ok(YNodeStatus::NotStarted)
+                }
+            },
+        }
+    }
+
+    pub fn stop() -> YHResult<()> {
+        let pid = match YNode::read_pid()? {
+            Some(pid) => pid,
+            None => {
+                return Err(YHErrorKind::NotFound.into());
+            },
+        };
+
+        if !YNode::is_locked()? || !YNode::is_alive(pid) {
+            return YNode::cleanup();
+        }
+
+        OpenOptions::new()
+            .write(true)
+            .create(true)
+            .open(YNode::stop_path()?)?;
+
+        let polls = YNODE_STOP_TIMEOUT_SECS * 1000 / YNODE_POLL_MILLIS;
+        for _ in 0..polls {
+            if !YNode::is_alive(pid) || !YNode::is_locked()? {
+                return Ok(());
+            }
+            thread::sleep(Duration::from_millis(YNODE_POLL_MILLIS));
+        }
+
+        Err(YHErrorKind::Other(format!("node {} did not stop", pid)).into())
     }
 }
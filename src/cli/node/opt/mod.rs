@@ -3,7 +3,6 @@
 pub enum YNodeOpt {
     #[structopt(name="start", about="Start the Yobicash node server", version="0.1.0", author="Christian Nyumbayire <christian@yobicash.org>")]
     Start {
-        #[structopt(short="v", long="verbose", help="Activate verbose mode")]
         #[structopt(long="light", help="Use light store")]
         light: bool,
         #[structopt(long="temporary", help="Use temporary store")]
@@ -22,5 +21,89 @@ pub enum YNodeOpt {
     Stop {
         #[structopt(short="v", long="verbose", help="Activate verbose mode")]
         verbose: bool,
-    }
+    },
+    #[structopt(name="check", about="Check the integrity of the Yobicash node store", version="0.1.0", author="Christian Nyumbayire <christian@yobicash.org>")]
+    Check {
+        #[structopt(long="repair", help="Repair the inconsistencies found")]
+        repair: bool,
+        #[structopt(short="v", long="verbose", help="Activate verbose mode")]
+        verbose: bool,
+    },
+    #[structopt(name="backup", about="Back up the Yobicash node store to a snapshot file", version="0.1.0", author="Christian Nyumbayire <christian@yobicash.org>")]
+    Backup {
+        #[structopt(help="Snapshot file path")]
+        path: String,
+        #[structopt(short="v", long="verbose", help="Activate verbose mode")]
+        verbose: bool,
+    },
+    #[structopt(name="reindex", about="Rebuild the Yobicash node store indexes", version="0.1.0", author="Christian Nyumbayire <christian@yobicash.org>")]
+    Reindex {
+        #[structopt(short="v", long="verbose", help="Activate verbose mode")]
+        verbose: bool,
+    },
+    #[structopt(name="restore", about="Restore the Yobicash node store from a snapshot file", version="0.1.0", author="Christian Nyumbayire <christian@yobicash.org>")]
+    Restore {
+        #[structopt(help="Snapshot file path")]
+        path: String,
+        #[structopt(short="v", long="verbose", help="Activate verbose mode")]
+        verbose: bool,
+    },
+    #[structopt(name="prune", about="Prune the Yobicash node store down to a light store", version="0.1.0", author="Christian Nyumbayire <christian@yobicash.org>")]
+    Prune {
+        #[structopt(short="v", long="verbose", help="Activate verbose mode")]
+        verbose: bool,
+    },
+    #[structopt(name="change-password", about="Change the Yobicash node password and re-encrypt the wallets and the store", version="0.1.0", author="Christian Nyumbayire <christian@yobicash.org>")]
+    ChangePassword {
+        #[structopt(short="v", long="verbose", help="Activate verbose mode")]
+        verbose: bool,
+    },
+    #[structopt(name="mnemonic", about="Show the seed phrase of a Yobicash node wallet", version="0.1.0", author="Christian Nyumbayire <christian@yobicash.org>")]
+    Mnemonic {
+        #[structopt(help="Wallet name")]
+        wallet: String,
+        #[structopt(short="v", long="verbose", help="Activate verbose mode")]
+        verbose: bool,
+    },
+    #[structopt(name="export", about="Export Yobicash node resources", version="0.1.0", author="Christian Nyumbayire <christian@yobicash.org>")]
+    Export {
+        #[structopt(short="v", long="verbose", help="Activate verbose mode")]
+        verbose: bool,
+        #[structopt(subcommand)]
+        cmd: ExportCommands,
+    },
+    #[structopt(name="import", about="Import Yobicash node resources", version="0.1.0", author="Christian Nyumbayire <christian@yobicash.org>")]
+    Import {
+        #[structopt(short="v", long="verbose", help="Activate verbose mode")]
+        verbose: bool,
+        #[structopt(subcommand)]
+        cmd: ImportCommands,
+    },
+    #[structopt(name="restore-wallet", about="Restore a Yobicash node wallet from its seed phrase", version="0.1.0", author="Christian Nyumbayire <christian@yobicash.org>")]
+    RestoreWallet {
+        #[structopt(help="Wallet name")]
+        wallet: String,
+        #[structopt(short="v", long="verbose", help="Activate verbose mode")]
+        verbose: bool,
+    },
+}
+
+#[derive(StructOpt, Debug)]
+pub enum ExportCommands {
+    #[structopt(name="wallet", about="Export a Yobicash node wallet to a file encrypted with a passphrase", version="0.1.0", author="Christian Nyumbayire <christian@yobicash.org>")]
+    Wallet {
+        #[structopt(help="Wallet name")]
+        wallet: String,
+        #[structopt(short="o", long="out", help="Export file path")]
+        out: String,
+    },
+}
+
+#[derive(StructOpt, Debug)]
+pub enum ImportCommands {
+    #[structopt(name="wallet", about="Import a Yobicash node wallet from a file encrypted with a passphrase", version="0.1.0", author="Christian Nyumbayire <christian@yobicash.org>")]
+    Wallet {
+        #[structopt(short="i", long="in", help="Export file path")]
+        input: String,
+    },
 }
@@ -0,0 +1,45 @@
+use libc;
+use std::env;
+use std::mem;
+use std::io::{self, Write};
+use errors::*;
+
+pub struct YPrompt;
+
+impl YPrompt {
+    fn set_echo(term: &libc::termios, echo: bool) -> YHResult<()> {
+        let mut term = *term;
+        if echo {
+            term.c_lflag |= libc::ECHO;
+        } else {
+            term.c_lflag &= !libc::ECHO;
+        }
+        if unsafe { libc::tcsetattr(libc::STDIN_FILENO, libc::TCSANOW, &term) } != 0 {
+            return Err(io::Error::last_os_error().into());
+        }
+        Ok(())
+    }
+
+    // secrets are taken from the environment or typed with the terminal echo off
+    pub fn read_secret(var: &str, prompt: &str) -> YHResult<String> {
+        if let Ok(secret) = env::var(var) {
+            return Ok(secret);
+        }
+        print!("{}", prompt);
+        io::stdout().flush()?;
+        let mut term: libc::termios = unsafe { mem::zeroed() };
+        let tty = unsafe { libc::isatty(libc::STDIN_FILENO) == 1 &&
+                           libc::tcgetattr(libc::STDIN_FILENO, &mut term) == 0 };
+        if tty {
+            YPrompt::set_echo(&term, false)?;
+        }
+        let mut secret = String::new();
+        let res = io::stdin().read_line(&mut secret);
+        if tty {
+            YPrompt::set_echo(&term, true)?;
+            println!();
+        }
+        res?;
+        Ok(secret.trim_right_matches(|c| c == '\n' || c == '\r').to_string())
+    }
+}
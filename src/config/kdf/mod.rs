@@ -0,0 +1,105 @@
+use libyobicash::utils::random::YRandom;
+use libyobicash::crypto::hash::sha::YSHA256;
+use libyobicash::crypto::key::YKey32;
+use scrypt::{scrypt, ScryptParams};
+use utils::hex::*;
+use errors::*;
+
+pub const YKDF_SALT_SIZE: u32 = 32;
+
+pub const YKDF_OUTPUT_SIZE: usize = 32;
+
+#[derive(Clone, Eq, PartialEq, Debug, Deserialize, Serialize)]
+pub struct YKdfParams {
+    pub salt: String,
+    pub log_n: u8,
+    pub r: u32,
+    pub p: u32,
+}
+
+impl Default for YKdfParams {
+    fn default() -> YKdfParams {
+        YKdfParams {
+            salt: YKdfParams::gen_salt(),
+            log_n: YKdfParams::default_log_n(),
+            r: YKdfParams::default_r(),
+            p: YKdfParams::default_p(),
+        }
+    }
+}
+
+impl YKdfParams {
+    pub fn new(log_n: u8, r: u32, p: u32) -> YHResult<YKdfParams> {
+        let params = YKdfParams {
+            salt: YKdfParams::gen_salt(),
+            log_n: log_n,
+            r: r,
+            p: p,
+        };
+        params.check()?;
+        Ok(params)
+    }
+
+    pub fn gen_salt() -> String {
+        to_hex(&YRandom::bytes(YKDF_SALT_SIZE))
+    }
+
+    pub fn default_log_n() -> u8 {
+        14
+    }
+
+    pub fn default_r() -> u32 {
+        8
+    }
+
+    pub fn default_p() -> u32 {
+        1
+    }
+
+    pub fn check(&self) -> YHResult<()> {
+        if from_hex(&self.salt)?.len() != YKDF_SALT_SIZE as usize {
+            return Err(YHErrorKind::InvalidLength.into());
+        }
+        self.scrypt_params()?;
+        Ok(())
+    }
+
+    fn scrypt_params(&self) -> YHResult<ScryptParams> {
+        ScryptParams::new(self.log_n, self.r, self.p)
+            .map_err(|_| YHErrorKind::InvalidValue.into())
+    }
+
+    pub fn derive_key(&self, pswd: &str) -> YHResult<YKey32> {
+        self.check()?;
+        let salt = from_hex(&self.salt)?;
+        let mut output = [0u8; YKDF_OUTPUT_SIZE];
+        scrypt(pswd.as_bytes(), &salt, &self.scrypt_params()?, &mut output)
+            .map_err(|_| YHErrorKind::InvalidLength)?;
+        Ok(YKey32(YSHA256::hash(&output).0))
+    }
+
+    pub fn verifier(key: YKey32) -> String {
+        let mut seed = b"verifier".to_vec();
+        seed.extend(key.0.iter().cloned());
+        to_hex(&YSHA256::hash(&seed).0)
+    }
+
+    pub fn verify(&self, pswd: &str, verifier: &str) -> YHResult<YKey32> {
+        let key = self.derive_key(pswd)?;
+        let expected = from_hex(verifier)?;
+        let found = from_hex(&YKdfParams::verifier(key))?;
+        if !constant_time_eq(&expected, &found) {
+            return Err(YHErrorKind::InvalidPassword.into());
+        }
+        Ok(key)
+    }
+}
+
+pub fn constant_time_eq(a: &[u8], b: &[u8]) -> bool {
+    if a.len() != b.len() {
+        return false;
+    }
+    a.iter()
+        .zip(b.iter())
+        .fold(0u8, |acc, (x, y)| acc | (x ^ y)) == 0
+}
@@ -1,8 +1,10 @@
 use libyobicash::utils::random::YRandom;
 use libyobicash::crypto::hash::sha::YSHA512;
+use libyobicash::crypto::elliptic::keys::YSecretKey;
+use libyobicash::crypto::key::YKey32;
 use serde_json;
 use std::env::home_dir;
-use std::fs::{create_dir_all, OpenOptions};
+use std::fs::{create_dir_all, rename, remove_file, OpenOptions};
 use std::path::{Path, PathBuf};
 use std::io::BufReader;
 use std::io::prelude::*;
@@ -10,6 +12,10 @@ use std::convert::AsRef;
 use network::host::YHost;
 use errors::*;
 
+pub mod kdf;
+
+pub use self::kdf::*;
+
 pub struct YConfigDir;
 
 impl YConfigDir {
@@ -74,37 +80,74 @@ impl YConfigDir {
 
 #[derive(Clone, Eq, PartialEq, Debug, Deserialize, Serialize)]
 pub struct YConfig {
+    #[serde(skip)]
     pub password: String,
+    #[serde(default, rename="password", skip_serializing_if="Option::is_none")]
+    pub legacy_password: Option<String>,
+    #[serde(default)]
+    pub kdf: YKdfParams,
+    #[serde(default)]
+    pub verifier: String,
+    #[serde(default, rename="secret_key", skip_serializing)]
+    pub legacy_secret_key: Option<YSecretKey>,
+    #[serde(default="YConfig::default_wallet")]
+    pub wallet: String,
     pub light_mode: bool,
+    #[serde(default="YConfig::default_data_retention")]
+    pub data_retention: u64,
     pub seed: Vec<YHost>,
     pub host: YHost,
+    #[serde(default="YConfig::default_control")]
+    pub control: YHost,
     pub max_conns: u16,
     pub price: u64,
 }
 
 impl Default for YConfig {
     fn default() -> YConfig {
-        YConfig {
-            password: YConfig::gen_pswd(),
+        YConfig::with_kdf(YKdfParams::default()).unwrap()
+    }
+}
+
+impl YConfig {
+    pub fn with_kdf(kdf: YKdfParams) -> YHResult<YConfig> {
+        let password = YConfig::gen_pswd();
+        let verifier = YKdfParams::verifier(kdf.derive_key(&password)?);
+        Ok(YConfig {
+            password: password,
+            legacy_password: None,
+            kdf: kdf,
+            verifier: verifier,
+            legacy_secret_key: None,
+            wallet: YConfig::default_wallet(),
             light_mode: YConfig::default_light_mode(),
+            data_retention: YConfig::default_data_retention(),
             seed: YConfig::default_seed(),
             host: YConfig::default_host(),
+            control: YConfig::default_control(),
             max_conns: YConfig::default_max_conns(),
             price: YConfig::default_price(),
-        }
+        })
     }
-}
 
-impl YConfig {
-    pub fn new(pswd: &str, light_mode: bool, seed: &Vec<YHost>, host: YHost, max_conns: u16, price: u64) -> YHResult<YConfig> {
+    pub fn new(pswd: &str, wallet: &str, light_mode: bool, seed: &Vec<YHost>, host: YHost, control: YHost, max_conns: u16, price: u64) -> YHResult<YConfig> {
         if pswd.len() < 16 {
             return Err(YHErrorKind::InvalidLength.into());
         }
+        let kdf = YKdfParams::default();
+        let verifier = YKdfParams::verifier(kdf.derive_key(pswd)?);
         Ok(YConfig {
             password: String::from(pswd),
+            legacy_password: None,
+            kdf: kdf,
+            verifier: verifier,
+            legacy_secret_key: None,
+            wallet: String::from(wallet),
             light_mode: light_mode,
+            data_retention: YConfig::default_data_retention(),
             seed: seed.clone(),
             host: host,
+            control: control,
             max_conns: max_conns,
             price: price,
         })
@@ -114,10 +157,22 @@ impl YConfig {
         YSHA512::hash(YRandom::bytes(32).as_slice()).to_hex()
     }
 
+    pub fn master_key(&self) -> YHResult<YKey32> {
+        self.kdf.verify(&self.password, &self.verifier)
+    }
+
+    pub fn default_wallet() -> String {
+        String::from("node")
+    }
+
     pub fn default_light_mode() -> bool {
         false
     }
 
+    pub fn default_data_retention() -> u64 {
+        30 * 24 * 60 * 60
+    }
+
     pub fn default_seed() -> Vec<YHost> {
         vec![YConfig::default_host()]
     }
@@ -126,6 +181,10 @@ impl YConfig {
         YHost::default()
     }
 
+    pub fn default_control() -> YHost {
+        YHost::new([127, 0, 0, 1], 2113)
+    }
+
     pub fn default_max_conns() -> u16 {
         8
     }
@@ -173,19 +232,58 @@ impl YConfig {
         let mut file = OpenOptions::new()
             .write(true)
             .create(true)
-            .truncate(false)
+            .truncate(true)
             .open(YConfig::path()?)?;
         file.write_all(self.to_json()?.as_bytes())?;
         Ok(())
     }
 
+    pub fn tmp_path() -> YHResult<String> {
+        Ok(format!("{}.tmp", YConfig::path()?))
+    }
+
+    // a config staged with write_tmp only replaces config.json on commit_tmp
+    pub fn write_tmp(&self) -> YHResult<()> {
+        let mut file = OpenOptions::new()
+            .write(true)
+            .create(true)
+            .truncate(true)
+            .open(YConfig::tmp_path()?)?;
+        file.write_all(self.to_json()?.as_bytes())?;
+        file.sync_all()?;
+        Ok(())
+    }
+
+    pub fn commit_tmp() -> YHResult<()> {
+        rename(YConfig::tmp_path()?, YConfig::path()?)?;
+        Ok(())
+    }
+
+    pub fn discard_tmp() -> YHResult<()> {
+        remove_file(YConfig::tmp_path()?)?;
+        Ok(())
+    }
+
     pub fn create(&self) -> YHResult<()> {
         YConfigDir::create_home()?;
         self.write()
     }
 
-    pub fn create_default() -> YHResult<()> {
-        let config = YConfig::default();
+    pub fn create_default(pswd: &str) -> YHResult<()> {
+        let mut config = YConfig::default();
+        config.set_password(pswd)?;
         config.create()
     }
+
+    pub fn set_password(&mut self, pswd: &str) -> YHResult<()> {
+        if pswd.len() < 16 {
+            return Err(YHErrorKind::InvalidLength.into());
+        }
+        let kdf = YKdfParams::default();
+        self.verifier = YKdfParams::verifier(kdf.derive_key(pswd)?);
+        self.kdf = kdf;
+        self.password = String::from(pswd);
+        self.legacy_password = None;
+        Ok(())
+    }
 }
@@ -102,6 +102,10 @@ error_chain! {
         description("Invalid ip")
     }
 
+    Unauthorized {
+        description("Unauthorized")
+    }
+
     MaxConnectionsReached {
         description("Max connections reached")
     }
@@ -114,6 +118,42 @@ error_chain! {
         description("Not connected")
     }
 
+    NoTransaction {
+        description("No transaction")
+    }
+
+    TransactionAborted {
+        description("Transaction aborted")
+    }
+
+    InvalidChecksum {
+        description("Invalid checksum")
+    }
+
+    InvalidSchemaVersion {
+        description("Invalid schema version")
+    }
+
+    UnencryptedStore {
+        description("Unencrypted store")
+    }
+
+    TooManyInputs {
+        description("Too many inputs")
+    }
+
+    FeeTooHigh {
+        description("Fee too high")
+    }
+
+    NotHDWallet {
+        description("Not an hd wallet")
+    }
+
+    Pruned {
+        description("Pruned")
+    }
+
     Other(desc: String) {
         description(desc.as_str())
     }
@@ -15,8 +15,11 @@ extern crate serde;
 #[macro_use]
 extern crate serde_derive;
 extern crate serde_json;
+extern crate scrypt;
+extern crate libc;
 
 pub mod errors;
+pub mod utils;
 pub mod version;
 pub mod store;
 pub mod models;
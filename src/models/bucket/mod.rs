@@ -14,6 +14,13 @@ pub enum YBucket {
     PeersByIp=5,
     PeersByLastTime=6,
     Keys=7,
+    Meta=8,
+    TxsByWallet=9,
+    CbsByWallet=10,
+    DataByWallet=11,
+    UTXOsByRecipient=12,
+    SpenderByUTXO=13,
+    Reservations=14,
     Unknown,
 }
 
@@ -34,12 +41,39 @@ impl From<u32> for YBucket {
             5 => YBucket::PeersByIp,
             6 => YBucket::PeersByLastTime,
             7 => YBucket::Keys,
+            8 => YBucket::Meta,
+            9 => YBucket::TxsByWallet,
+            10 => YBucket::CbsByWallet,
+            11 => YBucket::DataByWallet,
+            12 => YBucket::UTXOsByRecipient,
+            13 => YBucket::SpenderByUTXO,
+            14 => YBucket::Reservations,
             _ => YBucket::Unknown,
         }
     }
 }
 
 impl YBucket {
+    pub fn all() -> Vec<YBucket> {
+        vec![
+            YBucket::Transactions,
+            YBucket::Coinbases,
+            YBucket::Data,
+            YBucket::UTXO,
+            YBucket::Wallets,
+            YBucket::PeersByIp,
+            YBucket::PeersByLastTime,
+            YBucket::Keys,
+            YBucket::Meta,
+            YBucket::TxsByWallet,
+            YBucket::CbsByWallet,
+            YBucket::DataByWallet,
+            YBucket::UTXOsByRecipient,
+            YBucket::SpenderByUTXO,
+            YBucket::Reservations,
+        ]
+    }
+
     pub fn to_bytes(&self) -> Vec<u8> {
         let mut buf = BytesMut::new();
         buf.put_u32::<BigEndian>(*self as u32);
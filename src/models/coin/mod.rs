@@ -1,7 +1,7 @@
 use libyobicash::errors::YErrorKind as LibErrorKind;
 use libyobicash::utils::time::YTime;
 use libyobicash::crypto::hash::digest::YDigest64;
-use libyobicash::crypto::elliptic::keys::YSecretKey;
+use libyobicash::crypto::elliptic::keys::{YSecretKey, YPublicKey};
 use libyobicash::crypto::mac::YMACCode;
 use libyobicash::amount::YAmount;
 use bytes::{BytesMut, BufMut, BigEndian, ByteOrder};
@@ -113,7 +113,9 @@ impl YCoin {
         buf.put_u32::<BigEndian>(self.has_data as u32);
         if let Some(tag) = self.tag {
             buf.put_u32::<BigEndian>(1);
-            buf.put(tag.to_bytes());
+            let tag_buf = tag.to_bytes();
+            buf.put_u32::<BigEndian>(tag_buf.len() as u32);
+            buf.put(tag_buf);
         } else {
             buf.put_u32::<BigEndian>(0);
         }
@@ -141,17 +143,21 @@ impl YCoin {
             return Err(YHErrorKind::InvalidValue.into());
         };
         let has_tag = BigEndian::read_u32(b.get(152..156).unwrap());
+        let mut i = 156;
         let mut tag = None;
         match has_tag {
             0 => {},
             1 => {
-                tag = Some(YMACCode::from_bytes(b.get(156..160).unwrap())?);
+                let tag_size = BigEndian::read_u32(b.get(i..i+4).ok_or(YHErrorKind::InvalidLength)?) as usize;
+                i += 4;
+                tag = Some(YMACCode::from_bytes(b.get(i..i+tag_size).ok_or(YHErrorKind::InvalidLength)?)?);
+                i += tag_size;
             },
             _ => {
                 return Err(YHErrorKind::InvalidValue.into());
             },
         }
-        let amount = YAmount::from_bytes(b.get(160..).unwrap());
+        let amount = YAmount::from_bytes(b.get(i..).unwrap());
         let coin = YCoin {
             date: date,
             sk: sk,
@@ -167,6 +173,10 @@ impl YCoin {
         Ok(coin)
     }
 
+    pub fn to_public(&self) -> YPublicCoin {
+        YPublicCoin::from_coin(self)
+    }
+
     pub fn to_json(&self) -> YHResult<String> {
         let json = serde_json::to_string(self)?;
         Ok(json)
@@ -177,3 +187,123 @@ impl YCoin {
         Ok(coin)
     }
 }
+
+#[derive(Clone, Eq, PartialEq, Debug, Serialize, Deserialize)]
+pub struct YPublicCoin {
+    pub date: YTime,
+    pub pk: YPublicKey,
+    pub kind: YCoinKind,
+    pub id: YDigest64,
+    pub idx: u32,
+    pub height: u32,
+    pub has_data: bool,
+    pub tag: Option<YMACCode>,
+    pub amount: YAmount,
+}
+
+impl YPublicCoin {
+    pub fn from_coin(coin: &YCoin) -> YPublicCoin {
+        YPublicCoin {
+            date: coin.date.clone(),
+            pk: coin.sk.to_public(),
+            kind: coin.kind,
+            id: coin.id,
+            idx: coin.idx,
+            height: coin.height,
+            has_data: coin.has_data,
+            tag: coin.tag,
+            amount: coin.amount.clone(),
+        }
+    }
+
+    pub fn check(&self) -> YHResult<()> {
+        if self.date > YTime::now() {
+            return Err(YHErrorKind::Lib(LibErrorKind::InvalidTime).into());
+        }
+        if self.has_data && self.tag.is_none() {
+            return Err(YHErrorKind::InvalidCoin.into());
+        }
+        Ok(())
+    }
+
+    pub fn to_bytes(&self) -> YHResult<Vec<u8>> {
+        let mut buf = BytesMut::new();
+        buf.put(&self.date.to_bytes()[..]);
+        buf.put(self.pk.to_bytes());
+        buf.put(self.kind.to_bytes());
+        buf.put(self.id.to_bytes());
+        buf.put_u32::<BigEndian>(self.idx);
+        buf.put_u32::<BigEndian>(self.height);
+        buf.put_u32::<BigEndian>(self.has_data as u32);
+        if let Some(tag) = self.tag {
+            buf.put_u32::<BigEndian>(1);
+            let tag_buf = tag.to_bytes();
+            buf.put_u32::<BigEndian>(tag_buf.len() as u32);
+            buf.put(tag_buf);
+        } else {
+            buf.put_u32::<BigEndian>(0);
+        }
+        buf.put(self.amount.to_bytes());
+        Ok(buf.to_vec())
+    }
+
+    pub fn from_bytes(buf: &[u8]) -> YHResult<YPublicCoin> {
+        if buf.len() < 156 {
+            return Err(YHErrorKind::Lib(LibErrorKind::InvalidLength).into());
+        }
+        let mut b = BytesMut::new();
+        b.extend_from_slice(buf);
+        let date = YTime::from_bytes(b.get(0..8).unwrap())?;
+        let pk = YPublicKey::from_bytes(b.get(8..72).unwrap())?;
+        let kind = YCoinKind::from_bytes(b.get(72..76).unwrap())?;
+        let id = YDigest64::from_bytes(b.get(76..140).unwrap())?;
+        let idx = BigEndian::read_u32(b.get(140..144).unwrap());
+        let height = BigEndian::read_u32(b.get(144..148).unwrap());
+        let has_data_n = BigEndian::read_u32(b.get(148..152).unwrap());
+        let mut has_data = false;
+        if has_data_n == 1 {
+            has_data = true;
+        } else if has_data_n != 0 {
+            return Err(YHErrorKind::InvalidValue.into());
+        };
+        let has_tag = BigEndian::read_u32(b.get(152..156).unwrap());
+        let mut i = 156;
+        let mut tag = None;
+        match has_tag {
+            0 => {},
+            1 => {
+                let tag_size = BigEndian::read_u32(b.get(i..i+4).ok_or(YHErrorKind::InvalidLength)?) as usize;
+                i += 4;
+                tag = Some(YMACCode::from_bytes(b.get(i..i+tag_size).ok_or(YHErrorKind::InvalidLength)?)?);
+                i += tag_size;
+            },
+            _ => {
+                return Err(YHErrorKind::InvalidValue.into());
+            },
+        }
+        let amount = YAmount::from_bytes(b.get(i..).unwrap());
+        let coin = YPublicCoin {
+            date: date,
+            pk: pk,
+            kind: kind,
+            id: id,
+            idx: idx,
+            height: height,
+            has_data: has_data,
+            tag: tag,
+            amount: amount,
+        };
+        coin.check()?;
+        Ok(coin)
+    }
+
+    pub fn to_json(&self) -> YHResult<String> {
+        let json = serde_json::to_string(self)?;
+        Ok(json)
+    }
+
+    pub fn from_json(s: &str) -> YHResult<YPublicCoin> {
+        let coin = serde_json::from_str(s)?;
+        Ok(coin)
+    }
+}
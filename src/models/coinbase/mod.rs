@@ -2,6 +2,7 @@ use libyobicash::utils::random::YRandom;
 use libyobicash::crypto::hash::digest::YDigest64;
 use libyobicash::crypto::key::YKey32;
 use libyobicash::crypto::elliptic::keys::*;
+use libyobicash::transaction::YTransaction as LibTransaction;
 use libyobicash::coinbase::YCoinbase as LibCoinbase;
 use libyobicash::utxo::YUTXO as LibUTXO;
 use serde_json;
@@ -12,8 +13,12 @@ use models::transaction::*;
 use models::coin::*;
 use models::utxo::*;
 use models::wallet::*;
+use models::hd::*;
+use models::index::*;
 use errors::*;
 
+pub const YGENESYS_DIFFICULTY: u32 = 3;
+
 #[derive(Clone, Eq, PartialEq, Debug, Default, Serialize, Deserialize)]
 pub struct YCoinbase(pub LibCoinbase);
 
@@ -88,121 +93,174 @@ impl YCoinbase {
         Ok(coinbases)
     }
 
-    pub fn mine<S: YStorage>(store: &mut S, key: YKey32, wallet_name: &str, id: YDigest64, incr: u32, fee_pk: YPublicKey) -> YHResult<(YCoinbase, u32)> {
-        let tx = YTransaction::get(store, id)?;
-        let diff = LibCoinbase::difficulty(tx.internal().outputs[0].height);
-        let (txs, cbs) = tx.list_ancestors(store)?;
-        let txs_len = txs.len();
-        let cbs_len = cbs.len();
-        let txs_chunks_len = min(txs_len as u32, diff);
-        let cbs_chunks_len = min(cbs_len as u32, diff);
-        let txs_indexes = YRandom::u32_sample(0, txs_len as u32, txs_chunks_len as u32);
-        let cbs_indexes = YRandom::u32_sample(0, cbs_len as u32, cbs_chunks_len as u32);
-        let mut chunks = Vec::new();
-
-        for tx_idx in txs_indexes {
-            let buf = txs[tx_idx as usize].internal().to_bytes()?;
-            let buf_len = buf.len();
-            let byte_idx = YRandom::u32_range(0, buf_len as u32);
-            chunks.push(buf[byte_idx as usize]);
-        }
-
-        for cb_idx in cbs_indexes {
-            let buf = cbs[cb_idx as usize].internal().to_bytes()?;
-            let buf_len = buf.len();
-            let byte_idx = YRandom::u32_range(0, buf_len as u32);
-            chunks.push(buf[byte_idx as usize]);
+    pub fn list_from<S: YStorage>(store: &S, after: &Option<YStoreKey>, limit: u32) -> YHResult<(Vec<YCoinbase>, Option<YStoreKey>)> {
+        let store_buck = YBucket::Coinbases.to_store_buck();
+        let page = store.list_from(&store_buck, after, limit)?;
+        let mut coinbases = Vec::new();
+        for key in page.keys {
+            let item = store.get(&store_buck, &key)?;
+            let cb = YCoinbase::from_value(&item.value)?;
+            coinbases.push(cb);
         }
+        Ok((coinbases, page.next))
+    }
 
-        let miner_sk = YSecretKey::random();
-        let recipient_sk = YSecretKey::random();
-        let recipient_pk = recipient_sk.to_public();
+    pub fn mine<S: YStorage>(store: &mut S, key: YKey32, wallet_name: &str, id: YDigest64, incr: u32, fee_pk: YPublicKey) -> YHResult<(YCoinbase, u32)> {
+        atomically(store, |store| {
+            let tx = YTransaction::get(store, id)?;
+            let diff = LibCoinbase::difficulty(tx.internal().outputs[0].height);
+            let (txs, cbs) = tx.list_ancestors(store)?;
+            let txs_len = txs.len();
+            let cbs_len = cbs.len();
+            let txs_chunks_len = min(txs_len as u32, diff);
+            let cbs_chunks_len = min(cbs_len as u32, diff);
+            let txs_indexes = YRandom::u32_sample(0, txs_len as u32, txs_chunks_len as u32);
+            let cbs_indexes = YRandom::u32_sample(0, cbs_len as u32, cbs_chunks_len as u32);
+            let mut chunks = Vec::new();
+
+            for tx_idx in txs_indexes {
+                let buf = txs[tx_idx as usize].internal().to_bytes()?;
+                let buf_len = buf.len();
+                let byte_idx = YRandom::u32_range(0, buf_len as u32);
+                chunks.push(buf[byte_idx as usize]);
+            }
+
+            for cb_idx in cbs_indexes {
+                let buf = cbs[cb_idx as usize].internal().to_bytes()?;
+                let buf_len = buf.len();
+                let byte_idx = YRandom::u32_range(0, buf_len as u32);
+                chunks.push(buf[byte_idx as usize]);
+            }
+
+            let mut wallet = YWallet::get(store, key, wallet_name)?;
+            let miner_sk = wallet.next_key(YHD_RECEIVE_BRANCH)?;
+            let recipient_sk = wallet.next_key(YHD_RECEIVE_BRANCH)?;
+            let recipient_pk = recipient_sk.to_public();
         
-        let (_cb, tries) = LibCoinbase::mine(id, diff,
-                                             &chunks, incr,
-                                             miner_sk, recipient_pk,
-                                             fee_pk)?;
-        let cb = YCoinbase(_cb.clone());
-        cb.create(store)?;
-
-        let date = _cb.time;
-        let kind = YCoinKind::Coinbase;
-        let id = _cb.id;
-        let idx = 0u32;
-        let height = 0u32;
-        let has_data = false;
-        let tag = None;
-        let amount = _cb.outputs[idx as usize].amount.clone();
-        let coin = YCoin::new(date, miner_sk,
-                              kind, id, idx,
-                              height, has_data,
-                              tag, &amount)?;
+            let (_cb, tries) = LibCoinbase::mine(id, diff,
+                                                 &chunks, incr,
+                                                 miner_sk, recipient_pk,
+                                                 fee_pk)?;
+            let cb = YCoinbase(_cb.clone());
+            cb.create(store)?;
+
+            let date = _cb.time;
+            let kind = YCoinKind::Coinbase;
+            let id = _cb.id;
+            let idx = 0u32;
+            let height = 0u32;
+            let has_data = false;
+            let tag = None;
+            let amount = _cb.outputs[idx as usize].amount.clone();
+            let coin = YCoin::new(date, miner_sk,
+                                  kind, id, idx,
+                                  height, has_data,
+                                  tag, &amount)?;
         
-        for idx in 0.._cb.outputs.len() as u32 {
-            let _utxo = LibUTXO::from_output(&_cb.outputs[idx as usize], id, idx as u32);
-            YUTXO::new(&_utxo).create(store)?;
-        }
+            for idx in 0.._cb.outputs.len() as u32 {
+                let _utxo = LibUTXO::from_output(&_cb.outputs[idx as usize], id, idx as u32);
+                YUTXO::new(&_utxo).create(store)?;
+            }
         
-        let mut wallet = YWallet::get(store, key, wallet_name)?;
-        wallet.balance += amount;
-        wallet.ucoins.push(coin);
-        wallet.update(store, key)?;
+            wallet.balance += amount;
+            wallet.ucoins.push(coin);
+            wallet.update(store, key)?;
+            YIndex::put_wallet_cb(store, key, wallet_name, id)?;
 
-        Ok((cb, tries))
+            Ok((cb, tries))
+        })
     }
 
     pub fn mine_genesys<S: YStorage>(store: &mut S,
                                      key: YKey32,
                                      wallet_name: &str,
+                                     diff: u32,
                                      incr: u32, fee_pk: YPublicKey)
             -> YHResult<((YCoinbase, YTransaction), u32)> {
-        let diff = 3;
-
-        let miner_sk = YSecretKey::random();
-        let recipient_sk = YSecretKey::random();
-        let recipient_pk = recipient_sk.to_public();
+        atomically(store, |store| {
+            YCoinbase::check_difficulty(diff)?;
+            let (miner_sk, recipient_pk) = YCoinbase::genesys_keys(store, key, wallet_name)?;
+            let ((_cb, _tx), tries) = YCoinbase::mine(diff, incr, miner_sk, recipient_pk, fee_pk)?;
+            let genesys = YCoinbase::store_genesys(store, key, wallet_name, miner_sk, &_cb, &_tx)?;
+            Ok((genesys, tries))
+        })
+    }
 
-        let chunks = YRandom::bytes(diff);
-        let ((_cb, _tx), tries) = LibCoinbase::mine_genesys(incr, &chunks,
-                                                            miner_sk, recipient_pk,
-                                                            fee_pk)?;
-
-        let tx = YTransaction(_tx.clone());
-        tx.create(store)?;
-
-        let cb = YCoinbase(_cb.clone());
-        cb.create(store)?;
-
-        let date = _cb.time;
-        let kind = YCoinKind::Coinbase;
-        let id = _cb.id;
-        let idx = 0u32;
-        let height = 0u32;
-        let has_data = false;
-        let tag = None;
-        let amount = _cb.outputs[idx as usize].amount.clone();
-        let coin = YCoin::new(date, miner_sk,
-                              kind, id, idx,
-                              height, has_data,
-                              tag, &amount)?;
-        
-        for idx in 0.._cb.outputs.len() as u32 {
-            let _utxo = LibUTXO::from_output(&_cb.outputs[idx as usize], id, idx as u32);
-            YUTXO::new(&_utxo).create(store)?;
+    fn check_difficulty(diff: u32) -> YHResult<()> {
+        if diff < 3 || diff > 63 {
+            return Err(YHErrorKind::InvalidDifficulty.into());
         }
+        Ok(())
+    }
 
-        let mut wallet = YWallet::get(store, key, wallet_name)?;
-        wallet.balance += amount;
-        wallet.ucoins.push(coin);
-        wallet.update(store, key)?;
+    // the keys are taken before mining, so the proof of work can run without the store
+    pub fn genesys_keys<S: YStorage>(store: &mut S, key: YKey32, wallet_name: &str) -> YHResult<(YSecretKey, YPublicKey)> {
+        atomically(store, |store| {
+            let mut wallet = YWallet::get(store, key, wallet_name)?;
+            let miner_sk = wallet.next_key(YHD_RECEIVE_BRANCH)?;
+            let recipient_sk = wallet.next_key(YHD_RECEIVE_BRANCH)?;
+            wallet.update(store, key)?;
+            Ok((miner_sk, recipient_sk.to_public()))
+        })
+    }
 
-        Ok(((cb, tx), tries))
+    pub fn mine(diff: u32, incr: u32, miner_sk: YSecretKey, recipient_pk: YPublicKey, fee_pk: YPublicKey)
+            -> YHResult<((LibCoinbase, LibTransaction), u32)> {
+        YCoinbase::check_difficulty(diff)?;
+        let chunks = YRandom::bytes(diff);
+        let genesys = LibCoinbase::mine_genesys(incr, &chunks,
+                                                miner_sk, recipient_pk,
+                                                fee_pk)?;
+        Ok(genesys)
+    }
+
+    pub fn store_genesys<S: YStorage>(store: &mut S,
+                                      key: YKey32,
+                                      wallet_name: &str,
+                                      miner_sk: YSecretKey,
+                                      _cb: &LibCoinbase,
+                                      _tx: &LibTransaction)
+            -> YHResult<(YCoinbase, YTransaction)> {
+        atomically(store, |store| {
+            let mut wallet = YWallet::get(store, key, wallet_name)?;
+
+            let tx = YTransaction(_tx.clone());
+            tx.create(store)?;
+
+            let cb = YCoinbase(_cb.clone());
+            cb.create(store)?;
+
+            let date = _cb.time.clone();
+            let kind = YCoinKind::Coinbase;
+            let id = _cb.id;
+            let idx = 0u32;
+            let height = 0u32;
+            let has_data = false;
+            let tag = None;
+            let amount = _cb.outputs[idx as usize].amount.clone();
+            let coin = YCoin::new(date, miner_sk,
+                                  kind, id, idx,
+                                  height, has_data,
+                                  tag, &amount)?;
+        
+            for idx in 0.._cb.outputs.len() as u32 {
+                let _utxo = LibUTXO::from_output(&_cb.outputs[idx as usize], id, idx as u32);
+                YUTXO::new(&_utxo).create(store)?;
+            }
+
+            wallet.balance += amount;
+            wallet.ucoins.push(coin);
+            wallet.update(store, key)?;
+            YIndex::put_wallet_cb(store, key, wallet_name, id)?;
+
+            Ok((cb, tx))
+        })
     }
 
     pub fn confirm<S: YStorage>(store: &mut S, key: YKey32, wallet_name: &str, id: YDigest64, incr: u32, fee_pk: YPublicKey) -> YHResult<(bool, Option<(YCoinbase, YTransaction)>)> {
         match YCoinbase::lookup(store, id) {
             Ok(true) => {
-                let (genesys, _) = YCoinbase::mine_genesys(store, key, wallet_name, incr, fee_pk)?;
+                let (genesys, _) = YCoinbase::mine_genesys(store, key, wallet_name, YGENESYS_DIFFICULTY, incr, fee_pk)?;
                 Ok((true, Some(genesys)))
             },
             Ok(false) => {
@@ -212,6 +270,25 @@ impl YCoinbase {
         }
     }
 
+    pub fn push<S: YStorage>(store: &mut S, _cb: &LibCoinbase) -> YHResult<YCoinbase> {
+        atomically(store, |store| {
+            let cb = YCoinbase::new(_cb)?;
+            let id = _cb.id;
+            if YCoinbase::lookup(store, id)? {
+                return Err(YHErrorKind::AlreadyFound.into());
+            }
+
+            for idx in 0.._cb.outputs.len() as u32 {
+                let _utxo = LibUTXO::from_output(&_cb.outputs[idx as usize], id, idx as u32);
+                YUTXO::new(&_utxo).create(store)?;
+            }
+
+            cb.create(store)?;
+
+            Ok(cb)
+        })
+    }
+
     pub fn get<S: YStorage>(store: &S, id: YDigest64) -> YHResult<YCoinbase> {
         let store_buck = YBucket::Coinbases.to_store_buck();
         let key = id.to_bytes();
@@ -85,6 +85,28 @@ impl YData {
         Ok(data)
     }
 
+    pub fn list_from<S: YStorage>(store: &S, after: &Option<YStoreKey>, limit: u32) -> YHResult<(Vec<YData>, Option<YStoreKey>)> {
+        let store_buck = YBucket::Data.to_store_buck();
+        let page = store.list_from(&store_buck, after, limit)?;
+        let mut data = Vec::new();
+        for key in page.keys {
+            let item = store.get(&store_buck, &key)?;
+            let d = YData::from_value(&item.value)?;
+            data.push(d);
+        }
+        Ok((data, page.next))
+    }
+
+    pub fn get_by_checksum<S: YStorage>(store: &S, checksum: YDigest64) -> YHResult<YData> {
+        let count = YData::count(store)?;
+        for data in YData::list(store, 0, count)? {
+            if data.0.checksum == checksum {
+                return Ok(data);
+            }
+        }
+        Err(YHErrorKind::NotFound.into())
+    }
+
     pub fn get<S: YStorage>(store: &S, checksum: YDigest64, tag: YMACCode) -> YHResult<YData> {
         let store_buck = YBucket::Data.to_store_buck();
         let mut key = Vec::new();
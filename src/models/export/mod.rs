@@ -0,0 +1,168 @@
+use libyobicash::crypto::hash::digest::YDigest64;
+use libyobicash::crypto::hash::sha::YSHA512;
+use libyobicash::crypto::encryption::symmetric::YSymmetricEncryption as YSE;
+use bytes::{BytesMut, BufMut, BigEndian, ByteOrder};
+use std::fs::OpenOptions;
+use std::io::prelude::*;
+use config::kdf::*;
+use models::wallet::*;
+use errors::*;
+
+pub const YWALLET_EXPORT_PREFIX: u32 = 0x59574c54;
+
+pub const YWALLET_EXPORT_VERSION: u32 = 1;
+
+// the wallet is encrypted under a key derived from a passphrase chosen at export time,
+// so that the file can be moved to a node with a different password
+#[derive(Clone, Eq, PartialEq, Debug)]
+pub struct YWalletExport {
+    pub version: u32,
+    pub kdf: YKdfParams,
+    pub verifier: String,
+    pub checksum: YDigest64,
+    pub payload: Vec<u8>,
+}
+
+impl YWalletExport {
+    pub fn new(wallet: &YWallet, passphrase: &str) -> YHResult<YWalletExport> {
+        YWalletExport::with_kdf(wallet, passphrase, YKdfParams::default())
+    }
+
+    pub fn with_kdf(wallet: &YWallet, passphrase: &str, kdf: YKdfParams) -> YHResult<YWalletExport> {
+        wallet.check()?;
+        let key = kdf.derive_key(passphrase)?;
+        let wallet_buf = wallet.to_bytes()?;
+        let mut payload = Vec::new();
+        payload.put_u32::<BigEndian>(wallet_buf.len() as u32);
+        payload.put(wallet_buf.as_slice());
+        let padding = (16 - payload.len() % 16) % 16;
+        for _ in 0..padding {
+            payload.push(0);
+        }
+        Ok(YWalletExport {
+            version: YWALLET_EXPORT_VERSION,
+            kdf: kdf,
+            verifier: YKdfParams::verifier(key),
+            checksum: YSHA512::hash(&wallet_buf),
+            payload: YSE::encrypt(key, &payload)?,
+        })
+    }
+
+    pub fn check(&self) -> YHResult<()> {
+        if self.version != YWALLET_EXPORT_VERSION {
+            return Err(YHErrorKind::InvalidValue.into());
+        }
+        self.kdf.check()?;
+        if self.payload.is_empty() || self.payload.len() % 16 != 0 {
+            return Err(YHErrorKind::InvalidLength.into());
+        }
+        Ok(())
+    }
+
+    pub fn wallet(&self, passphrase: &str) -> YHResult<YWallet> {
+        self.check()?;
+        let key = self.kdf.verify(passphrase, &self.verifier)?;
+        let dec = YSE::decrypt(key, &self.payload)?;
+        if dec.len() < 4 {
+            return Err(YHErrorKind::InvalidLength.into());
+        }
+        let wallet_len = BigEndian::read_u32(&dec[0..4]) as usize;
+        let wallet_buf = dec.get(4..4+wallet_len).ok_or(YHErrorKind::InvalidLength)?;
+        if YSHA512::hash(wallet_buf) != self.checksum {
+            return Err(YHErrorKind::InvalidChecksum.into());
+        }
+        let wallet = YWallet::from_bytes(wallet_buf)?;
+        wallet.check()?;
+        Ok(wallet)
+    }
+
+    fn put_field(buf: &mut BytesMut, field: &[u8]) {
+        buf.put_u32::<BigEndian>(field.len() as u32);
+        buf.put(field);
+    }
+
+    fn read_field(buf: &[u8], i: &mut usize) -> YHResult<Vec<u8>> {
+        let len_buf = buf.get(*i..*i+4).ok_or(YHErrorKind::InvalidLength)?;
+        let len = BigEndian::read_u32(len_buf) as usize;
+        *i += 4;
+        let field = buf.get(*i..*i+len).ok_or(YHErrorKind::InvalidLength)?;
+        *i += len;
+        Ok(field.to_vec())
+    }
+
+    pub fn to_bytes(&self) -> YHResult<Vec<u8>> {
+        self.check()?;
+        let mut buf = BytesMut::new();
+        buf.put_u32::<BigEndian>(YWALLET_EXPORT_PREFIX);
+        buf.put_u32::<BigEndian>(self.version);
+        YWalletExport::put_field(&mut buf, self.kdf.salt.as_bytes());
+        buf.put_u32::<BigEndian>(self.kdf.log_n as u32);
+        buf.put_u32::<BigEndian>(self.kdf.r);
+        buf.put_u32::<BigEndian>(self.kdf.p);
+        YWalletExport::put_field(&mut buf, self.verifier.as_bytes());
+        buf.put(self.checksum.to_bytes());
+        YWalletExport::put_field(&mut buf, &self.payload);
+        Ok(buf.to_vec())
+    }
+
+    pub fn from_bytes(buf: &[u8]) -> YHResult<YWalletExport> {
+        if buf.len() < 8 {
+            return Err(YHErrorKind::InvalidLength.into());
+        }
+        if BigEndian::read_u32(&buf[0..4]) != YWALLET_EXPORT_PREFIX {
+            return Err(YHErrorKind::InvalidValue.into());
+        }
+        let version = BigEndian::read_u32(&buf[4..8]);
+        let mut i = 8;
+        let salt = String::from_utf8(YWalletExport::read_field(buf, &mut i)?)?;
+        let params = buf.get(i..i+12).ok_or(YHErrorKind::InvalidLength)?;
+        let log_n = BigEndian::read_u32(&params[0..4]);
+        if log_n > u8::max_value() as u32 {
+            return Err(YHErrorKind::InvalidValue.into());
+        }
+        let r = BigEndian::read_u32(&params[4..8]);
+        let p = BigEndian::read_u32(&params[8..12]);
+        i += 12;
+        let verifier = String::from_utf8(YWalletExport::read_field(buf, &mut i)?)?;
+        let checksum = YDigest64::from_bytes(buf.get(i..i+64).ok_or(YHErrorKind::InvalidLength)?)?;
+        i += 64;
+        let payload = YWalletExport::read_field(buf, &mut i)?;
+        if i != buf.len() {
+            return Err(YHErrorKind::InvalidLength.into());
+        }
+        let export = YWalletExport {
+            version: version,
+            kdf: YKdfParams {
+                salt: salt,
+                log_n: log_n as u8,
+                r: r,
+                p: p,
+            },
+            verifier: verifier,
+            checksum: checksum,
+            payload: payload,
+        };
+        export.check()?;
+        Ok(export)
+    }
+
+    pub fn write(&self, path: &str) -> YHResult<()> {
+        let buf = self.to_bytes()?;
+        let mut file = OpenOptions::new()
+            .write(true)
+            .create_new(true)
+            .open(path)?;
+        file.write_all(&buf)?;
+        file.sync_all()?;
+        Ok(())
+    }
+
+    pub fn read(path: &str) -> YHResult<YWalletExport> {
+        let mut file = OpenOptions::new()
+            .read(true)
+            .open(path)?;
+        let mut buf = Vec::new();
+        file.read_to_end(&mut buf)?;
+        YWalletExport::from_bytes(&buf)
+    }
+}
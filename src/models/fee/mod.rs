@@ -0,0 +1,143 @@
+use libyobicash::crypto::elliptic::keys::YPublicKey;
+use libyobicash::amount::YAmount;
+use libyobicash::transaction::YTransaction as LibTransaction;
+use std::cmp::Ordering;
+use utils::amount::*;
+use errors::*;
+
+#[derive(Clone, Eq, PartialEq, Debug, Default)]
+pub struct YPeerPrice {
+    pub pk: YPublicKey,
+    pub price: YAmount,
+}
+
+impl YPeerPrice {
+    pub fn new(pk: YPublicKey, price: &YAmount) -> YPeerPrice {
+        YPeerPrice {
+            pk: pk,
+            price: price.clone(),
+        }
+    }
+
+    // the node quoting the median price is the one paid, as it sets the estimate
+    pub fn median(prices: &Vec<YPeerPrice>) -> Option<YPeerPrice> {
+        if prices.is_empty() {
+            return None;
+        }
+        let mut prices = prices.clone();
+        prices.sort_by(|a, b| a.price.partial_cmp(&b.price).unwrap_or(Ordering::Equal));
+        Some(prices[prices.len() / 2].clone())
+    }
+}
+
+#[derive(Clone, Eq, PartialEq, Debug, Default)]
+pub struct YFeeEstimator {
+    pub prices: Vec<YAmount>,
+}
+
+impl YFeeEstimator {
+    pub fn new(prices: &Vec<YAmount>) -> YFeeEstimator {
+        YFeeEstimator {
+            prices: prices.clone(),
+        }
+    }
+
+    pub fn add_price(&mut self, price: &YAmount) {
+        self.prices.push(price.clone());
+    }
+
+    // the median keeps a single greedy or broken peer from moving the estimate
+    pub fn price(&self) -> YAmount {
+        if self.prices.is_empty() {
+            return YAmount::zero();
+        }
+        let mut prices = self.prices.clone();
+        prices.sort_by(|a, b| a.partial_cmp(b).unwrap_or(Ordering::Equal));
+        prices[prices.len() / 2].clone()
+    }
+
+    // data payloads are stored by the confirming nodes, so they are paid on top of the transaction bytes
+    pub fn estimate(&self, tx_size: u32, data_size: u32) -> YAmount {
+        let size = tx_size as u64 + data_size as u64;
+        amount_mul(&self.price(), size)
+    }
+
+    pub fn estimate_tx(&self, tx: &LibTransaction) -> YHResult<YAmount> {
+        let tx_size = tx.to_bytes()?.len() as u32;
+        let mut data_size = 0;
+        for output in tx.outputs.iter() {
+            if let Some(ref data) = output.data {
+                data_size += data.to_bytes()?.len() as u32;
+            }
+        }
+        Ok(self.estimate(tx_size, data_size))
+    }
+}
+
+#[derive(Clone, Eq, PartialEq, Debug, Default)]
+pub struct YFee {
+    pub pk: YPublicKey,
+    pub amount: Option<YAmount>,
+    pub max_amount: Option<YAmount>,
+    pub estimator: YFeeEstimator,
+}
+
+impl YFee {
+    pub fn new(pk: YPublicKey, amount: &YAmount) -> YFee {
+        YFee {
+            pk: pk,
+            amount: Some(amount.clone()),
+            max_amount: None,
+            estimator: YFeeEstimator::default(),
+        }
+    }
+
+    pub fn estimated(pk: YPublicKey, estimator: &YFeeEstimator, max_amount: Option<YAmount>) -> YFee {
+        YFee {
+            pk: pk,
+            amount: None,
+            max_amount: max_amount,
+            estimator: estimator.clone(),
+        }
+    }
+
+    pub fn zero() -> YFee {
+        YFee::new(YPublicKey::default(), &YAmount::zero())
+    }
+
+    pub fn amount_for(&self, tx: &LibTransaction) -> YHResult<YAmount> {
+        self.amount_for_all(&vec![tx.clone()])
+    }
+
+    pub fn amount_for_all(&self, txs: &Vec<LibTransaction>) -> YHResult<YAmount> {
+        let amount = match self.amount {
+            Some(ref amount) => amount.clone(),
+            None => {
+                let mut amount = YAmount::zero();
+                for tx in txs {
+                    amount += self.estimator.estimate_tx(tx)?;
+                }
+                amount
+            },
+        };
+        self.capped(amount)
+    }
+
+    // the signed size of an unsigned transaction isn't known yet, so its encoding size is used
+    pub fn amount_for_size(&self, tx_size: u32) -> YHResult<YAmount> {
+        let amount = match self.amount {
+            Some(ref amount) => amount.clone(),
+            None => self.estimator.estimate(tx_size, 0),
+        };
+        self.capped(amount)
+    }
+
+    fn capped(&self, amount: YAmount) -> YHResult<YAmount> {
+        if let Some(ref max_amount) = self.max_amount {
+            if amount > *max_amount {
+                return Err(YHErrorKind::FeeTooHigh.into());
+            }
+        }
+        Ok(amount)
+    }
+}
@@ -0,0 +1,247 @@
+use libyobicash::crypto::key::YKey32;
+use libyobicash::amount::YAmount;
+use serde_json;
+use store::common::*;
+use models::bucket::*;
+use models::data::*;
+use models::utxo::*;
+use models::transaction::*;
+use models::coinbase::*;
+use models::keys::*;
+use models::wallet::*;
+use models::peer::*;
+use models::index::*;
+use models::prune::*;
+use utils::hex::*;
+use errors::*;
+
+#[derive(Copy, Clone, Eq, PartialEq, Debug, Serialize, Deserialize)]
+pub enum YFsckIssueKind {
+    InvalidRecord,
+    DanglingUTXO,
+    MissingCoinUTXO,
+    InvalidBalance,
+    PeerIndexMismatch,
+}
+
+#[derive(Clone, Eq, PartialEq, Debug, Serialize, Deserialize)]
+pub struct YFsckIssue {
+    pub bucket: YBucket,
+    pub key: String,
+    pub kind: YFsckIssueKind,
+    pub description: String,
+    pub repaired: bool,
+}
+
+#[derive(Clone, Eq, PartialEq, Debug, Default, Serialize, Deserialize)]
+pub struct YFsckReport {
+    pub records: u32,
+    pub issues: Vec<YFsckIssue>,
+}
+
+impl YFsckReport {
+    pub fn push(&mut self, bucket: YBucket, key: &[u8], kind: YFsckIssueKind, description: &str, repaired: bool) {
+        self.issues.push(YFsckIssue {
+            bucket: bucket,
+            key: to_hex(key),
+            kind: kind,
+            description: description.to_string(),
+            repaired: repaired,
+        });
+    }
+
+    pub fn unrepaired(&self) -> u32 {
+        self.issues.iter().filter(|issue| !issue.repaired).count() as u32
+    }
+
+    pub fn is_ok(&self) -> bool {
+        self.unrepaired() == 0
+    }
+
+    pub fn to_json(&self) -> YHResult<String> {
+        let json = serde_json::to_string_pretty(self)?;
+        Ok(json)
+    }
+}
+
+pub struct YFsck;
+
+impl YFsck {
+    pub fn run<S: YStorage>(store: &mut S, ekey: YKey32, repair: bool) -> YHResult<YFsckReport> {
+        if repair {
+            atomically(store, |store| YFsck::walk(store, ekey, true))
+        } else {
+            YFsck::walk(store, ekey, false)
+        }
+    }
+
+    fn records<S, T, F>(store: &mut S, report: &mut YFsckReport, bucket: YBucket, repair: bool, decode: F)
+            -> YHResult<Vec<(YStoreKey, T)>>
+        where S: YStorage,
+              F: Fn(&YStoreValue) -> YHResult<T>
+    {
+        let store_buck = bucket.to_store_buck();
+        let keys = store.list(&store_buck, 0, u32::max_value())?;
+        let mut records = Vec::new();
+        for key in keys {
+            report.records += 1;
+            let item = store.get(&store_buck, &key)?;
+            match decode(&item.value) {
+                Ok(record) => records.push((key, record)),
+                Err(err) => {
+                    report.push(bucket, &key, YFsckIssueKind::InvalidRecord,
+                                &format!("{}", err), repair);
+                    if repair {
+                        store.delete(&store_buck, &key)?;
+                    }
+                },
+            }
+        }
+        Ok(records)
+    }
+
+    fn walk<S: YStorage>(store: &mut S, ekey: YKey32, repair: bool) -> YHResult<YFsckReport> {
+        let mut report = YFsckReport::default();
+
+        YFsck::records(store, &mut report, YBucket::Transactions, repair, |value| {
+            let tx = YTransaction::from_value(value)?;
+            tx.check()?;
+            Ok(tx)
+        })?;
+
+        YFsck::records(store, &mut report, YBucket::Coinbases, repair, |value| {
+            let cb = YCoinbase::from_value(value)?;
+            cb.check()?;
+            Ok(cb)
+        })?;
+
+        YFsck::records(store, &mut report, YBucket::Data, repair, |value| {
+            let data = YData::from_value(value)?;
+            data.check()?;
+            Ok(data)
+        })?;
+
+        // keys hold the coins secret keys, deleting them could destroy funds
+        YFsck::records(store, &mut report, YBucket::Keys, false, |value| {
+            let keys = YKeys::from_value(value)?;
+            keys.check()?;
+            Ok(keys)
+        })?;
+
+        let utxos = YFsck::records(store, &mut report, YBucket::UTXO, repair, |value| {
+            YUTXO::from_value(value)
+        })?;
+        YFsck::check_utxos(store, &mut report, &utxos, repair)?;
+
+        // a wallet that fails to decode may just be under a different key, so it is never deleted either
+        let wallets = YFsck::records(store, &mut report, YBucket::Wallets, false, |value| {
+            let wallet = YWallet::dec_value(ekey, value)?;
+            wallet.check()?;
+            Ok(wallet)
+        })?;
+        YFsck::check_wallets(store, &mut report, ekey, &wallets, repair)?;
+
+        let peers = YFsck::records(store, &mut report, YBucket::PeersByIp, repair, |value| {
+            let peer = YPeer::from_value(value)?;
+            peer.check()?;
+            Ok(peer)
+        })?;
+        let peer_times = YFsck::records(store, &mut report, YBucket::PeersByLastTime, repair, |value| {
+            if value.len() != 4 {
+                return Err(YHErrorKind::InvalidLength.into());
+            }
+            Ok(value.clone())
+        })?;
+        YFsck::check_peers(store, &mut report, &peers, &peer_times, repair)?;
+
+        Ok(report)
+    }
+
+    fn check_utxos<S: YStorage>(store: &mut S, report: &mut YFsckReport, utxos: &Vec<(YStoreKey, YUTXO)>, repair: bool) -> YHResult<()> {
+        // light stores keep the utxos of pruned transactions
+        if YPrune::mode(store)? == YStorageMode::Light {
+            return Ok(());
+        }
+        for &(ref key, ref utxo) in utxos.iter() {
+            let id = utxo.0.id;
+            if YTransaction::lookup(store, id)? || YCoinbase::lookup(store, id)? {
+                continue;
+            }
+            report.push(YBucket::UTXO, key, YFsckIssueKind::DanglingUTXO,
+                        "utxo references a missing transaction or coinbase", repair);
+            if repair {
+                utxo.delete(store)?;
+            }
+        }
+        Ok(())
+    }
+
+    fn check_wallets<S: YStorage>(store: &mut S, report: &mut YFsckReport, ekey: YKey32, wallets: &Vec<(YStoreKey, YWallet)>, repair: bool) -> YHResult<()> {
+        for &(ref key, ref wallet) in wallets.iter() {
+            for coin in wallet.ucoins.iter() {
+                if !YUTXO::lookup(store, coin.id, coin.idx)? {
+                    report.push(YBucket::Wallets, key, YFsckIssueKind::MissingCoinUTXO,
+                                &format!("unspent coin {}:{} has no utxo", to_hex(&coin.id.to_bytes()), coin.idx), false);
+                }
+            }
+
+            for coin in wallet.scoins.iter() {
+                if !YUTXO::lookup(store, coin.id, coin.idx)? &&
+                    YIndex::get_spender(store, coin.id, coin.idx)?.is_none() {
+                    report.push(YBucket::Wallets, key, YFsckIssueKind::MissingCoinUTXO,
+                                &format!("spent coin {}:{} has no utxo or spending input", to_hex(&coin.id.to_bytes()), coin.idx), false);
+                }
+            }
+
+            let mut balance = YAmount::zero();
+            for coin in wallet.ucoins.iter() {
+                balance += coin.amount.clone();
+            }
+            if balance != wallet.balance {
+                report.push(YBucket::Wallets, key, YFsckIssueKind::InvalidBalance,
+                            "wallet balance differs from the sum of its unspent coins", repair);
+                if repair {
+                    let mut wallet = wallet.clone();
+                    wallet.balance = balance;
+                    wallet.update(store, ekey)?;
+                }
+            }
+        }
+        Ok(())
+    }
+
+    fn check_peers<S: YStorage>(store: &mut S, report: &mut YFsckReport, peers: &Vec<(YStoreKey, YPeer)>, peer_times: &Vec<(YStoreKey, YStoreValue)>, repair: bool) -> YHResult<()> {
+        let lt_buck = YBucket::PeersByLastTime.to_store_buck();
+
+        for &(ref lt_key, ref ip_key) in peer_times.iter() {
+            if peers.iter().any(|&(ref key, _)| key == ip_key) {
+                continue;
+            }
+            report.push(YBucket::PeersByLastTime, lt_key, YFsckIssueKind::PeerIndexMismatch,
+                        "last time entry references a missing peer", repair);
+            if repair {
+                store.delete(&lt_buck, lt_key)?;
+            }
+        }
+
+        for &(ref ip_key, ref peer) in peers.iter() {
+            let last_time = peer.last_time.to_bytes();
+            let entries: Vec<&YStoreKey> = peer_times.iter()
+                .filter(|&&(_, ref value)| value == ip_key)
+                .map(|&(ref lt_key, _)| lt_key)
+                .collect();
+            if entries.len() == 1 && entries[0].starts_with(&last_time) {
+                continue;
+            }
+            report.push(YBucket::PeersByIp, ip_key, YFsckIssueKind::PeerIndexMismatch,
+                        "peer has no matching last time entry", repair);
+            if repair {
+                for lt_key in entries {
+                    store.delete(&lt_buck, lt_key)?;
+                }
+                store.put(&lt_buck, &peer.by_last_time_key()?, ip_key)?;
+            }
+        }
+        Ok(())
+    }
+}
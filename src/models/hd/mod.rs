@@ -0,0 +1,217 @@
+use libyobicash::utils::random::YRandom;
+use libyobicash::crypto::hash::sha::{YSHA256, YSHA512};
+use libyobicash::crypto::key::YKey32;
+use libyobicash::crypto::elliptic::scalar::YScalar;
+use libyobicash::crypto::elliptic::point::YPoint;
+use libyobicash::crypto::elliptic::keys::YSecretKey;
+use bytes::{BufMut, BigEndian};
+use store::common::*;
+use models::wallet::*;
+use models::keys::*;
+use models::index::*;
+use errors::*;
+
+pub const YHD_ENTROPY_SIZE: u32 = 32;
+
+pub const YHD_RECEIVE_BRANCH: u32 = 0;
+
+pub const YHD_CHANGE_BRANCH: u32 = 1;
+
+pub const YHD_GAP_LIMIT: u32 = 20;
+
+const YHD_HARDENED: u32 = 0x8000_0000;
+
+const YMNEMONIC_WORDS: &str = include_str!("english.txt");
+
+pub struct YMnemonic;
+
+impl YMnemonic {
+    pub fn words() -> Vec<&'static str> {
+        YMNEMONIC_WORDS.lines().collect()
+    }
+
+    pub fn gen_entropy() -> Vec<u8> {
+        YRandom::bytes(YHD_ENTROPY_SIZE)
+    }
+
+    pub fn from_entropy(entropy: &[u8]) -> YHResult<String> {
+        if entropy.len() < 16 || entropy.len() > 32 || entropy.len() % 4 != 0 {
+            return Err(YHErrorKind::InvalidLength.into());
+        }
+        let checksum = YSHA256::hash(entropy).0;
+        let mut bits = Vec::new();
+        for byte in entropy.iter() {
+            for i in (0..8).rev() {
+                bits.push((byte >> i) & 1 == 1);
+            }
+        }
+        for i in 0..entropy.len() / 4 {
+            bits.push((checksum[i / 8] >> (7 - i % 8)) & 1 == 1);
+        }
+        let words = YMnemonic::words();
+        let phrase: Vec<&str> = bits.chunks(11)
+            .map(|chunk| words[chunk.iter().fold(0, |acc, &bit| (acc << 1) | bit as usize)])
+            .collect();
+        Ok(phrase.join(" "))
+    }
+
+    pub fn to_entropy(phrase: &str) -> YHResult<Vec<u8>> {
+        let phrase: Vec<&str> = phrase.split_whitespace().collect();
+        if phrase.len() < 12 || phrase.len() > 24 || phrase.len() % 3 != 0 {
+            return Err(YHErrorKind::InvalidLength.into());
+        }
+        let words = YMnemonic::words();
+        let mut bits = Vec::new();
+        for word in phrase.iter() {
+            let idx = words.iter()
+                .position(|w| w == word)
+                .ok_or(YHErrorKind::InvalidValue)?;
+            for i in (0..11).rev() {
+                bits.push((idx >> i) & 1 == 1);
+            }
+        }
+        let entropy_bits = bits.len() - bits.len() / 33;
+        let entropy: Vec<u8> = bits[..entropy_bits].chunks(8)
+            .map(|chunk| chunk.iter().fold(0u8, |acc, &bit| (acc << 1) | bit as u8))
+            .collect();
+        if YMnemonic::from_entropy(&entropy)? != phrase.join(" ") {
+            return Err(YHErrorKind::InvalidChecksum.into());
+        }
+        Ok(entropy)
+    }
+
+    // bip39 seed: pbkdf2 with hmac-sha512, 2048 rounds and an empty passphrase
+    pub fn to_seed(phrase: &str) -> YHResult<Vec<u8>> {
+        YMnemonic::to_entropy(phrase)?;
+        let phrase: Vec<&str> = phrase.split_whitespace().collect();
+        let pswd = phrase.join(" ");
+        let mut salt = b"mnemonic".to_vec();
+        salt.put_u32::<BigEndian>(1);
+        let mut u = hmac_sha512(pswd.as_bytes(), &salt);
+        let mut seed = u.clone();
+        for _ in 1..2048 {
+            u = hmac_sha512(pswd.as_bytes(), &u);
+            for (s, b) in seed.iter_mut().zip(u.iter()) {
+                *s ^= *b;
+            }
+        }
+        Ok(seed)
+    }
+}
+
+#[derive(Clone, Eq, PartialEq, Debug)]
+pub struct YHDKey {
+    pub key: Vec<u8>,
+    pub chain: Vec<u8>,
+}
+
+impl YHDKey {
+    pub fn master(seed: &[u8]) -> YHDKey {
+        YHDKey::from_hmac(hmac_sha512(b"yobicash seed", seed))
+    }
+
+    pub fn from_mnemonic(phrase: &str) -> YHResult<YHDKey> {
+        let seed = YMnemonic::to_seed(phrase)?;
+        Ok(YHDKey::master(&seed))
+    }
+
+    fn from_hmac(buf: Vec<u8>) -> YHDKey {
+        YHDKey {
+            key: buf[..32].to_vec(),
+            chain: buf[32..].to_vec(),
+        }
+    }
+
+    // only hardened children: public derivation isn't needed and would leak the parent key
+    pub fn child(&self, idx: u32) -> YHDKey {
+        let mut msg = vec![0];
+        msg.put(self.key.as_slice());
+        msg.put_u32::<BigEndian>(idx | YHD_HARDENED);
+        YHDKey::from_hmac(hmac_sha512(&self.chain, &msg))
+    }
+
+    pub fn derive(&self, path: &[u32]) -> YHDKey {
+        path.iter().fold(self.clone(), |key, idx| key.child(*idx))
+    }
+
+    // the key bytes are reduced modulo the group order by the library scalar
+    pub fn secret_key(&self) -> YHResult<YSecretKey> {
+        let scalar = YScalar::from_bytes(&self.key)?;
+        Ok(YSecretKey::new(YPoint::default(), scalar))
+    }
+}
+
+fn hmac_sha512(key: &[u8], msg: &[u8]) -> Vec<u8> {
+    let mut key = key.to_vec();
+    if key.len() > 128 {
+        key = YSHA512::hash(&key).to_bytes();
+    }
+    key.resize(128, 0);
+    let mut inner: Vec<u8> = key.iter().map(|b| b ^ 0x36).collect();
+    inner.put(msg);
+    let mut outer: Vec<u8> = key.iter().map(|b| b ^ 0x5c).collect();
+    outer.put(YSHA512::hash(&inner).to_bytes());
+    YSHA512::hash(&outer).to_bytes()
+}
+
+pub struct YHD;
+
+impl YHD {
+    pub fn restore<S: YStorage>(store: &mut S, ekey: YKey32, name: &str, phrase: &str) -> YHResult<YWallet> {
+        atomically(store, |store| {
+            let mut wallet = YWallet::from_mnemonic(name, phrase)?;
+            wallet.create(store, ekey)?;
+            YHD::rescan(store, ekey, &mut wallet)?;
+            Ok(wallet)
+        })
+    }
+
+    pub fn rescan<S: YStorage>(store: &mut S, ekey: YKey32, wallet: &mut YWallet) -> YHResult<u32> {
+        let root = wallet.root_key()?;
+        atomically(store, |store| {
+            let mut found = 0;
+            for branch in vec![YHD_RECEIVE_BRANCH, YHD_CHANGE_BRANCH] {
+                let branch_key = root.derive(&[wallet.account, branch]);
+                let mut idx = 0;
+                let mut gap = 0;
+                let mut next = 0;
+                while gap < YHD_GAP_LIMIT {
+                    let sk = branch_key.child(idx).secret_key()?;
+                    let utxos = YIndex::list_recipient_utxos(store, &sk.to_public())?;
+                    if utxos.is_empty() {
+                        gap += 1;
+                    } else {
+                        gap = 0;
+                        next = idx + 1;
+                    }
+                    // the index keeps the spent outputs too
+                    for utxo in utxos {
+                        if wallet.add_coin(store, ekey, sk, &utxo)? {
+                            let (id, idx) = (utxo.internal().id, utxo.internal().idx);
+                            if YIndex::get_spender(store, id, idx)?.is_some() {
+                                wallet.spend_coin(id, idx);
+                            }
+                            found += 1;
+                        }
+                    }
+                    idx += 1;
+                }
+                if branch == YHD_RECEIVE_BRANCH && next > wallet.receive_idx {
+                    wallet.receive_idx = next;
+                }
+                if branch == YHD_CHANGE_BRANCH && next > wallet.change_idx {
+                    wallet.change_idx = next;
+                }
+            }
+            for idx in 0..wallet.receive_idx {
+                let sk = wallet.derive_key(YHD_RECEIVE_BRANCH, idx)?;
+                let keys = YKeys::from_wallet(sk, ekey, &wallet.name)?;
+                if !YKeys::lookup(store, keys.pk)? {
+                    keys.create(store)?;
+                }
+            }
+            wallet.update(store, ekey)?;
+            Ok(found)
+        })
+    }
+}
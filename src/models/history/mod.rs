@@ -0,0 +1,363 @@
+use libyobicash::utils::time::YTime;
+use libyobicash::crypto::key::YKey32;
+use libyobicash::crypto::hash::digest::YDigest64;
+use libyobicash::crypto::elliptic::keys::YPublicKey;
+use libyobicash::amount::YAmount;
+use libyobicash::transaction::YTransaction as LibTransaction;
+use bytes::{BytesMut, BufMut, BigEndian, ByteOrder};
+use serde_json;
+use std::fmt;
+use std::str::FromStr;
+use store::common::*;
+use models::coin::*;
+use models::transaction::*;
+use models::coinbase::*;
+use models::wallet::*;
+use models::index::*;
+use utils::hex::*;
+use utils::amount::*;
+use errors::*;
+
+#[derive(Copy, Clone, Eq, PartialEq, Debug, Serialize, Deserialize)]
+pub enum YHistoryDirection {
+    Mined=0,
+    Received=1,
+    Sent=2,
+}
+
+impl YHistoryDirection {
+    pub fn to_bytes(&self) -> Vec<u8> {
+        let mut buf = BytesMut::new();
+        buf.put_u32::<BigEndian>(*self as u32);
+        buf.to_vec()
+    }
+
+    pub fn from_bytes(b: &[u8]) -> YHResult<YHistoryDirection> {
+        if b.len() != 4 {
+            return Err(YHErrorKind::InvalidLength.into());
+        }
+        match BigEndian::read_u32(b) {
+            0 => Ok(YHistoryDirection::Mined),
+            1 => Ok(YHistoryDirection::Received),
+            2 => Ok(YHistoryDirection::Sent),
+            _ => Err(YHErrorKind::InvalidValue.into()),
+        }
+    }
+}
+
+impl fmt::Display for YHistoryDirection {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let s = match *self {
+            YHistoryDirection::Mined => "mined",
+            YHistoryDirection::Received => "received",
+            YHistoryDirection::Sent => "sent",
+        };
+        write!(f, "{}", s)
+    }
+}
+
+#[derive(Clone, Eq, PartialEq, Debug, Serialize, Deserialize)]
+pub struct YHistoryEntry {
+    pub time: YTime,
+    pub id: YDigest64,
+    pub kind: YCoinKind,
+    pub direction: YHistoryDirection,
+    pub amount: YAmount,
+    pub counterparty: Option<YPublicKey>,
+    pub has_data: bool,
+    pub balance: YAmount,
+}
+
+impl YHistoryEntry {
+    pub fn new(time: YTime, id: YDigest64, kind: YCoinKind, direction: YHistoryDirection, amount: &YAmount, counterparty: Option<YPublicKey>, has_data: bool) -> YHistoryEntry {
+        YHistoryEntry {
+            time: time,
+            id: id,
+            kind: kind,
+            direction: direction,
+            amount: amount.clone(),
+            counterparty: counterparty,
+            has_data: has_data,
+            balance: YAmount::zero(),
+        }
+    }
+
+    pub fn timestamp(&self) -> u64 {
+        BigEndian::read_u64(&self.time.to_bytes()[..])
+    }
+
+    pub fn to_bytes(&self) -> YHResult<Vec<u8>> {
+        let mut buf = BytesMut::new();
+        buf.put(&self.time.to_bytes()[..]);
+        buf.put(self.id.to_bytes());
+        buf.put(self.kind.to_bytes());
+        buf.put(self.direction.to_bytes());
+        if let Some(ref counterparty) = self.counterparty {
+            buf.put_u32::<BigEndian>(1);
+            buf.put(counterparty.to_bytes());
+        } else {
+            buf.put_u32::<BigEndian>(0);
+        }
+        buf.put_u32::<BigEndian>(self.has_data as u32);
+        let amount_buf = self.amount.to_bytes();
+        buf.put_u32::<BigEndian>(amount_buf.len() as u32);
+        buf.put(amount_buf);
+        let balance_buf = self.balance.to_bytes();
+        buf.put_u32::<BigEndian>(balance_buf.len() as u32);
+        buf.put(balance_buf);
+        Ok(buf.to_vec())
+    }
+
+    pub fn from_bytes(buf: &[u8]) -> YHResult<YHistoryEntry> {
+        if buf.len() < 92 {
+            return Err(YHErrorKind::InvalidLength.into());
+        }
+        let mut b = BytesMut::new();
+        b.extend_from_slice(buf);
+        let time = YTime::from_bytes(b.get(0..8).unwrap())?;
+        let id = YDigest64::from_bytes(b.get(8..72).unwrap())?;
+        let kind = YCoinKind::from_bytes(b.get(72..76).unwrap())?;
+        let direction = YHistoryDirection::from_bytes(b.get(76..80).unwrap())?;
+        let mut i = 84;
+        let counterparty = match BigEndian::read_u32(b.get(80..84).unwrap()) {
+            0 => None,
+            1 => {
+                i += 64;
+                Some(YPublicKey::from_bytes(b.get(84..148).ok_or(YHErrorKind::InvalidLength)?)?)
+            },
+            _ => { return Err(YHErrorKind::InvalidValue.into()); },
+        };
+        let has_data = match BigEndian::read_u32(b.get(i..i+4).ok_or(YHErrorKind::InvalidLength)?) {
+            0 => false,
+            1 => true,
+            _ => { return Err(YHErrorKind::InvalidValue.into()); },
+        };
+        i += 4;
+        let amount_size = BigEndian::read_u32(b.get(i..i+4).ok_or(YHErrorKind::InvalidLength)?) as usize;
+        i += 4;
+        let amount = YAmount::from_bytes(b.get(i..i+amount_size).ok_or(YHErrorKind::InvalidLength)?);
+        i += amount_size;
+        let balance_size = BigEndian::read_u32(b.get(i..i+4).ok_or(YHErrorKind::InvalidLength)?) as usize;
+        i += 4;
+        let balance = YAmount::from_bytes(b.get(i..i+balance_size).ok_or(YHErrorKind::InvalidLength)?);
+        i += balance_size;
+        if i != buf.len() {
+            return Err(YHErrorKind::InvalidLength.into());
+        }
+        Ok(YHistoryEntry {
+            time: time,
+            id: id,
+            kind: kind,
+            direction: direction,
+            amount: amount,
+            counterparty: counterparty,
+            has_data: has_data,
+            balance: balance,
+        })
+    }
+
+    pub fn to_csv(&self) -> YHResult<String> {
+        let kind = match self.kind {
+            YCoinKind::Coinbase => "coinbase",
+            YCoinKind::Transaction => "transaction",
+        };
+        let counterparty = match self.counterparty {
+            Some(ref counterparty) => to_hex(&counterparty.to_bytes()),
+            None => String::new(),
+        };
+        Ok(format!("{},{},{},{},{},{},{},{}",
+                   self.timestamp(),
+                   self.id.to_hex(),
+                   kind,
+                   self.direction,
+                   amount_to_u64(&self.amount)?,
+                   counterparty,
+                   self.has_data,
+                   amount_to_u64(&self.balance)?))
+    }
+}
+
+#[derive(Copy, Clone, Eq, PartialEq, Debug)]
+pub enum YHistoryFormat {
+    Csv,
+    Json,
+}
+
+impl Default for YHistoryFormat {
+    fn default() -> YHistoryFormat {
+        YHistoryFormat::Json
+    }
+}
+
+impl FromStr for YHistoryFormat {
+    type Err = YHError;
+
+    fn from_str(s: &str) -> YHResult<YHistoryFormat> {
+        match s {
+            "csv" => Ok(YHistoryFormat::Csv),
+            "json" => Ok(YHistoryFormat::Json),
+            _ => Err(YHErrorKind::UnknownValue.into()),
+        }
+    }
+}
+
+pub const YHISTORY_CSV_HEADER: &str = "time,id,kind,direction,amount,counterparty,has_data,balance";
+
+#[derive(Clone, Eq, PartialEq, Debug, Default, Serialize, Deserialize)]
+pub struct YHistory {
+    pub wallet: String,
+    pub entries: Vec<YHistoryEntry>,
+}
+
+impl YHistory {
+    pub fn new(wallet: &str, entries: &Vec<YHistoryEntry>) -> YHistory {
+        YHistory {
+            wallet: String::from(wallet),
+            entries: entries.clone(),
+        }
+    }
+
+    // an entry is the net effect of a coinbase or a transaction on the wallet coins,
+    // coins spent by a transaction are paid back by its change outputs
+    pub fn build<S: YStorage>(store: &S, ekey: YKey32, wallet_name: &str) -> YHResult<YHistory> {
+        let wallet = YWallet::get(store, ekey, wallet_name)?;
+        let coins: Vec<YCoin> = wallet.ucoins.iter()
+            .chain(wallet.scoins.iter())
+            .cloned()
+            .collect();
+
+        let mut entries = Vec::new();
+
+        let mut cb_ids = Vec::new();
+        let mut tx_ids = YIndex::list_wallet_txs(store, ekey, wallet_name)?;
+        for coin in coins.iter() {
+            let ids = match coin.kind {
+                YCoinKind::Coinbase => &mut cb_ids,
+                YCoinKind::Transaction => &mut tx_ids,
+            };
+            if !ids.contains(&coin.id) {
+                ids.push(coin.id);
+            }
+        }
+
+        for id in cb_ids {
+            let cb_coins: Vec<&YCoin> = coins.iter()
+                .filter(|coin| coin.kind == YCoinKind::Coinbase && coin.id == id)
+                .collect();
+            let time = if YCoinbase::lookup(store, id)? {
+                YCoinbase::get(store, id)?.internal().time
+            } else {
+                cb_coins[0].date.clone()
+            };
+            let amount = cb_coins.iter().fold(YAmount::zero(), |tot, coin| tot + coin.amount.clone());
+            entries.push(YHistoryEntry::new(time, id, YCoinKind::Coinbase, YHistoryDirection::Mined, &amount, None, false));
+        }
+
+        for id in tx_ids {
+            let tx_coins: Vec<&YCoin> = coins.iter()
+                .filter(|coin| coin.kind == YCoinKind::Transaction && coin.id == id)
+                .collect();
+            let received = tx_coins.iter().fold(YAmount::zero(), |tot, coin| tot + coin.amount.clone());
+            let has_data = tx_coins.iter().any(|coin| coin.has_data);
+
+            // pruned stores may have dropped the transaction, only the received coins are known
+            if !YTransaction::lookup(store, id)? {
+                if received != YAmount::zero() {
+                    let time = tx_coins[0].date.clone();
+                    entries.push(YHistoryEntry::new(time, id, YCoinKind::Transaction, YHistoryDirection::Received, &received, None, has_data));
+                }
+                continue;
+            }
+
+            let tx = YTransaction::get(store, id)?.internal();
+            let mut spent = YAmount::zero();
+            for input in tx.inputs.iter() {
+                for coin in coins.iter().filter(|coin| coin.id == input.id && coin.idx == input.idx) {
+                    spent += coin.amount.clone();
+                }
+            }
+
+            if spent > received {
+                let amount = spent - received;
+                let output = tx.outputs.iter()
+                    .enumerate()
+                    .find(|&(idx, _)| !tx_coins.iter().any(|coin| coin.idx == idx as u32))
+                    .map(|(_, output)| output.clone());
+                let counterparty = output.clone().map(|output| output.recipient);
+                let has_data = output.map(|output| output.data.is_some()).unwrap_or(false);
+                entries.push(YHistoryEntry::new(tx.time.clone(), id, YCoinKind::Transaction, YHistoryDirection::Sent, &amount, counterparty, has_data));
+            } else if received > spent {
+                let amount = received - spent;
+                let counterparty = YHistory::sender(store, &tx)?;
+                entries.push(YHistoryEntry::new(tx.time.clone(), id, YCoinKind::Transaction, YHistoryDirection::Received, &amount, counterparty, has_data));
+            }
+        }
+
+        entries.sort_by_key(|entry| entry.timestamp());
+
+        // a sent entry larger than the running balance means coins are missing from the wallet
+        let mut balance = YAmount::zero();
+        for entry in entries.iter_mut() {
+            balance = match entry.direction {
+                YHistoryDirection::Sent => {
+                    if entry.amount > balance {
+                        let msg = format!("history entry {} spends more than the wallet balance", entry.id.to_hex());
+                        return Err(YHErrorKind::Other(msg).into());
+                    }
+                    balance - entry.amount.clone()
+                },
+                _ => balance + entry.amount.clone(),
+            };
+            entry.balance = balance.clone();
+        }
+
+        Ok(YHistory::new(wallet_name, &entries))
+    }
+
+    // the sender is known only by the key of the first coin it spent
+    fn sender<S: YStorage>(store: &S, tx: &LibTransaction) -> YHResult<Option<YPublicKey>> {
+        let input = match tx.inputs.first() {
+            Some(input) => input.clone(),
+            None => { return Ok(None); },
+        };
+        let outputs = if input.height == 0 {
+            if !YCoinbase::lookup(store, input.id)? {
+                return Ok(None);
+            }
+            YCoinbase::get(store, input.id)?.internal().outputs
+        } else {
+            if !YTransaction::lookup(store, input.id)? {
+                return Ok(None);
+            }
+            YTransaction::get(store, input.id)?.internal().outputs
+        };
+        Ok(outputs.get(input.idx as usize).map(|output| output.recipient.clone()))
+    }
+
+    pub fn to_csv(&self) -> YHResult<String> {
+        let mut csv = String::from(YHISTORY_CSV_HEADER);
+        csv.push('\n');
+        for entry in self.entries.iter() {
+            csv.push_str(&entry.to_csv()?);
+            csv.push('\n');
+        }
+        Ok(csv)
+    }
+
+    pub fn to_json(&self) -> YHResult<String> {
+        let json = serde_json::to_string(self)?;
+        Ok(json)
+    }
+
+    pub fn from_json(s: &str) -> YHResult<YHistory> {
+        let history = serde_json::from_str(s)?;
+        Ok(history)
+    }
+
+    pub fn format(&self, format: YHistoryFormat) -> YHResult<String> {
+        match format {
+            YHistoryFormat::Csv => self.to_csv(),
+            YHistoryFormat::Json => self.to_json(),
+        }
+    }
+}
@@ -0,0 +1,253 @@
+use libyobicash::crypto::hash::digest::YDigest64;
+use libyobicash::crypto::hash::sha::YSHA512;
+use libyobicash::crypto::key::YKey32;
+use libyobicash::crypto::elliptic::keys::YPublicKey;
+use bytes::{BufMut, BigEndian};
+use store::common::*;
+use models::bucket::*;
+use models::coin::*;
+use models::data::*;
+use models::utxo::*;
+use models::transaction::*;
+use models::wallet::*;
+use errors::*;
+
+pub struct YIndex;
+
+impl YIndex {
+    pub fn buckets() -> Vec<YBucket> {
+        vec![
+            YBucket::TxsByWallet,
+            YBucket::CbsByWallet,
+            YBucket::DataByWallet,
+            YBucket::UTXOsByRecipient,
+            YBucket::SpenderByUTXO,
+        ]
+    }
+
+    pub fn wallet_key(ekey: YKey32, wallet_name: &str) -> YHResult<Vec<u8>> {
+        let enc_name = YWallet::enc_name(ekey, wallet_name)?;
+        Ok(YSHA512::hash(enc_name.as_slice()).to_bytes())
+    }
+
+    pub fn prefix(bucket: YBucket, owner: &[u8]) -> YStoreBuck {
+        let mut prefix = bucket.to_store_buck();
+        prefix.put(owner);
+        prefix
+    }
+
+    pub fn put<S: YStorage>(store: &mut S, bucket: YBucket, owner: &[u8], key: &YStoreKey, value: &YStoreValue) -> YHResult<()> {
+        let prefix = YIndex::prefix(bucket, owner);
+        store.put(&prefix, key, value)
+    }
+
+    pub fn delete<S: YStorage>(store: &mut S, bucket: YBucket, owner: &[u8], key: &YStoreKey) -> YHResult<()> {
+        let prefix = YIndex::prefix(bucket, owner);
+        if store.lookup(&prefix, key)? {
+            store.delete(&prefix, key)?;
+        }
+        Ok(())
+    }
+
+    pub fn list<S: YStorage>(store: &S, bucket: YBucket, owner: &[u8]) -> YHResult<Vec<YStoreKey>> {
+        let prefix = YIndex::prefix(bucket, owner);
+        store.list(&prefix, 0, u32::max_value())
+    }
+
+    pub fn list_from<S: YStorage>(store: &S, bucket: YBucket, owner: &[u8], after: &Option<YStoreKey>, limit: u32) -> YHResult<YStorePage> {
+        let prefix = YIndex::prefix(bucket, owner);
+        store.list_from(&prefix, after, limit)
+    }
+
+    pub fn put_wallet_tx<S: YStorage>(store: &mut S, ekey: YKey32, wallet_name: &str, id: YDigest64) -> YHResult<()> {
+        let owner = YIndex::wallet_key(ekey, wallet_name)?;
+        YIndex::put(store, YBucket::TxsByWallet, &owner, &id.to_bytes(), &Vec::new())
+    }
+
+    pub fn put_wallet_cb<S: YStorage>(store: &mut S, ekey: YKey32, wallet_name: &str, id: YDigest64) -> YHResult<()> {
+        let owner = YIndex::wallet_key(ekey, wallet_name)?;
+        YIndex::put(store, YBucket::CbsByWallet, &owner, &id.to_bytes(), &Vec::new())
+    }
+
+    pub fn put_wallet_data<S: YStorage>(store: &mut S, ekey: YKey32, wallet_name: &str, data: &YData) -> YHResult<()> {
+        let owner = YIndex::wallet_key(ekey, wallet_name)?;
+        YIndex::put(store, YBucket::DataByWallet, &owner, &data.key()?, &Vec::new())
+    }
+
+    pub fn put_utxo<S: YStorage>(store: &mut S, utxo: &YUTXO) -> YHResult<()> {
+        let owner = utxo.0.recipient.to_bytes();
+        YIndex::put(store, YBucket::UTXOsByRecipient, &owner, &utxo.key()?, &Vec::new())
+    }
+
+    pub fn delete_utxo<S: YStorage>(store: &mut S, utxo: &YUTXO) -> YHResult<()> {
+        let owner = utxo.0.recipient.to_bytes();
+        YIndex::delete(store, YBucket::UTXOsByRecipient, &owner, &utxo.key()?)
+    }
+
+    pub fn put_spender<S: YStorage>(store: &mut S, tx: &YTransaction) -> YHResult<()> {
+        let tx_id = tx.0.id.to_bytes();
+        for input in tx.0.inputs.iter() {
+            let mut key = Vec::new();
+            key.put(input.id.to_bytes());
+            key.put_u32::<BigEndian>(input.idx);
+            YIndex::put(store, YBucket::SpenderByUTXO, &[], &key, &tx_id)?;
+        }
+        Ok(())
+    }
+
+    pub fn delete_spender<S: YStorage>(store: &mut S, tx: &YTransaction) -> YHResult<()> {
+        for input in tx.0.inputs.iter() {
+            let mut key = Vec::new();
+            key.put(input.id.to_bytes());
+            key.put_u32::<BigEndian>(input.idx);
+            YIndex::delete(store, YBucket::SpenderByUTXO, &[], &key)?;
+        }
+        Ok(())
+    }
+
+    pub fn list_wallet_txs<S: YStorage>(store: &S, ekey: YKey32, wallet_name: &str) -> YHResult<Vec<YDigest64>> {
+        let owner = YIndex::wallet_key(ekey, wallet_name)?;
+        let mut ids = Vec::new();
+        for key in YIndex::list(store, YBucket::TxsByWallet, &owner)? {
+            ids.push(YDigest64::from_bytes(&key)?);
+        }
+        Ok(ids)
+    }
+
+    pub fn list_wallet_cbs<S: YStorage>(store: &S, ekey: YKey32, wallet_name: &str) -> YHResult<Vec<YDigest64>> {
+        let owner = YIndex::wallet_key(ekey, wallet_name)?;
+        let mut ids = Vec::new();
+        for key in YIndex::list(store, YBucket::CbsByWallet, &owner)? {
+            ids.push(YDigest64::from_bytes(&key)?);
+        }
+        Ok(ids)
+    }
+
+    pub fn list_wallet_txs_from<S: YStorage>(store: &S, ekey: YKey32, wallet_name: &str, after: &Option<YStoreKey>, limit: u32) -> YHResult<(Vec<YDigest64>, Option<YStoreKey>)> {
+        let owner = YIndex::wallet_key(ekey, wallet_name)?;
+        let page = YIndex::list_from(store, YBucket::TxsByWallet, &owner, after, limit)?;
+        let mut ids = Vec::new();
+        for key in page.keys {
+            ids.push(YDigest64::from_bytes(&key)?);
+        }
+        Ok((ids, page.next))
+    }
+
+    pub fn list_wallet_cbs_from<S: YStorage>(store: &S, ekey: YKey32, wallet_name: &str, after: &Option<YStoreKey>, limit: u32) -> YHResult<(Vec<YDigest64>, Option<YStoreKey>)> {
+        let owner = YIndex::wallet_key(ekey, wallet_name)?;
+        let page = YIndex::list_from(store, YBucket::CbsByWallet, &owner, after, limit)?;
+        let mut ids = Vec::new();
+        for key in page.keys {
+            ids.push(YDigest64::from_bytes(&key)?);
+        }
+        Ok((ids, page.next))
+    }
+
+    pub fn list_wallet_data<S: YStorage>(store: &S, ekey: YKey32, wallet_name: &str) -> YHResult<Vec<YData>> {
+        let owner = YIndex::wallet_key(ekey, wallet_name)?;
+        let store_buck = YBucket::Data.to_store_buck();
+        let mut data = Vec::new();
+        for key in YIndex::list(store, YBucket::DataByWallet, &owner)? {
+            let item = store.get(&store_buck, &key)?;
+            data.push(YData::from_value(&item.value)?);
+        }
+        Ok(data)
+    }
+
+    pub fn list_wallet_data_from<S: YStorage>(store: &S, ekey: YKey32, wallet_name: &str, after: &Option<YStoreKey>, limit: u32) -> YHResult<(Vec<YData>, Option<YStoreKey>)> {
+        let owner = YIndex::wallet_key(ekey, wallet_name)?;
+        let store_buck = YBucket::Data.to_store_buck();
+        let page = YIndex::list_from(store, YBucket::DataByWallet, &owner, after, limit)?;
+        let mut data = Vec::new();
+        for key in page.keys {
+            let item = store.get(&store_buck, &key)?;
+            data.push(YData::from_value(&item.value)?);
+        }
+        Ok((data, page.next))
+    }
+
+    pub fn list_recipient_utxos<S: YStorage>(store: &S, recipient: &YPublicKey) -> YHResult<Vec<YUTXO>> {
+        let owner = recipient.to_bytes();
+        let store_buck = YBucket::UTXO.to_store_buck();
+        let mut utxos = Vec::new();
+        for key in YIndex::list(store, YBucket::UTXOsByRecipient, &owner)? {
+            let item = store.get(&store_buck, &key)?;
+            utxos.push(YUTXO::from_value(&item.value)?);
+        }
+        Ok(utxos)
+    }
+
+    pub fn get_spender<S: YStorage>(store: &S, id: YDigest64, idx: u32) -> YHResult<Option<YDigest64>> {
+        let prefix = YIndex::prefix(YBucket::SpenderByUTXO, &[]);
+        let mut key = Vec::new();
+        key.put(id.to_bytes());
+        key.put_u32::<BigEndian>(idx);
+        if !store.lookup(&prefix, &key)? {
+            return Ok(None);
+        }
+        let item = store.get(&prefix, &key)?;
+        Ok(Some(YDigest64::from_bytes(&item.value)?))
+    }
+
+    pub fn clear<S: YStorage>(store: &mut S) -> YHResult<()> {
+        for bucket in YIndex::buckets() {
+            let store_buck = bucket.to_store_buck();
+            for key in store.list(&store_buck, 0, u32::max_value())? {
+                store.delete(&store_buck, &key)?;
+            }
+        }
+        Ok(())
+    }
+
+    pub fn rebuild<S: YStorage>(store: &mut S, ekey: YKey32) -> YHResult<()> {
+        atomically(store, |store| {
+            YIndex::clear(store)?;
+
+            let utxos_count = YUTXO::count(store)?;
+            for utxo in YUTXO::list(store, 0, utxos_count)? {
+                YIndex::put_utxo(store, &utxo)?;
+            }
+
+            let txs_count = YTransaction::count(store)?;
+            for tx in YTransaction::list(store, 0, txs_count)? {
+                YIndex::put_spender(store, &tx)?;
+            }
+
+            let wallets_count = YWallet::count(store)?;
+            for wallet in YWallet::list(store, ekey, 0, wallets_count)? {
+                let mut coins = wallet.ucoins.clone();
+                coins.extend(wallet.scoins.clone());
+                for coin in coins {
+                    match coin.kind {
+                        YCoinKind::Transaction => {
+                            YIndex::put_wallet_tx(store, ekey, &wallet.name, coin.id)?;
+                            // pruned stores may have dropped the transaction, and its data with it
+                            if coin.has_data && YTransaction::lookup(store, coin.id)? {
+                                let tx = YTransaction::get(store, coin.id)?.internal();
+                                let output = tx.outputs.get(coin.idx as usize)
+                                    .ok_or(YHErrorKind::NotFound)?
+                                    .clone();
+                                if let Some(_data) = output.data {
+                                    let data = YData::new(&_data)?;
+                                    YIndex::put_wallet_data(store, ekey, &wallet.name, &data)?;
+                                }
+                            }
+                        },
+                        YCoinKind::Coinbase => {
+                            YIndex::put_wallet_cb(store, ekey, &wallet.name, coin.id)?;
+                        },
+                    }
+                }
+
+                // a send without change leaves the wallet only through its spent inputs
+                for coin in wallet.scoins.iter() {
+                    if let Some(spender) = YIndex::get_spender(store, coin.id, coin.idx)? {
+                        YIndex::put_wallet_tx(store, ekey, &wallet.name, spender)?;
+                    }
+                }
+            }
+
+            Ok(())
+        })
+    }
+}
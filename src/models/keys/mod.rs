@@ -1,28 +1,46 @@
+use libyobicash::crypto::key::YKey32;
 use libyobicash::crypto::elliptic::keys::*;
 use serde_json;
-use bytes::{BufMut, BytesMut};
+use bytes::{BufMut, BytesMut, BigEndian, ByteOrder};
 use store::common::*;
 use models::bucket::*;
+use models::wallet::*;
 use errors::*;
 
 #[derive(Clone, Eq, PartialEq, Debug, Default, Serialize, Deserialize)]
 pub struct YKeys {
     pub sk: YSecretKey,
     pub pk: YPublicKey,
+    #[serde(default)]
+    pub wallet: Vec<u8>,
 }
 
 impl YKeys {
     pub fn new() -> YKeys {
-        let sk = YSecretKey::random();
-        let pk = sk.to_public();
+        YKeys::from_secret(YSecretKey::random())
+    }
+
+    pub fn from_secret(sk: YSecretKey) -> YKeys {
         YKeys {
             sk: sk,
-            pk: pk,
+            pk: sk.to_public(),
+            wallet: Vec::new(),
         }
     }
 
+    // the link is the encrypted wallet name, so it doesn't leak which wallet owns the key
+    pub fn from_wallet(sk: YSecretKey, ekey: YKey32, wallet_name: &str) -> YHResult<YKeys> {
+        let mut keys = YKeys::from_secret(sk);
+        keys.wallet = YWallet::enc_name(ekey, wallet_name)?;
+        Ok(keys)
+    }
+
+    pub fn is_linked(&self) -> bool {
+        !self.wallet.is_empty()
+    }
+
     pub fn check(&self) -> YHResult<()> {
-        if self.pk == self.sk.to_public() {
+        if self.pk != self.sk.to_public() {
             return Err(YHErrorKind::InvalidKey.into());
         }
         Ok(())
@@ -33,19 +51,33 @@ impl YKeys {
         let mut buf = BytesMut::new();
         buf.put(self.sk.to_bytes());
         buf.put(self.pk.to_bytes());
+        if self.is_linked() {
+            buf.put_u32::<BigEndian>(self.wallet.len() as u32);
+            buf.put(self.wallet.as_slice());
+        }
         Ok(buf.to_vec())
     }
 
     pub fn from_bytes(buf: &[u8]) -> YHResult<YKeys> {
-        if buf.len() != 128 {
+        if buf.len() < 128 || (buf.len() > 128 && buf.len() < 132) {
             return Err(YHErrorKind::InvalidLength.into());
         }
         let sk = YSecretKey::from_bytes(&buf[0..64])?;
         let pk = YPublicKey::from_bytes(&buf[64..128])?;
+        let mut wallet = Vec::new();
+        if buf.len() > 128 {
+            let wallet_size = BigEndian::read_u32(&buf[128..132]) as usize;
+            if buf.len() != 132 + wallet_size {
+                return Err(YHErrorKind::InvalidLength.into());
+            }
+            wallet = buf[132..].to_vec();
+        }
         let keys = YKeys {
             sk: sk,
             pk: pk,
+            wallet: wallet,
         };
+        keys.check()?;
         Ok(keys)
     }
 
@@ -96,6 +128,28 @@ impl YKeys {
         Ok(data)
     }
 
+    pub fn list_from<S: YStorage>(store: &S, after: &Option<YStoreKey>, limit: u32) -> YHResult<(Vec<YKeys>, Option<YStoreKey>)> {
+        let store_buck = YBucket::Keys.to_store_buck();
+        let page = store.list_from(&store_buck, after, limit)?;
+        let mut data = Vec::new();
+        for key in page.keys {
+            let item = store.get(&store_buck, &key)?;
+            let d = YKeys::from_value(&item.value)?;
+            data.push(d);
+        }
+        Ok((data, page.next))
+    }
+
+    pub fn list_by_wallet<S: YStorage>(store: &S, ekey: YKey32, wallet_name: &str) -> YHResult<Vec<YKeys>> {
+        let wallet = YWallet::enc_name(ekey, wallet_name)?;
+        let count = YKeys::count(store)?;
+        let keys = YKeys::list(store, 0, count)?
+            .into_iter()
+            .filter(|keys| keys.wallet == wallet)
+            .collect();
+        Ok(keys)
+    }
+
     pub fn get<S: YStorage>(store: &S, pk: YPublicKey) -> YHResult<YKeys> {
         let store_buck = YBucket::Keys.to_store_buck();
         let key = pk.to_bytes();
@@ -113,6 +167,16 @@ impl YKeys {
         store.put(&store_buck, &key, &value)
     }
 
+    pub fn update<S: YStorage>(&self, store: &mut S) -> YHResult<()> {
+        let store_buck = YBucket::Keys.to_store_buck();
+        let key = self.key()?;
+        if !store.lookup(&store_buck, &key)? {
+            return Err(YHErrorKind::NotFound.into());
+        }
+        let value = self.value()?;
+        store.put(&store_buck, &key, &value)
+    }
+
     pub fn delete<S: YStorage>(&self, store: &mut S) -> YHResult<()> {
         let store_buck = YBucket::Keys.to_store_buck();
         let key = self.key()?;
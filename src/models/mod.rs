@@ -2,18 +2,44 @@ pub mod bucket;
 pub mod data;
 pub mod utxo;
 pub mod coin;
+pub mod selection;
+pub mod fee;
+pub mod payment;
+pub mod unsigned;
+pub mod reservation;
+pub mod export;
+pub mod history;
 pub mod transaction;
 pub mod coinbase;
 pub mod keys;
 pub mod wallet;
+pub mod hd;
 pub mod peer;
+pub mod node_key;
+pub mod schema;
+pub mod index;
+pub mod fsck;
+pub mod prune;
 
 pub use self::bucket::*;
 pub use self::data::*;
 pub use self::utxo::*;
 pub use self::coin::*;
+pub use self::selection::*;
+pub use self::fee::*;
+pub use self::payment::*;
+pub use self::unsigned::*;
+pub use self::reservation::*;
+pub use self::export::*;
+pub use self::history::*;
 pub use self::transaction::*;
 pub use self::coinbase::*;
 pub use self::keys::*;
 pub use self::wallet::*;
+pub use self::hd::*;
 pub use self::peer::*;
+pub use self::node_key::*;
+pub use self::schema::*;
+pub use self::index::*;
+pub use self::fsck::*;
+pub use self::prune::*;
@@ -0,0 +1,41 @@
+use libyobicash::crypto::elliptic::keys::YSecretKey;
+use store::common::*;
+use models::bucket::*;
+use errors::*;
+
+pub struct YNodeKey;
+
+impl YNodeKey {
+    pub fn key() -> YStoreKey {
+        b"node_secret_key".to_vec()
+    }
+
+    pub fn lookup<S: YStorage>(store: &S) -> YHResult<bool> {
+        let store_buck = YBucket::Meta.to_store_buck();
+        store.lookup(&store_buck, &YNodeKey::key())
+    }
+
+    pub fn get<S: YStorage>(store: &S) -> YHResult<YSecretKey> {
+        let store_buck = YBucket::Meta.to_store_buck();
+        let item = store.get(&store_buck, &YNodeKey::key())?;
+        let sk = YSecretKey::from_bytes(&item.value)?;
+        Ok(sk)
+    }
+
+    pub fn set<S: YStorage>(store: &mut S, sk: &YSecretKey) -> YHResult<()> {
+        let store_buck = YBucket::Meta.to_store_buck();
+        store.put(&store_buck, &YNodeKey::key(), &sk.to_bytes())
+    }
+
+    // configs written before the store held the node key carry it in plaintext
+    pub fn load<S: YStorage>(store: &mut S, legacy: Option<YSecretKey>, read_only: bool) -> YHResult<YSecretKey> {
+        if YNodeKey::lookup(store)? {
+            return YNodeKey::get(store);
+        }
+        let sk = legacy.unwrap_or_else(YSecretKey::random);
+        if !read_only {
+            YNodeKey::set(store, &sk)?;
+        }
+        Ok(sk)
+    }
+}
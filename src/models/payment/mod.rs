@@ -0,0 +1,70 @@
+use libyobicash::crypto::elliptic::keys::YPublicKey;
+use libyobicash::amount::YAmount;
+use serde_json;
+use bytes::{BytesMut, BufMut, BigEndian, ByteOrder};
+use errors::*;
+
+#[derive(Clone, Eq, PartialEq, Debug, Default, Serialize, Deserialize)]
+pub struct YPayment {
+    pub to: YPublicKey,
+    pub amount: YAmount,
+}
+
+impl YPayment {
+    pub fn new(to: YPublicKey, amount: &YAmount) -> YHResult<YPayment> {
+        let payment = YPayment {
+            to: to,
+            amount: amount.clone(),
+        };
+        payment.check()?;
+        Ok(payment)
+    }
+
+    pub fn check(&self) -> YHResult<()> {
+        if self.amount == YAmount::zero() {
+            return Err(YHErrorKind::InvalidValue.into());
+        }
+        Ok(())
+    }
+
+    pub fn total(payments: &Vec<YPayment>) -> YAmount {
+        let mut total = YAmount::zero();
+        for payment in payments {
+            total += payment.amount.clone();
+        }
+        total
+    }
+
+    pub fn to_bytes(&self) -> YHResult<Vec<u8>> {
+        self.check()?;
+        let mut buf = BytesMut::new();
+        buf.put(self.to.to_bytes());
+        let amount_buf = self.amount.to_bytes();
+        buf.put_u32::<BigEndian>(amount_buf.len() as u32);
+        buf.put(amount_buf);
+        Ok(buf.to_vec())
+    }
+
+    pub fn from_bytes(buf: &[u8]) -> YHResult<YPayment> {
+        if buf.len() < 68 {
+            return Err(YHErrorKind::InvalidLength.into());
+        }
+        let to = YPublicKey::from_bytes(&buf[0..64])?;
+        let amount_size = BigEndian::read_u32(&buf[64..68]) as usize;
+        if buf.len() != 68 + amount_size {
+            return Err(YHErrorKind::InvalidLength.into());
+        }
+        let amount = YAmount::from_bytes(&buf[68..]);
+        YPayment::new(to, &amount)
+    }
+
+    pub fn to_json(&self) -> YHResult<String> {
+        let json = serde_json::to_string(self)?;
+        Ok(json)
+    }
+
+    pub fn from_json(s: &str) -> YHResult<YPayment> {
+        let payment = serde_json::from_str(s)?;
+        Ok(payment)
+    }
+}
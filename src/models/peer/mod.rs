@@ -128,6 +128,18 @@ impl YPeer {
         Ok(peers)
     }
 
+    pub fn list_by_ip_from<S: YStorage>(store: &S, after: &Option<YStoreKey>, limit: u32) -> YHResult<(Vec<YPeer>, Option<YStoreKey>)> {
+        let store_buck = YBucket::PeersByIp.to_store_buck();
+        let page = store.list_from(&store_buck, after, limit)?;
+        let mut peers = Vec::new();
+        for key in page.keys {
+            let item = store.get(&store_buck, &key)?;
+            let peer = YPeer::from_value(&item.value)?;
+            peers.push(peer);
+        }
+        Ok((peers, page.next))
+    }
+
     pub fn list_by_last_time<S: YStorage>(store: &S, skip: u32, count: u32) -> YHResult<Vec<Ipv4Addr>> {
         let store_buck = YBucket::PeersByLastTime.to_store_buck();
         let _keys = store.list_reverse(&store_buck, skip, count)?;
@@ -150,45 +162,51 @@ impl YPeer {
     }
 
     pub fn create<S: YStorage>(&self, store: &mut S) -> YHResult<()> {
-        let store_buck_ip = YBucket::PeersByIp.to_store_buck();
-        let key_ip = self.by_ip_key()?;
-        if store.lookup(&store_buck_ip, &key_ip)? {
-            return Err(YHErrorKind::AlreadyFound.into());
-        }
-        let value = self.value()?;
-        store.put(&store_buck_ip, &key_ip, &value)?;
-        let store_buck_lt = YBucket::PeersByLastTime.to_store_buck();
-        let key_lt = self.by_last_time_key()?;
-        if store.lookup(&store_buck_lt, &key_lt)? {
-            return Err(YHErrorKind::AlreadyFound.into());
-        }
-        store.put(&store_buck_lt, &key_lt, &key_ip)?;
-        Ok(())
+        atomically(store, |store| {
+            let store_buck_ip = YBucket::PeersByIp.to_store_buck();
+            let key_ip = self.by_ip_key()?;
+            if store.lookup(&store_buck_ip, &key_ip)? {
+                return Err(YHErrorKind::AlreadyFound.into());
+            }
+            let value = self.value()?;
+            store.put(&store_buck_ip, &key_ip, &value)?;
+            let store_buck_lt = YBucket::PeersByLastTime.to_store_buck();
+            let key_lt = self.by_last_time_key()?;
+            if store.lookup(&store_buck_lt, &key_lt)? {
+                return Err(YHErrorKind::AlreadyFound.into());
+            }
+            store.put(&store_buck_lt, &key_lt, &key_ip)?;
+            Ok(())
+        })
     }
 
     pub fn update<S: YStorage>(&self, store: &mut S) -> YHResult<()> {
-        let store_buck_ip = YBucket::PeersByIp.to_store_buck();
-        let key_ip = self.by_ip_key()?;
-        if !store.lookup(&store_buck_ip, &key_ip)? {
-            return Err(YHErrorKind::NotFound.into());
-        }
-        self.delete(store)?;
-        self.create(store)
+        atomically(store, |store| {
+            let store_buck_ip = YBucket::PeersByIp.to_store_buck();
+            let key_ip = self.by_ip_key()?;
+            if !store.lookup(&store_buck_ip, &key_ip)? {
+                return Err(YHErrorKind::NotFound.into());
+            }
+            self.delete(store)?;
+            self.create(store)
+        })
     }
 
     pub fn delete<S: YStorage>(&self, store: &mut S) -> YHResult<()> {
-        let store_buck_ip = YBucket::PeersByIp.to_store_buck();
-        let key_ip = self.by_ip_key()?;
-        if !store.lookup(&store_buck_ip, &key_ip)? {
-            return Err(YHErrorKind::NotFound.into());
-        }
-        store.delete(&store_buck_ip, &key_ip)?;
-        let store_buck_lt = YBucket::PeersByLastTime.to_store_buck();
-        let key_lt = self.by_last_time_key()?;
-        if !store.lookup(&store_buck_lt, &key_lt)? {
-            return Err(YHErrorKind::NotFound.into());
-        }
-        store.delete(&store_buck_lt, &key_lt)?;
-        Ok(())
+        atomically(store, |store| {
+            let store_buck_ip = YBucket::PeersByIp.to_store_buck();
+            let key_ip = self.by_ip_key()?;
+            if !store.lookup(&store_buck_ip, &key_ip)? {
+                return Err(YHErrorKind::NotFound.into());
+            }
+            store.delete(&store_buck_ip, &key_ip)?;
+            let store_buck_lt = YBucket::PeersByLastTime.to_store_buck();
+            let key_lt = self.by_last_time_key()?;
+            if !store.lookup(&store_buck_lt, &key_lt)? {
+                return Err(YHErrorKind::NotFound.into());
+            }
+            store.delete(&store_buck_lt, &key_lt)?;
+            Ok(())
+        })
     }
 }
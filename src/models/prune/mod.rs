@@ -0,0 +1,184 @@
+use libyobicash::crypto::key::YKey32;
+use libyobicash::utils::time::YTime;
+use libyobicash::data::YData as LibData;
+use bytes::{BytesMut, BufMut, BigEndian, ByteOrder};
+use serde_json;
+use std::collections::{HashMap, HashSet};
+use store::common::*;
+use models::bucket::*;
+use models::coin::*;
+use models::data::*;
+use models::transaction::*;
+use models::coinbase::*;
+use models::wallet::*;
+use models::index::*;
+use errors::*;
+
+#[derive(Clone, Eq, PartialEq, Debug, Default, Serialize, Deserialize)]
+pub struct YPruneReport {
+    pub transactions: u32,
+    pub coinbases: u32,
+    pub data: u32,
+}
+
+impl YPruneReport {
+    pub fn to_json(&self) -> YHResult<String> {
+        let json = serde_json::to_string_pretty(self)?;
+        Ok(json)
+    }
+}
+
+pub struct YPrune;
+
+impl YPrune {
+    pub fn mode_key() -> YStoreKey {
+        b"storage_mode".to_vec()
+    }
+
+    pub fn mode<S: YStorage>(store: &S) -> YHResult<YStorageMode> {
+        let store_buck = YBucket::Meta.to_store_buck();
+        let key = YPrune::mode_key();
+        if !store.lookup(&store_buck, &key)? {
+            return Ok(YStorageMode::Full);
+        }
+        let item = store.get(&store_buck, &key)?;
+        if item.value.len() != 4 {
+            return Err(YHErrorKind::InvalidLength.into());
+        }
+        match BigEndian::read_u32(&item.value) {
+            0 => Ok(YStorageMode::Full),
+            1 => Ok(YStorageMode::Light),
+            _ => Err(YHErrorKind::InvalidValue.into()),
+        }
+    }
+
+    pub fn set_mode<S: YStorage>(store: &mut S, mode: YStorageMode) -> YHResult<()> {
+        // a pruned store can't be turned back into a full one
+        if mode == YStorageMode::Full && YPrune::mode(store)? == YStorageMode::Light {
+            return Err(YHErrorKind::InvalidValue.into());
+        }
+        let store_buck = YBucket::Meta.to_store_buck();
+        let key = YPrune::mode_key();
+        let mut value = BytesMut::new();
+        value.put_u32::<BigEndian>(mode as u32);
+        store.put(&store_buck, &key, &value.to_vec())
+    }
+
+    pub fn limit_time(retention: u64) -> YHResult<YTime> {
+        let now = YTime::now().to_bytes();
+        let secs = BigEndian::read_u64(&now[..]);
+        let mut buf = BytesMut::new();
+        buf.put_u64::<BigEndian>(secs.saturating_sub(retention));
+        let time = YTime::from_bytes(&buf.to_vec())?;
+        Ok(time)
+    }
+
+    fn data_key(output_data: &LibData) -> YStoreKey {
+        let mut key = Vec::new();
+        key.put(output_data.checksum.to_bytes());
+        key.put(output_data.tag.to_bytes());
+        key
+    }
+
+    fn keep_set<S: YStorage>(store: &S, ekey: YKey32) -> YHResult<(HashSet<YStoreKey>, HashSet<YStoreKey>, HashSet<YStoreKey>)> {
+        let mut txs = HashSet::new();
+        let mut cbs = HashSet::new();
+        let mut data = HashSet::new();
+        let mut pending = Vec::new();
+
+        let wallets_count = YWallet::count(store)?;
+        for wallet in YWallet::list(store, ekey, 0, wallets_count)? {
+            let mut coins = wallet.ucoins.clone();
+            coins.extend(wallet.scoins.clone());
+            for coin in coins {
+                match coin.kind {
+                    YCoinKind::Transaction => pending.push(coin.id),
+                    YCoinKind::Coinbase => { cbs.insert(coin.id.to_bytes()); },
+                }
+            }
+            pending.extend(YIndex::list_wallet_txs(store, ekey, &wallet.name)?);
+            for id in YIndex::list_wallet_cbs(store, ekey, &wallet.name)? {
+                cbs.insert(id.to_bytes());
+            }
+            let owner = YIndex::wallet_key(ekey, &wallet.name)?;
+            data.extend(YIndex::list(store, YBucket::DataByWallet, &owner)?);
+        }
+
+        while let Some(id) = pending.pop() {
+            if !txs.insert(id.to_bytes()) || !YTransaction::lookup(store, id)? {
+                continue;
+            }
+            for input in YTransaction::get(store, id)?.internal().inputs {
+                if input.height != 0 {
+                    pending.push(input.id);
+                } else {
+                    cbs.insert(input.id.to_bytes());
+                }
+            }
+        }
+
+        Ok((txs, cbs, data))
+    }
+
+    pub fn run<S: YStorage>(store: &mut S, ekey: YKey32, retention: u64) -> YHResult<YPruneReport> {
+        let limit_time = YPrune::limit_time(retention)?;
+        atomically(store, |store| {
+            YPrune::set_mode(store, YStorageMode::Light)?;
+            let (keep_txs, keep_cbs, keep_data) = YPrune::keep_set(store, ekey)?;
+            let mut report = YPruneReport::default();
+
+            // data is dropped with the newest transaction carrying it, so foreign
+            // transactions are kept for the retention window too
+            let mut carriers: HashMap<YStoreKey, YTime> = HashMap::new();
+            let txs_count = YTransaction::count(store)?;
+            for tx in YTransaction::list(store, 0, txs_count)? {
+                let _tx = tx.internal();
+                for output in _tx.outputs.iter() {
+                    if let Some(ref _data) = output.data {
+                        let key = YPrune::data_key(_data);
+                        let newer = match carriers.get(&key) {
+                            Some(time) => _tx.time > *time,
+                            None => true,
+                        };
+                        if newer {
+                            carriers.insert(key, _tx.time.clone());
+                        }
+                    }
+                }
+                if keep_txs.contains(&_tx.id.to_bytes()) || _tx.time >= limit_time {
+                    continue;
+                }
+                tx.delete(store)?;
+                report.transactions += 1;
+            }
+
+            let cbs_count = YCoinbase::count(store)?;
+            for cb in YCoinbase::list(store, 0, cbs_count)? {
+                let _cb = cb.internal();
+                if keep_cbs.contains(&_cb.id.to_bytes()) || _cb.time >= limit_time {
+                    continue;
+                }
+                cb.delete(store)?;
+                report.coinbases += 1;
+            }
+
+            let data_count = YData::count(store)?;
+            for data in YData::list(store, 0, data_count)? {
+                let key = data.key()?;
+                if keep_data.contains(&key) {
+                    continue;
+                }
+                let expired = match carriers.get(&key) {
+                    Some(time) => *time < limit_time,
+                    None => true,
+                };
+                if expired {
+                    data.delete(store)?;
+                    report.data += 1;
+                }
+            }
+
+            Ok(report)
+        })
+    }
+}
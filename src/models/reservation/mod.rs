@@ -0,0 +1,66 @@
+use libyobicash::crypto::hash::digest::YDigest64;
+use libyobicash::transaction::YTransaction as LibTransaction;
+use bytes::{BytesMut, BufMut, BigEndian, ByteOrder};
+use std::time::{SystemTime, UNIX_EPOCH};
+use store::common::*;
+use models::bucket::*;
+use models::coin::*;
+use errors::*;
+
+pub const YRESERVATION_SECS: u64 = 86400;
+
+// the inputs of an unsigned transaction are held until the signed transaction is pushed back,
+// or until the reservation expires if it never is
+pub struct YReservation;
+
+impl YReservation {
+    pub fn key(id: YDigest64, idx: u32) -> YStoreKey {
+        let mut buf = BytesMut::new();
+        buf.put(id.to_bytes());
+        buf.put_u32::<BigEndian>(idx);
+        buf.to_vec()
+    }
+
+    fn now() -> u64 {
+        SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map(|d| d.as_secs())
+            .unwrap_or(0)
+    }
+
+    pub fn reserve<S: YStorage>(store: &mut S, coins: &Vec<YCoin>) -> YHResult<()> {
+        let store_buck = YBucket::Reservations.to_store_buck();
+        let mut value = BytesMut::new();
+        value.put_u64::<BigEndian>(YReservation::now() + YRESERVATION_SECS);
+        for coin in coins.iter() {
+            store.put(&store_buck, &YReservation::key(coin.id, coin.idx), &value.to_vec())?;
+        }
+        Ok(())
+    }
+
+    pub fn is_reserved<S: YStorage>(store: &S, id: YDigest64, idx: u32) -> YHResult<bool> {
+        let store_buck = YBucket::Reservations.to_store_buck();
+        let key = YReservation::key(id, idx);
+        if !store.lookup(&store_buck, &key)? {
+            return Ok(false);
+        }
+        let item = store.get(&store_buck, &key)?;
+        if item.value.len() != 8 {
+            return Err(YHErrorKind::InvalidLength.into());
+        }
+        Ok(BigEndian::read_u64(&item.value) > YReservation::now())
+    }
+
+    pub fn release<S: YStorage>(store: &mut S, tx: &LibTransaction) -> YHResult<u32> {
+        let store_buck = YBucket::Reservations.to_store_buck();
+        let mut released = 0;
+        for input in tx.inputs.iter() {
+            let key = YReservation::key(input.id, input.idx);
+            if store.lookup(&store_buck, &key)? {
+                store.delete(&store_buck, &key)?;
+                released += 1;
+            }
+        }
+        Ok(released)
+    }
+}
@@ -0,0 +1,82 @@
+use libyobicash::crypto::key::YKey32;
+use bytes::{BytesMut, BufMut, BigEndian, ByteOrder};
+use store::common::*;
+use models::bucket::*;
+use models::index::*;
+use errors::*;
+
+pub const YSCHEMA_VERSION: u32 = 2;
+
+pub struct YSchema;
+
+impl YSchema {
+    pub fn version_key() -> YStoreKey {
+        b"schema_version".to_vec()
+    }
+
+    pub fn is_empty<S: YStorage>(store: &S) -> YHResult<bool> {
+        for buck in YBucket::all() {
+            if buck == YBucket::Meta || YIndex::buckets().contains(&buck) {
+                continue;
+            }
+            if store.count(&buck.to_store_buck())? != 0 {
+                return Ok(false);
+            }
+        }
+        Ok(true)
+    }
+
+    pub fn version<S: YStorage>(store: &S) -> YHResult<u32> {
+        let store_buck = YBucket::Meta.to_store_buck();
+        let key = YSchema::version_key();
+        if !store.lookup(&store_buck, &key)? {
+            return Ok(0);
+        }
+        let item = store.get(&store_buck, &key)?;
+        if item.value.len() != 4 {
+            return Err(YHErrorKind::InvalidLength.into());
+        }
+        Ok(BigEndian::read_u32(&item.value))
+    }
+
+    pub fn set_version<S: YStorage>(store: &mut S, version: u32) -> YHResult<()> {
+        let store_buck = YBucket::Meta.to_store_buck();
+        let key = YSchema::version_key();
+        let mut value = BytesMut::new();
+        value.put_u32::<BigEndian>(version);
+        store.put(&store_buck, &key, &value.to_vec())
+    }
+
+    pub fn check<S: YStorage>(store: &S) -> YHResult<u32> {
+        let version = YSchema::version(store)?;
+        if version > YSCHEMA_VERSION {
+            return Err(YHErrorKind::InvalidSchemaVersion.into());
+        }
+        Ok(version)
+    }
+
+    pub fn migrate<S: YStorage>(store: &mut S, ekey: YKey32) -> YHResult<u32> {
+        let mut version = YSchema::check(store)?;
+        if version == 0 && YSchema::is_empty(store)? {
+            YSchema::set_version(store, YSCHEMA_VERSION)?;
+            return Ok(YSCHEMA_VERSION);
+        }
+        while version < YSCHEMA_VERSION {
+            atomically(store, |store| {
+                YSchema::migrate_step(store, ekey, version)?;
+                YSchema::set_version(store, version + 1)
+            })?;
+            version += 1;
+        }
+        Ok(version)
+    }
+
+    fn migrate_step<S: YStorage>(store: &mut S, ekey: YKey32, from: u32) -> YHResult<()> {
+        match from {
+            // v0 stores predate the meta bucket, their records are already in the v1 format
+            0 => Ok(()),
+            1 => YIndex::rebuild(store, ekey),
+            _ => Err(YHErrorKind::InvalidSchemaVersion.into()),
+        }
+    }
+}
@@ -0,0 +1,225 @@
+use libyobicash::utils::random::YRandom;
+use libyobicash::amount::YAmount;
+use std::cmp::Ordering;
+use std::str::FromStr;
+use models::coin::*;
+use errors::*;
+
+pub const YSELECTION_MAX_INPUTS: u32 = 256;
+
+pub const YBNB_MAX_TRIES: u32 = 100_000;
+
+pub trait YCoinSelector {
+    fn select(&self, coins: &Vec<YCoin>, amount: &YAmount, max_inputs: u32) -> YHResult<Vec<YCoin>>;
+}
+
+fn check_funds(coins: &Vec<YCoin>, amount: &YAmount, max_inputs: u32) -> YHResult<()> {
+    let mut tot_amount = YAmount::zero();
+    for coin in coins {
+        tot_amount += coin.amount.clone();
+    }
+    if tot_amount < *amount {
+        return Err(YHErrorKind::NotEnoughFunds.into());
+    }
+    if max_inputs == 0 {
+        return Err(YHErrorKind::TooManyInputs.into());
+    }
+    Ok(())
+}
+
+fn cmp_amount(a: &YCoin, b: &YCoin) -> Ordering {
+    a.amount.partial_cmp(&b.amount).unwrap_or(Ordering::Equal)
+}
+
+fn accumulate(coins: Vec<YCoin>, amount: &YAmount, max_inputs: u32) -> YHResult<Vec<YCoin>> {
+    let mut selected = Vec::new();
+    let mut tot_amount = YAmount::zero();
+    for coin in coins {
+        if selected.len() as u32 == max_inputs {
+            return Err(YHErrorKind::TooManyInputs.into());
+        }
+        tot_amount += coin.amount.clone();
+        selected.push(coin);
+        if tot_amount >= *amount {
+            return Ok(selected);
+        }
+    }
+    Err(YHErrorKind::NotEnoughFunds.into())
+}
+
+#[derive(Copy, Clone, Eq, PartialEq, Debug, Default)]
+pub struct YFirstFit;
+
+impl YCoinSelector for YFirstFit {
+    fn select(&self, coins: &Vec<YCoin>, amount: &YAmount, max_inputs: u32) -> YHResult<Vec<YCoin>> {
+        check_funds(coins, amount, max_inputs)?;
+        accumulate(coins.clone(), amount, max_inputs)
+    }
+}
+
+#[derive(Copy, Clone, Eq, PartialEq, Debug, Default)]
+pub struct YLargestFirst;
+
+impl YCoinSelector for YLargestFirst {
+    fn select(&self, coins: &Vec<YCoin>, amount: &YAmount, max_inputs: u32) -> YHResult<Vec<YCoin>> {
+        check_funds(coins, amount, max_inputs)?;
+        let mut coins = coins.clone();
+        coins.sort_by(|a, b| cmp_amount(b, a));
+        accumulate(coins, amount, max_inputs)
+    }
+}
+
+#[derive(Copy, Clone, Eq, PartialEq, Debug, Default)]
+pub struct YSmallestFirst;
+
+impl YCoinSelector for YSmallestFirst {
+    // slides a window of at most max_inputs coins up the sorted coins,
+    // so that the dust is consumed first without going over the limit
+    fn select(&self, coins: &Vec<YCoin>, amount: &YAmount, max_inputs: u32) -> YHResult<Vec<YCoin>> {
+        check_funds(coins, amount, max_inputs)?;
+        let mut coins = coins.clone();
+        coins.sort_by(cmp_amount);
+        let mut selected: Vec<YCoin> = Vec::new();
+        let mut tot_amount = YAmount::zero();
+        for coin in coins {
+            tot_amount += coin.amount.clone();
+            selected.push(coin);
+            if selected.len() as u32 > max_inputs {
+                let dropped = selected.remove(0);
+                tot_amount = tot_amount - dropped.amount;
+            }
+            if tot_amount >= *amount {
+                return Ok(selected);
+            }
+        }
+        Err(YHErrorKind::TooManyInputs.into())
+    }
+}
+
+#[derive(Copy, Clone, Eq, PartialEq, Debug, Default)]
+pub struct YBranchAndBound;
+
+impl YBranchAndBound {
+    pub fn exact_match(coins: &Vec<YCoin>, amount: &YAmount, max_inputs: u32) -> Option<Vec<YCoin>> {
+        let mut coins = coins.clone();
+        coins.sort_by(|a, b| cmp_amount(b, a));
+        let mut remaining = vec![YAmount::zero(); coins.len() + 1];
+        for i in (0..coins.len()).rev() {
+            remaining[i] = remaining[i + 1].clone() + coins[i].amount.clone();
+        }
+        let mut selected = Vec::new();
+        let mut tries = 0;
+        if YBranchAndBound::search(&coins, &remaining, amount, max_inputs,
+                                   0, YAmount::zero(), &mut selected, &mut tries) {
+            Some(selected.iter().map(|i| coins[*i].clone()).collect())
+        } else {
+            None
+        }
+    }
+
+    fn search(coins: &Vec<YCoin>, remaining: &Vec<YAmount>, amount: &YAmount, max_inputs: u32,
+              idx: usize, tot_amount: YAmount, selected: &mut Vec<usize>, tries: &mut u32) -> bool {
+        if tot_amount == *amount && !selected.is_empty() {
+            return true;
+        }
+        *tries += 1;
+        if *tries > YBNB_MAX_TRIES ||
+            idx == coins.len() ||
+            selected.len() as u32 == max_inputs ||
+            tot_amount > *amount ||
+            tot_amount.clone() + remaining[idx].clone() < *amount {
+            return false;
+        }
+        selected.push(idx);
+        let with_coin = tot_amount.clone() + coins[idx].amount.clone();
+        if YBranchAndBound::search(coins, remaining, amount, max_inputs, idx + 1, with_coin, selected, tries) {
+            return true;
+        }
+        selected.pop();
+        YBranchAndBound::search(coins, remaining, amount, max_inputs, idx + 1, tot_amount, selected, tries)
+    }
+}
+
+impl YCoinSelector for YBranchAndBound {
+    // looks for a changeless selection, falling back to largest-first when there is none
+    fn select(&self, coins: &Vec<YCoin>, amount: &YAmount, max_inputs: u32) -> YHResult<Vec<YCoin>> {
+        check_funds(coins, amount, max_inputs)?;
+        match YBranchAndBound::exact_match(coins, amount, max_inputs) {
+            Some(selected) => Ok(selected),
+            None => YLargestFirst.select(coins, amount, max_inputs),
+        }
+    }
+}
+
+#[derive(Copy, Clone, Eq, PartialEq, Debug, Default)]
+pub struct YRandomSelect;
+
+impl YCoinSelector for YRandomSelect {
+    fn select(&self, coins: &Vec<YCoin>, amount: &YAmount, max_inputs: u32) -> YHResult<Vec<YCoin>> {
+        check_funds(coins, amount, max_inputs)?;
+        let mut coins = coins.clone();
+        for i in (1..coins.len()).rev() {
+            let j = YRandom::u32_range(0, i as u32 + 1) as usize;
+            coins.swap(i, j);
+        }
+        match accumulate(coins.clone(), amount, max_inputs) {
+            Ok(selected) => Ok(selected),
+            Err(_) => YLargestFirst.select(&coins, amount, max_inputs),
+        }
+    }
+}
+
+#[derive(Copy, Clone, Eq, PartialEq, Debug, Serialize, Deserialize)]
+pub enum YCoinSelection {
+    FirstFit=0,
+    LargestFirst=1,
+    SmallestFirst=2,
+    BranchAndBound=3,
+    Random=4,
+}
+
+impl Default for YCoinSelection {
+    fn default() -> YCoinSelection {
+        YCoinSelection::FirstFit
+    }
+}
+
+impl YCoinSelection {
+    pub fn from_u32(n: u32) -> YHResult<YCoinSelection> {
+        match n {
+            0 => Ok(YCoinSelection::FirstFit),
+            1 => Ok(YCoinSelection::LargestFirst),
+            2 => Ok(YCoinSelection::SmallestFirst),
+            3 => Ok(YCoinSelection::BranchAndBound),
+            4 => Ok(YCoinSelection::Random),
+            _ => Err(YHErrorKind::UnknownValue.into()),
+        }
+    }
+}
+
+impl FromStr for YCoinSelection {
+    type Err = YHError;
+
+    fn from_str(s: &str) -> YHResult<YCoinSelection> {
+        match s {
+            "first-fit" => Ok(YCoinSelection::FirstFit),
+            "largest-first" => Ok(YCoinSelection::LargestFirst),
+            "smallest-first" => Ok(YCoinSelection::SmallestFirst),
+            "branch-and-bound" => Ok(YCoinSelection::BranchAndBound),
+            "random" => Ok(YCoinSelection::Random),
+            _ => Err(YHErrorKind::UnknownValue.into()),
+        }
+    }
+}
+
+impl YCoinSelector for YCoinSelection {
+    fn select(&self, coins: &Vec<YCoin>, amount: &YAmount, max_inputs: u32) -> YHResult<Vec<YCoin>> {
+        match *self {
+            YCoinSelection::FirstFit => YFirstFit.select(coins, amount, max_inputs),
+            YCoinSelection::LargestFirst => YLargestFirst.select(coins, amount, max_inputs),
+            YCoinSelection::SmallestFirst => YSmallestFirst.select(coins, amount, max_inputs),
+            YCoinSelection::BranchAndBound => YBranchAndBound.select(coins, amount, max_inputs),
+            YCoinSelection::Random => YRandomSelect.select(coins, amount, max_inputs),
+        }
+    }
+}
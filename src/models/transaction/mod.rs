@@ -10,8 +10,12 @@ use models::bucket::*;
 use models::data::*;
 use models::coinbase::*;
 use models::coin::*;
+use models::selection::*;
+use models::fee::*;
 use models::utxo::*;
 use models::wallet::*;
+use models::hd::*;
+use models::index::*;
 use errors::*;
 
 #[derive(Clone, Eq, PartialEq, Debug, Default, Serialize, Deserialize)]
@@ -90,6 +94,18 @@ impl YTransaction {
         Ok(transactions)
     }
 
+    pub fn list_from<S: YStorage>(store: &S, after: &Option<YStoreKey>, limit: u32) -> YHResult<(Vec<YTransaction>, Option<YStoreKey>)> {
+        let store_buck = YBucket::Transactions.to_store_buck();
+        let page = store.list_from(&store_buck, after, limit)?;
+        let mut transactions = Vec::new();
+        for key in page.keys {
+            let item = store.get(&store_buck, &key)?;
+            let tx = YTransaction::from_value(&item.value)?;
+            transactions.push(tx);
+        }
+        Ok((transactions, page.next))
+    }
+
     pub fn list_ancestors<S: YStorage>(&self, store: &S) -> YHResult<(Vec<YTransaction>, Vec<YCoinbase>)> {
         let start_tx = self.internal();
         let mut height = start_tx.outputs[0].height;
@@ -177,306 +193,395 @@ impl YTransaction {
     }
 
     pub fn create<S: YStorage>(&self, store: &mut S) -> YHResult<()> {
-        let store_buck = YBucket::Transactions.to_store_buck();
-        let key = self.key()?;
-        if store.lookup(&store_buck, &key)? {
-            return Err(YHErrorKind::AlreadyFound.into());
-        }
-        let value = self.value()?;
-        store.put(&store_buck, &key, &value)
+        atomically(store, |store| {
+            let store_buck = YBucket::Transactions.to_store_buck();
+            let key = self.key()?;
+            if store.lookup(&store_buck, &key)? {
+                return Err(YHErrorKind::AlreadyFound.into());
+            }
+            let value = self.value()?;
+            store.put(&store_buck, &key, &value)?;
+            YIndex::put_spender(store, self)
+        })
+    }
+
+    pub fn push<S: YStorage>(store: &mut S, _tx: &LibTransaction) -> YHResult<YTransaction> {
+        atomically(store, |store| {
+            _tx.check()?;
+            let id = _tx.id;
+            if YTransaction::lookup(store, id)? {
+                return Err(YHErrorKind::AlreadyFound.into());
+            }
+
+            let outputs = _tx.outputs.clone();
+
+            for idx in 0..outputs.len() {
+                let output = outputs[idx].clone();
+                if let Some(_data) = output.data.clone() {
+                    if !YData::lookup(store, _data.checksum, _data.tag)? {
+                        let data = YData::new(&_data)?;
+                        data.create(store)?;
+                    }
+                }
+                let _utxo = LibUTXO::from_output(&output, id, idx as u32);
+                YUTXO::new(&_utxo).create(store)?;
+            }
+
+            let tx = YTransaction::new(_tx)?;
+            tx.create(store)?;
+
+            Ok(tx)
+        })
     }
 
     pub fn create_raw<S: YStorage>(store: &mut S, key: YKey32, wallet_name: &str, raw: &str, _sks: &Vec<YSecretKey>) -> YHResult<YTransaction> {
-        let _tx = LibTransaction::from_hex(raw)?;
-        let date = _tx.time.clone();
-        let kind = YCoinKind::Transaction;
-        let id = _tx.id;
-        let inputs = _tx.inputs.clone();
-        let outputs = _tx.outputs.clone();
-        let height = outputs[0].height;
-
-        let mut sks = _sks.clone();
-        sks.dedup();
+        atomically(store, |store| {
+            let _tx = LibTransaction::from_hex(raw)?;
+            let date = _tx.time.clone();
+            let kind = YCoinKind::Transaction;
+            let id = _tx.id;
+            let inputs = _tx.inputs.clone();
+            let outputs = _tx.outputs.clone();
+            let height = outputs[0].height;
+
+            let mut sks = _sks.clone();
+            sks.dedup();
             
-        if sks.len() != outputs.len() {
-            return Err(YHErrorKind::InvalidLength.into());
-        }
+            if sks.len() != outputs.len() {
+                return Err(YHErrorKind::InvalidLength.into());
+            }
 
-        let mut wallet = YWallet::get(store, key, wallet_name)?;
-
-        for input in inputs {
-            let id = input.id;
-            let idx = input.idx;
-            let height = input.height;
-
-            let ucoins_len = wallet.ucoins.len();
-
-            if height != 0 {
-                let tx = YTransaction::get(store, id)?.internal();
-                let date = tx.time.clone();
-                let kind = YCoinKind::Transaction;
-                let mut found = false;
-
-                for i in 0..ucoins_len {
-                    let ucoin = wallet.ucoins[i].clone();
-                    if ucoin.date == date &&
-                        ucoin.kind == kind &&
-                        ucoin.id == id &&
-                        ucoin.idx == idx &&
-                        ucoin.height == height {
-                        wallet.ucoins.remove(i);
-                        wallet.scoins.push(ucoin);
-                        found = true;
+            let mut wallet = YWallet::get(store, key, wallet_name)?;
+
+            for input in inputs {
+                let id = input.id;
+                let idx = input.idx;
+                let height = input.height;
+
+                let ucoins_len = wallet.ucoins.len();
+
+                if height != 0 {
+                    let tx = YTransaction::get(store, id)?.internal();
+                    let date = tx.time.clone();
+                    let kind = YCoinKind::Transaction;
+                    let mut found = false;
+
+                    for i in 0..ucoins_len {
+                        let ucoin = wallet.ucoins[i].clone();
+                        if ucoin.date == date &&
+                            ucoin.kind == kind &&
+                            ucoin.id == id &&
+                            ucoin.idx == idx &&
+                            ucoin.height == height {
+                            wallet.ucoins.remove(i);
+                            wallet.scoins.push(ucoin);
+                            found = true;
+                        }
                     }
-                }
-                if !found {
-                    return Err(YHErrorKind::NotFound.into());
-                }
-            } else {
-                let cb = YCoinbase::get(store, id)?.internal();
-                let date = cb.time.clone();
-                let kind = YCoinKind::Coinbase;
-                let mut found = false;
-
-                for i in 0..ucoins_len {
-                    let ucoin = wallet.ucoins[i].clone();
-                    if ucoin.date == date &&
-                        ucoin.kind == kind &&
-                        ucoin.id == id &&
-                        ucoin.idx == idx &&
-                        ucoin.height == height {
-                        wallet.ucoins.remove(i);
-                        wallet.scoins.push(ucoin);
-                        found = true;
+                    if !found {
+                        return Err(YHErrorKind::NotFound.into());
+                    }
+                } else {
+                    let cb = YCoinbase::get(store, id)?.internal();
+                    let date = cb.time.clone();
+                    let kind = YCoinKind::Coinbase;
+                    let mut found = false;
+
+                    for i in 0..ucoins_len {
+                        let ucoin = wallet.ucoins[i].clone();
+                        if ucoin.date == date &&
+                            ucoin.kind == kind &&
+                            ucoin.id == id &&
+                            ucoin.idx == idx &&
+                            ucoin.height == height {
+                            wallet.ucoins.remove(i);
+                            wallet.scoins.push(ucoin);
+                            found = true;
+                        }
+                    }
+                    if !found {
+                        return Err(YHErrorKind::NotFound.into());
                     }
-                }
-                if !found {
-                    return Err(YHErrorKind::NotFound.into());
                 }
             }
-        }
        
-        for idx in 0..outputs.len() {
-            let output = outputs[idx].clone();
-            let has_data = output.data.is_some();
-            let tag = if !has_data {
-                None
-            } else {
-                Some(output.data.clone().unwrap().tag)
-            };
-            let amount = output.amount.clone();
-            let coin = YCoin {
-                date: date.clone(),
-                sk: sks[idx],
-                kind: kind,
-                id: id,
-                idx: idx as u32,
-                height: height,
-                has_data: has_data,
-                tag: tag,
-                amount: amount,
-            };
-            wallet.ucoins.push(coin);
-            if has_data {
-                let _data = output.clone().data.unwrap();
-                let data = YData::new(&_data)?;
-                data.create(store)?;
+            for idx in 0..outputs.len() {
+                let output = outputs[idx].clone();
+                let has_data = output.data.is_some();
+                let tag = if !has_data {
+                    None
+                } else {
+                    Some(output.data.clone().unwrap().tag)
+                };
+                let amount = output.amount.clone();
+                let coin = YCoin {
+                    date: date.clone(),
+                    sk: sks[idx],
+                    kind: kind,
+                    id: id,
+                    idx: idx as u32,
+                    height: height,
+                    has_data: has_data,
+                    tag: tag,
+                    amount: amount,
+                };
+                wallet.ucoins.push(coin);
+                if has_data {
+                    let _data = output.clone().data.unwrap();
+                    let data = YData::new(&_data)?;
+                    data.create(store)?;
+                    YIndex::put_wallet_data(store, key, wallet_name, &data)?;
+                }
+                let _utxo = LibUTXO::from_output(&output, id, idx as u32);
+                YUTXO::new(&_utxo).create(store)?;
             }
-            let _utxo = LibUTXO::from_output(&output, id, idx as u32);
-            YUTXO::new(&_utxo).create(store)?;
-        }
 
-        let tx = YTransaction(_tx);
-        tx.create(store)?;
+            let tx = YTransaction(_tx);
+            tx.create(store)?;
+            YIndex::put_wallet_tx(store, key, wallet_name, tx.0.id)?;
 
-        wallet.update(store, key)?;
+            wallet.update(store, key)?;
 
-        Ok(tx)
+            Ok(tx)
+        })
     }
 
-    pub fn create_coins<S: YStorage>(store: &mut S, key: YKey32, wallet_name: &str, to: YPublicKey, amount: YAmount, keep_data: bool) -> YHResult<YTransaction> {
-        let coins_sk = YSecretKey::random();
-        let change_sk = YSecretKey::random();
-        let change_pk = change_sk.to_public();
-        
-        let mut wallet = YWallet::get(store, key, wallet_name)?;
-        
-        let ucoins = if keep_data {
-            wallet.select_coins_no_data(amount.clone())?
-        } else {
-            wallet.select_coins(amount.clone())?
-        };
-       
-        let mut sks = Vec::new();
-        let mut xs = Vec::new();
+    fn spend_ucoins(wallet: &mut YWallet, ucoins: &Vec<YCoin>) -> YHResult<()> {
         for ucoin in ucoins.clone() {
-            sks.push(ucoin.sk);
-            xs.push(ucoin.sk.sk);
+            let pos = wallet.ucoins.iter().position(|c| *c == ucoin);
+            match pos {
+                Some(i) => {
+                    wallet.ucoins.remove(i);
+                    wallet.balance = wallet.balance.clone() - ucoin.amount.clone();
+                    wallet.scoins.push(ucoin);
+                },
+                None => {
+                    return Err(YHErrorKind::NotFound.into());
+                },
+            }
         }
-        
-        let mut utxos = Vec::new();
+        Ok(())
+    }
 
-        for ucoin in ucoins.clone() {
-            let id = ucoin.id;
-            let idx = ucoin.idx;
-            let height = ucoin.height;
-            let recipient = ucoin.sk.to_public();
-            let amount = ucoin.amount;
-            let utxo = LibUTXO::new(id, idx, height, recipient, amount);
-            utxos.push(utxo);
+    // the fee is paid by a separate transaction to the confirming node, built in the same store transaction
+    fn pay_fee<S: YStorage, C: YCoinSelector>(store: &mut S, key: YKey32, wallet_name: &str, txs: &Vec<YTransaction>, selector: &C, max_inputs: u32, fee: &YFee) -> YHResult<Option<YTransaction>> {
+        let _txs = txs.iter().map(|tx| tx.internal()).collect();
+        let amount = fee.amount_for_all(&_txs)?;
+        if amount == YAmount::zero() {
+            return Ok(None);
         }
+        let (fee_tx, _) = YTransaction::create_coins(store, key, wallet_name, fee.pk, amount, true, selector, max_inputs, &YFee::zero())?;
+        Ok(Some(fee_tx))
+    }
 
-        let _tx = LibTransaction::new_coins(&coins_sk, &change_sk,
-                                            &to, &change_pk, amount,
-                                            &utxos, &xs,
-                                            None, None)?;
-
-        // NB: change it if the coin selection
-        //     is improved
-        for i in 0..ucoins.len() {
-            let ucoin = ucoins[i].clone();
-            wallet.ucoins.remove(i);
-            wallet.scoins.push(ucoin);
-        }
+    pub fn create_coins<S: YStorage, C: YCoinSelector>(store: &mut S, key: YKey32, wallet_name: &str, to: YPublicKey, amount: YAmount, keep_data: bool, selector: &C, max_inputs: u32, fee: &YFee) -> YHResult<(YTransaction, Option<YTransaction>)> {
+        atomically(store, |store| {
+            let coins_sk = YSecretKey::random();
 
-        let date = _tx.time.clone();
-        let kind = YCoinKind::Transaction;
-        let id = _tx.id;
-
-        let outputs = _tx.outputs.clone();
-
-        for idx in 0..outputs.len() {
-            let output = outputs[idx].clone();
-            let has_data = output.data.is_some();
-            let tag = if !has_data {
-                None
-            } else {
-                Some(output.data.clone().unwrap().tag)
-            };
-            let height = output.height;
-            let amount = output.amount.clone();
-            let coin = YCoin {
-                date: date.clone(),
-                sk: sks[idx],
-                kind: kind,
-                id: id,
-                idx: idx as u32,
-                height: height,
-                has_data: has_data,
-                tag: tag,
-                amount: amount,
-            };
-            wallet.ucoins.push(coin);
-            let _utxo = LibUTXO::from_output(&output, id, idx as u32);
-            YUTXO::new(&_utxo).create(store)?;
-        }
+            let mut wallet = YWallet::get(store, key, wallet_name)?;
+            let change_sk = wallet.next_key(YHD_CHANGE_BRANCH)?;
+            let change_pk = change_sk.to_public();
+        
+            let ucoins = wallet.select_unreserved(store, selector, amount.clone(), max_inputs, keep_data)?;
+       
+            let mut xs = Vec::new();
+            for ucoin in ucoins.clone() {
+                xs.push(ucoin.sk.sk);
+            }
+        
+            let mut utxos = Vec::new();
+
+            for ucoin in ucoins.clone() {
+                let id = ucoin.id;
+                let idx = ucoin.idx;
+                let height = ucoin.height;
+                let recipient = ucoin.sk.to_public();
+                let amount = ucoin.amount;
+                let utxo = LibUTXO::new(id, idx, height, recipient, amount);
+                utxos.push(utxo);
+            }
 
-        let tx = YTransaction(_tx);
-        tx.create(store)?;
+            let _tx = LibTransaction::new_coins(&coins_sk, &change_sk,
+                                                &to, &change_pk, amount,
+                                                &utxos, &xs,
+                                                None, None)?;
 
-        wallet.update(store, key)?;
+            YTransaction::spend_ucoins(&mut wallet, &ucoins)?;
 
-        Ok(tx)
+            let date = _tx.time.clone();
+            let kind = YCoinKind::Transaction;
+            let id = _tx.id;
+
+            let outputs = _tx.outputs.clone();
+
+            for idx in 0..outputs.len() {
+                let output = outputs[idx].clone();
+                let has_data = output.data.is_some();
+                let tag = if !has_data {
+                    None
+                } else {
+                    Some(output.data.clone().unwrap().tag)
+                };
+                if output.recipient == change_pk {
+                    let height = output.height;
+                    let amount = output.amount.clone();
+                    let coin = YCoin {
+                        date: date.clone(),
+                        sk: change_sk,
+                        kind: kind,
+                        id: id,
+                        idx: idx as u32,
+                        height: height,
+                        has_data: has_data,
+                        tag: tag,
+                        amount: amount.clone(),
+                    };
+                    wallet.balance += amount;
+                    wallet.ucoins.push(coin);
+                }
+                let _utxo = LibUTXO::from_output(&output, id, idx as u32);
+                YUTXO::new(&_utxo).create(store)?;
+            }
+
+            let tx = YTransaction(_tx);
+            tx.create(store)?;
+            YIndex::put_wallet_tx(store, key, wallet_name, tx.0.id)?;
+
+            wallet.update(store, key)?;
+
+            let fee_tx = YTransaction::pay_fee(store, key, wallet_name, &vec![tx.clone()], selector, max_inputs, fee)?;
+
+            Ok((tx, fee_tx))
+        })
     }
 
-    pub fn create_data<S: YStorage>(store: &mut S, key: YKey32, wallet_name: &str, to: YPublicKey, buf: &[u8], keep_data: bool) -> YHResult<YTransaction> {
-        let data_sk = YSecretKey::random();
-        let change_sk = YSecretKey::random();
-        let change_pk = change_sk.to_public();
-        
-        let mut wallet = YWallet::get(store, key, wallet_name)?;
+    pub fn create_data<S: YStorage, C: YCoinSelector>(store: &mut S, key: YKey32, wallet_name: &str, to: YPublicKey, buf: &[u8], keep_data: bool, selector: &C, max_inputs: u32, fee: &YFee) -> YHResult<(YTransaction, Option<YTransaction>)> {
+        atomically(store, |store| {
+            let data_sk = YSecretKey::random();
+
+            let mut wallet = YWallet::get(store, key, wallet_name)?;
+            let change_sk = wallet.next_key(YHD_CHANGE_BRANCH)?;
+            let change_pk = change_sk.to_public();
        
-        let amount = YAmount::from_u64((buf.len()*2) as u64)?;
+            let amount = YAmount::from_u64((buf.len()*2) as u64)?;
 
-        let ucoins = if keep_data {
-            wallet.select_coins_no_data(amount)?
-        } else {
-            wallet.select_coins(amount)?
-        };
+            let ucoins = wallet.select_unreserved(store, selector, amount, max_inputs, keep_data)?;
         
-        let mut sks = Vec::new();
-        let mut xs = Vec::new();
-        for ucoin in ucoins.clone() {
-            sks.push(ucoin.sk);
-            xs.push(ucoin.sk.sk);
-        }
+            let mut xs = Vec::new();
+            for ucoin in ucoins.clone() {
+                xs.push(ucoin.sk.sk);
+            }
         
-        let mut utxos = Vec::new();
-
-        for ucoin in ucoins.clone() {
-            let id = ucoin.id;
-            let idx = ucoin.idx;
-            let height = ucoin.height;
-            let recipient = ucoin.sk.to_public();
-            let amount = ucoin.amount;
-            let utxo = LibUTXO::new(id, idx, height, recipient, amount);
-            utxos.push(utxo);
-        }
+            let mut utxos = Vec::new();
+
+            for ucoin in ucoins.clone() {
+                let id = ucoin.id;
+                let idx = ucoin.idx;
+                let height = ucoin.height;
+                let recipient = ucoin.sk.to_public();
+                let amount = ucoin.amount;
+                let utxo = LibUTXO::new(id, idx, height, recipient, amount);
+                utxos.push(utxo);
+            }
         
-        let _tx = LibTransaction::new_data(&data_sk, &change_sk,
-                                           &to, &change_pk, buf,
-                                           &utxos, &xs,
-                                           None, None)?;
-
-        // NB: change it if the coin selection
-        //     is improved
-        for i in 0..ucoins.len() {
-            let ucoin = ucoins[i].clone();
-            wallet.ucoins.remove(i);
-            wallet.scoins.push(ucoin);
-        }
+            let _tx = LibTransaction::new_data(&data_sk, &change_sk,
+                                               &to, &change_pk, buf,
+                                               &utxos, &xs,
+                                               None, None)?;
+
+            YTransaction::spend_ucoins(&mut wallet, &ucoins)?;
+
+            let date = _tx.time.clone();
+            let kind = YCoinKind::Transaction;
+            let id = _tx.id;
+
+            let outputs = _tx.outputs.clone();
 
-        let date = _tx.time.clone();
-        let kind = YCoinKind::Transaction;
-        let id = _tx.id;
-
-        let outputs = _tx.outputs.clone();
-
-        for idx in 0..outputs.len() {
-            let output = outputs[idx].clone();
-            let has_data = output.data.is_some();
-            let tag = if !has_data {
-                None
-            } else {
-                Some(output.data.clone().unwrap().tag)
-            };
-            let height = output.height;
-            let amount = output.amount.clone();
-            let coin = YCoin {
-                date: date.clone(),
-                sk: sks[idx],
-                kind: kind,
-                id: id,
-                idx: idx as u32,
-                height: height,
-                has_data: has_data,
-                tag: tag,
-                amount: amount,
-            };
-            wallet.ucoins.push(coin);
-            if has_data {
-                let _data = output.clone().data.unwrap();
-                let data = YData::new(&_data)?;
-                data.create(store)?;
+            for idx in 0..outputs.len() {
+                let output = outputs[idx].clone();
+                let has_data = output.data.is_some();
+                let tag = if !has_data {
+                    None
+                } else {
+                    Some(output.data.clone().unwrap().tag)
+                };
+                if output.recipient == change_pk {
+                    let height = output.height;
+                    let amount = output.amount.clone();
+                    let coin = YCoin {
+                        date: date.clone(),
+                        sk: change_sk,
+                        kind: kind,
+                        id: id,
+                        idx: idx as u32,
+                        height: height,
+                        has_data: has_data,
+                        tag: tag,
+                        amount: amount.clone(),
+                    };
+                    wallet.balance += amount;
+                    wallet.ucoins.push(coin);
+                }
+                if has_data {
+                    let _data = output.clone().data.unwrap();
+                    let data = YData::new(&_data)?;
+                    data.create(store)?;
+                }
+                let _utxo = LibUTXO::from_output(&output, id, idx as u32);
+                YUTXO::new(&_utxo).create(store)?;
             }
-            let _utxo = LibUTXO::from_output(&output, id, idx as u32);
-            YUTXO::new(&_utxo).create(store)?;
+
+            let tx = YTransaction(_tx);
+            tx.create(store)?;
+            YIndex::put_wallet_tx(store, key, wallet_name, tx.0.id)?;
+
+            wallet.update(store, key)?;
+
+            let fee_tx = YTransaction::pay_fee(store, key, wallet_name, &vec![tx.clone()], selector, max_inputs, fee)?;
+
+            Ok((tx, fee_tx))
+        })
+    }
+
+    // NB: libyobicash transactions pay a single recipient plus change, so a batch can't be one
+    // transaction with a change output yet. Until the library builds multi-output transactions,
+    // a batch is a transaction per payment, all built in one store transaction so that either
+    // every payment is created or none is, and a single fee transaction for the whole batch
+    pub fn create_batch<S: YStorage, C: YCoinSelector>(store: &mut S, key: YKey32, wallet_name: &str, payments: &Vec<(YPublicKey, YAmount)>, keep_data: bool, selector: &C, max_inputs: u32, fee: &YFee) -> YHResult<(Vec<YTransaction>, Option<YTransaction>)> {
+        if payments.is_empty() {
+            return Err(YHErrorKind::InvalidLength.into());
         }
+        atomically(store, |store| {
+            let mut total = YAmount::zero();
+            for &(_, ref amount) in payments.iter() {
+                total += amount.clone();
+            }
+            let wallet = YWallet::get(store, key, wallet_name)?;
+            if wallet.balance < total {
+                return Err(YHErrorKind::NotEnoughFunds.into());
+            }
 
-        let tx = YTransaction(_tx);
-        tx.create(store)?;
+            let mut txs = Vec::new();
+            for &(to, ref amount) in payments.iter() {
+                let (tx, _) = YTransaction::create_coins(store, key, wallet_name, to, amount.clone(), keep_data, selector, max_inputs, &YFee::zero())?;
+                txs.push(tx);
+            }
 
-        wallet.update(store, key)?;
+            let fee_tx = YTransaction::pay_fee(store, key, wallet_name, &txs, selector, max_inputs, fee)?;
 
-        Ok(tx)
+            Ok((txs, fee_tx))
+        })
     }
 
     pub fn delete<S: YStorage>(&self, store: &mut S) -> YHResult<()> {
-        let store_buck = YBucket::Transactions.to_store_buck();
-        let key = self.key()?;
-        if !store.lookup(&store_buck, &key)? {
-            return Err(YHErrorKind::NotFound.into());
-        }
-        store.delete(&store_buck, &key)
+        atomically(store, |store| {
+            let store_buck = YBucket::Transactions.to_store_buck();
+            let key = self.key()?;
+            if !store.lookup(&store_buck, &key)? {
+                return Err(YHErrorKind::NotFound.into());
+            }
+            store.delete(&store_buck, &key)?;
+            YIndex::delete_spender(store, self)
+        })
     }
 }
@@ -0,0 +1,337 @@
+use libyobicash::crypto::key::YKey32;
+use libyobicash::crypto::elliptic::keys::{YSecretKey, YPublicKey};
+use libyobicash::amount::YAmount;
+use libyobicash::utxo::YUTXO as LibUTXO;
+use libyobicash::transaction::YTransaction as LibTransaction;
+use bytes::{BytesMut, BufMut, BigEndian, ByteOrder};
+use serde_json;
+use std::collections::HashMap;
+use store::common::*;
+use models::selection::*;
+use models::fee::*;
+use models::coin::*;
+use models::reservation::*;
+use models::hd::*;
+use models::keys::*;
+use models::utxo::*;
+use models::wallet::*;
+use errors::*;
+
+pub const YUNSIGNED_TX_VERSION: u32 = 1;
+
+#[derive(Clone, Eq, PartialEq, Debug, Default, Serialize, Deserialize)]
+pub struct YUnsignedInput {
+    pub branch: u32,
+    pub idx: u32,
+    pub utxo: YUTXO,
+}
+
+impl YUnsignedInput {
+    pub fn to_bytes(&self) -> YHResult<Vec<u8>> {
+        let mut buf = BytesMut::new();
+        buf.put_u32::<BigEndian>(self.branch);
+        buf.put_u32::<BigEndian>(self.idx);
+        let utxo_buf = self.utxo.to_bytes()?;
+        buf.put_u32::<BigEndian>(utxo_buf.len() as u32);
+        buf.put(utxo_buf);
+        Ok(buf.to_vec())
+    }
+
+    pub fn from_bytes(buf: &[u8]) -> YHResult<YUnsignedInput> {
+        if buf.len() < 12 {
+            return Err(YHErrorKind::InvalidLength.into());
+        }
+        let branch = BigEndian::read_u32(&buf[0..4]);
+        let idx = BigEndian::read_u32(&buf[4..8]);
+        let utxo_size = BigEndian::read_u32(&buf[8..12]) as usize;
+        if buf.len() != 12 + utxo_size {
+            return Err(YHErrorKind::InvalidLength.into());
+        }
+        let utxo = YUTXO::from_bytes(&buf[12..])?;
+        Ok(YUnsignedInput {
+            branch: branch,
+            idx: idx,
+            utxo: utxo,
+        })
+    }
+}
+
+// a coins transaction built by the online node from its wallet coins, without any secret key:
+// the inputs keep their hd paths so that an offline signer holding the seed can sign it,
+// and the fee is paid to the node by a second transaction spending the change
+#[derive(Clone, Eq, PartialEq, Debug, Default, Serialize, Deserialize)]
+pub struct YUnsignedTransaction {
+    pub version: u32,
+    pub account: u32,
+    pub to: YPublicKey,
+    pub amount: YAmount,
+    pub change_idx: u32,
+    pub change_pk: YPublicKey,
+    pub fee_pk: YPublicKey,
+    pub fee: YAmount,
+    pub fee_change_idx: u32,
+    pub fee_change_pk: YPublicKey,
+    pub inputs: Vec<YUnsignedInput>,
+}
+
+impl YUnsignedTransaction {
+    pub fn check(&self) -> YHResult<()> {
+        if self.version != YUNSIGNED_TX_VERSION {
+            return Err(YHErrorKind::InvalidValue.into());
+        }
+        if self.inputs.is_empty() {
+            return Err(YHErrorKind::InvalidLength.into());
+        }
+        let mut total = YAmount::zero();
+        for input in self.inputs.iter() {
+            if input.branch != YHD_RECEIVE_BRANCH && input.branch != YHD_CHANGE_BRANCH {
+                return Err(YHErrorKind::InvalidValue.into());
+            }
+            total += input.utxo.internal().amount;
+        }
+        if total < self.amount.clone() + self.fee.clone() {
+            return Err(YHErrorKind::NotEnoughFunds.into());
+        }
+        Ok(())
+    }
+
+    pub fn to_bytes(&self) -> YHResult<Vec<u8>> {
+        self.check()?;
+        let mut buf = BytesMut::new();
+        buf.put_u32::<BigEndian>(self.version);
+        buf.put_u32::<BigEndian>(self.account);
+        buf.put(self.to.to_bytes());
+        let amount_buf = self.amount.to_bytes();
+        buf.put_u32::<BigEndian>(amount_buf.len() as u32);
+        buf.put(amount_buf);
+        buf.put_u32::<BigEndian>(self.change_idx);
+        buf.put(self.change_pk.to_bytes());
+        buf.put(self.fee_pk.to_bytes());
+        let fee_buf = self.fee.to_bytes();
+        buf.put_u32::<BigEndian>(fee_buf.len() as u32);
+        buf.put(fee_buf);
+        buf.put_u32::<BigEndian>(self.fee_change_idx);
+        buf.put(self.fee_change_pk.to_bytes());
+        buf.put_u32::<BigEndian>(self.inputs.len() as u32);
+        for input in self.inputs.iter() {
+            let input_buf = input.to_bytes()?;
+            buf.put_u32::<BigEndian>(input_buf.len() as u32);
+            buf.put(input_buf);
+        }
+        Ok(buf.to_vec())
+    }
+
+    pub fn from_bytes(buf: &[u8]) -> YHResult<YUnsignedTransaction> {
+        let mut b = BytesMut::new();
+        b.extend_from_slice(buf);
+        let version = BigEndian::read_u32(b.get(0..4).ok_or(YHErrorKind::InvalidLength)?);
+        let account = BigEndian::read_u32(b.get(4..8).ok_or(YHErrorKind::InvalidLength)?);
+        let to = YPublicKey::from_bytes(b.get(8..72).ok_or(YHErrorKind::InvalidLength)?)?;
+        let mut i = 72;
+        let amount_size = BigEndian::read_u32(b.get(i..i+4).ok_or(YHErrorKind::InvalidLength)?) as usize;
+        i += 4;
+        let amount = YAmount::from_bytes(b.get(i..i+amount_size).ok_or(YHErrorKind::InvalidLength)?);
+        i += amount_size;
+        let change_idx = BigEndian::read_u32(b.get(i..i+4).ok_or(YHErrorKind::InvalidLength)?);
+        i += 4;
+        let change_pk = YPublicKey::from_bytes(b.get(i..i+64).ok_or(YHErrorKind::InvalidLength)?)?;
+        i += 64;
+        let fee_pk = YPublicKey::from_bytes(b.get(i..i+64).ok_or(YHErrorKind::InvalidLength)?)?;
+        i += 64;
+        let fee_size = BigEndian::read_u32(b.get(i..i+4).ok_or(YHErrorKind::InvalidLength)?) as usize;
+        i += 4;
+        let fee = YAmount::from_bytes(b.get(i..i+fee_size).ok_or(YHErrorKind::InvalidLength)?);
+        i += fee_size;
+        let fee_change_idx = BigEndian::read_u32(b.get(i..i+4).ok_or(YHErrorKind::InvalidLength)?);
+        i += 4;
+        let fee_change_pk = YPublicKey::from_bytes(b.get(i..i+64).ok_or(YHErrorKind::InvalidLength)?)?;
+        i += 64;
+        let count = BigEndian::read_u32(b.get(i..i+4).ok_or(YHErrorKind::InvalidLength)?);
+        i += 4;
+        let mut inputs = Vec::new();
+        for _ in 0..count {
+            let size = BigEndian::read_u32(b.get(i..i+4).ok_or(YHErrorKind::InvalidLength)?) as usize;
+            i += 4;
+            inputs.push(YUnsignedInput::from_bytes(b.get(i..i+size).ok_or(YHErrorKind::InvalidLength)?)?);
+            i += size;
+        }
+        if i != buf.len() {
+            return Err(YHErrorKind::InvalidLength.into());
+        }
+        let unsigned = YUnsignedTransaction {
+            version: version,
+            account: account,
+            to: to,
+            amount: amount,
+            change_idx: change_idx,
+            change_pk: change_pk,
+            fee_pk: fee_pk,
+            fee: fee,
+            fee_change_idx: fee_change_idx,
+            fee_change_pk: fee_change_pk,
+            inputs: inputs,
+        };
+        unsigned.check()?;
+        Ok(unsigned)
+    }
+
+    pub fn to_json(&self) -> YHResult<String> {
+        let json = serde_json::to_string(self)?;
+        Ok(json)
+    }
+
+    pub fn from_json(s: &str) -> YHResult<YUnsignedTransaction> {
+        let unsigned = serde_json::from_str(s)?;
+        Ok(unsigned)
+    }
+
+    // the hd paths of the wallet keys by public key, derived once per transaction
+    fn paths(wallet: &YWallet) -> YHResult<HashMap<Vec<u8>, (u32, u32)>> {
+        let mut paths = HashMap::new();
+        for &(branch, count) in [(YHD_RECEIVE_BRANCH, wallet.receive_idx), (YHD_CHANGE_BRANCH, wallet.change_idx)].iter() {
+            for idx in 0..count {
+                let pk = wallet.derive_key(branch, idx)?.to_public();
+                paths.insert(pk.to_bytes(), (branch, idx));
+            }
+        }
+        Ok(paths)
+    }
+
+    fn reserve_change_key<S: YStorage>(store: &mut S, key: YKey32, wallet: &mut YWallet) -> YHResult<YPublicKey> {
+        let sk = wallet.next_key(YHD_CHANGE_BRANCH)?;
+        let keys = YKeys::from_wallet(sk, key, &wallet.name)?;
+        if !YKeys::lookup(store, keys.pk)? {
+            keys.create(store)?;
+        }
+        Ok(keys.pk)
+    }
+
+    fn to_inputs(paths: &HashMap<Vec<u8>, (u32, u32)>, ucoins: &Vec<YCoin>) -> YHResult<Vec<YUnsignedInput>> {
+        let mut inputs = Vec::new();
+        for ucoin in ucoins.iter() {
+            let &(branch, idx) = paths.get(&ucoin.sk.to_public().to_bytes())
+                .ok_or(YHErrorKind::NotFound)?;
+            let utxo = LibUTXO::new(ucoin.id, ucoin.idx, ucoin.height, ucoin.sk.to_public(), ucoin.amount.clone());
+            inputs.push(YUnsignedInput {
+                branch: branch,
+                idx: idx,
+                utxo: YUTXO::new(&utxo),
+            });
+        }
+        Ok(inputs)
+    }
+
+    // the selected coins stay unspent until the signed transaction is pushed back, but are reserved
+    // so that no other transaction selects them, and the change keys are linked to the wallet
+    pub fn create<S: YStorage, C: YCoinSelector>(store: &mut S, key: YKey32, wallet_name: &str, to: YPublicKey, amount: YAmount, keep_data: bool, selector: &C, max_inputs: u32, fee: &YFee) -> YHResult<YUnsignedTransaction> {
+        atomically(store, |store| {
+            let mut wallet = YWallet::get(store, key, wallet_name)?;
+            if !wallet.is_hd() {
+                return Err(YHErrorKind::NotHDWallet.into());
+            }
+
+            // the coins paid to the node key have no hd path, so an offline signer couldn't sign them
+            let paths = YUnsignedTransaction::paths(&wallet)?;
+            let has_path = |ucoin: &YCoin| paths.contains_key(&ucoin.sk.to_public().to_bytes());
+
+            let change_idx = wallet.change_idx;
+            let change_pk = YUnsignedTransaction::reserve_change_key(store, key, &mut wallet)?;
+
+            let mut unsigned = YUnsignedTransaction {
+                version: YUNSIGNED_TX_VERSION,
+                account: wallet.account,
+                to: to,
+                amount: amount,
+                change_idx: change_idx,
+                change_pk: change_pk,
+                fee_pk: fee.pk,
+                fee: YAmount::zero(),
+                fee_change_idx: 0,
+                fee_change_pk: YPublicKey::default(),
+                inputs: Vec::new(),
+            };
+
+            // the fee grows with the inputs selected to pay it, so the selection is repeated until it covers it
+            let mut ucoins;
+            loop {
+                let total = unsigned.amount.clone() + unsigned.fee.clone();
+                ucoins = wallet.select_unreserved_by(store, selector, total, max_inputs, keep_data, &has_path)?;
+                unsigned.inputs = YUnsignedTransaction::to_inputs(&paths, &ucoins)?;
+                let fee_amount = fee.amount_for_size(unsigned.to_bytes()?.len() as u32)?;
+                if fee_amount <= unsigned.fee {
+                    break;
+                }
+                unsigned.fee = fee_amount;
+            }
+
+            if unsigned.fee != YAmount::zero() {
+                unsigned.fee_change_idx = wallet.change_idx;
+                unsigned.fee_change_pk = YUnsignedTransaction::reserve_change_key(store, key, &mut wallet)?;
+            }
+
+            YReservation::reserve(store, &ucoins)?;
+            wallet.update(store, key)?;
+
+            unsigned.check()?;
+            Ok(unsigned)
+        })
+    }
+
+    fn change_key(&self, root: &YHDKey, idx: u32, pk: YPublicKey) -> YHResult<YSecretKey> {
+        let sk = root.derive(&[self.account, YHD_CHANGE_BRANCH, idx]).secret_key()?;
+        if sk.to_public() != pk {
+            return Err(YHErrorKind::InvalidValue.into());
+        }
+        Ok(sk)
+    }
+
+    // libyobicash signs every input at once while building the transaction,
+    // so the signer needs the keys of all the inputs
+    pub fn sign(&self, root: &YHDKey) -> YHResult<(LibTransaction, Option<LibTransaction>)> {
+        self.check()?;
+        let change_sk = self.change_key(root, self.change_idx, self.change_pk)?;
+
+        let mut utxos = Vec::new();
+        let mut xs = Vec::new();
+        for input in self.inputs.iter() {
+            let sk = root.derive(&[self.account, input.branch, input.idx]).secret_key()?;
+            let utxo = input.utxo.internal();
+            if sk.to_public() != utxo.recipient {
+                return Err(YHErrorKind::InvalidValue.into());
+            }
+            xs.push(sk.sk);
+            utxos.push(utxo);
+        }
+
+        let coins_sk = YSecretKey::random();
+        let tx = LibTransaction::new_coins(&coins_sk, &change_sk,
+                                           &self.to, &self.change_pk, self.amount.clone(),
+                                           &utxos, &xs,
+                                           None, None)?;
+        if self.fee == YAmount::zero() {
+            return Ok((tx, None));
+        }
+
+        let fee_change_sk = self.change_key(root, self.fee_change_idx, self.fee_change_pk)?;
+        let idx = tx.outputs.iter()
+            .position(|output| output.recipient == self.change_pk)
+            .ok_or(YHErrorKind::NotEnoughFunds)?;
+        let change = LibUTXO::from_output(&tx.outputs[idx], tx.id, idx as u32);
+        let fee_tx = LibTransaction::new_coins(&YSecretKey::random(), &fee_change_sk,
+                                               &self.fee_pk, &self.fee_change_pk, self.fee.clone(),
+                                               &vec![change], &vec![change_sk.sk],
+                                               None, None)?;
+        Ok((tx, Some(fee_tx)))
+    }
+
+    // the signer can use a wallet export instead of typing the seed phrase
+    pub fn sign_with_wallet(&self, wallet: &YWallet) -> YHResult<(LibTransaction, Option<LibTransaction>)> {
+        if !wallet.is_hd() {
+            return Err(YHErrorKind::NotHDWallet.into());
+        }
+        if wallet.account != self.account {
+            return Err(YHErrorKind::InvalidValue.into());
+        }
+        self.sign(&wallet.root_key()?)
+    }
+}
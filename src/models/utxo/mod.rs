@@ -6,6 +6,7 @@ use store::common::*;
 use models::bucket::*;
 use models::transaction::*;
 use models::coinbase::*;
+use models::index::*;
 use errors::*;
 
 #[derive(Clone, Eq, PartialEq, Debug, Default, Serialize, Deserialize)]
@@ -79,6 +80,18 @@ impl YUTXO {
         Ok(utxos)
     }
 
+    pub fn list_from<S: YStorage>(store: &S, after: &Option<YStoreKey>, limit: u32) -> YHResult<(Vec<YUTXO>, Option<YStoreKey>)> {
+        let store_buck = YBucket::UTXO.to_store_buck();
+        let page = store.list_from(&store_buck, after, limit)?;
+        let mut utxos = Vec::new();
+        for key in page.keys {
+            let item = store.get(&store_buck, &key)?;
+            let utxo = YUTXO::from_value(&item.value)?;
+            utxos.push(utxo);
+        }
+        Ok((utxos, page.next))
+    }
+
     pub fn list_by_tx<S: YStorage>(store: &S, id: YDigest64) -> YHResult<Vec<YUTXO>> {
         let tx = YTransaction::get(store, id)?;
         let outputs_len = tx.internal().outputs.len() as u32;
@@ -121,21 +134,27 @@ impl YUTXO {
     }
 
     pub fn create<S: YStorage>(&self, store: &mut S) -> YHResult<()> {
-        let store_buck = YBucket::UTXO.to_store_buck();
-        let key = self.key()?;
-        if store.lookup(&store_buck, &key)? {
-            return Err(YHErrorKind::AlreadyFound.into());
-        }
-        let value = self.value()?;
-        store.put(&store_buck, &key, &value)
+        atomically(store, |store| {
+            let store_buck = YBucket::UTXO.to_store_buck();
+            let key = self.key()?;
+            if store.lookup(&store_buck, &key)? {
+                return Err(YHErrorKind::AlreadyFound.into());
+            }
+            let value = self.value()?;
+            store.put(&store_buck, &key, &value)?;
+            YIndex::put_utxo(store, self)
+        })
     }
 
     pub fn delete<S: YStorage>(&self, store: &mut S) -> YHResult<()> {
-        let store_buck = YBucket::UTXO.to_store_buck();
-        let key = self.key()?;
-        if !store.lookup(&store_buck, &key)? {
-            return Err(YHErrorKind::NotFound.into());
-        }
-        store.delete(&store_buck, &key)
+        atomically(store, |store| {
+            let store_buck = YBucket::UTXO.to_store_buck();
+            let key = self.key()?;
+            if !store.lookup(&store_buck, &key)? {
+                return Err(YHErrorKind::NotFound.into());
+            }
+            store.delete(&store_buck, &key)?;
+            YIndex::delete_utxo(store, self)
+        })
     }
 }
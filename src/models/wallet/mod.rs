@@ -1,12 +1,25 @@
 use libyobicash::errors::YErrorKind as LibErrorKind;
 use libyobicash::amount::YAmount;
+use libyobicash::utils::time::YTime;
+use libyobicash::crypto::hash::digest::YDigest64;
 use libyobicash::crypto::key::YKey32;
+use libyobicash::crypto::elliptic::keys::{YSecretKey, YPublicKey};
 use libyobicash::crypto::encryption::symmetric::YSymmetricEncryption as YSE;
+use libyobicash::transaction::YTransaction as LibTransaction;
 use serde_json;
 use bytes::{BytesMut, BufMut, BigEndian, ByteOrder};
 use store::common::*;
 use models::bucket::*;
 use models::coin::*;
+use models::selection::*;
+use models::hd::*;
+use models::keys::*;
+use models::utxo::*;
+use models::data::*;
+use models::transaction::*;
+use models::coinbase::*;
+use models::index::*;
+use models::reservation::*;
 use errors::*;
 
 #[derive(Clone, Eq, PartialEq, Debug, Serialize, Deserialize)]
@@ -15,6 +28,20 @@ pub struct YWallet {
     pub balance: YAmount,
     pub scoins: Vec<YCoin>,
     pub ucoins: Vec<YCoin>,
+    #[serde(default)]
+    pub seed: Vec<u8>,
+    #[serde(default)]
+    pub account: u32,
+    #[serde(default)]
+    pub receive_idx: u32,
+    #[serde(default)]
+    pub change_idx: u32,
+}
+
+impl Default for YWallet {
+    fn default() -> YWallet {
+        YWallet::new("")
+    }
 }
 
 impl YWallet {
@@ -24,10 +51,59 @@ impl YWallet {
             balance: YAmount::zero(),
             scoins: Vec::new(),
             ucoins: Vec::new(),
+            seed: YMnemonic::gen_entropy(),
+            account: 0,
+            receive_idx: 0,
+            change_idx: 0,
+        }
+    }
+
+    pub fn from_mnemonic(name: &str, phrase: &str) -> YHResult<YWallet> {
+        let mut wallet = YWallet::new(name);
+        wallet.seed = YMnemonic::to_entropy(phrase)?;
+        Ok(wallet)
+    }
+
+    pub fn is_hd(&self) -> bool {
+        !self.seed.is_empty()
+    }
+
+    pub fn mnemonic(&self) -> YHResult<String> {
+        if !self.is_hd() {
+            return Err(YHErrorKind::NotHDWallet.into());
         }
+        YMnemonic::from_entropy(&self.seed)
+    }
+
+    pub fn root_key(&self) -> YHResult<YHDKey> {
+        YHDKey::from_mnemonic(&self.mnemonic()?)
+    }
+
+    pub fn derive_key(&self, branch: u32, idx: u32) -> YHResult<YSecretKey> {
+        self.root_key()?
+            .derive(&[self.account, branch, idx])
+            .secret_key()
+    }
+
+    // wallets created before hd derivation keep using random keys
+    pub fn next_key(&mut self, branch: u32) -> YHResult<YSecretKey> {
+        if !self.is_hd() {
+            return Ok(YSecretKey::random());
+        }
+        let idx = if branch == YHD_CHANGE_BRANCH {
+            self.change_idx += 1;
+            self.change_idx - 1
+        } else {
+            self.receive_idx += 1;
+            self.receive_idx - 1
+        };
+        self.derive_key(branch, idx)
     }
 
     pub fn check(&self) -> YHResult<()> {
+        if self.is_hd() {
+            YMnemonic::from_entropy(&self.seed)?;
+        }
         let scoins_len = self.scoins.len();
         for i in 0..scoins_len {
             self.scoins[i].check()?;
@@ -68,47 +144,87 @@ impl YWallet {
             buf.put_u32::<BigEndian>(size as u32);
             buf.put(ucoin_buf);
         }
+        if self.is_hd() {
+            buf.put_u32::<BigEndian>(self.seed.len() as u32);
+            buf.put(self.seed.as_slice());
+            buf.put_u32::<BigEndian>(self.account);
+            buf.put_u32::<BigEndian>(self.receive_idx);
+            buf.put_u32::<BigEndian>(self.change_idx);
+        }
         Ok(buf.to_vec())
     }
 
     pub fn from_bytes(buf: &[u8]) -> YHResult<YWallet> {
-        if buf.len() < 8 {
+        if buf.len() < 16 {
             return Err(YHErrorKind::InvalidLength.into());
         }
         let mut b = BytesMut::new();
         b.extend_from_slice(buf);
-        let name_len = BigEndian::read_u32(b.get(0..4).unwrap()) as usize;
-        let i = 4 + name_len;
-        let name = String::from_utf8_lossy(b.get(4..i).unwrap()).into();
-        let balance_size = BigEndian::read_u32(b.get(i..4+i).unwrap()) as usize;
-        let j = 4 + i + balance_size;
-        let balance = YAmount::from_bytes(b.get(4+i..j).unwrap());
-        let scoins_len = BigEndian::read_u32(b.get(j..4+j).unwrap()) as usize;
+        let name_size = BigEndian::read_u32(b.get(0..4).unwrap()) as usize;
+        let mut i = 4;
+        let name = String::from_utf8(b.get(i..i+name_size).ok_or(YHErrorKind::InvalidLength)?.to_vec())?;
+        i += name_size;
+        let balance_size = BigEndian::read_u32(b.get(i..i+4).ok_or(YHErrorKind::InvalidLength)?) as usize;
+        i += 4;
+        let balance = YAmount::from_bytes(b.get(i..i+balance_size).ok_or(YHErrorKind::InvalidLength)?);
+        i += balance_size;
+        let scoins_len = BigEndian::read_u32(b.get(i..i+4).ok_or(YHErrorKind::InvalidLength)?) as usize;
+        i += 4;
         let mut scoins = Vec::new();
-        for k in 0..scoins_len {
-            let scoin_size = BigEndian::read_u32(b.get(k..k+4).unwrap()) as usize;
-            let kk = k+4+scoin_size;
-            let scoin = YCoin::from_bytes(b.get(k+4..kk).unwrap())?;
+        for _ in 0..scoins_len {
+            let scoin_size = BigEndian::read_u32(b.get(i..i+4).ok_or(YHErrorKind::InvalidLength)?) as usize;
+            i += 4;
+            let scoin = YCoin::from_bytes(b.get(i..i+scoin_size).ok_or(YHErrorKind::InvalidLength)?)?;
+            i += scoin_size;
             scoins.push(scoin);
         }
-        let ucoins_len = BigEndian::read_u32(b.get(j..4+j).unwrap()) as usize;
+        let ucoins_len = BigEndian::read_u32(b.get(i..i+4).ok_or(YHErrorKind::InvalidLength)?) as usize;
+        i += 4;
         let mut ucoins = Vec::new();
-        for k in 0..ucoins_len {
-            let ucoin_size = BigEndian::read_u32(b.get(k..k+4).unwrap()) as usize;
-            let kk = k+4+ucoin_size;
-            let ucoin = YCoin::from_bytes(b.get(k+4..kk).unwrap())?;
+        for _ in 0..ucoins_len {
+            let ucoin_size = BigEndian::read_u32(b.get(i..i+4).ok_or(YHErrorKind::InvalidLength)?) as usize;
+            i += 4;
+            let ucoin = YCoin::from_bytes(b.get(i..i+ucoin_size).ok_or(YHErrorKind::InvalidLength)?)?;
+            i += ucoin_size;
             ucoins.push(ucoin);
         }
+        let mut seed = Vec::new();
+        let mut account = 0;
+        let mut receive_idx = 0;
+        let mut change_idx = 0;
+        if i != buf.len() {
+            let seed_size = BigEndian::read_u32(b.get(i..i+4).ok_or(YHErrorKind::InvalidLength)?) as usize;
+            i += 4;
+            seed = b.get(i..i+seed_size).ok_or(YHErrorKind::InvalidLength)?.to_vec();
+            i += seed_size;
+            account = BigEndian::read_u32(b.get(i..i+4).ok_or(YHErrorKind::InvalidLength)?);
+            i += 4;
+            receive_idx = BigEndian::read_u32(b.get(i..i+4).ok_or(YHErrorKind::InvalidLength)?);
+            i += 4;
+            change_idx = BigEndian::read_u32(b.get(i..i+4).ok_or(YHErrorKind::InvalidLength)?);
+            i += 4;
+        }
+        if i != buf.len() {
+            return Err(YHErrorKind::InvalidLength.into());
+        }
         let wallet = YWallet {
             name: name,
             balance: balance,
             scoins: scoins,
             ucoins: ucoins,
+            seed: seed,
+            account: account,
+            receive_idx: receive_idx,
+            change_idx: change_idx,
         };
         wallet.check()?;
         Ok(wallet)
     }
 
+    pub fn to_public(&self) -> YPublicWallet {
+        YPublicWallet::from_wallet(self)
+    }
+
     pub fn to_json(&self) -> YHResult<String> {
         let json = serde_json::to_string(self)?;
         Ok(json)
@@ -119,11 +235,10 @@ impl YWallet {
         Ok(wallet)
     }
 
-    pub fn enc_key(&self, ekey: YKey32) -> YHResult<YStoreKey> {
-        self.check()?;
+    pub fn enc_name(ekey: YKey32, name: &str) -> YHResult<YStoreKey> {
         let mut key = Vec::new();
-        key.put(self.name.as_bytes());
-        let padding = key.len() % 16;
+        key.put(name.as_bytes());
+        let padding = (16 - key.len() % 16) % 16;
         for _ in 0..padding {
             key.push(0);
         }
@@ -131,6 +246,11 @@ impl YWallet {
         Ok(key)
     }
 
+    pub fn enc_key(&self, ekey: YKey32) -> YHResult<YStoreKey> {
+        self.check()?;
+        YWallet::enc_name(ekey, &self.name)
+    }
+
     pub fn enc_value(&self, ekey: YKey32) -> YHResult<YStoreValue> {
         self.check()?;
         let wallet_buf = self.to_bytes()?;
@@ -138,7 +258,7 @@ impl YWallet {
         let mut value = Vec::new();
         value.put_u32::<BigEndian>(wallet_len);
         value.put(wallet_buf);
-        let padding = value.len() % 16;
+        let padding = (16 - value.len() % 16) % 16;
         for _ in 0..padding {
             value.push(0);
         }
@@ -148,20 +268,17 @@ impl YWallet {
 
     pub fn dec_value(ekey: YKey32, value: &YStoreValue) -> YHResult<YWallet> {
         let dec = YSE::decrypt(ekey, value)?;
-        let wallet_len = BigEndian::read_u32(dec.get(0..4).unwrap());
-        let wallet_buf = dec.get(4..wallet_len as usize).unwrap();
+        if dec.len() < 4 {
+            return Err(YHErrorKind::InvalidLength.into());
+        }
+        let wallet_len = BigEndian::read_u32(dec.get(0..4).unwrap()) as usize;
+        let wallet_buf = dec.get(4..4+wallet_len).ok_or(YHErrorKind::InvalidLength)?;
         YWallet::from_bytes(wallet_buf)
     }
 
     pub fn lookup<S: YStorage>(store: &S, ekey: YKey32, name: &str) -> YHResult<bool> {
         let store_buck = YBucket::Wallets.to_store_buck();
-        let mut key = Vec::new();
-        key.put(name.as_bytes());
-        let padding = key.len() % 16;
-        for _ in 0..padding {
-            key.push(0);
-        }
-        key = YSE::encrypt(ekey, &key)?;
+        let key = YWallet::enc_name(ekey, name)?;
         store.lookup(&store_buck, &key)
     }
 
@@ -182,64 +299,218 @@ impl YWallet {
         Ok(wallets)
     }
 
-    pub fn select_coins_no_data(&self, amount: YAmount) -> YHResult<Vec<YCoin>> {
-        if self.balance < amount {
-            return Err(YHErrorKind::NotEnoughFunds.into());    
-        }
-        let mut coins = Vec::new();
-        let mut tot_amount = YAmount::zero();
-        for ucoin in self.ucoins.clone() {
-            if !ucoin.has_data {
-                coins.push(ucoin.clone());
-                tot_amount += ucoin.amount;
-                if tot_amount >= amount {
-                    break;
-                }
-            }
-        }
-        if tot_amount >= amount {
-            return Ok(coins);
-        } else {
-            return Err(YHErrorKind::NotEnoughFunds.into());
+    pub fn list_from<S: YStorage>(store: &S, ekey: YKey32, after: &Option<YStoreKey>, limit: u32) -> YHResult<(Vec<YWallet>, Option<YStoreKey>)> {
+        let store_buck = YBucket::Wallets.to_store_buck();
+        let page = store.list_from(&store_buck, after, limit)?;
+        let mut wallets = Vec::new();
+        for key in page.keys {
+            let item = store.get(&store_buck, &key)?;
+            let wallet = YWallet::dec_value(ekey, &item.value)?;
+            wallets.push(wallet);
         }
+        Ok((wallets, page.next))
     }
 
-    pub fn select_coins(&self, amount: YAmount) -> YHResult<Vec<YCoin>> {
-        if self.balance < amount {
-            return Err(YHErrorKind::NotEnoughFunds.into());    
-        }
-        if self.balance == amount {
-            return Ok(self.ucoins.clone());
-        }
-        let mut coins = Vec::new();
-        let mut tot_amount = YAmount::zero();
-        for ucoin in self.ucoins.clone() {
-            coins.push(ucoin.clone());
-            tot_amount += ucoin.amount;
-            if tot_amount >= amount {
-                break;
+    pub fn select_ucoins<C: YCoinSelector>(&self, selector: &C, amount: YAmount, max_inputs: u32, keep_data: bool) -> YHResult<Vec<YCoin>> {
+        let ucoins = self.ucoins.iter()
+            .filter(|ucoin| !(keep_data && ucoin.has_data))
+            .cloned()
+            .collect();
+        selector.select(&ucoins, &amount, max_inputs)
+    }
+
+    // coins held by an unsigned transaction waiting to be signed can't be selected again
+    pub fn select_unreserved<S: YStorage, C: YCoinSelector>(&self, store: &S, selector: &C, amount: YAmount, max_inputs: u32, keep_data: bool) -> YHResult<Vec<YCoin>> {
+        self.select_unreserved_by(store, selector, amount, max_inputs, keep_data, |_| true)
+    }
+
+    pub fn select_unreserved_by<S: YStorage, C: YCoinSelector, F: Fn(&YCoin) -> bool>(&self, store: &S, selector: &C, amount: YAmount, max_inputs: u32, keep_data: bool, accept: F) -> YHResult<Vec<YCoin>> {
+        let mut ucoins = Vec::new();
+        for ucoin in self.ucoins.iter().filter(|ucoin| !(keep_data && ucoin.has_data) && accept(ucoin)) {
+            if !YReservation::is_reserved(store, ucoin.id, ucoin.idx)? {
+                ucoins.push(ucoin.clone());
             }
         }
-        if tot_amount >= amount {
-            return Ok(coins);
-        } else {
-            return Err(YHErrorKind::NotEnoughFunds.into());
-        }
+        selector.select(&ucoins, &amount, max_inputs)
+    }
+
+    pub fn select_coins_no_data(&self, amount: YAmount) -> YHResult<Vec<YCoin>> {
+        self.select_ucoins(&YFirstFit, amount, u32::max_value(), true)
+    }
+
+    pub fn select_coins(&self, amount: YAmount) -> YHResult<Vec<YCoin>> {
+        self.select_ucoins(&YFirstFit, amount, u32::max_value(), false)
     }
 
     pub fn get<S: YStorage>(store: &S, ekey: YKey32, name: &str) -> YHResult<YWallet> {
         let store_buck = YBucket::Wallets.to_store_buck();
-        let mut key = Vec::new();
-        key.put(name.as_bytes());
-        let padding = key.len() % 16;
-        for _ in 0..padding {
-            key.push(0);
-        }
-        key = YSE::encrypt(ekey, &key)?;
+        let key = YWallet::enc_name(ekey, name)?;
         let item = store.get(&store_buck, &key)?;
         YWallet::dec_value(ekey, &item.value)
     }
 
+    pub fn get_by_key<S: YStorage>(store: &S, ekey: YKey32, key: &YStoreKey) -> YHResult<YWallet> {
+        let store_buck = YBucket::Wallets.to_store_buck();
+        let item = store.get(&store_buck, key)?;
+        YWallet::dec_value(ekey, &item.value)
+    }
+
+    pub fn new_receive_key<S: YStorage>(store: &mut S, ekey: YKey32, name: &str) -> YHResult<YPublicKey> {
+        atomically(store, |store| {
+            let mut wallet = YWallet::get(store, ekey, name)?;
+            let sk = wallet.next_key(YHD_RECEIVE_BRANCH)?;
+            let keys = YKeys::from_wallet(sk, ekey, name)?;
+            if !YKeys::lookup(store, keys.pk)? {
+                keys.create(store)?;
+            }
+            wallet.update(store, ekey)?;
+            Ok(keys.pk)
+        })
+    }
+
+    // adds the utxos paying a linked receive key to the owner wallet
+    pub fn receive<S: YStorage>(store: &mut S, ekey: YKey32, utxos: &Vec<YUTXO>) -> YHResult<u32> {
+        atomically(store, |store| {
+            let store_buck = YBucket::Wallets.to_store_buck();
+            let mut found = 0;
+            for utxo in utxos {
+                let recipient = utxo.internal().recipient;
+                if !YKeys::lookup(store, recipient)? {
+                    continue;
+                }
+                let keys = YKeys::get(store, recipient)?;
+                if !keys.is_linked() || !store.lookup(&store_buck, &keys.wallet)? {
+                    continue;
+                }
+                let mut wallet = YWallet::get_by_key(store, ekey, &keys.wallet)?;
+                if wallet.add_coin(store, ekey, keys.sk, utxo)? {
+                    wallet.update(store, ekey)?;
+                    found += 1;
+                }
+            }
+            Ok(found)
+        })
+    }
+
+    // the unspent coins are added back only if the local UTXO bucket still has them
+    pub fn import<S: YStorage>(store: &mut S, ekey: YKey32, wallet: &YWallet) -> YHResult<YWallet> {
+        wallet.check()?;
+        atomically(store, |store| {
+            if YWallet::lookup(store, ekey, &wallet.name)? {
+                return Err(YHErrorKind::AlreadyFound.into());
+            }
+            let mut imported = wallet.clone();
+            imported.ucoins = Vec::new();
+            imported.balance = YAmount::zero();
+            imported.create(store, ekey)?;
+
+            for ucoin in wallet.ucoins.iter() {
+                if !YUTXO::lookup(store, ucoin.id, ucoin.idx)? {
+                    continue;
+                }
+                let utxo = YUTXO::get(store, ucoin.id, ucoin.idx)?;
+                let _utxo = utxo.internal();
+                if _utxo.recipient != ucoin.sk.to_public() || _utxo.amount != ucoin.amount {
+                    continue;
+                }
+                // the coin may have been spent since the export
+                if imported.add_coin(store, ekey, ucoin.sk, &utxo)? && YIndex::get_spender(store, ucoin.id, ucoin.idx)?.is_some() {
+                    imported.spend_coin(ucoin.id, ucoin.idx);
+                }
+            }
+
+            if imported.is_hd() {
+                for idx in 0..imported.receive_idx {
+                    let sk = imported.derive_key(YHD_RECEIVE_BRANCH, idx)?;
+                    let keys = YKeys::from_wallet(sk, ekey, &imported.name)?;
+                    if !YKeys::lookup(store, keys.pk)? {
+                        keys.create(store)?;
+                    }
+                }
+            }
+
+            imported.update(store, ekey)?;
+            Ok(imported)
+        })
+    }
+
+    // marks as spent the wallet coins used by a transaction signed outside of the node
+    pub fn spend<S: YStorage>(store: &mut S, ekey: YKey32, tx: &LibTransaction) -> YHResult<u32> {
+        atomically(store, |store| {
+            let count = YWallet::count(store)?;
+            let mut found = 0;
+            for mut wallet in YWallet::list(store, ekey, 0, count)? {
+                let mut spent = false;
+                for input in tx.inputs.iter() {
+                    if wallet.spend_coin(input.id, input.idx) {
+                        spent = true;
+                        found += 1;
+                    }
+                }
+                if spent {
+                    wallet.update(store, ekey)?;
+                }
+            }
+            Ok(found)
+        })
+    }
+
+    pub fn spend_coin(&mut self, id: YDigest64, idx: u32) -> bool {
+        let pos = self.ucoins.iter()
+            .position(|ucoin| ucoin.id == id && ucoin.idx == idx);
+        match pos {
+            Some(pos) => {
+                let ucoin = self.ucoins.remove(pos);
+                self.balance = self.balance.clone() - ucoin.amount.clone();
+                self.scoins.push(ucoin);
+                true
+            },
+            None => false,
+        }
+    }
+
+    pub fn add_coin<S: YStorage>(&mut self, store: &mut S, ekey: YKey32, sk: YSecretKey, utxo: &YUTXO) -> YHResult<bool> {
+        let _utxo = utxo.internal();
+        let (id, idx) = (_utxo.id, _utxo.idx);
+        if self.ucoins.iter().chain(self.scoins.iter()).any(|coin| coin.id == id && coin.idx == idx) {
+            return Ok(false);
+        }
+
+        // pruned stores may have dropped the source, coinbase outputs have height 0
+        let mut date = YTime::now();
+        let mut has_data = false;
+        let mut tag = None;
+        let kind = if _utxo.height == 0 {
+            if YCoinbase::lookup(store, id)? {
+                date = YCoinbase::get(store, id)?.internal().time;
+            }
+            YIndex::put_wallet_cb(store, ekey, &self.name, id)?;
+            YCoinKind::Coinbase
+        } else {
+            if YTransaction::lookup(store, id)? {
+                let tx = YTransaction::get(store, id)?.internal();
+                date = tx.time.clone();
+                let output = tx.outputs.get(idx as usize)
+                    .ok_or(YHErrorKind::NotFound)?
+                    .clone();
+                if let Some(_data) = output.data {
+                    has_data = true;
+                    tag = Some(_data.tag.clone());
+                    YIndex::put_wallet_data(store, ekey, &self.name, &YData::new(&_data)?)?;
+                }
+            }
+            YIndex::put_wallet_tx(store, ekey, &self.name, id)?;
+            YCoinKind::Transaction
+        };
+
+        let coin = YCoin::new(date, sk, kind, id, idx,
+                              _utxo.height, has_data,
+                              tag, &_utxo.amount)?;
+        self.balance += _utxo.amount.clone();
+        self.ucoins.push(coin);
+        Ok(true)
+    }
+
     pub fn create<S: YStorage>(&self, store: &mut S, ekey: YKey32) -> YHResult<()> {
         let store_buck = YBucket::Wallets.to_store_buck();
         let key = self.enc_key(ekey)?;
@@ -269,3 +540,151 @@ impl YWallet {
         store.delete(&store_buck, &key)
     }
 }
+
+#[derive(Clone, Eq, PartialEq, Debug, Serialize, Deserialize)]
+pub struct YPublicWallet {
+    pub name: String,
+    pub balance: YAmount,
+    pub scoins: Vec<YPublicCoin>,
+    pub ucoins: Vec<YPublicCoin>,
+    pub is_hd: bool,
+    pub account: u32,
+    pub receive_idx: u32,
+    pub change_idx: u32,
+}
+
+impl Default for YPublicWallet {
+    fn default() -> YPublicWallet {
+        YPublicWallet::from_wallet(&YWallet::default())
+    }
+}
+
+impl YPublicWallet {
+    pub fn from_wallet(wallet: &YWallet) -> YPublicWallet {
+        YPublicWallet {
+            name: wallet.name.clone(),
+            balance: wallet.balance.clone(),
+            scoins: wallet.scoins.iter().map(|coin| coin.to_public()).collect(),
+            ucoins: wallet.ucoins.iter().map(|coin| coin.to_public()).collect(),
+            is_hd: wallet.is_hd(),
+            account: wallet.account,
+            receive_idx: wallet.receive_idx,
+            change_idx: wallet.change_idx,
+        }
+    }
+
+    pub fn check(&self) -> YHResult<()> {
+        for scoin in self.scoins.iter() {
+            scoin.check()?;
+        }
+        let mut ucoins_balance = YAmount::zero();
+        for ucoin in self.ucoins.iter() {
+            ucoin.check()?;
+            ucoins_balance += ucoin.amount.clone();
+        }
+        if ucoins_balance != self.balance {
+            return Err(YHErrorKind::Lib(LibErrorKind::InvalidAmount).into());
+        }
+        Ok(())
+    }
+
+    pub fn to_bytes(&self) -> YHResult<Vec<u8>> {
+        let mut buf = BytesMut::new();
+        let name_buf = self.name.as_bytes();
+        buf.put_u32::<BigEndian>(name_buf.len() as u32);
+        buf.put(name_buf);
+        let balance_buf = self.balance.to_bytes();
+        buf.put_u32::<BigEndian>(balance_buf.len() as u32);
+        buf.put(balance_buf);
+        buf.put_u32::<BigEndian>(self.scoins.len() as u32);
+        for scoin in self.scoins.iter() {
+            let scoin_buf = scoin.to_bytes()?;
+            buf.put_u32::<BigEndian>(scoin_buf.len() as u32);
+            buf.put(scoin_buf);
+        }
+        buf.put_u32::<BigEndian>(self.ucoins.len() as u32);
+        for ucoin in self.ucoins.iter() {
+            let ucoin_buf = ucoin.to_bytes()?;
+            buf.put_u32::<BigEndian>(ucoin_buf.len() as u32);
+            buf.put(ucoin_buf);
+        }
+        buf.put_u32::<BigEndian>(self.is_hd as u32);
+        buf.put_u32::<BigEndian>(self.account);
+        buf.put_u32::<BigEndian>(self.receive_idx);
+        buf.put_u32::<BigEndian>(self.change_idx);
+        Ok(buf.to_vec())
+    }
+
+    pub fn from_bytes(buf: &[u8]) -> YHResult<YPublicWallet> {
+        if buf.len() < 32 {
+            return Err(YHErrorKind::InvalidLength.into());
+        }
+        let mut b = BytesMut::new();
+        b.extend_from_slice(buf);
+        let name_size = BigEndian::read_u32(b.get(0..4).unwrap()) as usize;
+        let mut i = 4;
+        let name = String::from_utf8(b.get(i..i+name_size).ok_or(YHErrorKind::InvalidLength)?.to_vec())?;
+        i += name_size;
+        let balance_size = BigEndian::read_u32(b.get(i..i+4).ok_or(YHErrorKind::InvalidLength)?) as usize;
+        i += 4;
+        let balance = YAmount::from_bytes(b.get(i..i+balance_size).ok_or(YHErrorKind::InvalidLength)?);
+        i += balance_size;
+        let scoins_len = BigEndian::read_u32(b.get(i..i+4).ok_or(YHErrorKind::InvalidLength)?) as usize;
+        i += 4;
+        let mut scoins = Vec::new();
+        for _ in 0..scoins_len {
+            let scoin_size = BigEndian::read_u32(b.get(i..i+4).ok_or(YHErrorKind::InvalidLength)?) as usize;
+            i += 4;
+            scoins.push(YPublicCoin::from_bytes(b.get(i..i+scoin_size).ok_or(YHErrorKind::InvalidLength)?)?);
+            i += scoin_size;
+        }
+        let ucoins_len = BigEndian::read_u32(b.get(i..i+4).ok_or(YHErrorKind::InvalidLength)?) as usize;
+        i += 4;
+        let mut ucoins = Vec::new();
+        for _ in 0..ucoins_len {
+            let ucoin_size = BigEndian::read_u32(b.get(i..i+4).ok_or(YHErrorKind::InvalidLength)?) as usize;
+            i += 4;
+            ucoins.push(YPublicCoin::from_bytes(b.get(i..i+ucoin_size).ok_or(YHErrorKind::InvalidLength)?)?);
+            i += ucoin_size;
+        }
+        let is_hd = match BigEndian::read_u32(b.get(i..i+4).ok_or(YHErrorKind::InvalidLength)?) {
+            0 => false,
+            1 => true,
+            _ => {
+                return Err(YHErrorKind::InvalidValue.into());
+            },
+        };
+        i += 4;
+        let account = BigEndian::read_u32(b.get(i..i+4).ok_or(YHErrorKind::InvalidLength)?);
+        i += 4;
+        let receive_idx = BigEndian::read_u32(b.get(i..i+4).ok_or(YHErrorKind::InvalidLength)?);
+        i += 4;
+        let change_idx = BigEndian::read_u32(b.get(i..i+4).ok_or(YHErrorKind::InvalidLength)?);
+        i += 4;
+        if i != buf.len() {
+            return Err(YHErrorKind::InvalidLength.into());
+        }
+        let wallet = YPublicWallet {
+            name: name,
+            balance: balance,
+            scoins: scoins,
+            ucoins: ucoins,
+            is_hd: is_hd,
+            account: account,
+            receive_idx: receive_idx,
+            change_idx: change_idx,
+        };
+        wallet.check()?;
+        Ok(wallet)
+    }
+
+    pub fn to_json(&self) -> YHResult<String> {
+        let json = serde_json::to_string(self)?;
+        Ok(json)
+    }
+
+    pub fn from_json(s: &str) -> YHResult<YPublicWallet> {
+        let wallet = serde_json::from_str(s)?;
+        Ok(wallet)
+    }
+}
@@ -1,72 +1,83 @@
 use std::net::SocketAddr;
 use std::net::TcpStream;
 use std::time::Duration;
-use std::thread;
-use std::io::prelude::*;
-use std::io::ErrorKind as IOErrorKind;
 use config::*;
+use network::rpc_method::*;
+use network::frame::*;
+use network::message::request::*;
+use network::message::response::*;
 use errors::*;
 
+pub const YCLIENT_TIMEOUT_SECS: u64 = 30;
+
 #[derive(Debug)]
 pub struct YClient {
     pub address: SocketAddr,
+    pub timeout: Option<Duration>,
     pub connection: TcpStream,
 }
 
 impl YClient {
-    pub fn new(address: Option<SocketAddr>) -> YHResult<YClient> {
-      
+    pub fn new(address: Option<SocketAddr>, timeout: Option<Duration>) -> YHResult<YClient> {
+
         let address = if address.is_some() {
             address.unwrap()
         } else {
             YConfig::read()?.host.internal()
         };
 
-        let connection = TcpStream::connect(address)?;
+        let connection = YClient::connect(address, timeout)?;
 
         let client = YClient {
             address: address,
+            timeout: timeout,
             connection: connection,
         };
 
         Ok(client)
     }
 
-    fn read_reply(&mut self) -> YHResult<Vec<u8>> {
-        let mut msg = Vec::new();
-        loop {
-            match self.connection.read_to_end(&mut msg) {
-                Ok(_) => break,
-                Err(ref e) if e.kind() == IOErrorKind::WouldBlock => {
-                    let t = Duration::from_millis(10);
-                    thread::sleep(t);
-                },
-                Err(e) => {
-                    return Err(YHErrorKind::IO(e).into());
-                },
-            } 
-        }
-        
-        // TODO: decode the reply
-        
-        Ok(msg)
+    pub fn default_timeout() -> Option<Duration> {
+        Some(Duration::from_secs(YCLIENT_TIMEOUT_SECS))
+    }
+
+    fn connect(address: SocketAddr, timeout: Option<Duration>) -> YHResult<TcpStream> {
+        let connection = match timeout {
+            Some(timeout) => TcpStream::connect_timeout(&address, timeout)?,
+            None => TcpStream::connect(address)?,
+        };
+        connection.set_read_timeout(timeout)?;
+        connection.set_write_timeout(timeout)?;
+        connection.set_nodelay(true)?;
+        Ok(connection)
     }
 
-    fn handle_reply(&mut self) -> YHResult<()> {
-        let reply = self.read_reply()?;
-        
-        // TODO handle the reply
-        
-        println!("reply from {:?}: {:?}", self.address, reply);
+    pub fn reconnect(&mut self) -> YHResult<()> {
+        self.connection = YClient::connect(self.address, self.timeout)?;
         Ok(())
     }
 
-    pub fn send_request(&mut self, msg: &[u8]) -> YHResult<()> {
-        self.connection.write(msg)?;
-        self.connection.flush()?;
+    fn handle_reply(&mut self, req: &YRequest) -> YHResult<YResponse> {
+        let reply = YFrame::read(&mut self.connection)?
+            .ok_or(YHErrorKind::NotConnected)?;
+        let res = YResponse::from_bytes(&reply)?;
+        res.check()?;
+        // errors raised before the request was parsed carry no method
+        let method = res.method();
+        let is_error = match res {
+            YResponse::Error(_) => true,
+            _ => false,
+        };
+        if method != req.method() && !(is_error && method == YRPCMethod::Unknown) {
+            return Err(YHErrorKind::InvalidResponse.into());
+        }
+        Ok(res)
+    }
 
-        self.handle_reply()?;
+    pub fn send_request(&mut self, req: &YRequest) -> YHResult<YResponse> {
+        let msg = req.to_bytes()?;
+        YFrame::write(&mut self.connection, &msg)?;
 
-        Ok(())
+        self.handle_reply(req)
     }
 }
@@ -0,0 +1,47 @@
+use bytes::{BigEndian, ByteOrder};
+use std::io::prelude::*;
+use std::io::ErrorKind as IOErrorKind;
+use network::message::prefix::*;
+use errors::*;
+
+pub struct YFrame;
+
+impl YFrame {
+    // a peer closing the stream between two frames reads as None
+    pub fn read<R: Read>(stream: &mut R) -> YHResult<Option<Vec<u8>>> {
+        let mut header = [0u8; YMESSAGE_HEADER_SIZE];
+        match stream.read_exact(&mut header) {
+            Ok(_) => {},
+            Err(ref e) if e.kind() == IOErrorKind::UnexpectedEof => {
+                return Ok(None);
+            },
+            Err(e) => {
+                return Err(YHErrorKind::IO(e).into());
+            },
+        }
+
+        let prefix = BigEndian::read_u32(&header[0..4]);
+        if prefix != YMESSAGE_PREFIX {
+            return Err(YHErrorKind::InvalidMessagePrefix.into());
+        }
+
+        let size = BigEndian::read_u32(&header[12..16]) as usize;
+        if size > YMESSAGE_MAX_SIZE {
+            return Err(YHErrorKind::InvalidLength.into());
+        }
+
+        let mut payload = vec![0u8; size];
+        stream.read_exact(&mut payload)?;
+
+        let mut buf = Vec::new();
+        buf.extend_from_slice(&header[..]);
+        buf.extend(payload);
+        Ok(Some(buf))
+    }
+
+    pub fn write<W: Write>(stream: &mut W, buf: &[u8]) -> YHResult<()> {
+        stream.write_all(buf)?;
+        stream.flush()?;
+        Ok(())
+    }
+}
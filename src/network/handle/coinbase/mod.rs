@@ -2,45 +2,106 @@ use std::marker::PhantomData;
 use api::*;
 use store::*;
 use network::rpc_method::*;
-use network::message::ping::*;
-use network::message::peer::*;
-use network::message::data::*;
-use network::message::transaction::*;
 use network::message::coinbase::*;
 use network::message::error::*;
 use network::message::request::*;
 use network::message::response::*;
 use errors::*;
 
-pub struct YPingHandle<M, P: YStorage> {
+pub const YMINE_INCR: u32 = 1;
+
+pub struct YCoinbaseHandle<M, P: YStorage> {
     _memory: PhantomData<M>,
     _persistent: PhantomData<P>,
 }
 
-impl<M, P: YStorage> YPingHandle<M, P> {
-    pub fn handle(req: YRequest, api: &YAPI<M, P>) -> YHResult<YResponse> {
+impl<M: YStorage, P: YStorage> YCoinbaseHandle<M, P> {
+    pub fn handle(req: YRequest, api: &mut YAPI<M, P>) -> YHResult<YResponse> {
         match req {
-            YRequest::Ping(req) => {
-                unreachable!()
+            YRequest::GetCb(req) => {
+                match YCoinbaseHandle::get_cb(req, api) {
+                    Ok(res) => Ok(YResponse::GetCb(res)),
+                    Err(err) => {
+                        let res = YErrorRes::from_error(YRPCMethod::GetCb, err)?;
+                        Ok(YResponse::Error(res))
+                    },
+                }
+            },
+            YRequest::PushCb(req) => {
+                match YCoinbaseHandle::push_cb(req, api) {
+                    Ok(res) => Ok(YResponse::PushCb(res)),
+                    Err(err) => {
+                        let res = YErrorRes::from_error(YRPCMethod::PushCb, err)?;
+                        Ok(YResponse::Error(res))
+                    },
+                }
+            },
+            YRequest::Mine(req) => {
+                match YCoinbaseHandle::mine(req, api) {
+                    Ok(res) => Ok(YResponse::Mine(res)),
+                    Err(err) => {
+                        let res = YErrorRes::from_error(YRPCMethod::Mine, err)?;
+                        Ok(YResponse::Error(res))
+                    },
+                }
+            },
+            YRequest::ListCbs(req) => {
+                match YCoinbaseHandle::list_cbs(req, api) {
+                    Ok(res) => Ok(YResponse::ListCbs(res)),
+                    Err(err) => {
+                        let res = YErrorRes::from_error(YRPCMethod::ListCbs, err)?;
+                        Ok(YResponse::Error(res))
+                    },
+                }
             },
             _ => {
                 let err: YHError = YHErrorKind::InvalidRequest.into();
-                let method = YRPCMethod::Ping;
+                let method = YRPCMethod::GetCb;
                 let res = YErrorRes::from_error(method, err)?;
                 Ok(YResponse::Error(res)) 
             }
         }
     }
 
-    pub fn handle_bytes(buf: &[u8], api: &YAPI<M, P>) -> YHResult<Vec<u8>> {
+    pub fn get_cb(req: YGetCbReq, api: &YAPI<M, P>) -> YHResult<YGetCbRes> {
+        req.check()?;
+        let cb = api.get_coinbase(req.cb_id)?;
+        YGetCbRes::new(&cb.internal())
+    }
+
+    pub fn push_cb(req: YPushCbReq, api: &mut YAPI<M, P>) -> YHResult<YPushCbRes> {
+        req.check()?;
+        let cb = api.push_coinbase(&req.cb)?;
+        YPushCbRes::new(cb.internal().id)
+    }
+
+    pub fn mine(req: YMineReq, api: &mut YAPI<M, P>) -> YHResult<YMineRes> {
+        req.check()?;
+        let fee_pk = api.public_key();
+        let ((cb, _), _) = api.mine_genesys(&req.wallet, req.difficulty, YMINE_INCR, fee_pk)?;
+        YMineRes::new(&cb.internal())
+    }
+
+    pub fn list_cbs(req: YListCbsReq, api: &YAPI<M, P>) -> YHResult<YListCbsRes> {
+        req.check()?;
+        let after = cursor_from_bytes(&req.cursor);
+        let (_cbs, next) = api.list_coinbases_by_wallet_from(&req.wallet, &after, req.limit)?;
+        let mut cbs = Vec::new();
+        for cb in _cbs {
+            cbs.push(cb.internal());
+        }
+        YListCbsRes::new(&cbs, &cursor_to_bytes(&next))
+    }
+
+    pub fn handle_bytes(buf: &[u8], api: &mut YAPI<M, P>) -> YHResult<Vec<u8>> {
         let req = YRequest::from_bytes(buf)?;
-        let res = YPingHandle::handle(req, api)?;
+        let res = YCoinbaseHandle::handle(req, api)?;
         res.to_bytes()
     }
     
-    pub fn handle_json(obj: &[u8], api: &YAPI<M, P>) -> YHResult<Vec<u8>> {
+    pub fn handle_json(obj: &[u8], api: &mut YAPI<M, P>) -> YHResult<Vec<u8>> {
         let req = YRequest::from_json(obj)?;
-        let res = YPingHandle::handle(req, api)?;
+        let res = YCoinbaseHandle::handle(req, api)?;
         res.to_json()
     }
 }
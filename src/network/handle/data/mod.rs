@@ -2,45 +2,91 @@ use std::marker::PhantomData;
 use api::*;
 use store::*;
 use network::rpc_method::*;
-use network::message::ping::*;
-use network::message::peer::*;
 use network::message::data::*;
-use network::message::transaction::*;
-use network::message::coinbase::*;
 use network::message::error::*;
 use network::message::request::*;
 use network::message::response::*;
 use errors::*;
 
-pub struct YPingHandle<M, P: YStorage> {
+pub struct YDataHandle<M, P: YStorage> {
     _memory: PhantomData<M>,
     _persistent: PhantomData<P>,
 }
 
-impl<M, P: YStorage> YPingHandle<M, P> {
+impl<M: YStorage, P: YStorage> YDataHandle<M, P> {
     pub fn handle(req: YRequest, api: &YAPI<M, P>) -> YHResult<YResponse> {
         match req {
-            YRequest::Ping(req) => {
-                unreachable!()
+            YRequest::GetData(req) => {
+                match YDataHandle::get_data(req, api) {
+                    Ok(res) => Ok(YResponse::GetData(res)),
+                    Err(err) => {
+                        let res = YErrorRes::from_error(YRPCMethod::GetData, err)?;
+                        Ok(YResponse::Error(res))
+                    },
+                }
+            },
+            YRequest::ListData(req) => {
+                match YDataHandle::list_data(req, api) {
+                    Ok(res) => Ok(YResponse::ListData(res)),
+                    Err(err) => {
+                        let res = YErrorRes::from_error(YRPCMethod::ListData, err)?;
+                        Ok(YResponse::Error(res))
+                    },
+                }
+            },
+            YRequest::ListWalletData(req) => {
+                match YDataHandle::list_wallet_data(req, api) {
+                    Ok(res) => Ok(YResponse::ListWalletData(res)),
+                    Err(err) => {
+                        let res = YErrorRes::from_error(YRPCMethod::ListWalletData, err)?;
+                        Ok(YResponse::Error(res))
+                    },
+                }
             },
             _ => {
                 let err: YHError = YHErrorKind::InvalidRequest.into();
-                let method = YRPCMethod::Ping;
+                let method = YRPCMethod::GetData;
                 let res = YErrorRes::from_error(method, err)?;
                 Ok(YResponse::Error(res)) 
             }
         }
     }
 
+    pub fn get_data(req: YGetDataReq, api: &YAPI<M, P>) -> YHResult<YGetDataRes> {
+        req.check()?;
+        let data = api.get_data_by_checksum(req.checksum)?;
+        YGetDataRes::new(&data.internal())
+    }
+
+    pub fn list_data(req: YListDataReq, api: &YAPI<M, P>) -> YHResult<YListDataRes> {
+        req.check()?;
+        let mut data = Vec::new();
+        for d in api.list_transaction_data(req.tx_id)? {
+            data.push(d.internal());
+        }
+        YListDataRes::new(&data)
+    }
+
+    pub fn list_wallet_data(req: YListWalletDataReq, api: &YAPI<M, P>) -> YHResult<YListWalletDataRes> {
+        req.check()?;
+        let after = cursor_from_bytes(&req.cursor);
+        let (_data, next) = api.list_data_by_wallet_from(&req.wallet, &after, req.limit)?;
+        let mut data = Vec::new();
+        for d in _data {
+            data.push(d.internal());
+        }
+        YListWalletDataRes::new(&data, &cursor_to_bytes(&next))
+    }
+
     pub fn handle_bytes(buf: &[u8], api: &YAPI<M, P>) -> YHResult<Vec<u8>> {
         let req = YRequest::from_bytes(buf)?;
-        let res = YPingHandle::handle(req, api)?;
+        let res = YDataHandle::handle(req, api)?;
         res.to_bytes()
     }
     
     pub fn handle_json(obj: &[u8], api: &YAPI<M, P>) -> YHResult<Vec<u8>> {
         let req = YRequest::from_json(obj)?;
-        let res = YPingHandle::handle(req, api)?;
+        let res = YDataHandle::handle(req, api)?;
         res.to_json()
     }
 }
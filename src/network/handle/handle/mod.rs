@@ -0,0 +1,93 @@
+use std::marker::PhantomData;
+use api::*;
+use store::*;
+use network::rpc_method::*;
+use network::message::error::*;
+use network::message::request::*;
+use network::message::response::*;
+use network::handle::ping::*;
+use network::handle::peer::*;
+use network::handle::data::*;
+use network::handle::transaction::*;
+use network::handle::coinbase::*;
+use network::handle::wallet::*;
+use network::handle::utxo::*;
+use network::handle::info::*;
+use errors::*;
+
+pub struct YHandle<M, P: YStorage> {
+    _memory: PhantomData<M>,
+    _persistent: PhantomData<P>,
+}
+
+impl<M: YStorage, P: YStorage> YHandle<M, P> {
+    pub fn handle(req: YRequest, api: &mut YAPI<M, P>) -> YHResult<YResponse> {
+        match req {
+            YRequest::Ping(_) => {
+                YPingHandle::handle(req, api)
+            },
+            YRequest::ListPeers(_) => {
+                YPeerHandle::handle(req, api)
+            },
+            YRequest::GetData(_) |
+            YRequest::ListData(_) |
+            YRequest::ListWalletData(_) => {
+                YDataHandle::handle(req, api)
+            },
+            YRequest::GetTx(_) |
+            YRequest::ConfirmTx(_) |
+            YRequest::ListTxAncestors(_) |
+            YRequest::SendCoins(_) |
+            YRequest::SendData(_) |
+            YRequest::SendBatch(_) |
+            YRequest::CreateUnsignedTx(_) |
+            YRequest::PushTx(_) |
+            YRequest::ListTxs(_) => {
+                YTransactionHandle::handle(req, api)
+            },
+            YRequest::GetCb(_) |
+            YRequest::PushCb(_) |
+            YRequest::Mine(_) |
+            YRequest::ListCbs(_) => {
+                YCoinbaseHandle::handle(req, api)
+            },
+            YRequest::CreateWallet(_) |
+            YRequest::GetWallet(_) |
+            YRequest::ListWallets(_) |
+            YRequest::ListCoins(_) |
+            YRequest::CreateReceiveKey(_) |
+            YRequest::ListHistory(_) => {
+                YWalletHandle::handle(req, api)
+            },
+            YRequest::GetUTXO(_) |
+            YRequest::ListUTXOs(_) => {
+                YUTXOHandle::handle(req, api)
+            },
+            YRequest::GetInfo(_) => {
+                YInfoHandle::handle(req, api)
+            },
+        }
+    }
+
+    pub fn handle_bytes(buf: &[u8], api: &mut YAPI<M, P>) -> YHResult<Vec<u8>> {
+        let res = match YRequest::from_bytes(buf) {
+            Ok(req) => YHandle::handle(req, api)?,
+            Err(err) => {
+                let res = YErrorRes::from_error(YRPCMethod::Unknown, err)?;
+                YResponse::Error(res)
+            },
+        };
+        res.to_bytes()
+    }
+    
+    pub fn handle_json(obj: &[u8], api: &mut YAPI<M, P>) -> YHResult<Vec<u8>> {
+        let res = match YRequest::from_json(obj) {
+            Ok(req) => YHandle::handle(req, api)?,
+            Err(err) => {
+                let res = YErrorRes::from_error(YRPCMethod::Unknown, err)?;
+                YResponse::Error(res)
+            },
+        };
+        res.to_json()
+    }
+}
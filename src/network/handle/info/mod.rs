@@ -0,0 +1,60 @@
+use std::marker::PhantomData;
+use api::*;
+use store::*;
+use network::rpc_method::*;
+use network::message::info::*;
+use network::message::error::*;
+use network::message::request::*;
+use network::message::response::*;
+use errors::*;
+
+pub struct YInfoHandle<M, P: YStorage> {
+    _memory: PhantomData<M>,
+    _persistent: PhantomData<P>,
+}
+
+impl<M: YStorage, P: YStorage> YInfoHandle<M, P> {
+    pub fn handle(req: YRequest, api: &YAPI<M, P>) -> YHResult<YResponse> {
+        match req {
+            YRequest::GetInfo(req) => {
+                match YInfoHandle::get_info(req, api) {
+                    Ok(res) => Ok(YResponse::GetInfo(res)),
+                    Err(err) => {
+                        let res = YErrorRes::from_error(YRPCMethod::GetInfo, err)?;
+                        Ok(YResponse::Error(res))
+                    },
+                }
+            },
+            _ => {
+                let err: YHError = YHErrorKind::InvalidRequest.into();
+                let method = YRPCMethod::GetInfo;
+                let res = YErrorRes::from_error(method, err)?;
+                Ok(YResponse::Error(res)) 
+            }
+        }
+    }
+
+    pub fn get_info(req: YGetInfoReq, api: &YAPI<M, P>) -> YHResult<YGetInfoRes> {
+        req.check()?;
+        let info = api.info()?;
+        YGetInfoRes::new(&info.balance,
+                         info.wallets_count,
+                         info.ucoins_count,
+                         info.scoins_count,
+                         info.data_count,
+                         info.transactions_count,
+                         info.coinbases_count)
+    }
+
+    pub fn handle_bytes(buf: &[u8], api: &YAPI<M, P>) -> YHResult<Vec<u8>> {
+        let req = YRequest::from_bytes(buf)?;
+        let res = YInfoHandle::handle(req, api)?;
+        res.to_bytes()
+    }
+    
+    pub fn handle_json(obj: &[u8], api: &YAPI<M, P>) -> YHResult<Vec<u8>> {
+        let req = YRequest::from_json(obj)?;
+        let res = YInfoHandle::handle(req, api)?;
+        res.to_json()
+    }
+}
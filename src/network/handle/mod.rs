@@ -3,6 +3,9 @@ pub mod peer;
 pub mod data;
 pub mod transaction;
 pub mod coinbase;
+pub mod wallet;
+pub mod utxo;
+pub mod info;
 pub mod handle;
 
 pub use self::ping::*;
@@ -10,4 +13,7 @@ pub use self::peer::*;
 pub use self::data::*;
 pub use self::transaction::*;
 pub use self::coinbase::*;
+pub use self::wallet::*;
+pub use self::utxo::*;
+pub use self::info::*;
 pub use self::handle::*;
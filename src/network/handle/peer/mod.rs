@@ -2,45 +2,54 @@ use std::marker::PhantomData;
 use api::*;
 use store::*;
 use network::rpc_method::*;
-use network::message::ping::*;
 use network::message::peer::*;
-use network::message::data::*;
-use network::message::transaction::*;
-use network::message::coinbase::*;
 use network::message::error::*;
 use network::message::request::*;
 use network::message::response::*;
 use errors::*;
 
-pub struct YPingHandle<M, P: YStorage> {
+pub struct YPeerHandle<M, P: YStorage> {
     _memory: PhantomData<M>,
     _persistent: PhantomData<P>,
 }
 
-impl<M, P: YStorage> YPingHandle<M, P> {
+impl<M: YStorage, P: YStorage> YPeerHandle<M, P> {
     pub fn handle(req: YRequest, api: &YAPI<M, P>) -> YHResult<YResponse> {
         match req {
-            YRequest::Ping(req) => {
-                unreachable!()
+            YRequest::ListPeers(req) => {
+                match YPeerHandle::list_peers(req, api) {
+                    Ok(res) => Ok(YResponse::ListPeers(res)),
+                    Err(err) => {
+                        let res = YErrorRes::from_error(YRPCMethod::ListPeers, err)?;
+                        Ok(YResponse::Error(res))
+                    },
+                }
             },
             _ => {
                 let err: YHError = YHErrorKind::InvalidRequest.into();
-                let method = YRPCMethod::Ping;
+                let method = YRPCMethod::ListPeers;
                 let res = YErrorRes::from_error(method, err)?;
                 Ok(YResponse::Error(res)) 
             }
         }
     }
 
+    pub fn list_peers(req: YListPeersReq, api: &YAPI<M, P>) -> YHResult<YListPeersRes> {
+        req.check()?;
+        let after = cursor_from_bytes(&req.cursor);
+        let (peers, next) = api.list_peers_from(&after, req.limit)?;
+        YListPeersRes::new(&peers, &cursor_to_bytes(&next))
+    }
+
     pub fn handle_bytes(buf: &[u8], api: &YAPI<M, P>) -> YHResult<Vec<u8>> {
         let req = YRequest::from_bytes(buf)?;
-        let res = YPingHandle::handle(req, api)?;
+        let res = YPeerHandle::handle(req, api)?;
         res.to_bytes()
     }
     
     pub fn handle_json(obj: &[u8], api: &YAPI<M, P>) -> YHResult<Vec<u8>> {
         let req = YRequest::from_json(obj)?;
-        let res = YPingHandle::handle(req, api)?;
+        let res = YPeerHandle::handle(req, api)?;
         res.to_json()
     }
 }
@@ -3,10 +3,6 @@ use api::*;
 use store::*;
 use network::rpc_method::*;
 use network::message::ping::*;
-use network::message::peer::*;
-use network::message::data::*;
-use network::message::transaction::*;
-use network::message::coinbase::*;
 use network::message::error::*;
 use network::message::request::*;
 use network::message::response::*;
@@ -17,11 +13,17 @@ pub struct YPingHandle<M, P: YStorage> {
     _persistent: PhantomData<P>,
 }
 
-impl<M, P: YStorage> YPingHandle<M, P> {
+impl<M: YStorage, P: YStorage> YPingHandle<M, P> {
     pub fn handle(req: YRequest, api: &YAPI<M, P>) -> YHResult<YResponse> {
         match req {
             YRequest::Ping(req) => {
-                unreachable!()
+                match YPingHandle::ping(req, api) {
+                    Ok(res) => Ok(YResponse::Ping(res)),
+                    Err(err) => {
+                        let res = YErrorRes::from_error(YRPCMethod::Ping, err)?;
+                        Ok(YResponse::Error(res))
+                    },
+                }
             },
             _ => {
                 let err: YHError = YHErrorKind::InvalidRequest.into();
@@ -32,6 +34,13 @@ impl<M, P: YStorage> YPingHandle<M, P> {
         }
     }
 
+    pub fn ping(req: YPingReq, api: &YAPI<M, P>) -> YHResult<YPingRes> {
+        req.check()?;
+        let pk = api.public_key();
+        let price = api.price()?;
+        YPingRes::new(pk, &price)
+    }
+
     pub fn handle_bytes(buf: &[u8], api: &YAPI<M, P>) -> YHResult<Vec<u8>> {
         let req = YRequest::from_bytes(buf)?;
         let res = YPingHandle::handle(req, api)?;
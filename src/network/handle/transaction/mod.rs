@@ -1,46 +1,249 @@
+use libyobicash::amount::YAmount;
 use std::marker::PhantomData;
 use api::*;
 use store::*;
+use models::selection::*;
+use models::fee::*;
 use network::rpc_method::*;
-use network::message::ping::*;
-use network::message::peer::*;
-use network::message::data::*;
 use network::message::transaction::*;
-use network::message::coinbase::*;
 use network::message::error::*;
 use network::message::request::*;
 use network::message::response::*;
 use errors::*;
 
-pub struct YPingHandle<M, P: YStorage> {
+pub const YCONFIRM_INCR: u32 = 1;
+
+pub struct YTransactionHandle<M, P: YStorage> {
     _memory: PhantomData<M>,
     _persistent: PhantomData<P>,
 }
 
-impl<M, P: YStorage> YPingHandle<M, P> {
-    pub fn handle(req: YRequest, api: &YAPI<M, P>) -> YHResult<YResponse> {
+impl<M: YStorage, P: YStorage> YTransactionHandle<M, P> {
+    pub fn handle(req: YRequest, api: &mut YAPI<M, P>) -> YHResult<YResponse> {
         match req {
-            YRequest::Ping(req) => {
-                unreachable!()
+            YRequest::GetTx(req) => {
+                match YTransactionHandle::get_tx(req, api) {
+                    Ok(res) => Ok(YResponse::GetTx(res)),
+                    Err(err) => {
+                        let res = YErrorRes::from_error(YRPCMethod::GetTx, err)?;
+                        Ok(YResponse::Error(res))
+                    },
+                }
+            },
+            YRequest::ConfirmTx(req) => {
+                match YTransactionHandle::confirm_tx(req, api) {
+                    Ok(res) => Ok(YResponse::ConfirmTx(res)),
+                    Err(err) => {
+                        let res = YErrorRes::from_error(YRPCMethod::ConfirmTx, err)?;
+                        Ok(YResponse::Error(res))
+                    },
+                }
+            },
+            YRequest::ListTxAncestors(req) => {
+                match YTransactionHandle::list_tx_ancestors(req, api) {
+                    Ok(res) => Ok(YResponse::ListTxAncestors(res)),
+                    Err(err) => {
+                        let res = YErrorRes::from_error(YRPCMethod::ListTxAncestors, err)?;
+                        Ok(YResponse::Error(res))
+                    },
+                }
+            },
+            YRequest::SendCoins(req) => {
+                match YTransactionHandle::send_coins(req, api) {
+                    Ok(res) => Ok(YResponse::SendCoins(res)),
+                    Err(err) => {
+                        let res = YErrorRes::from_error(YRPCMethod::SendCoins, err)?;
+                        Ok(YResponse::Error(res))
+                    },
+                }
+            },
+            YRequest::SendData(req) => {
+                match YTransactionHandle::send_data(req, api) {
+                    Ok(res) => Ok(YResponse::SendData(res)),
+                    Err(err) => {
+                        let res = YErrorRes::from_error(YRPCMethod::SendData, err)?;
+                        Ok(YResponse::Error(res))
+                    },
+                }
+            },
+            YRequest::SendBatch(req) => {
+                match YTransactionHandle::send_batch(req, api) {
+                    Ok(res) => Ok(YResponse::SendBatch(res)),
+                    Err(err) => {
+                        let res = YErrorRes::from_error(YRPCMethod::SendBatch, err)?;
+                        Ok(YResponse::Error(res))
+                    },
+                }
+            },
+            YRequest::CreateUnsignedTx(req) => {
+                match YTransactionHandle::create_unsigned_tx(req, api) {
+                    Ok(res) => Ok(YResponse::CreateUnsignedTx(res)),
+                    Err(err) => {
+                        let res = YErrorRes::from_error(YRPCMethod::CreateUnsignedTx, err)?;
+                        Ok(YResponse::Error(res))
+                    },
+                }
+            },
+            YRequest::PushTx(req) => {
+                match YTransactionHandle::push_tx(req, api) {
+                    Ok(res) => Ok(YResponse::PushTx(res)),
+                    Err(err) => {
+                        let res = YErrorRes::from_error(YRPCMethod::PushTx, err)?;
+                        Ok(YResponse::Error(res))
+                    },
+                }
+            },
+            YRequest::ListTxs(req) => {
+                match YTransactionHandle::list_txs(req, api) {
+                    Ok(res) => Ok(YResponse::ListTxs(res)),
+                    Err(err) => {
+                        let res = YErrorRes::from_error(YRPCMethod::ListTxs, err)?;
+                        Ok(YResponse::Error(res))
+                    },
+                }
             },
             _ => {
                 let err: YHError = YHErrorKind::InvalidRequest.into();
-                let method = YRPCMethod::Ping;
+                let method = YRPCMethod::GetTx;
                 let res = YErrorRes::from_error(method, err)?;
                 Ok(YResponse::Error(res)) 
             }
         }
     }
 
-    pub fn handle_bytes(buf: &[u8], api: &YAPI<M, P>) -> YHResult<Vec<u8>> {
+    pub fn get_tx(req: YGetTxReq, api: &YAPI<M, P>) -> YHResult<YGetTxRes> {
+        req.check()?;
+        let tx = api.get_transaction(req.tx_id)?;
+        YGetTxRes::new(&tx.internal())
+    }
+
+    pub fn confirm_tx(req: YConfirmTxReq, api: &mut YAPI<M, P>) -> YHResult<YConfirmTxRes> {
+        req.check()?;
+        let wallet = api.config.wallet.clone();
+        let fee_pk = api.public_key();
+        match api.confirm_transaction(&wallet, req.tx_id, YCONFIRM_INCR, fee_pk)? {
+            (true, Some(cb)) => {
+                YConfirmTxRes::new(true, &cb.internal())
+            },
+            _ => {
+                Err(YHErrorKind::NotFound.into())
+            },
+        }
+    }
+
+    pub fn list_tx_ancestors(req: YListTxAncestorsReq, api: &mut YAPI<M, P>) -> YHResult<YListTxAncestorsRes> {
+        req.check()?;
+        let (_txs, _) = api.list_transaction_ancestors(req.tx_id)?;
+        let mut txs = Vec::new();
+        for tx in _txs {
+            txs.push(tx.internal());
+        }
+        YListTxAncestorsRes::new(&txs)
+    }
+
+    // a zero fee asks for an estimate, a zero max fee leaves it unbounded
+    pub fn fee(fee: &YAmount, max_fee: &YAmount, api: &YAPI<M, P>) -> YHResult<YFee> {
+        let fee_pk = api.fee_public_key()?;
+        let max_fee = if *max_fee == YAmount::zero() {
+            None
+        } else {
+            Some(max_fee.clone())
+        };
+        let mut fee = if *fee == YAmount::zero() {
+            YFee::estimated(fee_pk, &api.fee_estimator()?, None)
+        } else {
+            YFee::new(fee_pk, fee)
+        };
+        fee.max_amount = max_fee;
+        Ok(fee)
+    }
+
+    pub fn send_coins(req: YSendCoinsReq, api: &mut YAPI<M, P>) -> YHResult<YSendCoinsRes> {
+        req.check()?;
+        let key = api.get_key();
+        let fee = YTransactionHandle::fee(&req.fee, &req.max_fee, api)?;
+        let (tx, fee_tx) = api.create_coin_transaction(key, &req.wallet, req.to, req.amount.clone(), req.keep_data,
+                                                       &req.selection, YSELECTION_MAX_INPUTS, &fee)?;
+        let fee_tx = fee_tx.map(|fee_tx| fee_tx.internal());
+        api.queue_relay(&tx.internal());
+        if let Some(ref fee_tx) = fee_tx {
+            api.queue_relay(fee_tx);
+        }
+        YSendCoinsRes::new(&tx.internal(), &fee_tx)
+    }
+
+    pub fn send_data(req: YSendDataReq, api: &mut YAPI<M, P>) -> YHResult<YSendDataRes> {
+        req.check()?;
+        let key = api.get_key();
+        let fee = YTransactionHandle::fee(&req.fee, &req.max_fee, api)?;
+        let (tx, fee_tx) = api.create_data_transaction(key, &req.wallet, req.to, &req.data, req.keep_data,
+                                                       &req.selection, YSELECTION_MAX_INPUTS, &fee)?;
+        let fee_tx = fee_tx.map(|fee_tx| fee_tx.internal());
+        api.queue_relay(&tx.internal());
+        if let Some(ref fee_tx) = fee_tx {
+            api.queue_relay(fee_tx);
+        }
+        YSendDataRes::new(&tx.internal(), &fee_tx)
+    }
+
+    pub fn send_batch(req: YSendBatchReq, api: &mut YAPI<M, P>) -> YHResult<YSendBatchRes> {
+        req.check()?;
+        let key = api.get_key();
+        let fee = YTransactionHandle::fee(&req.fee, &req.max_fee, api)?;
+        let mut payments = Vec::new();
+        for payment in req.payments.iter() {
+            payments.push((payment.to, payment.amount.clone()));
+        }
+        let (_txs, fee_tx) = api.create_batch_transaction(key, &req.wallet, &payments, req.keep_data,
+                                                          &YCoinSelection::default(), YSELECTION_MAX_INPUTS, &fee)?;
+        let mut txs = Vec::new();
+        for tx in _txs {
+            api.queue_relay(&tx.internal());
+            txs.push(tx.internal());
+        }
+        let fee_tx = fee_tx.map(|fee_tx| fee_tx.internal());
+        if let Some(ref fee_tx) = fee_tx {
+            api.queue_relay(fee_tx);
+        }
+        YSendBatchRes::new(&txs, &fee_tx)
+    }
+
+    pub fn create_unsigned_tx(req: YCreateUnsignedTxReq, api: &mut YAPI<M, P>) -> YHResult<YCreateUnsignedTxRes> {
+        req.check()?;
+        let key = api.get_key();
+        let fee = YTransactionHandle::fee(&req.fee, &req.max_fee, api)?;
+        let unsigned = api.create_unsigned_transaction(key, &req.wallet, req.to, req.amount.clone(), req.keep_data,
+                                                       &req.selection, YSELECTION_MAX_INPUTS, &fee)?;
+        YCreateUnsignedTxRes::new(&unsigned.to_bytes()?)
+    }
+
+    pub fn push_tx(req: YPushTxReq, api: &mut YAPI<M, P>) -> YHResult<YPushTxRes> {
+        req.check()?;
+        let tx = api.push_transaction(&req.tx)?;
+        api.queue_relay(&req.tx);
+        YPushTxRes::new(tx.internal().id)
+    }
+
+    pub fn list_txs(req: YListTxsReq, api: &YAPI<M, P>) -> YHResult<YListTxsRes> {
+        req.check()?;
+        let after = cursor_from_bytes(&req.cursor);
+        let (_txs, next) = api.list_transactions_by_wallet_from(&req.wallet, &after, req.limit)?;
+        let mut txs = Vec::new();
+        for tx in _txs {
+            txs.push(tx.internal());
+        }
+        YListTxsRes::new(&txs, &cursor_to_bytes(&next))
+    }
+
+    pub fn handle_bytes(buf: &[u8], api: &mut YAPI<M, P>) -> YHResult<Vec<u8>> {
         let req = YRequest::from_bytes(buf)?;
-        let res = YPingHandle::handle(req, api)?;
+        let res = YTransactionHandle::handle(req, api)?;
         res.to_bytes()
     }
     
-    pub fn handle_json(obj: &[u8], api: &YAPI<M, P>) -> YHResult<Vec<u8>> {
+    pub fn handle_json(obj: &[u8], api: &mut YAPI<M, P>) -> YHResult<Vec<u8>> {
         let req = YRequest::from_json(obj)?;
-        let res = YPingHandle::handle(req, api)?;
+        let res = YTransactionHandle::handle(req, api)?;
         res.to_json()
     }
 }
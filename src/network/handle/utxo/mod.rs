@@ -0,0 +1,72 @@
+use std::marker::PhantomData;
+use api::*;
+use store::*;
+use network::rpc_method::*;
+use network::message::utxo::*;
+use network::message::error::*;
+use network::message::request::*;
+use network::message::response::*;
+use errors::*;
+
+pub struct YUTXOHandle<M, P: YStorage> {
+    _memory: PhantomData<M>,
+    _persistent: PhantomData<P>,
+}
+
+impl<M: YStorage, P: YStorage> YUTXOHandle<M, P> {
+    pub fn handle(req: YRequest, api: &YAPI<M, P>) -> YHResult<YResponse> {
+        match req {
+            YRequest::GetUTXO(req) => {
+                match YUTXOHandle::get_utxo(req, api) {
+                    Ok(res) => Ok(YResponse::GetUTXO(res)),
+                    Err(err) => {
+                        let res = YErrorRes::from_error(YRPCMethod::GetUTXO, err)?;
+                        Ok(YResponse::Error(res))
+                    },
+                }
+            },
+            YRequest::ListUTXOs(req) => {
+                match YUTXOHandle::list_utxos(req, api) {
+                    Ok(res) => Ok(YResponse::ListUTXOs(res)),
+                    Err(err) => {
+                        let res = YErrorRes::from_error(YRPCMethod::ListUTXOs, err)?;
+                        Ok(YResponse::Error(res))
+                    },
+                }
+            },
+            _ => {
+                let err: YHError = YHErrorKind::InvalidRequest.into();
+                let method = YRPCMethod::GetUTXO;
+                let res = YErrorRes::from_error(method, err)?;
+                Ok(YResponse::Error(res)) 
+            }
+        }
+    }
+
+    pub fn get_utxo(req: YGetUTXOReq, api: &YAPI<M, P>) -> YHResult<YGetUTXORes> {
+        req.check()?;
+        let utxo = api.get_utxo(req.tx_id, req.idx)?;
+        YGetUTXORes::new(&utxo.internal())
+    }
+
+    pub fn list_utxos(req: YListUTXOsReq, api: &YAPI<M, P>) -> YHResult<YListUTXOsRes> {
+        req.check()?;
+        let mut utxos = Vec::new();
+        for utxo in api.list_utxos(req.tx_id)? {
+            utxos.push(utxo.internal());
+        }
+        YListUTXOsRes::new(&utxos)
+    }
+
+    pub fn handle_bytes(buf: &[u8], api: &YAPI<M, P>) -> YHResult<Vec<u8>> {
+        let req = YRequest::from_bytes(buf)?;
+        let res = YUTXOHandle::handle(req, api)?;
+        res.to_bytes()
+    }
+    
+    pub fn handle_json(obj: &[u8], api: &YAPI<M, P>) -> YHResult<Vec<u8>> {
+        let req = YRequest::from_json(obj)?;
+        let res = YUTXOHandle::handle(req, api)?;
+        res.to_json()
+    }
+}
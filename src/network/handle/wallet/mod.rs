@@ -0,0 +1,142 @@
+use std::marker::PhantomData;
+use api::*;
+use store::*;
+use network::rpc_method::*;
+use network::message::wallet::*;
+use network::message::coin::*;
+use network::message::error::*;
+use network::message::request::*;
+use network::message::response::*;
+use errors::*;
+
+pub struct YWalletHandle<M, P: YStorage> {
+    _memory: PhantomData<M>,
+    _persistent: PhantomData<P>,
+}
+
+impl<M: YStorage, P: YStorage> YWalletHandle<M, P> {
+    pub fn handle(req: YRequest, api: &mut YAPI<M, P>) -> YHResult<YResponse> {
+        match req {
+            YRequest::CreateWallet(req) => {
+                match YWalletHandle::create_wallet(req, api) {
+                    Ok(res) => Ok(YResponse::CreateWallet(res)),
+                    Err(err) => {
+                        let res = YErrorRes::from_error(YRPCMethod::CreateWallet, err)?;
+                        Ok(YResponse::Error(res))
+                    },
+                }
+            },
+            YRequest::GetWallet(req) => {
+                match YWalletHandle::get_wallet(req, api) {
+                    Ok(res) => Ok(YResponse::GetWallet(res)),
+                    Err(err) => {
+                        let res = YErrorRes::from_error(YRPCMethod::GetWallet, err)?;
+                        Ok(YResponse::Error(res))
+                    },
+                }
+            },
+            YRequest::ListWallets(req) => {
+                match YWalletHandle::list_wallets(req, api) {
+                    Ok(res) => Ok(YResponse::ListWallets(res)),
+                    Err(err) => {
+                        let res = YErrorRes::from_error(YRPCMethod::ListWallets, err)?;
+                        Ok(YResponse::Error(res))
+                    },
+                }
+            },
+            YRequest::ListCoins(req) => {
+                match YWalletHandle::list_coins(req, api) {
+                    Ok(res) => Ok(YResponse::ListCoins(res)),
+                    Err(err) => {
+                        let res = YErrorRes::from_error(YRPCMethod::ListCoins, err)?;
+                        Ok(YResponse::Error(res))
+                    },
+                }
+            },
+            YRequest::CreateReceiveKey(req) => {
+                match YWalletHandle::create_receive_key(req, api) {
+                    Ok(res) => Ok(YResponse::CreateReceiveKey(res)),
+                    Err(err) => {
+                        let res = YErrorRes::from_error(YRPCMethod::CreateReceiveKey, err)?;
+                        Ok(YResponse::Error(res))
+                    },
+                }
+            },
+            YRequest::ListHistory(req) => {
+                match YWalletHandle::list_history(req, api) {
+                    Ok(res) => Ok(YResponse::ListHistory(res)),
+                    Err(err) => {
+                        let res = YErrorRes::from_error(YRPCMethod::ListHistory, err)?;
+                        Ok(YResponse::Error(res))
+                    },
+                }
+            },
+            _ => {
+                let err: YHError = YHErrorKind::InvalidRequest.into();
+                let method = YRPCMethod::GetWallet;
+                let res = YErrorRes::from_error(method, err)?;
+                Ok(YResponse::Error(res)) 
+            }
+        }
+    }
+
+    pub fn create_wallet(req: YCreateWalletReq, api: &mut YAPI<M, P>) -> YHResult<YCreateWalletRes> {
+        req.check()?;
+        api.create_wallet(&req.name)?;
+        let wallet = api.get_wallet(&req.name)?;
+        YCreateWalletRes::new(&wallet.to_public())
+    }
+
+    pub fn get_wallet(req: YGetWalletReq, api: &YAPI<M, P>) -> YHResult<YGetWalletRes> {
+        req.check()?;
+        let wallet = api.get_wallet(&req.name)?;
+        YGetWalletRes::new(&wallet.to_public())
+    }
+
+    pub fn list_wallets(req: YListWalletsReq, api: &YAPI<M, P>) -> YHResult<YListWalletsRes> {
+        req.check()?;
+        let after = cursor_from_bytes(&req.cursor);
+        let (wallets, next) = api.list_wallets_from(&after, req.limit)?;
+        let wallets = wallets.iter()
+            .map(|wallet| wallet.to_public())
+            .collect();
+        YListWalletsRes::new(&wallets, &cursor_to_bytes(&next))
+    }
+
+    pub fn list_coins(req: YListCoinsReq, api: &YAPI<M, P>) -> YHResult<YListCoinsRes> {
+        req.check()?;
+        let coins = match req.kind {
+            YCoinsKind::All => api.list_coins(&req.wallet)?,
+            YCoinsKind::Unspent => api.list_ucoins(&req.wallet)?,
+            YCoinsKind::Spent => api.list_scoins(&req.wallet)?,
+        };
+        let coins = coins.iter()
+            .map(|coin| coin.to_public())
+            .collect();
+        YListCoinsRes::new(&coins)
+    }
+
+    pub fn create_receive_key(req: YCreateReceiveKeyReq, api: &mut YAPI<M, P>) -> YHResult<YCreateReceiveKeyRes> {
+        req.check()?;
+        let pk = api.new_receive_key(&req.wallet)?;
+        YCreateReceiveKeyRes::new(pk)
+    }
+
+    pub fn list_history(req: YListHistoryReq, api: &YAPI<M, P>) -> YHResult<YListHistoryRes> {
+        req.check()?;
+        let history = api.wallet_history(&req.wallet)?;
+        YListHistoryRes::new(&history.entries)
+    }
+
+    pub fn handle_bytes(buf: &[u8], api: &mut YAPI<M, P>) -> YHResult<Vec<u8>> {
+        let req = YRequest::from_bytes(buf)?;
+        let res = YWalletHandle::handle(req, api)?;
+        res.to_bytes()
+    }
+    
+    pub fn handle_json(obj: &[u8], api: &mut YAPI<M, P>) -> YHResult<Vec<u8>> {
+        let req = YRequest::from_json(obj)?;
+        let res = YWalletHandle::handle(req, api)?;
+        res.to_json()
+    }
+}
@@ -0,0 +1,267 @@
+use libyobicash::errors::YErrorKind as LibErrorKind;
+use libyobicash::utils::random::*;
+use libyobicash::utils::time::*;
+use libyobicash::utils::version::*;
+use libyobicash::crypto::hash::digest::YDigest64;
+use libyobicash::crypto::hash::sha::YSHA512;
+use bytes::{BytesMut, BufMut, BigEndian, ByteOrder};
+use models::coin::YPublicCoin;
+use network::rpc_method::YRPCMethod;
+use version::*;
+use errors::*;
+
+#[derive(Copy, Clone, Eq, PartialEq, Debug, Serialize, Deserialize)]
+pub enum YCoinsKind {
+    All=0,
+    Unspent=1,
+    Spent=2,
+}
+
+impl Default for YCoinsKind {
+    fn default() -> YCoinsKind {
+        YCoinsKind::All
+    }
+}
+
+impl YCoinsKind {
+    pub fn to_bytes(&self) -> Vec<u8> {
+        let mut buf = BytesMut::new();
+        buf.put_u32::<BigEndian>(*self as u32);
+        buf.to_vec()
+    }
+
+    pub fn from_bytes(b: &[u8]) -> YHResult<YCoinsKind> {
+        if b.len() != 4 {
+            return Err(YHErrorKind::InvalidLength.into());
+        }
+        match BigEndian::read_u32(b) {
+            0 => { Ok(YCoinsKind::All) },
+            1 => { Ok(YCoinsKind::Unspent) },
+            2 => { Ok(YCoinsKind::Spent) },
+            _ => { Err(YHErrorKind::InvalidCoinKind.into()) },
+        }
+    }
+}
+
+#[derive(Clone, Eq, PartialEq, Debug, Default, Serialize, Deserialize)]
+pub struct YListCoinsReq {
+    pub id: YDigest64,
+    pub version: YVersion,
+    pub time: YTime,
+    pub nonce: u32,
+    pub method: YRPCMethod,
+    pub wallet: String,
+    pub kind: YCoinsKind,
+}
+
+impl YListCoinsReq {
+    pub fn new(wallet: &str, kind: YCoinsKind) -> YHResult<YListCoinsReq> {
+        let mut req = YListCoinsReq {
+            id: YDigest64::default(),
+            version: default_version(),
+            time: YTime::now(),
+            nonce: YRandom::u32(),
+            method: YRPCMethod::ListCoins,
+            wallet: String::from(wallet),
+            kind: kind,
+        };
+        req.id = req.calc_id()?;
+        Ok(req)
+    }
+
+    pub fn check(&self) -> YHResult<()> {
+        if self.id != self.calc_id()? {
+            return Err(YHErrorKind::Lib(LibErrorKind::InvalidChecksum).into());
+        }
+        if self.version.major() > default_version().major() {
+            return Err(YHErrorKind::Lib(LibErrorKind::InvalidVersion(self.version.to_string())).into());
+        }
+        if self.time > YTime::now() {
+            return Err(YHErrorKind::Lib(LibErrorKind::InvalidTime).into());
+        }
+        if self.method != YRPCMethod::ListCoins {
+            return Err(YHErrorKind::InvalidRPCMethod.into());
+        }
+        Ok(())
+    }
+
+    pub fn calc_id(&self) -> YHResult<YDigest64> {
+        let mut buf = BytesMut::new();
+        buf.put(&self.version.to_bytes()?[..]);
+        buf.put(&self.time.to_bytes()[..]);
+        buf.put_u32::<BigEndian>(self.nonce);
+        buf.put(self.method.to_bytes());
+        let wallet_buf = self.wallet.as_bytes();
+        buf.put_u32::<BigEndian>(wallet_buf.len() as u32);
+        buf.put(wallet_buf);
+        buf.put(self.kind.to_bytes());
+        Ok(YSHA512::hash(&buf.to_vec()))
+    }
+
+    pub fn to_bytes(&self) -> YHResult<Vec<u8>> {
+        self.check()?;
+        let mut buf = BytesMut::new();
+        buf.put(self.id.to_bytes());
+        buf.put(&self.version.to_bytes()?[..]);
+        buf.put(&self.time.to_bytes()[..]);
+        buf.put_u32::<BigEndian>(self.nonce);
+        buf.put(self.method.to_bytes());
+        let wallet_buf = self.wallet.as_bytes();
+        buf.put_u32::<BigEndian>(wallet_buf.len() as u32);
+        buf.put(wallet_buf);
+        buf.put(self.kind.to_bytes());
+        Ok(buf.to_vec())
+    }
+
+    pub fn from_bytes(buf: &[u8]) -> YHResult<YListCoinsReq> {
+        if buf.len() < 92 {
+            return Err(YHErrorKind::InvalidLength.into());
+        }
+        let mut b = BytesMut::new();
+        b.extend_from_slice(buf);
+        let id = YDigest64::from_bytes(b.get(0..64).unwrap())?;
+        let version = YVersion::from_bytes(b.get(64..76).unwrap())?;
+        let time = YTime::from_bytes(b.get(76..84).unwrap())?;
+        let nonce = BigEndian::read_u32(b.get(84..88).unwrap());
+        let method = BigEndian::read_u32(b.get(88..92).unwrap()).into();
+        let mut i = 92;
+        let wallet_size = BigEndian::read_u32(b.get(i..i+4).ok_or(YHErrorKind::InvalidLength)?) as usize;
+        i += 4;
+        let wallet = String::from_utf8(b.get(i..i+wallet_size).ok_or(YHErrorKind::InvalidLength)?.to_vec())?;
+        i += wallet_size;
+        let kind = YCoinsKind::from_bytes(b.get(i..i+4).ok_or(YHErrorKind::InvalidLength)?)?;
+        i += 4;
+        if i != buf.len() {
+            return Err(YHErrorKind::InvalidLength.into());
+        }
+        let req = YListCoinsReq {
+            id: id,
+            version: version,
+            time: time,
+            nonce: nonce,
+            method: method,
+            wallet: wallet,
+            kind: kind,
+        };
+        req.check()?;
+        Ok(req)
+    }
+}
+
+#[derive(Clone, Eq, PartialEq, Debug, Default, Serialize, Deserialize)]
+pub struct YListCoinsRes {
+    pub id: YDigest64,
+    pub version: YVersion,
+    pub time: YTime,
+    pub nonce: u32,
+    pub method: YRPCMethod,
+    pub count: u32,
+    pub coins: Vec<YPublicCoin>,
+}
+
+impl YListCoinsRes {
+    pub fn new(coins: &Vec<YPublicCoin>) -> YHResult<YListCoinsRes> {
+        let mut res = YListCoinsRes {
+            id: YDigest64::default(),
+            version: default_version(),
+            time: YTime::now(),
+            nonce: YRandom::u32(),
+            method: YRPCMethod::ListCoins,
+            count: coins.len() as u32,
+            coins: coins.clone(),
+        };
+        res.id = res.calc_id()?;
+        Ok(res)
+    }
+
+    pub fn check(&self) -> YHResult<()> {
+        if self.id != self.calc_id()? {
+            return Err(YHErrorKind::Lib(LibErrorKind::InvalidChecksum).into());
+        }
+        if self.version.major() > default_version().major() {
+            return Err(YHErrorKind::Lib(LibErrorKind::InvalidVersion(self.version.to_string())).into());
+        }
+        if self.time > YTime::now() {
+            return Err(YHErrorKind::Lib(LibErrorKind::InvalidTime).into());
+        }
+        if self.method != YRPCMethod::ListCoins {
+            return Err(YHErrorKind::InvalidRPCMethod.into());
+        }
+        if self.coins.len() != self.count as usize {
+            return Err(YHErrorKind::InvalidLength.into());
+        }
+        for coin in self.coins.iter() {
+            coin.check()?;
+        }
+        Ok(())
+    }
+
+    pub fn calc_id(&self) -> YHResult<YDigest64> {
+        let mut buf = BytesMut::new();
+        buf.put(&self.version.to_bytes()?[..]);
+        buf.put(&self.time.to_bytes()[..]);
+        buf.put_u32::<BigEndian>(self.nonce);
+        buf.put(self.method.to_bytes());
+        buf.put_u32::<BigEndian>(self.count);
+        for coin in self.coins.iter() {
+            let coin_buf = coin.to_bytes()?;
+            buf.put_u32::<BigEndian>(coin_buf.len() as u32);
+            buf.put(coin_buf);
+        }
+        Ok(YSHA512::hash(&buf.to_vec()))
+    }
+
+    pub fn to_bytes(&self) -> YHResult<Vec<u8>> {
+        self.check()?;
+        let mut buf = BytesMut::new();
+        buf.put(self.id.to_bytes());
+        buf.put(&self.version.to_bytes()?[..]);
+        buf.put(&self.time.to_bytes()[..]);
+        buf.put_u32::<BigEndian>(self.nonce);
+        buf.put(self.method.to_bytes());
+        buf.put_u32::<BigEndian>(self.count);
+        for coin in self.coins.iter() {
+            let coin_buf = coin.to_bytes()?;
+            buf.put_u32::<BigEndian>(coin_buf.len() as u32);
+            buf.put(coin_buf);
+        }
+        Ok(buf.to_vec())
+    }
+
+    pub fn from_bytes(buf: &[u8]) -> YHResult<YListCoinsRes> {
+        if buf.len() < 92 {
+            return Err(YHErrorKind::InvalidLength.into());
+        }
+        let mut b = BytesMut::new();
+        b.extend_from_slice(buf);
+        let id = YDigest64::from_bytes(b.get(0..64).unwrap())?;
+        let version = YVersion::from_bytes(b.get(64..76).unwrap())?;
+        let time = YTime::from_bytes(b.get(76..84).unwrap())?;
+        let nonce = BigEndian::read_u32(b.get(84..88).unwrap());
+        let method = BigEndian::read_u32(b.get(88..92).unwrap()).into();
+        let mut i = 92;
+        let count = BigEndian::read_u32(b.get(i..i+4).ok_or(YHErrorKind::InvalidLength)?);
+        i += 4;
+        let mut coins = Vec::new();
+        for _ in 0..count {
+            let size = BigEndian::read_u32(b.get(i..i+4).ok_or(YHErrorKind::InvalidLength)?) as usize;
+            i += 4;
+            coins.push(YPublicCoin::from_bytes(b.get(i..i+size).ok_or(YHErrorKind::InvalidLength)?)?);
+            i += size;
+        }
+        if i != buf.len() {
+            return Err(YHErrorKind::InvalidLength.into());
+        }
+        let res = YListCoinsRes {
+            id: id,
+            version: version,
+            time: time,
+            nonce: nonce,
+            method: method,
+            count: count,
+            coins: coins,
+        };
+        res.check()?;
+        Ok(res)
+    }
+}
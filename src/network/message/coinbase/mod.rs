@@ -73,7 +73,7 @@ impl YGetCbReq {
     }
 
     pub fn from_bytes(buf: &[u8]) -> YHResult<YGetCbReq> {
-        if buf.len() != 156 {
+        if buf.len() < 92 {
             return Err(YHErrorKind::InvalidLength.into());
         }
         let mut b = BytesMut::new();
@@ -83,8 +83,13 @@ impl YGetCbReq {
         let time = YTime::from_bytes(b.get(76..84).unwrap())?;
         let nonce = BigEndian::read_u32(b.get(84..88).unwrap());
         let method = BigEndian::read_u32(b.get(88..92).unwrap()).into();
-        let cb_id = YDigest64::from_bytes(b.get(92..156).unwrap())?;
-        let get_cb_req = YGetCbReq {
+        let mut i = 92;
+        let cb_id = YDigest64::from_bytes(b.get(i..i+64).ok_or(YHErrorKind::InvalidLength)?)?;
+        i += 64;
+        if i != buf.len() {
+            return Err(YHErrorKind::InvalidLength.into());
+        }
+        let req = YGetCbReq {
             id: id,
             version: version,
             time: time,
@@ -92,8 +97,8 @@ impl YGetCbReq {
             method: method,
             cb_id: cb_id,
         };
-        get_cb_req.check()?;
-        Ok(get_cb_req)
+        req.check()?;
+        Ok(req)
     }
 }
 
@@ -144,7 +149,9 @@ impl YGetCbRes {
         buf.put(&self.time.to_bytes()[..]);
         buf.put_u32::<BigEndian>(self.nonce);
         buf.put(self.method.to_bytes());
-        buf.put(self.cb.to_bytes()?);
+        let cb_buf = self.cb.to_bytes()?;
+        buf.put_u32::<BigEndian>(cb_buf.len() as u32);
+        buf.put(cb_buf);
         Ok(YSHA512::hash(&buf.to_vec()))
     }
 
@@ -156,12 +163,113 @@ impl YGetCbRes {
         buf.put(&self.time.to_bytes()[..]);
         buf.put_u32::<BigEndian>(self.nonce);
         buf.put(self.method.to_bytes());
-        buf.put(self.cb.to_bytes()?);
+        let cb_buf = self.cb.to_bytes()?;
+        buf.put_u32::<BigEndian>(cb_buf.len() as u32);
+        buf.put(cb_buf);
         Ok(buf.to_vec())
     }
 
     pub fn from_bytes(buf: &[u8]) -> YHResult<YGetCbRes> {
-        if buf.len() < 192 {
+        if buf.len() < 92 {
+            return Err(YHErrorKind::InvalidLength.into());
+        }
+        let mut b = BytesMut::new();
+        b.extend_from_slice(buf);
+        let id = YDigest64::from_bytes(b.get(0..64).unwrap())?;
+        let version = YVersion::from_bytes(b.get(64..76).unwrap())?;
+        let time = YTime::from_bytes(b.get(76..84).unwrap())?;
+        let nonce = BigEndian::read_u32(b.get(84..88).unwrap());
+        let method = BigEndian::read_u32(b.get(88..92).unwrap()).into();
+        let mut i = 92;
+        let cb_size = BigEndian::read_u32(b.get(i..i+4).ok_or(YHErrorKind::InvalidLength)?) as usize;
+        i += 4;
+        let cb = YCoinbase::from_bytes(b.get(i..i+cb_size).ok_or(YHErrorKind::InvalidLength)?)?;
+        i += cb_size;
+        if i != buf.len() {
+            return Err(YHErrorKind::InvalidLength.into());
+        }
+        let res = YGetCbRes {
+            id: id,
+            version: version,
+            time: time,
+            nonce: nonce,
+            method: method,
+            cb: cb,
+        };
+        res.check()?;
+        Ok(res)
+    }
+}
+
+#[derive(Clone, Eq, PartialEq, Debug, Default, Serialize, Deserialize)]
+pub struct YPushCbReq {
+    pub id: YDigest64,
+    pub version: YVersion,
+    pub time: YTime,
+    pub nonce: u32,
+    pub method: YRPCMethod,
+    pub cb: YCoinbase,
+}
+
+impl YPushCbReq {
+    pub fn new(cb: &YCoinbase) -> YHResult<YPushCbReq> {
+        let mut req = YPushCbReq {
+            id: YDigest64::default(),
+            version: default_version(),
+            time: YTime::now(),
+            nonce: YRandom::u32(),
+            method: YRPCMethod::PushCb,
+            cb: cb.clone(),
+        };
+        req.id = req.calc_id()?;
+        Ok(req)
+    }
+
+    pub fn check(&self) -> YHResult<()> {
+        if self.id != self.calc_id()? {
+            return Err(YHErrorKind::Lib(LibErrorKind::InvalidChecksum).into());
+        }
+        if self.version.major() > default_version().major() {
+            return Err(YHErrorKind::Lib(LibErrorKind::InvalidVersion(self.version.to_string())).into());
+        }
+        if self.time > YTime::now() {
+            return Err(YHErrorKind::Lib(LibErrorKind::InvalidTime).into());
+        }
+        if self.method != YRPCMethod::PushCb {
+            return Err(YHErrorKind::InvalidRPCMethod.into());
+        }
+        self.cb.check()?;
+        Ok(())
+    }
+
+    pub fn calc_id(&self) -> YHResult<YDigest64> {
+        let mut buf = BytesMut::new();
+        buf.put(&self.version.to_bytes()?[..]);
+        buf.put(&self.time.to_bytes()[..]);
+        buf.put_u32::<BigEndian>(self.nonce);
+        buf.put(self.method.to_bytes());
+        let cb_buf = self.cb.to_bytes()?;
+        buf.put_u32::<BigEndian>(cb_buf.len() as u32);
+        buf.put(cb_buf);
+        Ok(YSHA512::hash(&buf.to_vec()))
+    }
+
+    pub fn to_bytes(&self) -> YHResult<Vec<u8>> {
+        self.check()?;
+        let mut buf = BytesMut::new();
+        buf.put(self.id.to_bytes());
+        buf.put(&self.version.to_bytes()?[..]);
+        buf.put(&self.time.to_bytes()[..]);
+        buf.put_u32::<BigEndian>(self.nonce);
+        buf.put(self.method.to_bytes());
+        let cb_buf = self.cb.to_bytes()?;
+        buf.put_u32::<BigEndian>(cb_buf.len() as u32);
+        buf.put(cb_buf);
+        Ok(buf.to_vec())
+    }
+
+    pub fn from_bytes(buf: &[u8]) -> YHResult<YPushCbReq> {
+        if buf.len() < 92 {
             return Err(YHErrorKind::InvalidLength.into());
         }
         let mut b = BytesMut::new();
@@ -171,8 +279,15 @@ impl YGetCbRes {
         let time = YTime::from_bytes(b.get(76..84).unwrap())?;
         let nonce = BigEndian::read_u32(b.get(84..88).unwrap());
         let method = BigEndian::read_u32(b.get(88..92).unwrap()).into();
-        let cb = YCoinbase::from_bytes(b.get(92..).unwrap())?;
-        let get_cb_res = YGetCbRes {
+        let mut i = 92;
+        let cb_size = BigEndian::read_u32(b.get(i..i+4).ok_or(YHErrorKind::InvalidLength)?) as usize;
+        i += 4;
+        let cb = YCoinbase::from_bytes(b.get(i..i+cb_size).ok_or(YHErrorKind::InvalidLength)?)?;
+        i += cb_size;
+        if i != buf.len() {
+            return Err(YHErrorKind::InvalidLength.into());
+        }
+        let req = YPushCbReq {
             id: id,
             version: version,
             time: time,
@@ -180,7 +295,548 @@ impl YGetCbRes {
             method: method,
             cb: cb,
         };
-        get_cb_res.check()?;
-        Ok(get_cb_res)
+        req.check()?;
+        Ok(req)
+    }
+}
+
+#[derive(Clone, Eq, PartialEq, Debug, Default, Serialize, Deserialize)]
+pub struct YPushCbRes {
+    pub id: YDigest64,
+    pub version: YVersion,
+    pub time: YTime,
+    pub nonce: u32,
+    pub method: YRPCMethod,
+    pub cb_id: YDigest64,
+}
+
+impl YPushCbRes {
+    pub fn new(cb_id: YDigest64) -> YHResult<YPushCbRes> {
+        let mut res = YPushCbRes {
+            id: YDigest64::default(),
+            version: default_version(),
+            time: YTime::now(),
+            nonce: YRandom::u32(),
+            method: YRPCMethod::PushCb,
+            cb_id: cb_id,
+        };
+        res.id = res.calc_id()?;
+        Ok(res)
+    }
+
+    pub fn check(&self) -> YHResult<()> {
+        if self.id != self.calc_id()? {
+            return Err(YHErrorKind::Lib(LibErrorKind::InvalidChecksum).into());
+        }
+        if self.version.major() > default_version().major() {
+            return Err(YHErrorKind::Lib(LibErrorKind::InvalidVersion(self.version.to_string())).into());
+        }
+        if self.time > YTime::now() {
+            return Err(YHErrorKind::Lib(LibErrorKind::InvalidTime).into());
+        }
+        if self.method != YRPCMethod::PushCb {
+            return Err(YHErrorKind::InvalidRPCMethod.into());
+        }
+        Ok(())
+    }
+
+    pub fn calc_id(&self) -> YHResult<YDigest64> {
+        let mut buf = BytesMut::new();
+        buf.put(&self.version.to_bytes()?[..]);
+        buf.put(&self.time.to_bytes()[..]);
+        buf.put_u32::<BigEndian>(self.nonce);
+        buf.put(self.method.to_bytes());
+        buf.put(self.cb_id.to_bytes());
+        Ok(YSHA512::hash(&buf.to_vec()))
+    }
+
+    pub fn to_bytes(&self) -> YHResult<Vec<u8>> {
+        self.check()?;
+        let mut buf = BytesMut::new();
+        buf.put(self.id.to_bytes());
+        buf.put(&self.version.to_bytes()?[..]);
+        buf.put(&self.time.to_bytes()[..]);
+        buf.put_u32::<BigEndian>(self.nonce);
+        buf.put(self.method.to_bytes());
+        buf.put(self.cb_id.to_bytes());
+        Ok(buf.to_vec())
+    }
+
+    pub fn from_bytes(buf: &[u8]) -> YHResult<YPushCbRes> {
+        if buf.len() < 92 {
+            return Err(YHErrorKind::InvalidLength.into());
+        }
+        let mut b = BytesMut::new();
+        b.extend_from_slice(buf);
+        let id = YDigest64::from_bytes(b.get(0..64).unwrap())?;
+        let version = YVersion::from_bytes(b.get(64..76).unwrap())?;
+        let time = YTime::from_bytes(b.get(76..84).unwrap())?;
+        let nonce = BigEndian::read_u32(b.get(84..88).unwrap());
+        let method = BigEndian::read_u32(b.get(88..92).unwrap()).into();
+        let mut i = 92;
+        let cb_id = YDigest64::from_bytes(b.get(i..i+64).ok_or(YHErrorKind::InvalidLength)?)?;
+        i += 64;
+        if i != buf.len() {
+            return Err(YHErrorKind::InvalidLength.into());
+        }
+        let res = YPushCbRes {
+            id: id,
+            version: version,
+            time: time,
+            nonce: nonce,
+            method: method,
+            cb_id: cb_id,
+        };
+        res.check()?;
+        Ok(res)
+    }
+}
+
+#[derive(Clone, Eq, PartialEq, Debug, Default, Serialize, Deserialize)]
+pub struct YMineReq {
+    pub id: YDigest64,
+    pub version: YVersion,
+    pub time: YTime,
+    pub nonce: u32,
+    pub method: YRPCMethod,
+    pub wallet: String,
+    pub difficulty: u32,
+}
+
+impl YMineReq {
+    pub fn new(wallet: &str, difficulty: u32) -> YHResult<YMineReq> {
+        let mut req = YMineReq {
+            id: YDigest64::default(),
+            version: default_version(),
+            time: YTime::now(),
+            nonce: YRandom::u32(),
+            method: YRPCMethod::Mine,
+            wallet: String::from(wallet),
+            difficulty: difficulty,
+        };
+        req.id = req.calc_id()?;
+        Ok(req)
+    }
+
+    pub fn check(&self) -> YHResult<()> {
+        if self.id != self.calc_id()? {
+            return Err(YHErrorKind::Lib(LibErrorKind::InvalidChecksum).into());
+        }
+        if self.version.major() > default_version().major() {
+            return Err(YHErrorKind::Lib(LibErrorKind::InvalidVersion(self.version.to_string())).into());
+        }
+        if self.time > YTime::now() {
+            return Err(YHErrorKind::Lib(LibErrorKind::InvalidTime).into());
+        }
+        if self.method != YRPCMethod::Mine {
+            return Err(YHErrorKind::InvalidRPCMethod.into());
+        }
+        Ok(())
+    }
+
+    pub fn calc_id(&self) -> YHResult<YDigest64> {
+        let mut buf = BytesMut::new();
+        buf.put(&self.version.to_bytes()?[..]);
+        buf.put(&self.time.to_bytes()[..]);
+        buf.put_u32::<BigEndian>(self.nonce);
+        buf.put(self.method.to_bytes());
+        let wallet_buf = self.wallet.as_bytes();
+        buf.put_u32::<BigEndian>(wallet_buf.len() as u32);
+        buf.put(wallet_buf);
+        buf.put_u32::<BigEndian>(self.difficulty);
+        Ok(YSHA512::hash(&buf.to_vec()))
+    }
+
+    pub fn to_bytes(&self) -> YHResult<Vec<u8>> {
+        self.check()?;
+        let mut buf = BytesMut::new();
+        buf.put(self.id.to_bytes());
+        buf.put(&self.version.to_bytes()?[..]);
+        buf.put(&self.time.to_bytes()[..]);
+        buf.put_u32::<BigEndian>(self.nonce);
+        buf.put(self.method.to_bytes());
+        let wallet_buf = self.wallet.as_bytes();
+        buf.put_u32::<BigEndian>(wallet_buf.len() as u32);
+        buf.put(wallet_buf);
+        buf.put_u32::<BigEndian>(self.difficulty);
+        Ok(buf.to_vec())
+    }
+
+    pub fn from_bytes(buf: &[u8]) -> YHResult<YMineReq> {
+        if buf.len() < 92 {
+            return Err(YHErrorKind::InvalidLength.into());
+        }
+        let mut b = BytesMut::new();
+        b.extend_from_slice(buf);
+        let id = YDigest64::from_bytes(b.get(0..64).unwrap())?;
+        let version = YVersion::from_bytes(b.get(64..76).unwrap())?;
+        let time = YTime::from_bytes(b.get(76..84).unwrap())?;
+        let nonce = BigEndian::read_u32(b.get(84..88).unwrap());
+        let method = BigEndian::read_u32(b.get(88..92).unwrap()).into();
+        let mut i = 92;
+        let wallet_size = BigEndian::read_u32(b.get(i..i+4).ok_or(YHErrorKind::InvalidLength)?) as usize;
+        i += 4;
+        let wallet = String::from_utf8(b.get(i..i+wallet_size).ok_or(YHErrorKind::InvalidLength)?.to_vec())?;
+        i += wallet_size;
+        let difficulty = BigEndian::read_u32(b.get(i..i+4).ok_or(YHErrorKind::InvalidLength)?);
+        i += 4;
+        if i != buf.len() {
+            return Err(YHErrorKind::InvalidLength.into());
+        }
+        let req = YMineReq {
+            id: id,
+            version: version,
+            time: time,
+            nonce: nonce,
+            method: method,
+            wallet: wallet,
+            difficulty: difficulty,
+        };
+        req.check()?;
+        Ok(req)
+    }
+}
+
+#[derive(Clone, Eq, PartialEq, Debug, Default, Serialize, Deserialize)]
+pub struct YMineRes {
+    pub id: YDigest64,
+    pub version: YVersion,
+    pub time: YTime,
+    pub nonce: u32,
+    pub method: YRPCMethod,
+    pub cb: YCoinbase,
+}
+
+impl YMineRes {
+    pub fn new(cb: &YCoinbase) -> YHResult<YMineRes> {
+        let mut res = YMineRes {
+            id: YDigest64::default(),
+            version: default_version(),
+            time: YTime::now(),
+            nonce: YRandom::u32(),
+            method: YRPCMethod::Mine,
+            cb: cb.clone(),
+        };
+        res.id = res.calc_id()?;
+        Ok(res)
+    }
+
+    pub fn check(&self) -> YHResult<()> {
+        if self.id != self.calc_id()? {
+            return Err(YHErrorKind::Lib(LibErrorKind::InvalidChecksum).into());
+        }
+        if self.version.major() > default_version().major() {
+            return Err(YHErrorKind::Lib(LibErrorKind::InvalidVersion(self.version.to_string())).into());
+        }
+        if self.time > YTime::now() {
+            return Err(YHErrorKind::Lib(LibErrorKind::InvalidTime).into());
+        }
+        if self.method != YRPCMethod::Mine {
+            return Err(YHErrorKind::InvalidRPCMethod.into());
+        }
+        self.cb.check()?;
+        Ok(())
+    }
+
+    pub fn calc_id(&self) -> YHResult<YDigest64> {
+        let mut buf = BytesMut::new();
+        buf.put(&self.version.to_bytes()?[..]);
+        buf.put(&self.time.to_bytes()[..]);
+        buf.put_u32::<BigEndian>(self.nonce);
+        buf.put(self.method.to_bytes());
+        let cb_buf = self.cb.to_bytes()?;
+        buf.put_u32::<BigEndian>(cb_buf.len() as u32);
+        buf.put(cb_buf);
+        Ok(YSHA512::hash(&buf.to_vec()))
+    }
+
+    pub fn to_bytes(&self) -> YHResult<Vec<u8>> {
+        self.check()?;
+        let mut buf = BytesMut::new();
+        buf.put(self.id.to_bytes());
+        buf.put(&self.version.to_bytes()?[..]);
+        buf.put(&self.time.to_bytes()[..]);
+        buf.put_u32::<BigEndian>(self.nonce);
+        buf.put(self.method.to_bytes());
+        let cb_buf = self.cb.to_bytes()?;
+        buf.put_u32::<BigEndian>(cb_buf.len() as u32);
+        buf.put(cb_buf);
+        Ok(buf.to_vec())
+    }
+
+    pub fn from_bytes(buf: &[u8]) -> YHResult<YMineRes> {
+        if buf.len() < 92 {
+            return Err(YHErrorKind::InvalidLength.into());
+        }
+        let mut b = BytesMut::new();
+        b.extend_from_slice(buf);
+        let id = YDigest64::from_bytes(b.get(0..64).unwrap())?;
+        let version = YVersion::from_bytes(b.get(64..76).unwrap())?;
+        let time = YTime::from_bytes(b.get(76..84).unwrap())?;
+        let nonce = BigEndian::read_u32(b.get(84..88).unwrap());
+        let method = BigEndian::read_u32(b.get(88..92).unwrap()).into();
+        let mut i = 92;
+        let cb_size = BigEndian::read_u32(b.get(i..i+4).ok_or(YHErrorKind::InvalidLength)?) as usize;
+        i += 4;
+        let cb = YCoinbase::from_bytes(b.get(i..i+cb_size).ok_or(YHErrorKind::InvalidLength)?)?;
+        i += cb_size;
+        if i != buf.len() {
+            return Err(YHErrorKind::InvalidLength.into());
+        }
+        let res = YMineRes {
+            id: id,
+            version: version,
+            time: time,
+            nonce: nonce,
+            method: method,
+            cb: cb,
+        };
+        res.check()?;
+        Ok(res)
+    }
+}
+
+#[derive(Clone, Eq, PartialEq, Debug, Default, Serialize, Deserialize)]
+pub struct YListCbsReq {
+    pub id: YDigest64,
+    pub version: YVersion,
+    pub time: YTime,
+    pub nonce: u32,
+    pub method: YRPCMethod,
+    pub wallet: String,
+    pub cursor: Vec<u8>,
+    pub limit: u32,
+}
+
+impl YListCbsReq {
+    pub fn new(wallet: &str, cursor: &[u8], limit: u32) -> YHResult<YListCbsReq> {
+        let mut req = YListCbsReq {
+            id: YDigest64::default(),
+            version: default_version(),
+            time: YTime::now(),
+            nonce: YRandom::u32(),
+            method: YRPCMethod::ListCbs,
+            wallet: String::from(wallet),
+            cursor: cursor.to_vec(),
+            limit: limit,
+        };
+        req.id = req.calc_id()?;
+        Ok(req)
+    }
+
+    pub fn check(&self) -> YHResult<()> {
+        if self.id != self.calc_id()? {
+            return Err(YHErrorKind::Lib(LibErrorKind::InvalidChecksum).into());
+        }
+        if self.version.major() > default_version().major() {
+            return Err(YHErrorKind::Lib(LibErrorKind::InvalidVersion(self.version.to_string())).into());
+        }
+        if self.time > YTime::now() {
+            return Err(YHErrorKind::Lib(LibErrorKind::InvalidTime).into());
+        }
+        if self.method != YRPCMethod::ListCbs {
+            return Err(YHErrorKind::InvalidRPCMethod.into());
+        }
+        Ok(())
+    }
+
+    pub fn calc_id(&self) -> YHResult<YDigest64> {
+        let mut buf = BytesMut::new();
+        buf.put(&self.version.to_bytes()?[..]);
+        buf.put(&self.time.to_bytes()[..]);
+        buf.put_u32::<BigEndian>(self.nonce);
+        buf.put(self.method.to_bytes());
+        let wallet_buf = self.wallet.as_bytes();
+        buf.put_u32::<BigEndian>(wallet_buf.len() as u32);
+        buf.put(wallet_buf);
+        buf.put_u32::<BigEndian>(self.cursor.len() as u32);
+        buf.put(&self.cursor[..]);
+        buf.put_u32::<BigEndian>(self.limit);
+        Ok(YSHA512::hash(&buf.to_vec()))
+    }
+
+    pub fn to_bytes(&self) -> YHResult<Vec<u8>> {
+        self.check()?;
+        let mut buf = BytesMut::new();
+        buf.put(self.id.to_bytes());
+        buf.put(&self.version.to_bytes()?[..]);
+        buf.put(&self.time.to_bytes()[..]);
+        buf.put_u32::<BigEndian>(self.nonce);
+        buf.put(self.method.to_bytes());
+        let wallet_buf = self.wallet.as_bytes();
+        buf.put_u32::<BigEndian>(wallet_buf.len() as u32);
+        buf.put(wallet_buf);
+        buf.put_u32::<BigEndian>(self.cursor.len() as u32);
+        buf.put(&self.cursor[..]);
+        buf.put_u32::<BigEndian>(self.limit);
+        Ok(buf.to_vec())
+    }
+
+    pub fn from_bytes(buf: &[u8]) -> YHResult<YListCbsReq> {
+        if buf.len() < 92 {
+            return Err(YHErrorKind::InvalidLength.into());
+        }
+        let mut b = BytesMut::new();
+        b.extend_from_slice(buf);
+        let id = YDigest64::from_bytes(b.get(0..64).unwrap())?;
+        let version = YVersion::from_bytes(b.get(64..76).unwrap())?;
+        let time = YTime::from_bytes(b.get(76..84).unwrap())?;
+        let nonce = BigEndian::read_u32(b.get(84..88).unwrap());
+        let method = BigEndian::read_u32(b.get(88..92).unwrap()).into();
+        let mut i = 92;
+        let wallet_size = BigEndian::read_u32(b.get(i..i+4).ok_or(YHErrorKind::InvalidLength)?) as usize;
+        i += 4;
+        let wallet = String::from_utf8(b.get(i..i+wallet_size).ok_or(YHErrorKind::InvalidLength)?.to_vec())?;
+        i += wallet_size;
+        let cursor_size = BigEndian::read_u32(b.get(i..i+4).ok_or(YHErrorKind::InvalidLength)?) as usize;
+        i += 4;
+        let cursor = b.get(i..i+cursor_size).ok_or(YHErrorKind::InvalidLength)?.to_vec();
+        i += cursor_size;
+        let limit = BigEndian::read_u32(b.get(i..i+4).ok_or(YHErrorKind::InvalidLength)?);
+        i += 4;
+        if i != buf.len() {
+            return Err(YHErrorKind::InvalidLength.into());
+        }
+        let req = YListCbsReq {
+            id: id,
+            version: version,
+            time: time,
+            nonce: nonce,
+            method: method,
+            wallet: wallet,
+            cursor: cursor,
+            limit: limit,
+        };
+        req.check()?;
+        Ok(req)
+    }
+}
+
+#[derive(Clone, Eq, PartialEq, Debug, Default, Serialize, Deserialize)]
+pub struct YListCbsRes {
+    pub id: YDigest64,
+    pub version: YVersion,
+    pub time: YTime,
+    pub nonce: u32,
+    pub method: YRPCMethod,
+    pub count: u32,
+    pub cbs: Vec<YCoinbase>,
+    pub next: Vec<u8>,
+}
+
+impl YListCbsRes {
+    pub fn new(cbs: &Vec<YCoinbase>, next: &[u8]) -> YHResult<YListCbsRes> {
+        let mut res = YListCbsRes {
+            id: YDigest64::default(),
+            version: default_version(),
+            time: YTime::now(),
+            nonce: YRandom::u32(),
+            method: YRPCMethod::ListCbs,
+            count: cbs.len() as u32,
+            cbs: cbs.clone(),
+            next: next.to_vec(),
+        };
+        res.id = res.calc_id()?;
+        Ok(res)
+    }
+
+    pub fn check(&self) -> YHResult<()> {
+        if self.id != self.calc_id()? {
+            return Err(YHErrorKind::Lib(LibErrorKind::InvalidChecksum).into());
+        }
+        if self.version.major() > default_version().major() {
+            return Err(YHErrorKind::Lib(LibErrorKind::InvalidVersion(self.version.to_string())).into());
+        }
+        if self.time > YTime::now() {
+            return Err(YHErrorKind::Lib(LibErrorKind::InvalidTime).into());
+        }
+        if self.method != YRPCMethod::ListCbs {
+            return Err(YHErrorKind::InvalidRPCMethod.into());
+        }
+        if self.cbs.len() != self.count as usize {
+            return Err(YHErrorKind::InvalidLength.into());
+        }
+        for cb in self.cbs.iter() {
+            cb.check()?;
+        }
+        Ok(())
+    }
+
+    pub fn calc_id(&self) -> YHResult<YDigest64> {
+        let mut buf = BytesMut::new();
+        buf.put(&self.version.to_bytes()?[..]);
+        buf.put(&self.time.to_bytes()[..]);
+        buf.put_u32::<BigEndian>(self.nonce);
+        buf.put(self.method.to_bytes());
+        buf.put_u32::<BigEndian>(self.count);
+        for cb in self.cbs.iter() {
+            let cb_buf = cb.to_bytes()?;
+            buf.put_u32::<BigEndian>(cb_buf.len() as u32);
+            buf.put(cb_buf);
+        }
+        buf.put_u32::<BigEndian>(self.next.len() as u32);
+        buf.put(&self.next[..]);
+        Ok(YSHA512::hash(&buf.to_vec()))
+    }
+
+    pub fn to_bytes(&self) -> YHResult<Vec<u8>> {
+        self.check()?;
+        let mut buf = BytesMut::new();
+        buf.put(self.id.to_bytes());
+        buf.put(&self.version.to_bytes()?[..]);
+        buf.put(&self.time.to_bytes()[..]);
+        buf.put_u32::<BigEndian>(self.nonce);
+        buf.put(self.method.to_bytes());
+        buf.put_u32::<BigEndian>(self.count);
+        for cb in self.cbs.iter() {
+            let cb_buf = cb.to_bytes()?;
+            buf.put_u32::<BigEndian>(cb_buf.len() as u32);
+            buf.put(cb_buf);
+        }
+        buf.put_u32::<BigEndian>(self.next.len() as u32);
+        buf.put(&self.next[..]);
+        Ok(buf.to_vec())
+    }
+
+    pub fn from_bytes(buf: &[u8]) -> YHResult<YListCbsRes> {
+        if buf.len() < 92 {
+            return Err(YHErrorKind::InvalidLength.into());
+        }
+        let mut b = BytesMut::new();
+        b.extend_from_slice(buf);
+        let id = YDigest64::from_bytes(b.get(0..64).unwrap())?;
+        let version = YVersion::from_bytes(b.get(64..76).unwrap())?;
+        let time = YTime::from_bytes(b.get(76..84).unwrap())?;
+        let nonce = BigEndian::read_u32(b.get(84..88).unwrap());
+        let method = BigEndian::read_u32(b.get(88..92).unwrap()).into();
+        let mut i = 92;
+        let count = BigEndian::read_u32(b.get(i..i+4).ok_or(YHErrorKind::InvalidLength)?);
+        i += 4;
+        let mut cbs = Vec::new();
+        for _ in 0..count {
+            let size = BigEndian::read_u32(b.get(i..i+4).ok_or(YHErrorKind::InvalidLength)?) as usize;
+            i += 4;
+            cbs.push(YCoinbase::from_bytes(b.get(i..i+size).ok_or(YHErrorKind::InvalidLength)?)?);
+            i += size;
+        }
+        let next_size = BigEndian::read_u32(b.get(i..i+4).ok_or(YHErrorKind::InvalidLength)?) as usize;
+        i += 4;
+        let next = b.get(i..i+next_size).ok_or(YHErrorKind::InvalidLength)?.to_vec();
+        i += next_size;
+        if i != buf.len() {
+            return Err(YHErrorKind::InvalidLength.into());
+        }
+        let res = YListCbsRes {
+            id: id,
+            version: version,
+            time: time,
+            nonce: nonce,
+            method: method,
+            count: count,
+            cbs: cbs,
+            next: next,
+        };
+        res.check()?;
+        Ok(res)
     }
 }
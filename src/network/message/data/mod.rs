@@ -54,6 +54,7 @@ impl YListDataReq {
         let mut buf = BytesMut::new();
         buf.put(&self.version.to_bytes()?[..]);
         buf.put(&self.time.to_bytes()[..]);
+        buf.put_u32::<BigEndian>(self.nonce);
         buf.put(self.method.to_bytes());
         buf.put(self.tx_id.to_bytes());
         Ok(YSHA512::hash(&buf.to_vec()))
@@ -65,13 +66,14 @@ impl YListDataReq {
         buf.put(self.id.to_bytes());
         buf.put(&self.version.to_bytes()?[..]);
         buf.put(&self.time.to_bytes()[..]);
+        buf.put_u32::<BigEndian>(self.nonce);
         buf.put(self.method.to_bytes());
         buf.put(self.tx_id.to_bytes());
         Ok(buf.to_vec())
     }
 
     pub fn from_bytes(buf: &[u8]) -> YHResult<YListDataReq> {
-        if buf.len() != 156 {
+        if buf.len() < 92 {
             return Err(YHErrorKind::InvalidLength.into());
         }
         let mut b = BytesMut::new();
@@ -81,8 +83,13 @@ impl YListDataReq {
         let time = YTime::from_bytes(b.get(76..84).unwrap())?;
         let nonce = BigEndian::read_u32(b.get(84..88).unwrap());
         let method = BigEndian::read_u32(b.get(88..92).unwrap()).into();
-        let tx_id = YDigest64::from_bytes(b.get(92..156).unwrap())?;
-        let ls_data_req = YListDataReq {
+        let mut i = 92;
+        let tx_id = YDigest64::from_bytes(b.get(i..i+64).ok_or(YHErrorKind::InvalidLength)?)?;
+        i += 64;
+        if i != buf.len() {
+            return Err(YHErrorKind::InvalidLength.into());
+        }
+        let req = YListDataReq {
             id: id,
             version: version,
             time: time,
@@ -90,8 +97,8 @@ impl YListDataReq {
             method: method,
             tx_id: tx_id,
         };
-        ls_data_req.check()?;
-        Ok(ls_data_req)
+        req.check()?;
+        Ok(req)
     }
 }
 
@@ -137,25 +144,23 @@ impl YListDataRes {
         if self.data.len() != self.count as usize {
             return Err(YHErrorKind::InvalidLength.into());
         }
-        for data in self.data.clone() {
-            data.check()?;
+        for d in self.data.iter() {
+            d.check()?;
         }
         Ok(())
     }
 
     pub fn calc_id(&self) -> YHResult<YDigest64> {
-        self.check()?;
         let mut buf = BytesMut::new();
         buf.put(&self.version.to_bytes()?[..]);
         buf.put(&self.time.to_bytes()[..]);
         buf.put_u32::<BigEndian>(self.nonce);
         buf.put(self.method.to_bytes());
-        buf.put_u32::<BigEndian>(self.count as u32);
-        for i in 0..self.count as usize {
-            let data_buf = self.data[i].to_bytes()?;
-            let data_size = data_buf.len();
-            buf.put_u32::<BigEndian>(data_size as u32);
-            buf.put(data_buf);
+        buf.put_u32::<BigEndian>(self.count);
+        for d in self.data.iter() {
+            let d_buf = d.to_bytes()?;
+            buf.put_u32::<BigEndian>(d_buf.len() as u32);
+            buf.put(d_buf);
         }
         Ok(YSHA512::hash(&buf.to_vec()))
     }
@@ -168,18 +173,17 @@ impl YListDataRes {
         buf.put(&self.time.to_bytes()[..]);
         buf.put_u32::<BigEndian>(self.nonce);
         buf.put(self.method.to_bytes());
-        buf.put_u32::<BigEndian>(self.count as u32);
-        for i in 0..self.count as usize {
-            let data_buf = self.data[i].to_bytes()?;
-            let data_size = data_buf.len();
-            buf.put_u32::<BigEndian>(data_size as u32);
-            buf.put(data_buf);
+        buf.put_u32::<BigEndian>(self.count);
+        for d in self.data.iter() {
+            let d_buf = d.to_bytes()?;
+            buf.put_u32::<BigEndian>(d_buf.len() as u32);
+            buf.put(d_buf);
         }
         Ok(buf.to_vec())
     }
 
     pub fn from_bytes(buf: &[u8]) -> YHResult<YListDataRes> {
-        if buf.len() < 96 {
+        if buf.len() < 92 {
             return Err(YHErrorKind::InvalidLength.into());
         }
         let mut b = BytesMut::new();
@@ -189,15 +193,20 @@ impl YListDataRes {
         let time = YTime::from_bytes(b.get(76..84).unwrap())?;
         let nonce = BigEndian::read_u32(b.get(84..88).unwrap());
         let method = BigEndian::read_u32(b.get(88..92).unwrap()).into();
-        let count = BigEndian::read_u32(b.get(92..96).unwrap());
-        let mut data_buf = BytesMut::new();
-        data_buf.extend_from_slice(b.get(96..).unwrap());
+        let mut i = 92;
+        let count = BigEndian::read_u32(b.get(i..i+4).ok_or(YHErrorKind::InvalidLength)?);
+        i += 4;
         let mut data = Vec::new();
-        for i in 0..count as usize {
-            let size = BigEndian::read_u32(data_buf.get(i..i+4).unwrap()) as usize;
-            data.push(YData::from_bytes(b.get(i+4..i+4+size).unwrap())?);
+        for _ in 0..count {
+            let size = BigEndian::read_u32(b.get(i..i+4).ok_or(YHErrorKind::InvalidLength)?) as usize;
+            i += 4;
+            data.push(YData::from_bytes(b.get(i..i+size).ok_or(YHErrorKind::InvalidLength)?)?);
+            i += size;
         }
-        let ls_data_res = YListDataRes {
+        if i != buf.len() {
+            return Err(YHErrorKind::InvalidLength.into());
+        }
+        let res = YListDataRes {
             id: id,
             version: version,
             time: time,
@@ -206,7 +215,8 @@ impl YListDataRes {
             count: count,
             data: data,
         };
-        Ok(ls_data_res)
+        res.check()?;
+        Ok(res)
     }
 }
 
@@ -273,7 +283,7 @@ impl YGetDataReq {
     }
 
     pub fn from_bytes(buf: &[u8]) -> YHResult<YGetDataReq> {
-        if buf.len() != 156 {
+        if buf.len() < 92 {
             return Err(YHErrorKind::InvalidLength.into());
         }
         let mut b = BytesMut::new();
@@ -283,8 +293,13 @@ impl YGetDataReq {
         let time = YTime::from_bytes(b.get(76..84).unwrap())?;
         let nonce = BigEndian::read_u32(b.get(84..88).unwrap());
         let method = BigEndian::read_u32(b.get(88..92).unwrap()).into();
-        let checksum = YDigest64::from_bytes(b.get(92..156).unwrap())?;
-        let get_data_req = YGetDataReq {
+        let mut i = 92;
+        let checksum = YDigest64::from_bytes(b.get(i..i+64).ok_or(YHErrorKind::InvalidLength)?)?;
+        i += 64;
+        if i != buf.len() {
+            return Err(YHErrorKind::InvalidLength.into());
+        }
+        let req = YGetDataReq {
             id: id,
             version: version,
             time: time,
@@ -292,8 +307,8 @@ impl YGetDataReq {
             method: method,
             checksum: checksum,
         };
-        get_data_req.check()?;
-        Ok(get_data_req)
+        req.check()?;
+        Ok(req)
     }
 }
 
@@ -342,8 +357,11 @@ impl YGetDataRes {
         let mut buf = BytesMut::new();
         buf.put(&self.version.to_bytes()?[..]);
         buf.put(&self.time.to_bytes()[..]);
+        buf.put_u32::<BigEndian>(self.nonce);
         buf.put(self.method.to_bytes());
-        buf.put(self.data.to_bytes()?);
+        let data_buf = self.data.to_bytes()?;
+        buf.put_u32::<BigEndian>(data_buf.len() as u32);
+        buf.put(data_buf);
         Ok(YSHA512::hash(&buf.to_vec()))
     }
 
@@ -353,13 +371,16 @@ impl YGetDataRes {
         buf.put(self.id.to_bytes());
         buf.put(&self.version.to_bytes()?[..]);
         buf.put(&self.time.to_bytes()[..]);
+        buf.put_u32::<BigEndian>(self.nonce);
         buf.put(self.method.to_bytes());
-        buf.put(self.data.to_bytes()?);
+        let data_buf = self.data.to_bytes()?;
+        buf.put_u32::<BigEndian>(data_buf.len() as u32);
+        buf.put(data_buf);
         Ok(buf.to_vec())
     }
 
     pub fn from_bytes(buf: &[u8]) -> YHResult<YGetDataRes> {
-        if buf.len() < 192 {
+        if buf.len() < 92 {
             return Err(YHErrorKind::InvalidLength.into());
         }
         let mut b = BytesMut::new();
@@ -369,8 +390,15 @@ impl YGetDataRes {
         let time = YTime::from_bytes(b.get(76..84).unwrap())?;
         let nonce = BigEndian::read_u32(b.get(84..88).unwrap());
         let method = BigEndian::read_u32(b.get(88..92).unwrap()).into();
-        let data = YData::from_bytes(b.get(92..).unwrap())?;
-        let get_data_res = YGetDataRes {
+        let mut i = 92;
+        let data_size = BigEndian::read_u32(b.get(i..i+4).ok_or(YHErrorKind::InvalidLength)?) as usize;
+        i += 4;
+        let data = YData::from_bytes(b.get(i..i+data_size).ok_or(YHErrorKind::InvalidLength)?)?;
+        i += data_size;
+        if i != buf.len() {
+            return Err(YHErrorKind::InvalidLength.into());
+        }
+        let res = YGetDataRes {
             id: id,
             version: version,
             time: time,
@@ -378,7 +406,252 @@ impl YGetDataRes {
             method: method,
             data: data,
         };
-        get_data_res.check()?;
-        Ok(get_data_res)
+        res.check()?;
+        Ok(res)
+    }
+}
+
+#[derive(Clone, Eq, PartialEq, Debug, Default, Serialize, Deserialize)]
+pub struct YListWalletDataReq {
+    pub id: YDigest64,
+    pub version: YVersion,
+    pub time: YTime,
+    pub nonce: u32,
+    pub method: YRPCMethod,
+    pub wallet: String,
+    pub cursor: Vec<u8>,
+    pub limit: u32,
+}
+
+impl YListWalletDataReq {
+    pub fn new(wallet: &str, cursor: &[u8], limit: u32) -> YHResult<YListWalletDataReq> {
+        let mut req = YListWalletDataReq {
+            id: YDigest64::default(),
+            version: default_version(),
+            time: YTime::now(),
+            nonce: YRandom::u32(),
+            method: YRPCMethod::ListWalletData,
+            wallet: String::from(wallet),
+            cursor: cursor.to_vec(),
+            limit: limit,
+        };
+        req.id = req.calc_id()?;
+        Ok(req)
+    }
+
+    pub fn check(&self) -> YHResult<()> {
+        if self.id != self.calc_id()? {
+            return Err(YHErrorKind::Lib(LibErrorKind::InvalidChecksum).into());
+        }
+        if self.version.major() > default_version().major() {
+            return Err(YHErrorKind::Lib(LibErrorKind::InvalidVersion(self.version.to_string())).into());
+        }
+        if self.time > YTime::now() {
+            return Err(YHErrorKind::Lib(LibErrorKind::InvalidTime).into());
+        }
+        if self.method != YRPCMethod::ListWalletData {
+            return Err(YHErrorKind::InvalidRPCMethod.into());
+        }
+        Ok(())
+    }
+
+    pub fn calc_id(&self) -> YHResult<YDigest64> {
+        let mut buf = BytesMut::new();
+        buf.put(&self.version.to_bytes()?[..]);
+        buf.put(&self.time.to_bytes()[..]);
+        buf.put_u32::<BigEndian>(self.nonce);
+        buf.put(self.method.to_bytes());
+        let wallet_buf = self.wallet.as_bytes();
+        buf.put_u32::<BigEndian>(wallet_buf.len() as u32);
+        buf.put(wallet_buf);
+        buf.put_u32::<BigEndian>(self.cursor.len() as u32);
+        buf.put(&self.cursor[..]);
+        buf.put_u32::<BigEndian>(self.limit);
+        Ok(YSHA512::hash(&buf.to_vec()))
+    }
+
+    pub fn to_bytes(&self) -> YHResult<Vec<u8>> {
+        self.check()?;
+        let mut buf = BytesMut::new();
+        buf.put(self.id.to_bytes());
+        buf.put(&self.version.to_bytes()?[..]);
+        buf.put(&self.time.to_bytes()[..]);
+        buf.put_u32::<BigEndian>(self.nonce);
+        buf.put(self.method.to_bytes());
+        let wallet_buf = self.wallet.as_bytes();
+        buf.put_u32::<BigEndian>(wallet_buf.len() as u32);
+        buf.put(wallet_buf);
+        buf.put_u32::<BigEndian>(self.cursor.len() as u32);
+        buf.put(&self.cursor[..]);
+        buf.put_u32::<BigEndian>(self.limit);
+        Ok(buf.to_vec())
+    }
+
+    pub fn from_bytes(buf: &[u8]) -> YHResult<YListWalletDataReq> {
+        if buf.len() < 92 {
+            return Err(YHErrorKind::InvalidLength.into());
+        }
+        let mut b = BytesMut::new();
+        b.extend_from_slice(buf);
+        let id = YDigest64::from_bytes(b.get(0..64).unwrap())?;
+        let version = YVersion::from_bytes(b.get(64..76).unwrap())?;
+        let time = YTime::from_bytes(b.get(76..84).unwrap())?;
+        let nonce = BigEndian::read_u32(b.get(84..88).unwrap());
+        let method = BigEndian::read_u32(b.get(88..92).unwrap()).into();
+        let mut i = 92;
+        let wallet_size = BigEndian::read_u32(b.get(i..i+4).ok_or(YHErrorKind::InvalidLength)?) as usize;
+        i += 4;
+        let wallet = String::from_utf8(b.get(i..i+wallet_size).ok_or(YHErrorKind::InvalidLength)?.to_vec())?;
+        i += wallet_size;
+        let cursor_size = BigEndian::read_u32(b.get(i..i+4).ok_or(YHErrorKind::InvalidLength)?) as usize;
+        i += 4;
+        let cursor = b.get(i..i+cursor_size).ok_or(YHErrorKind::InvalidLength)?.to_vec();
+        i += cursor_size;
+        let limit = BigEndian::read_u32(b.get(i..i+4).ok_or(YHErrorKind::InvalidLength)?);
+        i += 4;
+        if i != buf.len() {
+            return Err(YHErrorKind::InvalidLength.into());
+        }
+        let req = YListWalletDataReq {
+            id: id,
+            version: version,
+            time: time,
+            nonce: nonce,
+            method: method,
+            wallet: wallet,
+            cursor: cursor,
+            limit: limit,
+        };
+        req.check()?;
+        Ok(req)
+    }
+}
+
+#[derive(Clone, Eq, PartialEq, Debug, Default, Serialize, Deserialize)]
+pub struct YListWalletDataRes {
+    pub id: YDigest64,
+    pub version: YVersion,
+    pub time: YTime,
+    pub nonce: u32,
+    pub method: YRPCMethod,
+    pub count: u32,
+    pub data: Vec<YData>,
+    pub next: Vec<u8>,
+}
+
+impl YListWalletDataRes {
+    pub fn new(data: &Vec<YData>, next: &[u8]) -> YHResult<YListWalletDataRes> {
+        let mut res = YListWalletDataRes {
+            id: YDigest64::default(),
+            version: default_version(),
+            time: YTime::now(),
+            nonce: YRandom::u32(),
+            method: YRPCMethod::ListWalletData,
+            count: data.len() as u32,
+            data: data.clone(),
+            next: next.to_vec(),
+        };
+        res.id = res.calc_id()?;
+        Ok(res)
+    }
+
+    pub fn check(&self) -> YHResult<()> {
+        if self.id != self.calc_id()? {
+            return Err(YHErrorKind::Lib(LibErrorKind::InvalidChecksum).into());
+        }
+        if self.version.major() > default_version().major() {
+            return Err(YHErrorKind::Lib(LibErrorKind::InvalidVersion(self.version.to_string())).into());
+        }
+        if self.time > YTime::now() {
+            return Err(YHErrorKind::Lib(LibErrorKind::InvalidTime).into());
+        }
+        if self.method != YRPCMethod::ListWalletData {
+            return Err(YHErrorKind::InvalidRPCMethod.into());
+        }
+        if self.data.len() != self.count as usize {
+            return Err(YHErrorKind::InvalidLength.into());
+        }
+        for d in self.data.iter() {
+            d.check()?;
+        }
+        Ok(())
+    }
+
+    pub fn calc_id(&self) -> YHResult<YDigest64> {
+        let mut buf = BytesMut::new();
+        buf.put(&self.version.to_bytes()?[..]);
+        buf.put(&self.time.to_bytes()[..]);
+        buf.put_u32::<BigEndian>(self.nonce);
+        buf.put(self.method.to_bytes());
+        buf.put_u32::<BigEndian>(self.count);
+        for d in self.data.iter() {
+            let d_buf = d.to_bytes()?;
+            buf.put_u32::<BigEndian>(d_buf.len() as u32);
+            buf.put(d_buf);
+        }
+        buf.put_u32::<BigEndian>(self.next.len() as u32);
+        buf.put(&self.next[..]);
+        Ok(YSHA512::hash(&buf.to_vec()))
+    }
+
+    pub fn to_bytes(&self) -> YHResult<Vec<u8>> {
+        self.check()?;
+        let mut buf = BytesMut::new();
+        buf.put(self.id.to_bytes());
+        buf.put(&self.version.to_bytes()?[..]);
+        buf.put(&self.time.to_bytes()[..]);
+        buf.put_u32::<BigEndian>(self.nonce);
+        buf.put(self.method.to_bytes());
+        buf.put_u32::<BigEndian>(self.count);
+        for d in self.data.iter() {
+            let d_buf = d.to_bytes()?;
+            buf.put_u32::<BigEndian>(d_buf.len() as u32);
+            buf.put(d_buf);
+        }
+        buf.put_u32::<BigEndian>(self.next.len() as u32);
+        buf.put(&self.next[..]);
+        Ok(buf.to_vec())
+    }
+
+    pub fn from_bytes(buf: &[u8]) -> YHResult<YListWalletDataRes> {
+        if buf.len() < 92 {
+            return Err(YHErrorKind::InvalidLength.into());
+        }
+        let mut b = BytesMut::new();
+        b.extend_from_slice(buf);
+        let id = YDigest64::from_bytes(b.get(0..64).unwrap())?;
+        let version = YVersion::from_bytes(b.get(64..76).unwrap())?;
+        let time = YTime::from_bytes(b.get(76..84).unwrap())?;
+        let nonce = BigEndian::read_u32(b.get(84..88).unwrap());
+        let method = BigEndian::read_u32(b.get(88..92).unwrap()).into();
+        let mut i = 92;
+        let count = BigEndian::read_u32(b.get(i..i+4).ok_or(YHErrorKind::InvalidLength)?);
+        i += 4;
+        let mut data = Vec::new();
+        for _ in 0..count {
+            let size = BigEndian::read_u32(b.get(i..i+4).ok_or(YHErrorKind::InvalidLength)?) as usize;
+            i += 4;
+            data.push(YData::from_bytes(b.get(i..i+size).ok_or(YHErrorKind::InvalidLength)?)?);
+            i += size;
+        }
+        let next_size = BigEndian::read_u32(b.get(i..i+4).ok_or(YHErrorKind::InvalidLength)?) as usize;
+        i += 4;
+        let next = b.get(i..i+next_size).ok_or(YHErrorKind::InvalidLength)?.to_vec();
+        i += next_size;
+        if i != buf.len() {
+            return Err(YHErrorKind::InvalidLength.into());
+        }
+        let res = YListWalletDataRes {
+            id: id,
+            version: version,
+            time: time,
+            nonce: nonce,
+            method: method,
+            count: count,
+            data: data,
+            next: next,
+        };
+        res.check()?;
+        Ok(res)
     }
 }
@@ -48,9 +48,6 @@ impl YErrorRes {
         if self.time > YTime::now() {
             return Err(YHErrorKind::Lib(LibErrorKind::InvalidTime).into());
         }
-        if self.message.len() > 20 {
-            return Err(YHErrorKind::InvalidLength.into());
-        }
         Ok(())
     }
 
@@ -60,22 +57,28 @@ impl YErrorRes {
         buf.put(&self.time.to_bytes()[..]);
         buf.put_u32::<BigEndian>(self.nonce);
         buf.put(self.method.to_bytes());
-        buf.put(self.message.as_bytes());
+        let message_buf = self.message.as_bytes();
+        buf.put_u32::<BigEndian>(message_buf.len() as u32);
+        buf.put(message_buf);
         Ok(YSHA512::hash(&buf.to_vec()))
     }
 
     pub fn to_bytes(&self) -> YHResult<Vec<u8>> {
+        self.check()?;
         let mut buf = BytesMut::new();
         buf.put(self.id.to_bytes());
         buf.put(&self.version.to_bytes()?[..]);
         buf.put(&self.time.to_bytes()[..]);
+        buf.put_u32::<BigEndian>(self.nonce);
         buf.put(self.method.to_bytes());
-        buf.put(self.message.as_bytes());
+        let message_buf = self.message.as_bytes();
+        buf.put_u32::<BigEndian>(message_buf.len() as u32);
+        buf.put(message_buf);
         Ok(buf.to_vec())
     }
 
     pub fn from_bytes(buf: &[u8]) -> YHResult<YErrorRes> {
-        if buf.len() < 92 {
+        if buf.len() < 96 {
             return Err(YHErrorKind::InvalidLength.into());
         }
         let mut b = BytesMut::new();
@@ -85,14 +88,20 @@ impl YErrorRes {
         let time = YTime::from_bytes(b.get(76..84).unwrap())?;
         let nonce = BigEndian::read_u32(b.get(84..88).unwrap());
         let method = BigEndian::read_u32(b.get(88..92).unwrap()).into();
-        let message = String::from_utf8_lossy(b.get(92..).unwrap()).into_owned();
-        Ok(YErrorRes {
+        let message_size = BigEndian::read_u32(b.get(92..96).unwrap()) as usize;
+        if buf.len() != 96 + message_size {
+            return Err(YHErrorKind::InvalidLength.into());
+        }
+        let message = String::from_utf8(b.get(96..).unwrap().to_vec())?;
+        let res = YErrorRes {
             id: id,
             version: version,
             time: time,
             nonce: nonce,
             method: method,
             message: message,
-        })
+        };
+        res.check()?;
+        Ok(res)
     }
 }
@@ -0,0 +1,232 @@
+use libyobicash::errors::YErrorKind as LibErrorKind;
+use libyobicash::utils::random::*;
+use libyobicash::utils::time::*;
+use libyobicash::utils::version::*;
+use libyobicash::crypto::hash::digest::YDigest64;
+use libyobicash::crypto::hash::sha::YSHA512;
+use libyobicash::amount::YAmount;
+use bytes::{BytesMut, BufMut, BigEndian, ByteOrder};
+use network::rpc_method::YRPCMethod;
+use version::*;
+use errors::*;
+
+#[derive(Clone, Eq, PartialEq, Debug, Default, Serialize, Deserialize)]
+pub struct YGetInfoReq {
+    pub id: YDigest64,
+    pub version: YVersion,
+    pub time: YTime,
+    pub nonce: u32,
+    pub method: YRPCMethod,
+}
+
+impl YGetInfoReq {
+    pub fn new() -> YHResult<YGetInfoReq> {
+        let mut req = YGetInfoReq {
+            id: YDigest64::default(),
+            version: default_version(),
+            time: YTime::now(),
+            nonce: YRandom::u32(),
+            method: YRPCMethod::GetInfo,
+        };
+        req.id = req.calc_id()?;
+        Ok(req)
+    }
+
+    pub fn check(&self) -> YHResult<()> {
+        if self.id != self.calc_id()? {
+            return Err(YHErrorKind::Lib(LibErrorKind::InvalidChecksum).into());
+        }
+        if self.version.major() > default_version().major() {
+            return Err(YHErrorKind::Lib(LibErrorKind::InvalidVersion(self.version.to_string())).into());
+        }
+        if self.time > YTime::now() {
+            return Err(YHErrorKind::Lib(LibErrorKind::InvalidTime).into());
+        }
+        if self.method != YRPCMethod::GetInfo {
+            return Err(YHErrorKind::InvalidRPCMethod.into());
+        }
+        Ok(())
+    }
+
+    pub fn calc_id(&self) -> YHResult<YDigest64> {
+        let mut buf = BytesMut::new();
+        buf.put(&self.version.to_bytes()?[..]);
+        buf.put(&self.time.to_bytes()[..]);
+        buf.put_u32::<BigEndian>(self.nonce);
+        buf.put(self.method.to_bytes());
+        Ok(YSHA512::hash(&buf.to_vec()))
+    }
+
+    pub fn to_bytes(&self) -> YHResult<Vec<u8>> {
+        self.check()?;
+        let mut buf = BytesMut::new();
+        buf.put(self.id.to_bytes());
+        buf.put(&self.version.to_bytes()?[..]);
+        buf.put(&self.time.to_bytes()[..]);
+        buf.put_u32::<BigEndian>(self.nonce);
+        buf.put(self.method.to_bytes());
+        Ok(buf.to_vec())
+    }
+
+    pub fn from_bytes(buf: &[u8]) -> YHResult<YGetInfoReq> {
+        if buf.len() != 92 {
+            return Err(YHErrorKind::InvalidLength.into());
+        }
+        let mut b = BytesMut::new();
+        b.extend_from_slice(buf);
+        let id = YDigest64::from_bytes(b.get(0..64).unwrap())?;
+        let version = YVersion::from_bytes(b.get(64..76).unwrap())?;
+        let time = YTime::from_bytes(b.get(76..84).unwrap())?;
+        let nonce = BigEndian::read_u32(b.get(84..88).unwrap());
+        let method = BigEndian::read_u32(b.get(88..92).unwrap()).into();
+        let req = YGetInfoReq {
+            id: id,
+            version: version,
+            time: time,
+            nonce: nonce,
+            method: method,
+        };
+        req.check()?;
+        Ok(req)
+    }
+}
+
+#[derive(Clone, Eq, PartialEq, Debug, Default, Serialize, Deserialize)]
+pub struct YGetInfoRes {
+    pub id: YDigest64,
+    pub version: YVersion,
+    pub time: YTime,
+    pub nonce: u32,
+    pub method: YRPCMethod,
+    pub balance: YAmount,
+    pub wallets_count: u32,
+    pub ucoins_count: u32,
+    pub scoins_count: u32,
+    pub data_count: u32,
+    pub transactions_count: u32,
+    pub coinbases_count: u32,
+}
+
+impl YGetInfoRes {
+    pub fn new(balance: &YAmount, wallets_count: u32, ucoins_count: u32, scoins_count: u32, data_count: u32, transactions_count: u32, coinbases_count: u32) -> YHResult<YGetInfoRes> {
+        let mut res = YGetInfoRes {
+            id: YDigest64::default(),
+            version: default_version(),
+            time: YTime::now(),
+            nonce: YRandom::u32(),
+            method: YRPCMethod::GetInfo,
+            balance: balance.clone(),
+            wallets_count: wallets_count,
+            ucoins_count: ucoins_count,
+            scoins_count: scoins_count,
+            data_count: data_count,
+            transactions_count: transactions_count,
+            coinbases_count: coinbases_count,
+        };
+        res.id = res.calc_id()?;
+        Ok(res)
+    }
+
+    pub fn check(&self) -> YHResult<()> {
+        if self.id != self.calc_id()? {
+            return Err(YHErrorKind::Lib(LibErrorKind::InvalidChecksum).into());
+        }
+        if self.version.major() > default_version().major() {
+            return Err(YHErrorKind::Lib(LibErrorKind::InvalidVersion(self.version.to_string())).into());
+        }
+        if self.time > YTime::now() {
+            return Err(YHErrorKind::Lib(LibErrorKind::InvalidTime).into());
+        }
+        if self.method != YRPCMethod::GetInfo {
+            return Err(YHErrorKind::InvalidRPCMethod.into());
+        }
+        Ok(())
+    }
+
+    pub fn calc_id(&self) -> YHResult<YDigest64> {
+        let mut buf = BytesMut::new();
+        buf.put(&self.version.to_bytes()?[..]);
+        buf.put(&self.time.to_bytes()[..]);
+        buf.put_u32::<BigEndian>(self.nonce);
+        buf.put(self.method.to_bytes());
+        let balance_buf = self.balance.to_bytes();
+        buf.put_u32::<BigEndian>(balance_buf.len() as u32);
+        buf.put(balance_buf);
+        buf.put_u32::<BigEndian>(self.wallets_count);
+        buf.put_u32::<BigEndian>(self.ucoins_count);
+        buf.put_u32::<BigEndian>(self.scoins_count);
+        buf.put_u32::<BigEndian>(self.data_count);
+        buf.put_u32::<BigEndian>(self.transactions_count);
+        buf.put_u32::<BigEndian>(self.coinbases_count);
+        Ok(YSHA512::hash(&buf.to_vec()))
+    }
+
+    pub fn to_bytes(&self) -> YHResult<Vec<u8>> {
+        self.check()?;
+        let mut buf = BytesMut::new();
+        buf.put(self.id.to_bytes());
+        buf.put(&self.version.to_bytes()?[..]);
+        buf.put(&self.time.to_bytes()[..]);
+        buf.put_u32::<BigEndian>(self.nonce);
+        buf.put(self.method.to_bytes());
+        let balance_buf = self.balance.to_bytes();
+        buf.put_u32::<BigEndian>(balance_buf.len() as u32);
+        buf.put(balance_buf);
+        buf.put_u32::<BigEndian>(self.wallets_count);
+        buf.put_u32::<BigEndian>(self.ucoins_count);
+        buf.put_u32::<BigEndian>(self.scoins_count);
+        buf.put_u32::<BigEndian>(self.data_count);
+        buf.put_u32::<BigEndian>(self.transactions_count);
+        buf.put_u32::<BigEndian>(self.coinbases_count);
+        Ok(buf.to_vec())
+    }
+
+    pub fn from_bytes(buf: &[u8]) -> YHResult<YGetInfoRes> {
+        if buf.len() < 92 {
+            return Err(YHErrorKind::InvalidLength.into());
+        }
+        let mut b = BytesMut::new();
+        b.extend_from_slice(buf);
+        let id = YDigest64::from_bytes(b.get(0..64).unwrap())?;
+        let version = YVersion::from_bytes(b.get(64..76).unwrap())?;
+        let time = YTime::from_bytes(b.get(76..84).unwrap())?;
+        let nonce = BigEndian::read_u32(b.get(84..88).unwrap());
+        let method = BigEndian::read_u32(b.get(88..92).unwrap()).into();
+        let mut i = 92;
+        let balance_size = BigEndian::read_u32(b.get(i..i+4).ok_or(YHErrorKind::InvalidLength)?) as usize;
+        i += 4;
+        let balance = YAmount::from_bytes(b.get(i..i+balance_size).ok_or(YHErrorKind::InvalidLength)?);
+        i += balance_size;
+        let wallets_count = BigEndian::read_u32(b.get(i..i+4).ok_or(YHErrorKind::InvalidLength)?);
+        i += 4;
+        let ucoins_count = BigEndian::read_u32(b.get(i..i+4).ok_or(YHErrorKind::InvalidLength)?);
+        i += 4;
+        let scoins_count = BigEndian::read_u32(b.get(i..i+4).ok_or(YHErrorKind::InvalidLength)?);
+        i += 4;
+        let data_count = BigEndian::read_u32(b.get(i..i+4).ok_or(YHErrorKind::InvalidLength)?);
+        i += 4;
+        let transactions_count = BigEndian::read_u32(b.get(i..i+4).ok_or(YHErrorKind::InvalidLength)?);
+        i += 4;
+        let coinbases_count = BigEndian::read_u32(b.get(i..i+4).ok_or(YHErrorKind::InvalidLength)?);
+        i += 4;
+        if i != buf.len() {
+            return Err(YHErrorKind::InvalidLength.into());
+        }
+        let res = YGetInfoRes {
+            id: id,
+            version: version,
+            time: time,
+            nonce: nonce,
+            method: method,
+            balance: balance,
+            wallets_count: wallets_count,
+            ucoins_count: ucoins_count,
+            scoins_count: scoins_count,
+            data_count: data_count,
+            transactions_count: transactions_count,
+            coinbases_count: coinbases_count,
+        };
+        res.check()?;
+        Ok(res)
+    }
+}
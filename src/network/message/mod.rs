@@ -3,6 +3,10 @@ pub mod peer;
 pub mod data;
 pub mod transaction;
 pub mod coinbase;
+pub mod wallet;
+pub mod coin;
+pub mod utxo;
+pub mod info;
 pub mod error;
 pub mod prefix;
 pub mod request;
@@ -13,6 +17,10 @@ pub use self::peer::*;
 pub use self::data::*;
 pub use self::transaction::*;
 pub use self::coinbase::*;
+pub use self::wallet::*;
+pub use self::coin::*;
+pub use self::utxo::*;
+pub use self::info::*;
 pub use self::error::*;
 pub use self::prefix::*;
 pub use self::request::*;
@@ -17,18 +17,20 @@ pub struct YListPeersReq {
     pub time: YTime,
     pub nonce: u32,
     pub method: YRPCMethod,
-    pub max: u32,
+    pub cursor: Vec<u8>,
+    pub limit: u32,
 }
 
 impl YListPeersReq {
-    pub fn new(max: u32) -> YHResult<YListPeersReq> {
+    pub fn new(cursor: &[u8], limit: u32) -> YHResult<YListPeersReq> {
         let mut req = YListPeersReq {
             id: YDigest64::default(),
             version: default_version(),
             time: YTime::now(),
             nonce: YRandom::u32(),
             method: YRPCMethod::ListPeers,
-            max: max,
+            cursor: cursor.to_vec(),
+            limit: limit,
         };
         req.id = req.calc_id()?;
         Ok(req)
@@ -51,12 +53,14 @@ impl YListPeersReq {
     }
 
     pub fn calc_id(&self) -> YHResult<YDigest64> {
-        self.check()?;
         let mut buf = BytesMut::new();
         buf.put(&self.version.to_bytes()?[..]);
         buf.put(&self.time.to_bytes()[..]);
+        buf.put_u32::<BigEndian>(self.nonce);
         buf.put(self.method.to_bytes());
-        buf.put_u32::<BigEndian>(self.max as u32);
+        buf.put_u32::<BigEndian>(self.cursor.len() as u32);
+        buf.put(&self.cursor[..]);
+        buf.put_u32::<BigEndian>(self.limit);
         Ok(YSHA512::hash(&buf.to_vec()))
     }
 
@@ -66,13 +70,16 @@ impl YListPeersReq {
         buf.put(self.id.to_bytes());
         buf.put(&self.version.to_bytes()?[..]);
         buf.put(&self.time.to_bytes()[..]);
+        buf.put_u32::<BigEndian>(self.nonce);
         buf.put(self.method.to_bytes());
-        buf.put_u32::<BigEndian>(self.max as u32);
+        buf.put_u32::<BigEndian>(self.cursor.len() as u32);
+        buf.put(&self.cursor[..]);
+        buf.put_u32::<BigEndian>(self.limit);
         Ok(buf.to_vec())
     }
 
     pub fn from_bytes(buf: &[u8]) -> YHResult<YListPeersReq> {
-        if buf.len() != 96 {
+        if buf.len() < 92 {
             return Err(YHErrorKind::InvalidLength.into());
         }
         let mut b = BytesMut::new();
@@ -82,17 +89,27 @@ impl YListPeersReq {
         let time = YTime::from_bytes(b.get(76..84).unwrap())?;
         let nonce = BigEndian::read_u32(b.get(84..88).unwrap());
         let method = BigEndian::read_u32(b.get(88..92).unwrap()).into();
-        let max = BigEndian::read_u32(b.get(92..96).unwrap());
-        let ls_peers_req = YListPeersReq {
+        let mut i = 92;
+        let cursor_size = BigEndian::read_u32(b.get(i..i+4).ok_or(YHErrorKind::InvalidLength)?) as usize;
+        i += 4;
+        let cursor = b.get(i..i+cursor_size).ok_or(YHErrorKind::InvalidLength)?.to_vec();
+        i += cursor_size;
+        let limit = BigEndian::read_u32(b.get(i..i+4).ok_or(YHErrorKind::InvalidLength)?);
+        i += 4;
+        if i != buf.len() {
+            return Err(YHErrorKind::InvalidLength.into());
+        }
+        let req = YListPeersReq {
             id: id,
             version: version,
             time: time,
             nonce: nonce,
             method: method,
-            max: max,
+            cursor: cursor,
+            limit: limit,
         };
-        ls_peers_req.check()?;
-        Ok(ls_peers_req)
+        req.check()?;
+        Ok(req)
     }
 }
 
@@ -105,10 +122,11 @@ pub struct YListPeersRes {
     pub method: YRPCMethod,
     pub count: u32,
     pub peers: Vec<YPeer>,
+    pub next: Vec<u8>,
 }
 
 impl YListPeersRes {
-    pub fn new(peers: &Vec<YPeer>) -> YHResult<YListPeersRes> {
+    pub fn new(peers: &Vec<YPeer>, next: &[u8]) -> YHResult<YListPeersRes> {
         let mut res = YListPeersRes {
             id: YDigest64::default(),
             version: default_version(),
@@ -117,6 +135,7 @@ impl YListPeersRes {
             method: YRPCMethod::ListPeers,
             count: peers.len() as u32,
             peers: peers.clone(),
+            next: next.to_vec(),
         };
         res.id = res.calc_id()?;
         Ok(res)
@@ -138,7 +157,7 @@ impl YListPeersRes {
         if self.peers.len() != self.count as usize {
             return Err(YHErrorKind::InvalidLength.into());
         }
-        for peer in self.peers.clone() {
+        for peer in self.peers.iter() {
             peer.check()?;
         }
         Ok(())
@@ -150,13 +169,14 @@ impl YListPeersRes {
         buf.put(&self.time.to_bytes()[..]);
         buf.put_u32::<BigEndian>(self.nonce);
         buf.put(self.method.to_bytes());
-        buf.put_u32::<BigEndian>(self.count as u32);
-        for i in 0..self.count as usize {
-            let peer_buf = self.peers[i].to_bytes()?;
-            let peer_size = peer_buf.len();
-            buf.put_u32::<BigEndian>(peer_size as u32);
+        buf.put_u32::<BigEndian>(self.count);
+        for peer in self.peers.iter() {
+            let peer_buf = peer.to_bytes()?;
+            buf.put_u32::<BigEndian>(peer_buf.len() as u32);
             buf.put(peer_buf);
         }
+        buf.put_u32::<BigEndian>(self.next.len() as u32);
+        buf.put(&self.next[..]);
         Ok(YSHA512::hash(&buf.to_vec()))
     }
 
@@ -164,19 +184,23 @@ impl YListPeersRes {
         self.check()?;
         let mut buf = BytesMut::new();
         buf.put(self.id.to_bytes());
+        buf.put(&self.version.to_bytes()?[..]);
+        buf.put(&self.time.to_bytes()[..]);
+        buf.put_u32::<BigEndian>(self.nonce);
         buf.put(self.method.to_bytes());
-        buf.put_u32::<BigEndian>(self.count as u32);
-        for i in 0..self.count as usize {
-            let peer_buf = self.peers[i].to_bytes()?;
-            let peer_size = peer_buf.len();
-            buf.put_u32::<BigEndian>(peer_size as u32);
+        buf.put_u32::<BigEndian>(self.count);
+        for peer in self.peers.iter() {
+            let peer_buf = peer.to_bytes()?;
+            buf.put_u32::<BigEndian>(peer_buf.len() as u32);
             buf.put(peer_buf);
         }
+        buf.put_u32::<BigEndian>(self.next.len() as u32);
+        buf.put(&self.next[..]);
         Ok(buf.to_vec())
     }
 
     pub fn from_bytes(buf: &[u8]) -> YHResult<YListPeersRes> {
-        if buf.len() < 96 {
+        if buf.len() < 92 {
             return Err(YHErrorKind::InvalidLength.into());
         }
         let mut b = BytesMut::new();
@@ -186,15 +210,24 @@ impl YListPeersRes {
         let time = YTime::from_bytes(b.get(76..84).unwrap())?;
         let nonce = BigEndian::read_u32(b.get(84..88).unwrap());
         let method = BigEndian::read_u32(b.get(88..92).unwrap()).into();
-        let count = BigEndian::read_u32(b.get(92..96).unwrap());
-        let mut peers_buf = BytesMut::new();
-        peers_buf.extend_from_slice(b.get(96..).unwrap());
+        let mut i = 92;
+        let count = BigEndian::read_u32(b.get(i..i+4).ok_or(YHErrorKind::InvalidLength)?);
+        i += 4;
         let mut peers = Vec::new();
-        for i in 0..count as usize {
-            let size = BigEndian::read_u32(peers_buf.get(i..i+4).unwrap()) as usize;
-            peers.push(YPeer::from_bytes(b.get(i+4..i+4+size).unwrap())?);
+        for _ in 0..count {
+            let size = BigEndian::read_u32(b.get(i..i+4).ok_or(YHErrorKind::InvalidLength)?) as usize;
+            i += 4;
+            peers.push(YPeer::from_bytes(b.get(i..i+size).ok_or(YHErrorKind::InvalidLength)?)?);
+            i += size;
         }
-        let ls_peers_res = YListPeersRes {
+        let next_size = BigEndian::read_u32(b.get(i..i+4).ok_or(YHErrorKind::InvalidLength)?) as usize;
+        i += 4;
+        let next = b.get(i..i+next_size).ok_or(YHErrorKind::InvalidLength)?.to_vec();
+        i += next_size;
+        if i != buf.len() {
+            return Err(YHErrorKind::InvalidLength.into());
+        }
+        let res = YListPeersRes {
             id: id,
             version: version,
             time: time,
@@ -202,8 +235,9 @@ impl YListPeersRes {
             method: method,
             count: count,
             peers: peers,
+            next: next,
         };
-        ls_peers_res.check()?;
-        Ok(ls_peers_res)
+        res.check()?;
+        Ok(res)
     }
 }
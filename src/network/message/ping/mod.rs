@@ -50,7 +50,6 @@ impl YPingReq {
     }
 
     pub fn calc_id(&self) -> YHResult<YDigest64> {
-        self.check()?;
         let mut buf = BytesMut::new();
         buf.put(&self.version.to_bytes()?[..]);
         buf.put(&self.time.to_bytes()[..]);
@@ -81,15 +80,15 @@ impl YPingReq {
         let time = YTime::from_bytes(b.get(76..84).unwrap())?;
         let nonce = BigEndian::read_u32(b.get(84..88).unwrap());
         let method = BigEndian::read_u32(b.get(88..92).unwrap()).into();
-        let ping_req = YPingReq {
+        let req = YPingReq {
             id: id,
             version: version,
             time: time,
             nonce: nonce,
             method: method,
         };
-        ping_req.check()?;
-        Ok(ping_req)
+        req.check()?;
+        Ok(req)
     }
 }
 
@@ -136,14 +135,15 @@ impl YPingRes {
     }
 
     pub fn calc_id(&self) -> YHResult<YDigest64> {
-        self.check()?;
         let mut buf = BytesMut::new();
         buf.put(&self.version.to_bytes()?[..]);
         buf.put(&self.time.to_bytes()[..]);
         buf.put_u32::<BigEndian>(self.nonce);
         buf.put(self.method.to_bytes());
         buf.put(self.public_key.to_bytes());
-        buf.put(self.price.to_bytes());
+        let price_buf = self.price.to_bytes();
+        buf.put_u32::<BigEndian>(price_buf.len() as u32);
+        buf.put(price_buf);
         Ok(YSHA512::hash(&buf.to_vec()))
     }
 
@@ -153,14 +153,17 @@ impl YPingRes {
         buf.put(self.id.to_bytes());
         buf.put(&self.version.to_bytes()?[..]);
         buf.put(&self.time.to_bytes()[..]);
+        buf.put_u32::<BigEndian>(self.nonce);
         buf.put(self.method.to_bytes());
         buf.put(self.public_key.to_bytes());
-        buf.put(self.price.to_bytes());
+        let price_buf = self.price.to_bytes();
+        buf.put_u32::<BigEndian>(price_buf.len() as u32);
+        buf.put(price_buf);
         Ok(buf.to_vec())
     }
 
     pub fn from_bytes(buf: &[u8]) -> YHResult<YPingRes> {
-        if buf.len() < 156 {
+        if buf.len() < 92 {
             return Err(YHErrorKind::InvalidLength.into());
         }
         let mut b = BytesMut::new();
@@ -170,9 +173,17 @@ impl YPingRes {
         let time = YTime::from_bytes(b.get(76..84).unwrap())?;
         let nonce = BigEndian::read_u32(b.get(84..88).unwrap());
         let method = BigEndian::read_u32(b.get(88..92).unwrap()).into();
-        let public_key = YPublicKey::from_bytes(b.get(92..156).unwrap())?;
-        let price = YAmount::from_bytes(b.get(156..).unwrap());
-        let ping_res = YPingRes {
+        let mut i = 92;
+        let public_key = YPublicKey::from_bytes(b.get(i..i+64).ok_or(YHErrorKind::InvalidLength)?)?;
+        i += 64;
+        let price_size = BigEndian::read_u32(b.get(i..i+4).ok_or(YHErrorKind::InvalidLength)?) as usize;
+        i += 4;
+        let price = YAmount::from_bytes(b.get(i..i+price_size).ok_or(YHErrorKind::InvalidLength)?);
+        i += price_size;
+        if i != buf.len() {
+            return Err(YHErrorKind::InvalidLength.into());
+        }
+        let res = YPingRes {
             id: id,
             version: version,
             time: time,
@@ -181,7 +192,7 @@ impl YPingRes {
             public_key: public_key,
             price: price,
         };
-        ping_res.check()?;
-        Ok(ping_res)
+        res.check()?;
+        Ok(res)
     }
 }
@@ -1 +1,5 @@
 pub const YMESSAGE_PREFIX: u32 = 2112;
+
+pub const YMESSAGE_HEADER_SIZE: usize = 16;
+
+pub const YMESSAGE_MAX_SIZE: usize = 1 << 26;
@@ -5,12 +5,17 @@ use network::message::peer::*;
 use network::message::data::*;
 use network::message::transaction::*;
 use network::message::coinbase::*;
+use network::message::wallet::*;
+use network::message::coin::*;
+use network::message::utxo::*;
+use network::message::info::*;
+use network::rpc_method::*;
 use network::message::prefix::*;
 use errors::*;
 
 pub const YREQUEST_STATUS: u32 = 0;
 
-#[derive(Clone, Debug, Serialize, Deserialize)]
+#[derive(Clone, Eq, PartialEq, Debug, Serialize, Deserialize)]
 pub enum YRequest {
     Ping(YPingReq),
     ListPeers(YListPeersReq),
@@ -20,59 +25,103 @@ pub enum YRequest {
     ConfirmTx(YConfirmTxReq),
     ListTxAncestors(YListTxAncestorsReq),
     GetCb(YGetCbReq),
+    CreateWallet(YCreateWalletReq),
+    GetWallet(YGetWalletReq),
+    ListWallets(YListWalletsReq),
+    ListCoins(YListCoinsReq),
+    GetUTXO(YGetUTXOReq),
+    ListUTXOs(YListUTXOsReq),
+    GetInfo(YGetInfoReq),
+    SendCoins(YSendCoinsReq),
+    SendData(YSendDataReq),
+    PushTx(YPushTxReq),
+    ListTxs(YListTxsReq),
+    PushCb(YPushCbReq),
+    ListCbs(YListCbsReq),
+    Mine(YMineReq),
+    ListWalletData(YListWalletDataReq),
+    CreateReceiveKey(YCreateReceiveKeyReq),
+    SendBatch(YSendBatchReq),
+    CreateUnsignedTx(YCreateUnsignedTxReq),
+    ListHistory(YListHistoryReq),
 }
 
 impl YRequest {
+    pub fn method(&self) -> YRPCMethod {
+        match *self {
+            YRequest::Ping(_) => YRPCMethod::Ping,
+            YRequest::ListPeers(_) => YRPCMethod::ListPeers,
+            YRequest::GetData(_) => YRPCMethod::GetData,
+            YRequest::ListData(_) => YRPCMethod::ListData,
+            YRequest::GetTx(_) => YRPCMethod::GetTx,
+            YRequest::ConfirmTx(_) => YRPCMethod::ConfirmTx,
+            YRequest::ListTxAncestors(_) => YRPCMethod::ListTxAncestors,
+            YRequest::GetCb(_) => YRPCMethod::GetCb,
+            YRequest::CreateWallet(_) => YRPCMethod::CreateWallet,
+            YRequest::GetWallet(_) => YRPCMethod::GetWallet,
+            YRequest::ListWallets(_) => YRPCMethod::ListWallets,
+            YRequest::ListCoins(_) => YRPCMethod::ListCoins,
+            YRequest::GetUTXO(_) => YRPCMethod::GetUTXO,
+            YRequest::ListUTXOs(_) => YRPCMethod::ListUTXOs,
+            YRequest::GetInfo(_) => YRPCMethod::GetInfo,
+            YRequest::SendCoins(_) => YRPCMethod::SendCoins,
+            YRequest::SendData(_) => YRPCMethod::SendData,
+            YRequest::PushTx(_) => YRPCMethod::PushTx,
+            YRequest::ListTxs(_) => YRPCMethod::ListTxs,
+            YRequest::PushCb(_) => YRPCMethod::PushCb,
+            YRequest::ListCbs(_) => YRPCMethod::ListCbs,
+            YRequest::Mine(_) => YRPCMethod::Mine,
+            YRequest::ListWalletData(_) => YRPCMethod::ListWalletData,
+            YRequest::CreateReceiveKey(_) => YRPCMethod::CreateReceiveKey,
+            YRequest::SendBatch(_) => YRPCMethod::SendBatch,
+            YRequest::CreateUnsignedTx(_) => YRPCMethod::CreateUnsignedTx,
+            YRequest::ListHistory(_) => YRPCMethod::ListHistory,
+        }
+    }
+
     pub fn to_bytes(&self) -> YHResult<Vec<u8>> {
-        let mut buf = BytesMut::new();
-        let mut req_buf = Vec::new();
+        let req_buf = match *self {
+            YRequest::Ping(ref req) => req.to_bytes()?,
+            YRequest::ListPeers(ref req) => req.to_bytes()?,
+            YRequest::GetData(ref req) => req.to_bytes()?,
+            YRequest::ListData(ref req) => req.to_bytes()?,
+            YRequest::GetTx(ref req) => req.to_bytes()?,
+            YRequest::ConfirmTx(ref req) => req.to_bytes()?,
+            YRequest::ListTxAncestors(ref req) => req.to_bytes()?,
+            YRequest::GetCb(ref req) => req.to_bytes()?,
+            YRequest::CreateWallet(ref req) => req.to_bytes()?,
+            YRequest::GetWallet(ref req) => req.to_bytes()?,
+            YRequest::ListWallets(ref req) => req.to_bytes()?,
+            YRequest::ListCoins(ref req) => req.to_bytes()?,
+            YRequest::GetUTXO(ref req) => req.to_bytes()?,
+            YRequest::ListUTXOs(ref req) => req.to_bytes()?,
+            YRequest::GetInfo(ref req) => req.to_bytes()?,
+            YRequest::SendCoins(ref req) => req.to_bytes()?,
+            YRequest::SendData(ref req) => req.to_bytes()?,
+            YRequest::PushTx(ref req) => req.to_bytes()?,
+            YRequest::ListTxs(ref req) => req.to_bytes()?,
+            YRequest::PushCb(ref req) => req.to_bytes()?,
+            YRequest::ListCbs(ref req) => req.to_bytes()?,
+            YRequest::Mine(ref req) => req.to_bytes()?,
+            YRequest::ListWalletData(ref req) => req.to_bytes()?,
+            YRequest::CreateReceiveKey(ref req) => req.to_bytes()?,
+            YRequest::SendBatch(ref req) => req.to_bytes()?,
+            YRequest::CreateUnsignedTx(ref req) => req.to_bytes()?,
+            YRequest::ListHistory(ref req) => req.to_bytes()?,
+        };
 
+        let mut buf = BytesMut::new();
         buf.put_u32::<BigEndian>(YMESSAGE_PREFIX);
         buf.put_u32::<BigEndian>(YREQUEST_STATUS);
-        
-        match *self {
-           YRequest::Ping(ref req) => {
-               buf.put_u32::<BigEndian>(0);
-               req_buf = req.to_bytes()?;
-           },
-           YRequest::ListPeers(ref req) => {
-               buf.put_u32::<BigEndian>(1);
-               req_buf = req.to_bytes()?;
-           },
-           YRequest::GetData(ref req) => {
-               buf.put_u32::<BigEndian>(2);
-               req_buf = req.to_bytes()?;
-           },
-           YRequest::ListData(ref req) => {
-               buf.put_u32::<BigEndian>(3);
-               req_buf = req.to_bytes()?;
-           },
-           YRequest::GetTx(ref req) => {
-               buf.put_u32::<BigEndian>(4);
-               req_buf = req.to_bytes()?;
-           },
-           YRequest::ConfirmTx(ref req) => {
-               buf.put_u32::<BigEndian>(5);
-               req_buf = req.to_bytes()?;
-           },
-           YRequest::ListTxAncestors(ref req) => {
-               buf.put_u32::<BigEndian>(6);
-               req_buf = req.to_bytes()?;
-           },
-           YRequest::GetCb(ref req) => {
-               buf.put_u32::<BigEndian>(7);
-               req_buf = req.to_bytes()?;
-           },
-        }
-
-        let req_size = req_buf.len() as u32;
-        buf.put_u32::<BigEndian>(req_size);
+        buf.put_u32::<BigEndian>(self.method() as u32);
+        buf.put_u32::<BigEndian>(req_buf.len() as u32);
+        buf.put(req_buf);
         Ok(buf.to_vec())
     }
 
     pub fn from_bytes(buf: &[u8]) -> YHResult<YRequest> {
         let buf_len = buf.len();
-        if buf_len < 16 {
+        if buf_len < YMESSAGE_HEADER_SIZE {
             return Err(YHErrorKind::InvalidLength.into());
         }
 
@@ -86,54 +135,130 @@ impl YRequest {
             return Err(YHErrorKind::InvalidMessageStatus.into());
         }
         
-        let kind = BigEndian::read_u32(&buf[8..12]);
-        if kind > 7 {
+        let method = YRPCMethod::from(BigEndian::read_u32(&buf[8..12]));
+        if method == YRPCMethod::Unknown {
             return Err(YHErrorKind::InvalidMessageKind.into());
         }
         
         let size = BigEndian::read_u32(&buf[12..16]) as usize;
-        if buf_len != size + 16 {
+        if buf_len != size + YMESSAGE_HEADER_SIZE {
             return Err(YHErrorKind::InvalidLength.into());
         }
 
-        let payload = &buf[16..];
+        let payload = &buf[YMESSAGE_HEADER_SIZE..];
         
-        match kind {
-            0 => {
+        match method {
+            YRPCMethod::Ping => {
                 let req = YPingReq::from_bytes(&payload)?;
                 Ok(YRequest::Ping(req))
             },
-            1 => {
+            YRPCMethod::ListPeers => {
                 let req = YListPeersReq::from_bytes(&payload)?;
                 Ok(YRequest::ListPeers(req))
             },
-            2 => {
+            YRPCMethod::GetData => {
                 let req = YGetDataReq::from_bytes(&payload)?;
                 Ok(YRequest::GetData(req))
             },
-            3 => {
+            YRPCMethod::ListData => {
                 let req = YListDataReq::from_bytes(&payload)?;
                 Ok(YRequest::ListData(req))
             },
-            4 => {
+            YRPCMethod::GetTx => {
                 let req = YGetTxReq::from_bytes(&payload)?;
                 Ok(YRequest::GetTx(req))
             },
-            5 => {
+            YRPCMethod::ConfirmTx => {
                 let req = YConfirmTxReq::from_bytes(&payload)?;
                 Ok(YRequest::ConfirmTx(req))
             },
-            6 => {
+            YRPCMethod::ListTxAncestors => {
                 let req = YListTxAncestorsReq::from_bytes(&payload)?;
                 Ok(YRequest::ListTxAncestors(req))
             },
-            7 => {
+            YRPCMethod::GetCb => {
                 let req = YGetCbReq::from_bytes(&payload)?;
                 Ok(YRequest::GetCb(req))
             },
-            _ => {
+            YRPCMethod::CreateWallet => {
+                let req = YCreateWalletReq::from_bytes(&payload)?;
+                Ok(YRequest::CreateWallet(req))
+            },
+            YRPCMethod::GetWallet => {
+                let req = YGetWalletReq::from_bytes(&payload)?;
+                Ok(YRequest::GetWallet(req))
+            },
+            YRPCMethod::ListWallets => {
+                let req = YListWalletsReq::from_bytes(&payload)?;
+                Ok(YRequest::ListWallets(req))
+            },
+            YRPCMethod::ListCoins => {
+                let req = YListCoinsReq::from_bytes(&payload)?;
+                Ok(YRequest::ListCoins(req))
+            },
+            YRPCMethod::GetUTXO => {
+                let req = YGetUTXOReq::from_bytes(&payload)?;
+                Ok(YRequest::GetUTXO(req))
+            },
+            YRPCMethod::ListUTXOs => {
+                let req = YListUTXOsReq::from_bytes(&payload)?;
+                Ok(YRequest::ListUTXOs(req))
+            },
+            YRPCMethod::GetInfo => {
+                let req = YGetInfoReq::from_bytes(&payload)?;
+                Ok(YRequest::GetInfo(req))
+            },
+            YRPCMethod::SendCoins => {
+                let req = YSendCoinsReq::from_bytes(&payload)?;
+                Ok(YRequest::SendCoins(req))
+            },
+            YRPCMethod::SendData => {
+                let req = YSendDataReq::from_bytes(&payload)?;
+                Ok(YRequest::SendData(req))
+            },
+            YRPCMethod::PushTx => {
+                let req = YPushTxReq::from_bytes(&payload)?;
+                Ok(YRequest::PushTx(req))
+            },
+            YRPCMethod::ListTxs => {
+                let req = YListTxsReq::from_bytes(&payload)?;
+                Ok(YRequest::ListTxs(req))
+            },
+            YRPCMethod::PushCb => {
+                let req = YPushCbReq::from_bytes(&payload)?;
+                Ok(YRequest::PushCb(req))
+            },
+            YRPCMethod::ListCbs => {
+                let req = YListCbsReq::from_bytes(&payload)?;
+                Ok(YRequest::ListCbs(req))
+            },
+            YRPCMethod::Mine => {
+                let req = YMineReq::from_bytes(&payload)?;
+                Ok(YRequest::Mine(req))
+            },
+            YRPCMethod::ListWalletData => {
+                let req = YListWalletDataReq::from_bytes(&payload)?;
+                Ok(YRequest::ListWalletData(req))
+            },
+            YRPCMethod::CreateReceiveKey => {
+                let req = YCreateReceiveKeyReq::from_bytes(&payload)?;
+                Ok(YRequest::CreateReceiveKey(req))
+            },
+            YRPCMethod::SendBatch => {
+                let req = YSendBatchReq::from_bytes(&payload)?;
+                Ok(YRequest::SendBatch(req))
+            },
+            YRPCMethod::CreateUnsignedTx => {
+                let req = YCreateUnsignedTxReq::from_bytes(&payload)?;
+                Ok(YRequest::CreateUnsignedTx(req))
+            },
+            YRPCMethod::ListHistory => {
+                let req = YListHistoryReq::from_bytes(&payload)?;
+                Ok(YRequest::ListHistory(req))
+            },
+            YRPCMethod::Unknown => {
                 Err(YHErrorKind::InvalidRequest.into())
-            }
+            },
         }
     }
     
@@ -5,13 +5,20 @@ use network::message::peer::*;
 use network::message::data::*;
 use network::message::transaction::*;
 use network::message::coinbase::*;
+use network::message::wallet::*;
+use network::message::coin::*;
+use network::message::utxo::*;
+use network::message::info::*;
 use network::message::error::*;
+use network::rpc_method::*;
 use network::message::prefix::*;
 use errors::*;
 
 pub const YRESPONSE_STATUS: u32 = 0;
 
-#[derive(Clone, Debug, Serialize, Deserialize)]
+pub const YRESPONSE_ERROR_STATUS: u32 = 1;
+
+#[derive(Clone, Eq, PartialEq, Debug, Serialize, Deserialize)]
 pub enum YResponse {
     Ping(YPingRes),
     ListPeers(YListPeersRes),
@@ -21,64 +28,144 @@ pub enum YResponse {
     ConfirmTx(YConfirmTxRes),
     ListTxAncestors(YListTxAncestorsRes),
     GetCb(YGetCbRes),
+    CreateWallet(YCreateWalletRes),
+    GetWallet(YGetWalletRes),
+    ListWallets(YListWalletsRes),
+    ListCoins(YListCoinsRes),
+    GetUTXO(YGetUTXORes),
+    ListUTXOs(YListUTXOsRes),
+    GetInfo(YGetInfoRes),
+    SendCoins(YSendCoinsRes),
+    SendData(YSendDataRes),
+    PushTx(YPushTxRes),
+    ListTxs(YListTxsRes),
+    PushCb(YPushCbRes),
+    ListCbs(YListCbsRes),
+    Mine(YMineRes),
+    ListWalletData(YListWalletDataRes),
+    CreateReceiveKey(YCreateReceiveKeyRes),
+    SendBatch(YSendBatchRes),
+    CreateUnsignedTx(YCreateUnsignedTxRes),
+    ListHistory(YListHistoryRes),
     Error(YErrorRes),
 }
 
 impl YResponse {
-    pub fn to_bytes(&self) -> YHResult<Vec<u8>> {
-        let mut buf = BytesMut::new();
-        let mut res_buf = Vec::new();
+    pub fn check(&self) -> YHResult<()> {
+        match *self {
+            YResponse::Ping(ref res) => res.check(),
+            YResponse::ListPeers(ref res) => res.check(),
+            YResponse::GetData(ref res) => res.check(),
+            YResponse::ListData(ref res) => res.check(),
+            YResponse::GetTx(ref res) => res.check(),
+            YResponse::ConfirmTx(ref res) => res.check(),
+            YResponse::ListTxAncestors(ref res) => res.check(),
+            YResponse::GetCb(ref res) => res.check(),
+            YResponse::CreateWallet(ref res) => res.check(),
+            YResponse::GetWallet(ref res) => res.check(),
+            YResponse::ListWallets(ref res) => res.check(),
+            YResponse::ListCoins(ref res) => res.check(),
+            YResponse::GetUTXO(ref res) => res.check(),
+            YResponse::ListUTXOs(ref res) => res.check(),
+            YResponse::GetInfo(ref res) => res.check(),
+            YResponse::SendCoins(ref res) => res.check(),
+            YResponse::SendData(ref res) => res.check(),
+            YResponse::PushTx(ref res) => res.check(),
+            YResponse::ListTxs(ref res) => res.check(),
+            YResponse::PushCb(ref res) => res.check(),
+            YResponse::ListCbs(ref res) => res.check(),
+            YResponse::Mine(ref res) => res.check(),
+            YResponse::ListWalletData(ref res) => res.check(),
+            YResponse::CreateReceiveKey(ref res) => res.check(),
+            YResponse::SendBatch(ref res) => res.check(),
+            YResponse::CreateUnsignedTx(ref res) => res.check(),
+            YResponse::ListHistory(ref res) => res.check(),
+            YResponse::Error(ref res) => res.check(),
+        }
+    }
 
-        buf.put_u32::<BigEndian>(YMESSAGE_PREFIX);
-        buf.put_u32::<BigEndian>(YRESPONSE_STATUS);
-        
+    pub fn method(&self) -> YRPCMethod {
         match *self {
-           YResponse::Ping(ref res) => {
-               buf.put_u32::<BigEndian>(0);
-               res_buf = res.to_bytes()?;
-           },
-           YResponse::ListPeers(ref res) => {
-               buf.put_u32::<BigEndian>(1);
-               res_buf = res.to_bytes()?;
-           },
-           YResponse::GetData(ref res) => {
-               buf.put_u32::<BigEndian>(2);
-               res_buf = res.to_bytes()?;
-           },
-           YResponse::ListData(ref res) => {
-               buf.put_u32::<BigEndian>(3);
-               res_buf = res.to_bytes()?;
-           },
-           YResponse::GetTx(ref res) => {
-               buf.put_u32::<BigEndian>(4);
-               res_buf = res.to_bytes()?;
-           },
-           YResponse::ConfirmTx(ref res) => {
-               buf.put_u32::<BigEndian>(5);
-               res_buf = res.to_bytes()?;
-           },
-           YResponse::ListTxAncestors(ref res) => {
-               buf.put_u32::<BigEndian>(6);
-               res_buf = res.to_bytes()?;
-           },
-           YResponse::GetCb(ref res) => {
-               buf.put_u32::<BigEndian>(7);
-               res_buf = res.to_bytes()?;
-           },
-           YResponse::Error(ref res) => {
-               buf.put_u32::<BigEndian>(8);
-               res_buf = res.to_bytes()?;
-           }
+            YResponse::Ping(_) => YRPCMethod::Ping,
+            YResponse::ListPeers(_) => YRPCMethod::ListPeers,
+            YResponse::GetData(_) => YRPCMethod::GetData,
+            YResponse::ListData(_) => YRPCMethod::ListData,
+            YResponse::GetTx(_) => YRPCMethod::GetTx,
+            YResponse::ConfirmTx(_) => YRPCMethod::ConfirmTx,
+            YResponse::ListTxAncestors(_) => YRPCMethod::ListTxAncestors,
+            YResponse::GetCb(_) => YRPCMethod::GetCb,
+            YResponse::CreateWallet(_) => YRPCMethod::CreateWallet,
+            YResponse::GetWallet(_) => YRPCMethod::GetWallet,
+            YResponse::ListWallets(_) => YRPCMethod::ListWallets,
+            YResponse::ListCoins(_) => YRPCMethod::ListCoins,
+            YResponse::GetUTXO(_) => YRPCMethod::GetUTXO,
+            YResponse::ListUTXOs(_) => YRPCMethod::ListUTXOs,
+            YResponse::GetInfo(_) => YRPCMethod::GetInfo,
+            YResponse::SendCoins(_) => YRPCMethod::SendCoins,
+            YResponse::SendData(_) => YRPCMethod::SendData,
+            YResponse::PushTx(_) => YRPCMethod::PushTx,
+            YResponse::ListTxs(_) => YRPCMethod::ListTxs,
+            YResponse::PushCb(_) => YRPCMethod::PushCb,
+            YResponse::ListCbs(_) => YRPCMethod::ListCbs,
+            YResponse::Mine(_) => YRPCMethod::Mine,
+            YResponse::ListWalletData(_) => YRPCMethod::ListWalletData,
+            YResponse::CreateReceiveKey(_) => YRPCMethod::CreateReceiveKey,
+            YResponse::SendBatch(_) => YRPCMethod::SendBatch,
+            YResponse::CreateUnsignedTx(_) => YRPCMethod::CreateUnsignedTx,
+            YResponse::ListHistory(_) => YRPCMethod::ListHistory,
+            YResponse::Error(ref res) => res.method,
         }
+    }
 
-        let res_size = res_buf.len() as u32;
-        buf.put_u32::<BigEndian>(res_size);
+    pub fn to_bytes(&self) -> YHResult<Vec<u8>> {
+        let res_buf = match *self {
+            YResponse::Ping(ref res) => res.to_bytes()?,
+            YResponse::ListPeers(ref res) => res.to_bytes()?,
+            YResponse::GetData(ref res) => res.to_bytes()?,
+            YResponse::ListData(ref res) => res.to_bytes()?,
+            YResponse::GetTx(ref res) => res.to_bytes()?,
+            YResponse::ConfirmTx(ref res) => res.to_bytes()?,
+            YResponse::ListTxAncestors(ref res) => res.to_bytes()?,
+            YResponse::GetCb(ref res) => res.to_bytes()?,
+            YResponse::CreateWallet(ref res) => res.to_bytes()?,
+            YResponse::GetWallet(ref res) => res.to_bytes()?,
+            YResponse::ListWallets(ref res) => res.to_bytes()?,
+            YResponse::ListCoins(ref res) => res.to_bytes()?,
+            YResponse::GetUTXO(ref res) => res.to_bytes()?,
+            YResponse::ListUTXOs(ref res) => res.to_bytes()?,
+            YResponse::GetInfo(ref res) => res.to_bytes()?,
+            YResponse::SendCoins(ref res) => res.to_bytes()?,
+            YResponse::SendData(ref res) => res.to_bytes()?,
+            YResponse::PushTx(ref res) => res.to_bytes()?,
+            YResponse::ListTxs(ref res) => res.to_bytes()?,
+            YResponse::PushCb(ref res) => res.to_bytes()?,
+            YResponse::ListCbs(ref res) => res.to_bytes()?,
+            YResponse::Mine(ref res) => res.to_bytes()?,
+            YResponse::ListWalletData(ref res) => res.to_bytes()?,
+            YResponse::CreateReceiveKey(ref res) => res.to_bytes()?,
+            YResponse::SendBatch(ref res) => res.to_bytes()?,
+            YResponse::CreateUnsignedTx(ref res) => res.to_bytes()?,
+            YResponse::ListHistory(ref res) => res.to_bytes()?,
+            YResponse::Error(ref res) => res.to_bytes()?,
+        };
+
+        let status = match *self {
+            YResponse::Error(_) => YRESPONSE_ERROR_STATUS,
+            _ => YRESPONSE_STATUS,
+        };
+
+        let mut buf = BytesMut::new();
+        buf.put_u32::<BigEndian>(YMESSAGE_PREFIX);
+        buf.put_u32::<BigEndian>(status);
+        buf.put_u32::<BigEndian>(self.method() as u32);
+        buf.put_u32::<BigEndian>(res_buf.len() as u32);
+        buf.put(res_buf);
         Ok(buf.to_vec())
     }
 
     pub fn from_bytes(buf: &[u8]) -> YHResult<YResponse> {
         let buf_len = buf.len();
-        if buf_len < 16 {
+        if buf_len < YMESSAGE_HEADER_SIZE {
             return Err(YHErrorKind::InvalidLength.into());
         }
 
@@ -88,62 +175,142 @@ impl YResponse {
         }
 
         let status = BigEndian::read_u32(&buf[4..8]);
-        if status != YRESPONSE_STATUS {
+        if status != YRESPONSE_STATUS && status != YRESPONSE_ERROR_STATUS {
             return Err(YHErrorKind::InvalidMessageStatus.into());
         }
         
-        let kind = BigEndian::read_u32(&buf[8..12]);
-        if kind > 8 {
+        let method = YRPCMethod::from(BigEndian::read_u32(&buf[8..12]));
+        if method == YRPCMethod::Unknown && status != YRESPONSE_ERROR_STATUS {
             return Err(YHErrorKind::InvalidMessageKind.into());
         }
         
         let size = BigEndian::read_u32(&buf[12..16]) as usize;
-        if buf_len != size + 16 {
+        if buf_len != size + YMESSAGE_HEADER_SIZE {
             return Err(YHErrorKind::InvalidLength.into());
         }
 
-        let payload = &buf[16..];
+        let payload = &buf[YMESSAGE_HEADER_SIZE..];
         
-        match kind {
-            0 => {
+        if status == YRESPONSE_ERROR_STATUS {
+            let res = YErrorRes::from_bytes(&payload)?;
+            if res.method != method {
+                return Err(YHErrorKind::InvalidRPCMethod.into());
+            }
+            return Ok(YResponse::Error(res));
+        }
+
+        match method {
+            YRPCMethod::Ping => {
                 let res = YPingRes::from_bytes(&payload)?;
                 Ok(YResponse::Ping(res))
             },
-            1 => {
+            YRPCMethod::ListPeers => {
                 let res = YListPeersRes::from_bytes(&payload)?;
                 Ok(YResponse::ListPeers(res))
             },
-            2 => {
+            YRPCMethod::GetData => {
                 let res = YGetDataRes::from_bytes(&payload)?;
                 Ok(YResponse::GetData(res))
             },
-            3 => {
+            YRPCMethod::ListData => {
                 let res = YListDataRes::from_bytes(&payload)?;
                 Ok(YResponse::ListData(res))
             },
-            4 => {
+            YRPCMethod::GetTx => {
                 let res = YGetTxRes::from_bytes(&payload)?;
                 Ok(YResponse::GetTx(res))
             },
-            5 => {
+            YRPCMethod::ConfirmTx => {
                 let res = YConfirmTxRes::from_bytes(&payload)?;
                 Ok(YResponse::ConfirmTx(res))
             },
-            6 => {
+            YRPCMethod::ListTxAncestors => {
                 let res = YListTxAncestorsRes::from_bytes(&payload)?;
                 Ok(YResponse::ListTxAncestors(res))
             },
-            7 => {
+            YRPCMethod::GetCb => {
                 let res = YGetCbRes::from_bytes(&payload)?;
                 Ok(YResponse::GetCb(res))
             },
-            8 => {
-                let res = YErrorRes::from_bytes(&payload)?;
-                Ok(YResponse::Error(res))
+            YRPCMethod::CreateWallet => {
+                let res = YCreateWalletRes::from_bytes(&payload)?;
+                Ok(YResponse::CreateWallet(res))
+            },
+            YRPCMethod::GetWallet => {
+                let res = YGetWalletRes::from_bytes(&payload)?;
+                Ok(YResponse::GetWallet(res))
+            },
+            YRPCMethod::ListWallets => {
+                let res = YListWalletsRes::from_bytes(&payload)?;
+                Ok(YResponse::ListWallets(res))
             },
-            _ => {
+            YRPCMethod::ListCoins => {
+                let res = YListCoinsRes::from_bytes(&payload)?;
+                Ok(YResponse::ListCoins(res))
+            },
+            YRPCMethod::GetUTXO => {
+                let res = YGetUTXORes::from_bytes(&payload)?;
+                Ok(YResponse::GetUTXO(res))
+            },
+            YRPCMethod::ListUTXOs => {
+                let res = YListUTXOsRes::from_bytes(&payload)?;
+                Ok(YResponse::ListUTXOs(res))
+            },
+            YRPCMethod::GetInfo => {
+                let res = YGetInfoRes::from_bytes(&payload)?;
+                Ok(YResponse::GetInfo(res))
+            },
+            YRPCMethod::SendCoins => {
+                let res = YSendCoinsRes::from_bytes(&payload)?;
+                Ok(YResponse::SendCoins(res))
+            },
+            YRPCMethod::SendData => {
+                let res = YSendDataRes::from_bytes(&payload)?;
+                Ok(YResponse::SendData(res))
+            },
+            YRPCMethod::PushTx => {
+                let res = YPushTxRes::from_bytes(&payload)?;
+                Ok(YResponse::PushTx(res))
+            },
+            YRPCMethod::ListTxs => {
+                let res = YListTxsRes::from_bytes(&payload)?;
+                Ok(YResponse::ListTxs(res))
+            },
+            YRPCMethod::PushCb => {
+                let res = YPushCbRes::from_bytes(&payload)?;
+                Ok(YResponse::PushCb(res))
+            },
+            YRPCMethod::ListCbs => {
+                let res = YListCbsRes::from_bytes(&payload)?;
+                Ok(YResponse::ListCbs(res))
+            },
+            YRPCMethod::Mine => {
+                let res = YMineRes::from_bytes(&payload)?;
+                Ok(YResponse::Mine(res))
+            },
+            YRPCMethod::ListWalletData => {
+                let res = YListWalletDataRes::from_bytes(&payload)?;
+                Ok(YResponse::ListWalletData(res))
+            },
+            YRPCMethod::CreateReceiveKey => {
+                let res = YCreateReceiveKeyRes::from_bytes(&payload)?;
+                Ok(YResponse::CreateReceiveKey(res))
+            },
+            YRPCMethod::SendBatch => {
+                let res = YSendBatchRes::from_bytes(&payload)?;
+                Ok(YResponse::SendBatch(res))
+            },
+            YRPCMethod::CreateUnsignedTx => {
+                let res = YCreateUnsignedTxRes::from_bytes(&payload)?;
+                Ok(YResponse::CreateUnsignedTx(res))
+            },
+            YRPCMethod::ListHistory => {
+                let res = YListHistoryRes::from_bytes(&payload)?;
+                Ok(YResponse::ListHistory(res))
+            },
+            YRPCMethod::Unknown => {
                 Err(YHErrorKind::InvalidResponse.into())
-            }
+            },
         }
     }
     
@@ -4,9 +4,13 @@ use libyobicash::utils::time::*;
 use libyobicash::utils::version::*;
 use libyobicash::crypto::hash::digest::YDigest64;
 use libyobicash::crypto::hash::sha::YSHA512;
+use libyobicash::crypto::elliptic::keys::YPublicKey;
+use libyobicash::amount::YAmount;
 use libyobicash::transaction::YTransaction;
 use libyobicash::coinbase::YCoinbase;
 use bytes::{BytesMut, BufMut, BigEndian, ByteOrder};
+use models::payment::YPayment;
+use models::selection::YCoinSelection;
 use network::rpc_method::YRPCMethod;
 use version::*;
 use errors::*;
@@ -52,10 +56,10 @@ impl YListTxAncestorsReq {
     }
 
     pub fn calc_id(&self) -> YHResult<YDigest64> {
-        self.check()?;
         let mut buf = BytesMut::new();
         buf.put(&self.version.to_bytes()?[..]);
         buf.put(&self.time.to_bytes()[..]);
+        buf.put_u32::<BigEndian>(self.nonce);
         buf.put(self.method.to_bytes());
         buf.put(self.tx_id.to_bytes());
         Ok(YSHA512::hash(&buf.to_vec()))
@@ -65,13 +69,16 @@ impl YListTxAncestorsReq {
         self.check()?;
         let mut buf = BytesMut::new();
         buf.put(self.id.to_bytes());
+        buf.put(&self.version.to_bytes()?[..]);
+        buf.put(&self.time.to_bytes()[..]);
+        buf.put_u32::<BigEndian>(self.nonce);
         buf.put(self.method.to_bytes());
         buf.put(self.tx_id.to_bytes());
         Ok(buf.to_vec())
     }
 
     pub fn from_bytes(buf: &[u8]) -> YHResult<YListTxAncestorsReq> {
-        if buf.len() != 156 {
+        if buf.len() < 92 {
             return Err(YHErrorKind::InvalidLength.into());
         }
         let mut b = BytesMut::new();
@@ -81,8 +88,13 @@ impl YListTxAncestorsReq {
         let time = YTime::from_bytes(b.get(76..84).unwrap())?;
         let nonce = BigEndian::read_u32(b.get(84..88).unwrap());
         let method = BigEndian::read_u32(b.get(88..92).unwrap()).into();
-        let tx_id = YDigest64::from_bytes(b.get(92..156).unwrap())?;
-        let ls_txs_req = YListTxAncestorsReq {
+        let mut i = 92;
+        let tx_id = YDigest64::from_bytes(b.get(i..i+64).ok_or(YHErrorKind::InvalidLength)?)?;
+        i += 64;
+        if i != buf.len() {
+            return Err(YHErrorKind::InvalidLength.into());
+        }
+        let req = YListTxAncestorsReq {
             id: id,
             version: version,
             time: time,
@@ -90,8 +102,8 @@ impl YListTxAncestorsReq {
             method: method,
             tx_id: tx_id,
         };
-        ls_txs_req.check()?;
-        Ok(ls_txs_req)
+        req.check()?;
+        Ok(req)
     }
 }
 
@@ -137,23 +149,22 @@ impl YListTxAncestorsRes {
         if self.txs.len() != self.count as usize {
             return Err(YHErrorKind::InvalidLength.into());
         }
-        for tx in self.txs.clone() {
-            tx.check()?
+        for tx in self.txs.iter() {
+            tx.check()?;
         }
         Ok(())
     }
 
     pub fn calc_id(&self) -> YHResult<YDigest64> {
-        self.check()?;
         let mut buf = BytesMut::new();
         buf.put(&self.version.to_bytes()?[..]);
         buf.put(&self.time.to_bytes()[..]);
+        buf.put_u32::<BigEndian>(self.nonce);
         buf.put(self.method.to_bytes());
-        buf.put_u32::<BigEndian>(self.count as u32);
-        for i in 0..self.count as usize {
-            let tx_buf = self.txs[i].to_bytes()?;
-            let tx_size = tx_buf.len();
-            buf.put_u32::<BigEndian>(tx_size as u32);
+        buf.put_u32::<BigEndian>(self.count);
+        for tx in self.txs.iter() {
+            let tx_buf = tx.to_bytes()?;
+            buf.put_u32::<BigEndian>(tx_buf.len() as u32);
             buf.put(tx_buf);
         }
         Ok(YSHA512::hash(&buf.to_vec()))
@@ -163,19 +174,21 @@ impl YListTxAncestorsRes {
         self.check()?;
         let mut buf = BytesMut::new();
         buf.put(self.id.to_bytes());
+        buf.put(&self.version.to_bytes()?[..]);
+        buf.put(&self.time.to_bytes()[..]);
+        buf.put_u32::<BigEndian>(self.nonce);
         buf.put(self.method.to_bytes());
-        buf.put_u32::<BigEndian>(self.count as u32);
-        for i in 0..self.count as usize {
-            let tx_buf = self.txs[i].to_bytes()?;
-            let tx_size = tx_buf.len();
-            buf.put_u32::<BigEndian>(tx_size as u32);
+        buf.put_u32::<BigEndian>(self.count);
+        for tx in self.txs.iter() {
+            let tx_buf = tx.to_bytes()?;
+            buf.put_u32::<BigEndian>(tx_buf.len() as u32);
             buf.put(tx_buf);
         }
         Ok(buf.to_vec())
     }
 
     pub fn from_bytes(buf: &[u8]) -> YHResult<YListTxAncestorsRes> {
-        if buf.len() < 96 {
+        if buf.len() < 92 {
             return Err(YHErrorKind::InvalidLength.into());
         }
         let mut b = BytesMut::new();
@@ -185,15 +198,20 @@ impl YListTxAncestorsRes {
         let time = YTime::from_bytes(b.get(76..84).unwrap())?;
         let nonce = BigEndian::read_u32(b.get(84..88).unwrap());
         let method = BigEndian::read_u32(b.get(88..92).unwrap()).into();
-        let count = BigEndian::read_u32(b.get(92..96).unwrap());
-        let mut tx_buf = BytesMut::new();
-        tx_buf.extend_from_slice(b.get(96..).unwrap());
+        let mut i = 92;
+        let count = BigEndian::read_u32(b.get(i..i+4).ok_or(YHErrorKind::InvalidLength)?);
+        i += 4;
         let mut txs = Vec::new();
-        for i in 0..count as usize {
-            let size = BigEndian::read_u32(tx_buf.get(i..i+4).unwrap()) as usize;
-            txs.push(YTransaction::from_bytes(b.get(i+4..i+4+size).unwrap())?);
+        for _ in 0..count {
+            let size = BigEndian::read_u32(b.get(i..i+4).ok_or(YHErrorKind::InvalidLength)?) as usize;
+            i += 4;
+            txs.push(YTransaction::from_bytes(b.get(i..i+size).ok_or(YHErrorKind::InvalidLength)?)?);
+            i += size;
+        }
+        if i != buf.len() {
+            return Err(YHErrorKind::InvalidLength.into());
         }
-        let ls_txs_res = YListTxAncestorsRes {
+        let res = YListTxAncestorsRes {
             id: id,
             version: version,
             time: time,
@@ -202,8 +220,8 @@ impl YListTxAncestorsRes {
             count: count,
             txs: txs,
         };
-        ls_txs_res.check()?;
-        Ok(ls_txs_res)
+        res.check()?;
+        Ok(res)
     }
 }
 
@@ -258,10 +276,10 @@ impl YGetTxReq {
     }
 
     pub fn calc_id(&self) -> YHResult<YDigest64> {
-        self.check()?;
         let mut buf = BytesMut::new();
         buf.put(&self.version.to_bytes()?[..]);
         buf.put(&self.time.to_bytes()[..]);
+        buf.put_u32::<BigEndian>(self.nonce);
         buf.put(self.method.to_bytes());
         buf.put(self.tx_id.to_bytes());
         Ok(YSHA512::hash(&buf.to_vec()))
@@ -271,13 +289,16 @@ impl YGetTxReq {
         self.check()?;
         let mut buf = BytesMut::new();
         buf.put(self.id.to_bytes());
+        buf.put(&self.version.to_bytes()?[..]);
+        buf.put(&self.time.to_bytes()[..]);
+        buf.put_u32::<BigEndian>(self.nonce);
         buf.put(self.method.to_bytes());
         buf.put(self.tx_id.to_bytes());
         Ok(buf.to_vec())
     }
 
     pub fn from_bytes(buf: &[u8]) -> YHResult<YGetTxReq> {
-        if buf.len() != 156 {
+        if buf.len() < 92 {
             return Err(YHErrorKind::InvalidLength.into());
         }
         let mut b = BytesMut::new();
@@ -287,8 +308,13 @@ impl YGetTxReq {
         let time = YTime::from_bytes(b.get(76..84).unwrap())?;
         let nonce = BigEndian::read_u32(b.get(84..88).unwrap());
         let method = BigEndian::read_u32(b.get(88..92).unwrap()).into();
-        let tx_id = YDigest64::from_bytes(b.get(92..156).unwrap())?;
-        let get_tx_req = YGetTxReq {
+        let mut i = 92;
+        let tx_id = YDigest64::from_bytes(b.get(i..i+64).ok_or(YHErrorKind::InvalidLength)?)?;
+        i += 64;
+        if i != buf.len() {
+            return Err(YHErrorKind::InvalidLength.into());
+        }
+        let req = YGetTxReq {
             id: id,
             version: version,
             time: time,
@@ -296,8 +322,8 @@ impl YGetTxReq {
             method: method,
             tx_id: tx_id,
         };
-        get_tx_req.check()?;
-        Ok(get_tx_req)
+        req.check()?;
+        Ok(req)
     }
 }
 
@@ -343,12 +369,14 @@ impl YGetTxRes {
     }
 
     pub fn calc_id(&self) -> YHResult<YDigest64> {
-        self.check()?;
         let mut buf = BytesMut::new();
         buf.put(&self.version.to_bytes()?[..]);
         buf.put(&self.time.to_bytes()[..]);
+        buf.put_u32::<BigEndian>(self.nonce);
         buf.put(self.method.to_bytes());
-        buf.put(self.tx.to_bytes()?);
+        let tx_buf = self.tx.to_bytes()?;
+        buf.put_u32::<BigEndian>(tx_buf.len() as u32);
+        buf.put(tx_buf);
         Ok(YSHA512::hash(&buf.to_vec()))
     }
 
@@ -356,13 +384,18 @@ impl YGetTxRes {
         self.check()?;
         let mut buf = BytesMut::new();
         buf.put(self.id.to_bytes());
+        buf.put(&self.version.to_bytes()?[..]);
+        buf.put(&self.time.to_bytes()[..]);
+        buf.put_u32::<BigEndian>(self.nonce);
         buf.put(self.method.to_bytes());
-        buf.put(self.tx.to_bytes()?);
+        let tx_buf = self.tx.to_bytes()?;
+        buf.put_u32::<BigEndian>(tx_buf.len() as u32);
+        buf.put(tx_buf);
         Ok(buf.to_vec())
     }
 
     pub fn from_bytes(buf: &[u8]) -> YHResult<YGetTxRes> {
-        if buf.len() < 156 {
+        if buf.len() < 92 {
             return Err(YHErrorKind::InvalidLength.into());
         }
         let mut b = BytesMut::new();
@@ -372,8 +405,15 @@ impl YGetTxRes {
         let time = YTime::from_bytes(b.get(76..84).unwrap())?;
         let nonce = BigEndian::read_u32(b.get(84..88).unwrap());
         let method = BigEndian::read_u32(b.get(88..92).unwrap()).into();
-        let tx = YTransaction::from_bytes(b.get(92..).unwrap())?;
-        let get_tx_res = YGetTxRes {
+        let mut i = 92;
+        let tx_size = BigEndian::read_u32(b.get(i..i+4).ok_or(YHErrorKind::InvalidLength)?) as usize;
+        i += 4;
+        let tx = YTransaction::from_bytes(b.get(i..i+tx_size).ok_or(YHErrorKind::InvalidLength)?)?;
+        i += tx_size;
+        if i != buf.len() {
+            return Err(YHErrorKind::InvalidLength.into());
+        }
+        let res = YGetTxRes {
             id: id,
             version: version,
             time: time,
@@ -381,8 +421,8 @@ impl YGetTxRes {
             method: method,
             tx: tx,
         };
-        get_tx_res.check()?;
-        Ok(get_tx_res)
+        res.check()?;
+        Ok(res)
     }
 }
 
@@ -427,10 +467,10 @@ impl YConfirmTxReq {
     }
 
     pub fn calc_id(&self) -> YHResult<YDigest64> {
-        self.check()?;
         let mut buf = BytesMut::new();
         buf.put(&self.version.to_bytes()?[..]);
         buf.put(&self.time.to_bytes()[..]);
+        buf.put_u32::<BigEndian>(self.nonce);
         buf.put(self.method.to_bytes());
         buf.put(self.tx_id.to_bytes());
         Ok(YSHA512::hash(&buf.to_vec()))
@@ -440,13 +480,16 @@ impl YConfirmTxReq {
         self.check()?;
         let mut buf = BytesMut::new();
         buf.put(self.id.to_bytes());
+        buf.put(&self.version.to_bytes()?[..]);
+        buf.put(&self.time.to_bytes()[..]);
+        buf.put_u32::<BigEndian>(self.nonce);
         buf.put(self.method.to_bytes());
         buf.put(self.tx_id.to_bytes());
         Ok(buf.to_vec())
     }
 
     pub fn from_bytes(buf: &[u8]) -> YHResult<YConfirmTxReq> {
-        if buf.len() != 156 {
+        if buf.len() < 92 {
             return Err(YHErrorKind::InvalidLength.into());
         }
         let mut b = BytesMut::new();
@@ -456,8 +499,13 @@ impl YConfirmTxReq {
         let time = YTime::from_bytes(b.get(76..84).unwrap())?;
         let nonce = BigEndian::read_u32(b.get(84..88).unwrap());
         let method = BigEndian::read_u32(b.get(88..92).unwrap()).into();
-        let tx_id = YDigest64::from_bytes(b.get(92..156).unwrap())?;
-        let conf_tx_req = YConfirmTxReq {
+        let mut i = 92;
+        let tx_id = YDigest64::from_bytes(b.get(i..i+64).ok_or(YHErrorKind::InvalidLength)?)?;
+        i += 64;
+        if i != buf.len() {
+            return Err(YHErrorKind::InvalidLength.into());
+        }
+        let req = YConfirmTxReq {
             id: id,
             version: version,
             time: time,
@@ -465,8 +513,8 @@ impl YConfirmTxReq {
             method: method,
             tx_id: tx_id,
         };
-        conf_tx_req.check()?;
-        Ok(conf_tx_req)
+        req.check()?;
+        Ok(req)
     }
 }
 
@@ -514,13 +562,15 @@ impl YConfirmTxRes {
     }
 
     pub fn calc_id(&self) -> YHResult<YDigest64> {
-        self.check()?;
         let mut buf = BytesMut::new();
         buf.put(&self.version.to_bytes()?[..]);
         buf.put(&self.time.to_bytes()[..]);
+        buf.put_u32::<BigEndian>(self.nonce);
         buf.put(self.method.to_bytes());
         buf.put_u32::<BigEndian>(self.ack as u32);
-        buf.put(self.cb.to_bytes()?);
+        let cb_buf = self.cb.to_bytes()?;
+        buf.put_u32::<BigEndian>(cb_buf.len() as u32);
+        buf.put(cb_buf);
         Ok(YSHA512::hash(&buf.to_vec()))
     }
 
@@ -528,14 +578,19 @@ impl YConfirmTxRes {
         self.check()?;
         let mut buf = BytesMut::new();
         buf.put(self.id.to_bytes());
+        buf.put(&self.version.to_bytes()?[..]);
+        buf.put(&self.time.to_bytes()[..]);
+        buf.put_u32::<BigEndian>(self.nonce);
         buf.put(self.method.to_bytes());
         buf.put_u32::<BigEndian>(self.ack as u32);
-        buf.put(self.cb.to_bytes()?);
+        let cb_buf = self.cb.to_bytes()?;
+        buf.put_u32::<BigEndian>(cb_buf.len() as u32);
+        buf.put(cb_buf);
         Ok(buf.to_vec())
     }
 
     pub fn from_bytes(buf: &[u8]) -> YHResult<YConfirmTxRes> {
-        if buf.len() < 96 {
+        if buf.len() < 92 {
             return Err(YHErrorKind::InvalidLength.into());
         }
         let mut b = BytesMut::new();
@@ -545,15 +600,21 @@ impl YConfirmTxRes {
         let time = YTime::from_bytes(b.get(76..84).unwrap())?;
         let nonce = BigEndian::read_u32(b.get(84..88).unwrap());
         let method = BigEndian::read_u32(b.get(88..92).unwrap()).into();
-        let ack_n = BigEndian::read_u32(b.get(92..96).unwrap());
-        let mut ack = false;
-        match ack_n {
-            0 => {},
-            1 => { ack = true; },
-            _ => { return Err(YHErrorKind::Other("Invalid ack".to_string()).into()); }
-        }
-        let cb = YCoinbase::from_bytes(b.get(96..).unwrap())?;
-        let confirm_tx_res = YConfirmTxRes {
+        let mut i = 92;
+        let ack = match BigEndian::read_u32(b.get(i..i+4).ok_or(YHErrorKind::InvalidLength)?) {
+            0 => false,
+            1 => true,
+            _ => { return Err(YHErrorKind::InvalidValue.into()); },
+        };
+        i += 4;
+        let cb_size = BigEndian::read_u32(b.get(i..i+4).ok_or(YHErrorKind::InvalidLength)?) as usize;
+        i += 4;
+        let cb = YCoinbase::from_bytes(b.get(i..i+cb_size).ok_or(YHErrorKind::InvalidLength)?)?;
+        i += cb_size;
+        if i != buf.len() {
+            return Err(YHErrorKind::InvalidLength.into());
+        }
+        let res = YConfirmTxRes {
             id: id,
             version: version,
             time: time,
@@ -562,7 +623,1588 @@ impl YConfirmTxRes {
             ack: ack,
             cb: cb,
         };
-        confirm_tx_res.check()?;
-        Ok(confirm_tx_res)
+        res.check()?;
+        Ok(res)
+    }
+}
+
+#[derive(Clone, Eq, PartialEq, Debug, Default, Serialize, Deserialize)]
+pub struct YSendCoinsReq {
+    pub id: YDigest64,
+    pub version: YVersion,
+    pub time: YTime,
+    pub nonce: u32,
+    pub method: YRPCMethod,
+    pub wallet: String,
+    pub to: YPublicKey,
+    pub amount: YAmount,
+    pub keep_data: bool,
+    pub selection: YCoinSelection,
+    pub fee: YAmount,
+    pub max_fee: YAmount,
+}
+
+impl YSendCoinsReq {
+    pub fn new(wallet: &str, to: YPublicKey, amount: &YAmount, keep_data: bool, selection: YCoinSelection, fee: &YAmount, max_fee: &YAmount) -> YHResult<YSendCoinsReq> {
+        let mut req = YSendCoinsReq {
+            id: YDigest64::default(),
+            version: default_version(),
+            time: YTime::now(),
+            nonce: YRandom::u32(),
+            method: YRPCMethod::SendCoins,
+            wallet: String::from(wallet),
+            to: to,
+            amount: amount.clone(),
+            keep_data: keep_data,
+            selection: selection,
+            fee: fee.clone(),
+            max_fee: max_fee.clone(),
+        };
+        req.id = req.calc_id()?;
+        Ok(req)
+    }
+
+    pub fn check(&self) -> YHResult<()> {
+        if self.id != self.calc_id()? {
+            return Err(YHErrorKind::Lib(LibErrorKind::InvalidChecksum).into());
+        }
+        if self.version.major() > default_version().major() {
+            return Err(YHErrorKind::Lib(LibErrorKind::InvalidVersion(self.version.to_string())).into());
+        }
+        if self.time > YTime::now() {
+            return Err(YHErrorKind::Lib(LibErrorKind::InvalidTime).into());
+        }
+        if self.method != YRPCMethod::SendCoins {
+            return Err(YHErrorKind::InvalidRPCMethod.into());
+        }
+        Ok(())
+    }
+
+    pub fn calc_id(&self) -> YHResult<YDigest64> {
+        let mut buf = BytesMut::new();
+        buf.put(&self.version.to_bytes()?[..]);
+        buf.put(&self.time.to_bytes()[..]);
+        buf.put_u32::<BigEndian>(self.nonce);
+        buf.put(self.method.to_bytes());
+        let wallet_buf = self.wallet.as_bytes();
+        buf.put_u32::<BigEndian>(wallet_buf.len() as u32);
+        buf.put(wallet_buf);
+        buf.put(self.to.to_bytes());
+        let amount_buf = self.amount.to_bytes();
+        buf.put_u32::<BigEndian>(amount_buf.len() as u32);
+        buf.put(amount_buf);
+        buf.put_u32::<BigEndian>(self.keep_data as u32);
+        buf.put_u32::<BigEndian>(self.selection as u32);
+        let fee_buf = self.fee.to_bytes();
+        buf.put_u32::<BigEndian>(fee_buf.len() as u32);
+        buf.put(fee_buf);
+        let max_fee_buf = self.max_fee.to_bytes();
+        buf.put_u32::<BigEndian>(max_fee_buf.len() as u32);
+        buf.put(max_fee_buf);
+        Ok(YSHA512::hash(&buf.to_vec()))
+    }
+
+    pub fn to_bytes(&self) -> YHResult<Vec<u8>> {
+        self.check()?;
+        let mut buf = BytesMut::new();
+        buf.put(self.id.to_bytes());
+        buf.put(&self.version.to_bytes()?[..]);
+        buf.put(&self.time.to_bytes()[..]);
+        buf.put_u32::<BigEndian>(self.nonce);
+        buf.put(self.method.to_bytes());
+        let wallet_buf = self.wallet.as_bytes();
+        buf.put_u32::<BigEndian>(wallet_buf.len() as u32);
+        buf.put(wallet_buf);
+        buf.put(self.to.to_bytes());
+        let amount_buf = self.amount.to_bytes();
+        buf.put_u32::<BigEndian>(amount_buf.len() as u32);
+        buf.put(amount_buf);
+        buf.put_u32::<BigEndian>(self.keep_data as u32);
+        buf.put_u32::<BigEndian>(self.selection as u32);
+        let fee_buf = self.fee.to_bytes();
+        buf.put_u32::<BigEndian>(fee_buf.len() as u32);
+        buf.put(fee_buf);
+        let max_fee_buf = self.max_fee.to_bytes();
+        buf.put_u32::<BigEndian>(max_fee_buf.len() as u32);
+        buf.put(max_fee_buf);
+        Ok(buf.to_vec())
+    }
+
+    pub fn from_bytes(buf: &[u8]) -> YHResult<YSendCoinsReq> {
+        if buf.len() < 92 {
+            return Err(YHErrorKind::InvalidLength.into());
+        }
+        let mut b = BytesMut::new();
+        b.extend_from_slice(buf);
+        let id = YDigest64::from_bytes(b.get(0..64).unwrap())?;
+        let version = YVersion::from_bytes(b.get(64..76).unwrap())?;
+        let time = YTime::from_bytes(b.get(76..84).unwrap())?;
+        let nonce = BigEndian::read_u32(b.get(84..88).unwrap());
+        let method = BigEndian::read_u32(b.get(88..92).unwrap()).into();
+        let mut i = 92;
+        let wallet_size = BigEndian::read_u32(b.get(i..i+4).ok_or(YHErrorKind::InvalidLength)?) as usize;
+        i += 4;
+        let wallet = String::from_utf8(b.get(i..i+wallet_size).ok_or(YHErrorKind::InvalidLength)?.to_vec())?;
+        i += wallet_size;
+        let to = YPublicKey::from_bytes(b.get(i..i+64).ok_or(YHErrorKind::InvalidLength)?)?;
+        i += 64;
+        let amount_size = BigEndian::read_u32(b.get(i..i+4).ok_or(YHErrorKind::InvalidLength)?) as usize;
+        i += 4;
+        let amount = YAmount::from_bytes(b.get(i..i+amount_size).ok_or(YHErrorKind::InvalidLength)?);
+        i += amount_size;
+        let keep_data = match BigEndian::read_u32(b.get(i..i+4).ok_or(YHErrorKind::InvalidLength)?) {
+            0 => false,
+            1 => true,
+            _ => { return Err(YHErrorKind::InvalidValue.into()); },
+        };
+        i += 4;
+        let selection = YCoinSelection::from_u32(BigEndian::read_u32(b.get(i..i+4).ok_or(YHErrorKind::InvalidLength)?))?;
+        i += 4;
+        let fee_size = BigEndian::read_u32(b.get(i..i+4).ok_or(YHErrorKind::InvalidLength)?) as usize;
+        i += 4;
+        let fee = YAmount::from_bytes(b.get(i..i+fee_size).ok_or(YHErrorKind::InvalidLength)?);
+        i += fee_size;
+        let max_fee_size = BigEndian::read_u32(b.get(i..i+4).ok_or(YHErrorKind::InvalidLength)?) as usize;
+        i += 4;
+        let max_fee = YAmount::from_bytes(b.get(i..i+max_fee_size).ok_or(YHErrorKind::InvalidLength)?);
+        i += max_fee_size;
+        if i != buf.len() {
+            return Err(YHErrorKind::InvalidLength.into());
+        }
+        let req = YSendCoinsReq {
+            id: id,
+            version: version,
+            time: time,
+            nonce: nonce,
+            method: method,
+            wallet: wallet,
+            to: to,
+            amount: amount,
+            keep_data: keep_data,
+            selection: selection,
+            fee: fee,
+            max_fee: max_fee,
+        };
+        req.check()?;
+        Ok(req)
+    }
+}
+
+#[derive(Clone, Eq, PartialEq, Debug, Default, Serialize, Deserialize)]
+pub struct YSendCoinsRes {
+    pub id: YDigest64,
+    pub version: YVersion,
+    pub time: YTime,
+    pub nonce: u32,
+    pub method: YRPCMethod,
+    pub tx: YTransaction,
+    pub fee_tx: Option<YTransaction>,
+}
+
+impl YSendCoinsRes {
+    // the fee is paid by a second transaction spending the change, if any
+    pub fn new(tx: &YTransaction, fee_tx: &Option<YTransaction>) -> YHResult<YSendCoinsRes> {
+        let mut res = YSendCoinsRes {
+            id: YDigest64::default(),
+            version: default_version(),
+            time: YTime::now(),
+            nonce: YRandom::u32(),
+            method: YRPCMethod::SendCoins,
+            tx: tx.clone(),
+            fee_tx: fee_tx.clone(),
+        };
+        res.id = res.calc_id()?;
+        Ok(res)
+    }
+
+    pub fn check(&self) -> YHResult<()> {
+        if self.id != self.calc_id()? {
+            return Err(YHErrorKind::Lib(LibErrorKind::InvalidChecksum).into());
+        }
+        if self.version.major() > default_version().major() {
+            return Err(YHErrorKind::Lib(LibErrorKind::InvalidVersion(self.version.to_string())).into());
+        }
+        if self.time > YTime::now() {
+            return Err(YHErrorKind::Lib(LibErrorKind::InvalidTime).into());
+        }
+        if self.method != YRPCMethod::SendCoins {
+            return Err(YHErrorKind::InvalidRPCMethod.into());
+        }
+        self.tx.check()?;
+        if let Some(ref fee_tx) = self.fee_tx {
+            fee_tx.check()?;
+        }
+        Ok(())
+    }
+
+    pub fn calc_id(&self) -> YHResult<YDigest64> {
+        let mut buf = BytesMut::new();
+        buf.put(&self.version.to_bytes()?[..]);
+        buf.put(&self.time.to_bytes()[..]);
+        buf.put_u32::<BigEndian>(self.nonce);
+        buf.put(self.method.to_bytes());
+        let tx_buf = self.tx.to_bytes()?;
+        buf.put_u32::<BigEndian>(tx_buf.len() as u32);
+        buf.put(tx_buf);
+        let fee_tx_buf = match self.fee_tx {
+            Some(ref fee_tx) => fee_tx.to_bytes()?,
+            None => Vec::new(),
+        };
+        buf.put_u32::<BigEndian>(fee_tx_buf.len() as u32);
+        buf.put(fee_tx_buf);
+        Ok(YSHA512::hash(&buf.to_vec()))
+    }
+
+    pub fn to_bytes(&self) -> YHResult<Vec<u8>> {
+        self.check()?;
+        let mut buf = BytesMut::new();
+        buf.put(self.id.to_bytes());
+        buf.put(&self.version.to_bytes()?[..]);
+        buf.put(&self.time.to_bytes()[..]);
+        buf.put_u32::<BigEndian>(self.nonce);
+        buf.put(self.method.to_bytes());
+        let tx_buf = self.tx.to_bytes()?;
+        buf.put_u32::<BigEndian>(tx_buf.len() as u32);
+        buf.put(tx_buf);
+        let fee_tx_buf = match self.fee_tx {
+            Some(ref fee_tx) => fee_tx.to_bytes()?,
+            None => Vec::new(),
+        };
+        buf.put_u32::<BigEndian>(fee_tx_buf.len() as u32);
+        buf.put(fee_tx_buf);
+        Ok(buf.to_vec())
+    }
+
+    pub fn from_bytes(buf: &[u8]) -> YHResult<YSendCoinsRes> {
+        if buf.len() < 92 {
+            return Err(YHErrorKind::InvalidLength.into());
+        }
+        let mut b = BytesMut::new();
+        b.extend_from_slice(buf);
+        let id = YDigest64::from_bytes(b.get(0..64).unwrap())?;
+        let version = YVersion::from_bytes(b.get(64..76).unwrap())?;
+        let time = YTime::from_bytes(b.get(76..84).unwrap())?;
+        let nonce = BigEndian::read_u32(b.get(84..88).unwrap());
+        let method = BigEndian::read_u32(b.get(88..92).unwrap()).into();
+        let mut i = 92;
+        let tx_size = BigEndian::read_u32(b.get(i..i+4).ok_or(YHErrorKind::InvalidLength)?) as usize;
+        i += 4;
+        let tx = YTransaction::from_bytes(b.get(i..i+tx_size).ok_or(YHErrorKind::InvalidLength)?)?;
+        i += tx_size;
+        let fee_tx_size = BigEndian::read_u32(b.get(i..i+4).ok_or(YHErrorKind::InvalidLength)?) as usize;
+        i += 4;
+        let fee_tx = if fee_tx_size == 0 {
+            None
+        } else {
+            Some(YTransaction::from_bytes(b.get(i..i+fee_tx_size).ok_or(YHErrorKind::InvalidLength)?)?)
+        };
+        i += fee_tx_size;
+        if i != buf.len() {
+            return Err(YHErrorKind::InvalidLength.into());
+        }
+        let res = YSendCoinsRes {
+            id: id,
+            version: version,
+            time: time,
+            nonce: nonce,
+            method: method,
+            tx: tx,
+            fee_tx: fee_tx,
+        };
+        res.check()?;
+        Ok(res)
+    }
+}
+
+#[derive(Clone, Eq, PartialEq, Debug, Default, Serialize, Deserialize)]
+pub struct YSendDataReq {
+    pub id: YDigest64,
+    pub version: YVersion,
+    pub time: YTime,
+    pub nonce: u32,
+    pub method: YRPCMethod,
+    pub wallet: String,
+    pub to: YPublicKey,
+    pub data: Vec<u8>,
+    pub keep_data: bool,
+    pub selection: YCoinSelection,
+    pub fee: YAmount,
+    pub max_fee: YAmount,
+}
+
+impl YSendDataReq {
+    pub fn new(wallet: &str, to: YPublicKey, data: &[u8], keep_data: bool, selection: YCoinSelection, fee: &YAmount, max_fee: &YAmount) -> YHResult<YSendDataReq> {
+        let mut req = YSendDataReq {
+            id: YDigest64::default(),
+            version: default_version(),
+            time: YTime::now(),
+            nonce: YRandom::u32(),
+            method: YRPCMethod::SendData,
+            wallet: String::from(wallet),
+            to: to,
+            data: data.to_vec(),
+            keep_data: keep_data,
+            selection: selection,
+            fee: fee.clone(),
+            max_fee: max_fee.clone(),
+        };
+        req.id = req.calc_id()?;
+        Ok(req)
+    }
+
+    pub fn check(&self) -> YHResult<()> {
+        if self.id != self.calc_id()? {
+            return Err(YHErrorKind::Lib(LibErrorKind::InvalidChecksum).into());
+        }
+        if self.version.major() > default_version().major() {
+            return Err(YHErrorKind::Lib(LibErrorKind::InvalidVersion(self.version.to_string())).into());
+        }
+        if self.time > YTime::now() {
+            return Err(YHErrorKind::Lib(LibErrorKind::InvalidTime).into());
+        }
+        if self.method != YRPCMethod::SendData {
+            return Err(YHErrorKind::InvalidRPCMethod.into());
+        }
+        Ok(())
+    }
+
+    pub fn calc_id(&self) -> YHResult<YDigest64> {
+        let mut buf = BytesMut::new();
+        buf.put(&self.version.to_bytes()?[..]);
+        buf.put(&self.time.to_bytes()[..]);
+        buf.put_u32::<BigEndian>(self.nonce);
+        buf.put(self.method.to_bytes());
+        let wallet_buf = self.wallet.as_bytes();
+        buf.put_u32::<BigEndian>(wallet_buf.len() as u32);
+        buf.put(wallet_buf);
+        buf.put(self.to.to_bytes());
+        buf.put_u32::<BigEndian>(self.data.len() as u32);
+        buf.put(&self.data[..]);
+        buf.put_u32::<BigEndian>(self.keep_data as u32);
+        buf.put_u32::<BigEndian>(self.selection as u32);
+        let fee_buf = self.fee.to_bytes();
+        buf.put_u32::<BigEndian>(fee_buf.len() as u32);
+        buf.put(fee_buf);
+        let max_fee_buf = self.max_fee.to_bytes();
+        buf.put_u32::<BigEndian>(max_fee_buf.len() as u32);
+        buf.put(max_fee_buf);
+        Ok(YSHA512::hash(&buf.to_vec()))
+    }
+
+    pub fn to_bytes(&self) -> YHResult<Vec<u8>> {
+        self.check()?;
+        let mut buf = BytesMut::new();
+        buf.put(self.id.to_bytes());
+        buf.put(&self.version.to_bytes()?[..]);
+        buf.put(&self.time.to_bytes()[..]);
+        buf.put_u32::<BigEndian>(self.nonce);
+        buf.put(self.method.to_bytes());
+        let wallet_buf = self.wallet.as_bytes();
+        buf.put_u32::<BigEndian>(wallet_buf.len() as u32);
+        buf.put(wallet_buf);
+        buf.put(self.to.to_bytes());
+        buf.put_u32::<BigEndian>(self.data.len() as u32);
+        buf.put(&self.data[..]);
+        buf.put_u32::<BigEndian>(self.keep_data as u32);
+        buf.put_u32::<BigEndian>(self.selection as u32);
+        let fee_buf = self.fee.to_bytes();
+        buf.put_u32::<BigEndian>(fee_buf.len() as u32);
+        buf.put(fee_buf);
+        let max_fee_buf = self.max_fee.to_bytes();
+        buf.put_u32::<BigEndian>(max_fee_buf.len() as u32);
+        buf.put(max_fee_buf);
+        Ok(buf.to_vec())
+    }
+
+    pub fn from_bytes(buf: &[u8]) -> YHResult<YSendDataReq> {
+        if buf.len() < 92 {
+            return Err(YHErrorKind::InvalidLength.into());
+        }
+        let mut b = BytesMut::new();
+        b.extend_from_slice(buf);
+        let id = YDigest64::from_bytes(b.get(0..64).unwrap())?;
+        let version = YVersion::from_bytes(b.get(64..76).unwrap())?;
+        let time = YTime::from_bytes(b.get(76..84).unwrap())?;
+        let nonce = BigEndian::read_u32(b.get(84..88).unwrap());
+        let method = BigEndian::read_u32(b.get(88..92).unwrap()).into();
+        let mut i = 92;
+        let wallet_size = BigEndian::read_u32(b.get(i..i+4).ok_or(YHErrorKind::InvalidLength)?) as usize;
+        i += 4;
+        let wallet = String::from_utf8(b.get(i..i+wallet_size).ok_or(YHErrorKind::InvalidLength)?.to_vec())?;
+        i += wallet_size;
+        let to = YPublicKey::from_bytes(b.get(i..i+64).ok_or(YHErrorKind::InvalidLength)?)?;
+        i += 64;
+        let data_size = BigEndian::read_u32(b.get(i..i+4).ok_or(YHErrorKind::InvalidLength)?) as usize;
+        i += 4;
+        let data = b.get(i..i+data_size).ok_or(YHErrorKind::InvalidLength)?.to_vec();
+        i += data_size;
+        let keep_data = match BigEndian::read_u32(b.get(i..i+4).ok_or(YHErrorKind::InvalidLength)?) {
+            0 => false,
+            1 => true,
+            _ => { return Err(YHErrorKind::InvalidValue.into()); },
+        };
+        i += 4;
+        let selection = YCoinSelection::from_u32(BigEndian::read_u32(b.get(i..i+4).ok_or(YHErrorKind::InvalidLength)?))?;
+        i += 4;
+        let fee_size = BigEndian::read_u32(b.get(i..i+4).ok_or(YHErrorKind::InvalidLength)?) as usize;
+        i += 4;
+        let fee = YAmount::from_bytes(b.get(i..i+fee_size).ok_or(YHErrorKind::InvalidLength)?);
+        i += fee_size;
+        let max_fee_size = BigEndian::read_u32(b.get(i..i+4).ok_or(YHErrorKind::InvalidLength)?) as usize;
+        i += 4;
+        let max_fee = YAmount::from_bytes(b.get(i..i+max_fee_size).ok_or(YHErrorKind::InvalidLength)?);
+        i += max_fee_size;
+        if i != buf.len() {
+            return Err(YHErrorKind::InvalidLength.into());
+        }
+        let req = YSendDataReq {
+            id: id,
+            version: version,
+            time: time,
+            nonce: nonce,
+            method: method,
+            wallet: wallet,
+            to: to,
+            data: data,
+            keep_data: keep_data,
+            selection: selection,
+            fee: fee,
+            max_fee: max_fee,
+        };
+        req.check()?;
+        Ok(req)
+    }
+}
+
+#[derive(Clone, Eq, PartialEq, Debug, Default, Serialize, Deserialize)]
+pub struct YSendDataRes {
+    pub id: YDigest64,
+    pub version: YVersion,
+    pub time: YTime,
+    pub nonce: u32,
+    pub method: YRPCMethod,
+    pub tx: YTransaction,
+    pub fee_tx: Option<YTransaction>,
+}
+
+impl YSendDataRes {
+    // the fee is paid by a second transaction spending the change, if any
+    pub fn new(tx: &YTransaction, fee_tx: &Option<YTransaction>) -> YHResult<YSendDataRes> {
+        let mut res = YSendDataRes {
+            id: YDigest64::default(),
+            version: default_version(),
+            time: YTime::now(),
+            nonce: YRandom::u32(),
+            method: YRPCMethod::SendData,
+            tx: tx.clone(),
+            fee_tx: fee_tx.clone(),
+        };
+        res.id = res.calc_id()?;
+        Ok(res)
+    }
+
+    pub fn check(&self) -> YHResult<()> {
+        if self.id != self.calc_id()? {
+            return Err(YHErrorKind::Lib(LibErrorKind::InvalidChecksum).into());
+        }
+        if self.version.major() > default_version().major() {
+            return Err(YHErrorKind::Lib(LibErrorKind::InvalidVersion(self.version.to_string())).into());
+        }
+        if self.time > YTime::now() {
+            return Err(YHErrorKind::Lib(LibErrorKind::InvalidTime).into());
+        }
+        if self.method != YRPCMethod::SendData {
+            return Err(YHErrorKind::InvalidRPCMethod.into());
+        }
+        self.tx.check()?;
+        if let Some(ref fee_tx) = self.fee_tx {
+            fee_tx.check()?;
+        }
+        Ok(())
+    }
+
+    pub fn calc_id(&self) -> YHResult<YDigest64> {
+        let mut buf = BytesMut::new();
+        buf.put(&self.version.to_bytes()?[..]);
+        buf.put(&self.time.to_bytes()[..]);
+        buf.put_u32::<BigEndian>(self.nonce);
+        buf.put(self.method.to_bytes());
+        let tx_buf = self.tx.to_bytes()?;
+        buf.put_u32::<BigEndian>(tx_buf.len() as u32);
+        buf.put(tx_buf);
+        let fee_tx_buf = match self.fee_tx {
+            Some(ref fee_tx) => fee_tx.to_bytes()?,
+            None => Vec::new(),
+        };
+        buf.put_u32::<BigEndian>(fee_tx_buf.len() as u32);
+        buf.put(fee_tx_buf);
+        Ok(YSHA512::hash(&buf.to_vec()))
+    }
+
+    pub fn to_bytes(&self) -> YHResult<Vec<u8>> {
+        self.check()?;
+        let mut buf = BytesMut::new();
+        buf.put(self.id.to_bytes());
+        buf.put(&self.version.to_bytes()?[..]);
+        buf.put(&self.time.to_bytes()[..]);
+        buf.put_u32::<BigEndian>(self.nonce);
+        buf.put(self.method.to_bytes());
+        let tx_buf = self.tx.to_bytes()?;
+        buf.put_u32::<BigEndian>(tx_buf.len() as u32);
+        buf.put(tx_buf);
+        let fee_tx_buf = match self.fee_tx {
+            Some(ref fee_tx) => fee_tx.to_bytes()?,
+            None => Vec::new(),
+        };
+        buf.put_u32::<BigEndian>(fee_tx_buf.len() as u32);
+        buf.put(fee_tx_buf);
+        Ok(buf.to_vec())
+    }
+
+    pub fn from_bytes(buf: &[u8]) -> YHResult<YSendDataRes> {
+        if buf.len() < 92 {
+            return Err(YHErrorKind::InvalidLength.into());
+        }
+        let mut b = BytesMut::new();
+        b.extend_from_slice(buf);
+        let id = YDigest64::from_bytes(b.get(0..64).unwrap())?;
+        let version = YVersion::from_bytes(b.get(64..76).unwrap())?;
+        let time = YTime::from_bytes(b.get(76..84).unwrap())?;
+        let nonce = BigEndian::read_u32(b.get(84..88).unwrap());
+        let method = BigEndian::read_u32(b.get(88..92).unwrap()).into();
+        let mut i = 92;
+        let tx_size = BigEndian::read_u32(b.get(i..i+4).ok_or(YHErrorKind::InvalidLength)?) as usize;
+        i += 4;
+        let tx = YTransaction::from_bytes(b.get(i..i+tx_size).ok_or(YHErrorKind::InvalidLength)?)?;
+        i += tx_size;
+        let fee_tx_size = BigEndian::read_u32(b.get(i..i+4).ok_or(YHErrorKind::InvalidLength)?) as usize;
+        i += 4;
+        let fee_tx = if fee_tx_size == 0 {
+            None
+        } else {
+            Some(YTransaction::from_bytes(b.get(i..i+fee_tx_size).ok_or(YHErrorKind::InvalidLength)?)?)
+        };
+        i += fee_tx_size;
+        if i != buf.len() {
+            return Err(YHErrorKind::InvalidLength.into());
+        }
+        let res = YSendDataRes {
+            id: id,
+            version: version,
+            time: time,
+            nonce: nonce,
+            method: method,
+            tx: tx,
+            fee_tx: fee_tx,
+        };
+        res.check()?;
+        Ok(res)
+    }
+}
+
+#[derive(Clone, Eq, PartialEq, Debug, Default, Serialize, Deserialize)]
+pub struct YPushTxReq {
+    pub id: YDigest64,
+    pub version: YVersion,
+    pub time: YTime,
+    pub nonce: u32,
+    pub method: YRPCMethod,
+    pub tx: YTransaction,
+}
+
+impl YPushTxReq {
+    pub fn new(tx: &YTransaction) -> YHResult<YPushTxReq> {
+        let mut req = YPushTxReq {
+            id: YDigest64::default(),
+            version: default_version(),
+            time: YTime::now(),
+            nonce: YRandom::u32(),
+            method: YRPCMethod::PushTx,
+            tx: tx.clone(),
+        };
+        req.id = req.calc_id()?;
+        Ok(req)
+    }
+
+    pub fn check(&self) -> YHResult<()> {
+        if self.id != self.calc_id()? {
+            return Err(YHErrorKind::Lib(LibErrorKind::InvalidChecksum).into());
+        }
+        if self.version.major() > default_version().major() {
+            return Err(YHErrorKind::Lib(LibErrorKind::InvalidVersion(self.version.to_string())).into());
+        }
+        if self.time > YTime::now() {
+            return Err(YHErrorKind::Lib(LibErrorKind::InvalidTime).into());
+        }
+        if self.method != YRPCMethod::PushTx {
+            return Err(YHErrorKind::InvalidRPCMethod.into());
+        }
+        self.tx.check()?;
+        Ok(())
+    }
+
+    pub fn calc_id(&self) -> YHResult<YDigest64> {
+        let mut buf = BytesMut::new();
+        buf.put(&self.version.to_bytes()?[..]);
+        buf.put(&self.time.to_bytes()[..]);
+        buf.put_u32::<BigEndian>(self.nonce);
+        buf.put(self.method.to_bytes());
+        let tx_buf = self.tx.to_bytes()?;
+        buf.put_u32::<BigEndian>(tx_buf.len() as u32);
+        buf.put(tx_buf);
+        Ok(YSHA512::hash(&buf.to_vec()))
+    }
+
+    pub fn to_bytes(&self) -> YHResult<Vec<u8>> {
+        self.check()?;
+        let mut buf = BytesMut::new();
+        buf.put(self.id.to_bytes());
+        buf.put(&self.version.to_bytes()?[..]);
+        buf.put(&self.time.to_bytes()[..]);
+        buf.put_u32::<BigEndian>(self.nonce);
+        buf.put(self.method.to_bytes());
+        let tx_buf = self.tx.to_bytes()?;
+        buf.put_u32::<BigEndian>(tx_buf.len() as u32);
+        buf.put(tx_buf);
+        Ok(buf.to_vec())
+    }
+
+    pub fn from_bytes(buf: &[u8]) -> YHResult<YPushTxReq> {
+        if buf.len() < 92 {
+            return Err(YHErrorKind::InvalidLength.into());
+        }
+        let mut b = BytesMut::new();
+        b.extend_from_slice(buf);
+        let id = YDigest64::from_bytes(b.get(0..64).unwrap())?;
+        let version = YVersion::from_bytes(b.get(64..76).unwrap())?;
+        let time = YTime::from_bytes(b.get(76..84).unwrap())?;
+        let nonce = BigEndian::read_u32(b.get(84..88).unwrap());
+        let method = BigEndian::read_u32(b.get(88..92).unwrap()).into();
+        let mut i = 92;
+        let tx_size = BigEndian::read_u32(b.get(i..i+4).ok_or(YHErrorKind::InvalidLength)?) as usize;
+        i += 4;
+        let tx = YTransaction::from_bytes(b.get(i..i+tx_size).ok_or(YHErrorKind::InvalidLength)?)?;
+        i += tx_size;
+        if i != buf.len() {
+            return Err(YHErrorKind::InvalidLength.into());
+        }
+        let req = YPushTxReq {
+            id: id,
+            version: version,
+            time: time,
+            nonce: nonce,
+            method: method,
+            tx: tx,
+        };
+        req.check()?;
+        Ok(req)
+    }
+}
+
+#[derive(Clone, Eq, PartialEq, Debug, Default, Serialize, Deserialize)]
+pub struct YPushTxRes {
+    pub id: YDigest64,
+    pub version: YVersion,
+    pub time: YTime,
+    pub nonce: u32,
+    pub method: YRPCMethod,
+    pub tx_id: YDigest64,
+}
+
+impl YPushTxRes {
+    pub fn new(tx_id: YDigest64) -> YHResult<YPushTxRes> {
+        let mut res = YPushTxRes {
+            id: YDigest64::default(),
+            version: default_version(),
+            time: YTime::now(),
+            nonce: YRandom::u32(),
+            method: YRPCMethod::PushTx,
+            tx_id: tx_id,
+        };
+        res.id = res.calc_id()?;
+        Ok(res)
+    }
+
+    pub fn check(&self) -> YHResult<()> {
+        if self.id != self.calc_id()? {
+            return Err(YHErrorKind::Lib(LibErrorKind::InvalidChecksum).into());
+        }
+        if self.version.major() > default_version().major() {
+            return Err(YHErrorKind::Lib(LibErrorKind::InvalidVersion(self.version.to_string())).into());
+        }
+        if self.time > YTime::now() {
+            return Err(YHErrorKind::Lib(LibErrorKind::InvalidTime).into());
+        }
+        if self.method != YRPCMethod::PushTx {
+            return Err(YHErrorKind::InvalidRPCMethod.into());
+        }
+        Ok(())
+    }
+
+    pub fn calc_id(&self) -> YHResult<YDigest64> {
+        let mut buf = BytesMut::new();
+        buf.put(&self.version.to_bytes()?[..]);
+        buf.put(&self.time.to_bytes()[..]);
+        buf.put_u32::<BigEndian>(self.nonce);
+        buf.put(self.method.to_bytes());
+        buf.put(self.tx_id.to_bytes());
+        Ok(YSHA512::hash(&buf.to_vec()))
+    }
+
+    pub fn to_bytes(&self) -> YHResult<Vec<u8>> {
+        self.check()?;
+        let mut buf = BytesMut::new();
+        buf.put(self.id.to_bytes());
+        buf.put(&self.version.to_bytes()?[..]);
+        buf.put(&self.time.to_bytes()[..]);
+        buf.put_u32::<BigEndian>(self.nonce);
+        buf.put(self.method.to_bytes());
+        buf.put(self.tx_id.to_bytes());
+        Ok(buf.to_vec())
+    }
+
+    pub fn from_bytes(buf: &[u8]) -> YHResult<YPushTxRes> {
+        if buf.len() < 92 {
+            return Err(YHErrorKind::InvalidLength.into());
+        }
+        let mut b = BytesMut::new();
+        b.extend_from_slice(buf);
+        let id = YDigest64::from_bytes(b.get(0..64).unwrap())?;
+        let version = YVersion::from_bytes(b.get(64..76).unwrap())?;
+        let time = YTime::from_bytes(b.get(76..84).unwrap())?;
+        let nonce = BigEndian::read_u32(b.get(84..88).unwrap());
+        let method = BigEndian::read_u32(b.get(88..92).unwrap()).into();
+        let mut i = 92;
+        let tx_id = YDigest64::from_bytes(b.get(i..i+64).ok_or(YHErrorKind::InvalidLength)?)?;
+        i += 64;
+        if i != buf.len() {
+            return Err(YHErrorKind::InvalidLength.into());
+        }
+        let res = YPushTxRes {
+            id: id,
+            version: version,
+            time: time,
+            nonce: nonce,
+            method: method,
+            tx_id: tx_id,
+        };
+        res.check()?;
+        Ok(res)
+    }
+}
+
+#[derive(Clone, Eq, PartialEq, Debug, Default, Serialize, Deserialize)]
+pub struct YListTxsReq {
+    pub id: YDigest64,
+    pub version: YVersion,
+    pub time: YTime,
+    pub nonce: u32,
+    pub method: YRPCMethod,
+    pub wallet: String,
+    pub cursor: Vec<u8>,
+    pub limit: u32,
+}
+
+impl YListTxsReq {
+    pub fn new(wallet: &str, cursor: &[u8], limit: u32) -> YHResult<YListTxsReq> {
+        let mut req = YListTxsReq {
+            id: YDigest64::default(),
+            version: default_version(),
+            time: YTime::now(),
+            nonce: YRandom::u32(),
+            method: YRPCMethod::ListTxs,
+            wallet: String::from(wallet),
+            cursor: cursor.to_vec(),
+            limit: limit,
+        };
+        req.id = req.calc_id()?;
+        Ok(req)
+    }
+
+    pub fn check(&self) -> YHResult<()> {
+        if self.id != self.calc_id()? {
+            return Err(YHErrorKind::Lib(LibErrorKind::InvalidChecksum).into());
+        }
+        if self.version.major() > default_version().major() {
+            return Err(YHErrorKind::Lib(LibErrorKind::InvalidVersion(self.version.to_string())).into());
+        }
+        if self.time > YTime::now() {
+            return Err(YHErrorKind::Lib(LibErrorKind::InvalidTime).into());
+        }
+        if self.method != YRPCMethod::ListTxs {
+            return Err(YHErrorKind::InvalidRPCMethod.into());
+        }
+        Ok(())
+    }
+
+    pub fn calc_id(&self) -> YHResult<YDigest64> {
+        let mut buf = BytesMut::new();
+        buf.put(&self.version.to_bytes()?[..]);
+        buf.put(&self.time.to_bytes()[..]);
+        buf.put_u32::<BigEndian>(self.nonce);
+        buf.put(self.method.to_bytes());
+        let wallet_buf = self.wallet.as_bytes();
+        buf.put_u32::<BigEndian>(wallet_buf.len() as u32);
+        buf.put(wallet_buf);
+        buf.put_u32::<BigEndian>(self.cursor.len() as u32);
+        buf.put(&self.cursor[..]);
+        buf.put_u32::<BigEndian>(self.limit);
+        Ok(YSHA512::hash(&buf.to_vec()))
+    }
+
+    pub fn to_bytes(&self) -> YHResult<Vec<u8>> {
+        self.check()?;
+        let mut buf = BytesMut::new();
+        buf.put(self.id.to_bytes());
+        buf.put(&self.version.to_bytes()?[..]);
+        buf.put(&self.time.to_bytes()[..]);
+        buf.put_u32::<BigEndian>(self.nonce);
+        buf.put(self.method.to_bytes());
+        let wallet_buf = self.wallet.as_bytes();
+        buf.put_u32::<BigEndian>(wallet_buf.len() as u32);
+        buf.put(wallet_buf);
+        buf.put_u32::<BigEndian>(self.cursor.len() as u32);
+        buf.put(&self.cursor[..]);
+        buf.put_u32::<BigEndian>(self.limit);
+        Ok(buf.to_vec())
+    }
+
+    pub fn from_bytes(buf: &[u8]) -> YHResult<YListTxsReq> {
+        if buf.len() < 92 {
+            return Err(YHErrorKind::InvalidLength.into());
+        }
+        let mut b = BytesMut::new();
+        b.extend_from_slice(buf);
+        let id = YDigest64::from_bytes(b.get(0..64).unwrap())?;
+        let version = YVersion::from_bytes(b.get(64..76).unwrap())?;
+        let time = YTime::from_bytes(b.get(76..84).unwrap())?;
+        let nonce = BigEndian::read_u32(b.get(84..88).unwrap());
+        let method = BigEndian::read_u32(b.get(88..92).unwrap()).into();
+        let mut i = 92;
+        let wallet_size = BigEndian::read_u32(b.get(i..i+4).ok_or(YHErrorKind::InvalidLength)?) as usize;
+        i += 4;
+        let wallet = String::from_utf8(b.get(i..i+wallet_size).ok_or(YHErrorKind::InvalidLength)?.to_vec())?;
+        i += wallet_size;
+        let cursor_size = BigEndian::read_u32(b.get(i..i+4).ok_or(YHErrorKind::InvalidLength)?) as usize;
+        i += 4;
+        let cursor = b.get(i..i+cursor_size).ok_or(YHErrorKind::InvalidLength)?.to_vec();
+        i += cursor_size;
+        let limit = BigEndian::read_u32(b.get(i..i+4).ok_or(YHErrorKind::InvalidLength)?);
+        i += 4;
+        if i != buf.len() {
+            return Err(YHErrorKind::InvalidLength.into());
+        }
+        let req = YListTxsReq {
+            id: id,
+            version: version,
+            time: time,
+            nonce: nonce,
+            method: method,
+            wallet: wallet,
+            cursor: cursor,
+            limit: limit,
+        };
+        req.check()?;
+        Ok(req)
+    }
+}
+
+#[derive(Clone, Eq, PartialEq, Debug, Default, Serialize, Deserialize)]
+pub struct YListTxsRes {
+    pub id: YDigest64,
+    pub version: YVersion,
+    pub time: YTime,
+    pub nonce: u32,
+    pub method: YRPCMethod,
+    pub count: u32,
+    pub txs: Vec<YTransaction>,
+    pub next: Vec<u8>,
+}
+
+impl YListTxsRes {
+    pub fn new(txs: &Vec<YTransaction>, next: &[u8]) -> YHResult<YListTxsRes> {
+        let mut res = YListTxsRes {
+            id: YDigest64::default(),
+            version: default_version(),
+            time: YTime::now(),
+            nonce: YRandom::u32(),
+            method: YRPCMethod::ListTxs,
+            count: txs.len() as u32,
+            txs: txs.clone(),
+            next: next.to_vec(),
+        };
+        res.id = res.calc_id()?;
+        Ok(res)
+    }
+
+    pub fn check(&self) -> YHResult<()> {
+        if self.id != self.calc_id()? {
+            return Err(YHErrorKind::Lib(LibErrorKind::InvalidChecksum).into());
+        }
+        if self.version.major() > default_version().major() {
+            return Err(YHErrorKind::Lib(LibErrorKind::InvalidVersion(self.version.to_string())).into());
+        }
+        if self.time > YTime::now() {
+            return Err(YHErrorKind::Lib(LibErrorKind::InvalidTime).into());
+        }
+        if self.method != YRPCMethod::ListTxs {
+            return Err(YHErrorKind::InvalidRPCMethod.into());
+        }
+        if self.txs.len() != self.count as usize {
+            return Err(YHErrorKind::InvalidLength.into());
+        }
+        for tx in self.txs.iter() {
+            tx.check()?;
+        }
+        Ok(())
+    }
+
+    pub fn calc_id(&self) -> YHResult<YDigest64> {
+        let mut buf = BytesMut::new();
+        buf.put(&self.version.to_bytes()?[..]);
+        buf.put(&self.time.to_bytes()[..]);
+        buf.put_u32::<BigEndian>(self.nonce);
+        buf.put(self.method.to_bytes());
+        buf.put_u32::<BigEndian>(self.count);
+        for tx in self.txs.iter() {
+            let tx_buf = tx.to_bytes()?;
+            buf.put_u32::<BigEndian>(tx_buf.len() as u32);
+            buf.put(tx_buf);
+        }
+        buf.put_u32::<BigEndian>(self.next.len() as u32);
+        buf.put(&self.next[..]);
+        Ok(YSHA512::hash(&buf.to_vec()))
+    }
+
+    pub fn to_bytes(&self) -> YHResult<Vec<u8>> {
+        self.check()?;
+        let mut buf = BytesMut::new();
+        buf.put(self.id.to_bytes());
+        buf.put(&self.version.to_bytes()?[..]);
+        buf.put(&self.time.to_bytes()[..]);
+        buf.put_u32::<BigEndian>(self.nonce);
+        buf.put(self.method.to_bytes());
+        buf.put_u32::<BigEndian>(self.count);
+        for tx in self.txs.iter() {
+            let tx_buf = tx.to_bytes()?;
+            buf.put_u32::<BigEndian>(tx_buf.len() as u32);
+            buf.put(tx_buf);
+        }
+        buf.put_u32::<BigEndian>(self.next.len() as u32);
+        buf.put(&self.next[..]);
+        Ok(buf.to_vec())
+    }
+
+    pub fn from_bytes(buf: &[u8]) -> YHResult<YListTxsRes> {
+        if buf.len() < 92 {
+            return Err(YHErrorKind::InvalidLength.into());
+        }
+        let mut b = BytesMut::new();
+        b.extend_from_slice(buf);
+        let id = YDigest64::from_bytes(b.get(0..64).unwrap())?;
+        let version = YVersion::from_bytes(b.get(64..76).unwrap())?;
+        let time = YTime::from_bytes(b.get(76..84).unwrap())?;
+        let nonce = BigEndian::read_u32(b.get(84..88).unwrap());
+        let method = BigEndian::read_u32(b.get(88..92).unwrap()).into();
+        let mut i = 92;
+        let count = BigEndian::read_u32(b.get(i..i+4).ok_or(YHErrorKind::InvalidLength)?);
+        i += 4;
+        let mut txs = Vec::new();
+        for _ in 0..count {
+            let size = BigEndian::read_u32(b.get(i..i+4).ok_or(YHErrorKind::InvalidLength)?) as usize;
+            i += 4;
+            txs.push(YTransaction::from_bytes(b.get(i..i+size).ok_or(YHErrorKind::InvalidLength)?)?);
+            i += size;
+        }
+        let next_size = BigEndian::read_u32(b.get(i..i+4).ok_or(YHErrorKind::InvalidLength)?) as usize;
+        i += 4;
+        let next = b.get(i..i+next_size).ok_or(YHErrorKind::InvalidLength)?.to_vec();
+        i += next_size;
+        if i != buf.len() {
+            return Err(YHErrorKind::InvalidLength.into());
+        }
+        let res = YListTxsRes {
+            id: id,
+            version: version,
+            time: time,
+            nonce: nonce,
+            method: method,
+            count: count,
+            txs: txs,
+            next: next,
+        };
+        res.check()?;
+        Ok(res)
+    }
+}
+
+#[derive(Clone, Eq, PartialEq, Debug, Default, Serialize, Deserialize)]
+pub struct YSendBatchReq {
+    pub id: YDigest64,
+    pub version: YVersion,
+    pub time: YTime,
+    pub nonce: u32,
+    pub method: YRPCMethod,
+    pub wallet: String,
+    pub count: u32,
+    pub payments: Vec<YPayment>,
+    pub keep_data: bool,
+    pub fee: YAmount,
+    pub max_fee: YAmount,
+}
+
+impl YSendBatchReq {
+    pub fn new(wallet: &str, payments: &Vec<YPayment>, keep_data: bool, fee: &YAmount, max_fee: &YAmount) -> YHResult<YSendBatchReq> {
+        let mut req = YSendBatchReq {
+            id: YDigest64::default(),
+            version: default_version(),
+            time: YTime::now(),
+            nonce: YRandom::u32(),
+            method: YRPCMethod::SendBatch,
+            wallet: String::from(wallet),
+            count: payments.len() as u32,
+            payments: payments.clone(),
+            keep_data: keep_data,
+            fee: fee.clone(),
+            max_fee: max_fee.clone(),
+        };
+        req.id = req.calc_id()?;
+        Ok(req)
+    }
+
+    pub fn check(&self) -> YHResult<()> {
+        if self.id != self.calc_id()? {
+            return Err(YHErrorKind::Lib(LibErrorKind::InvalidChecksum).into());
+        }
+        if self.version.major() > default_version().major() {
+            return Err(YHErrorKind::Lib(LibErrorKind::InvalidVersion(self.version.to_string())).into());
+        }
+        if self.time > YTime::now() {
+            return Err(YHErrorKind::Lib(LibErrorKind::InvalidTime).into());
+        }
+        if self.method != YRPCMethod::SendBatch {
+            return Err(YHErrorKind::InvalidRPCMethod.into());
+        }
+        if self.payments.len() != self.count as usize {
+            return Err(YHErrorKind::InvalidLength.into());
+        }
+        for payment in self.payments.iter() {
+            payment.check()?;
+        }
+        Ok(())
+    }
+
+    pub fn calc_id(&self) -> YHResult<YDigest64> {
+        let mut buf = BytesMut::new();
+        buf.put(&self.version.to_bytes()?[..]);
+        buf.put(&self.time.to_bytes()[..]);
+        buf.put_u32::<BigEndian>(self.nonce);
+        buf.put(self.method.to_bytes());
+        let wallet_buf = self.wallet.as_bytes();
+        buf.put_u32::<BigEndian>(wallet_buf.len() as u32);
+        buf.put(wallet_buf);
+        buf.put_u32::<BigEndian>(self.count);
+        for payment in self.payments.iter() {
+            let payment_buf = payment.to_bytes()?;
+            buf.put_u32::<BigEndian>(payment_buf.len() as u32);
+            buf.put(payment_buf);
+        }
+        buf.put_u32::<BigEndian>(self.keep_data as u32);
+        let fee_buf = self.fee.to_bytes();
+        buf.put_u32::<BigEndian>(fee_buf.len() as u32);
+        buf.put(fee_buf);
+        let max_fee_buf = self.max_fee.to_bytes();
+        buf.put_u32::<BigEndian>(max_fee_buf.len() as u32);
+        buf.put(max_fee_buf);
+        Ok(YSHA512::hash(&buf.to_vec()))
+    }
+
+    pub fn to_bytes(&self) -> YHResult<Vec<u8>> {
+        self.check()?;
+        let mut buf = BytesMut::new();
+        buf.put(self.id.to_bytes());
+        buf.put(&self.version.to_bytes()?[..]);
+        buf.put(&self.time.to_bytes()[..]);
+        buf.put_u32::<BigEndian>(self.nonce);
+        buf.put(self.method.to_bytes());
+        let wallet_buf = self.wallet.as_bytes();
+        buf.put_u32::<BigEndian>(wallet_buf.len() as u32);
+        buf.put(wallet_buf);
+        buf.put_u32::<BigEndian>(self.count);
+        for payment in self.payments.iter() {
+            let payment_buf = payment.to_bytes()?;
+            buf.put_u32::<BigEndian>(payment_buf.len() as u32);
+            buf.put(payment_buf);
+        }
+        buf.put_u32::<BigEndian>(self.keep_data as u32);
+        let fee_buf = self.fee.to_bytes();
+        buf.put_u32::<BigEndian>(fee_buf.len() as u32);
+        buf.put(fee_buf);
+        let max_fee_buf = self.max_fee.to_bytes();
+        buf.put_u32::<BigEndian>(max_fee_buf.len() as u32);
+        buf.put(max_fee_buf);
+        Ok(buf.to_vec())
+    }
+
+    pub fn from_bytes(buf: &[u8]) -> YHResult<YSendBatchReq> {
+        if buf.len() < 92 {
+            return Err(YHErrorKind::InvalidLength.into());
+        }
+        let mut b = BytesMut::new();
+        b.extend_from_slice(buf);
+        let id = YDigest64::from_bytes(b.get(0..64).unwrap())?;
+        let version = YVersion::from_bytes(b.get(64..76).unwrap())?;
+        let time = YTime::from_bytes(b.get(76..84).unwrap())?;
+        let nonce = BigEndian::read_u32(b.get(84..88).unwrap());
+        let method = BigEndian::read_u32(b.get(88..92).unwrap()).into();
+        let mut i = 92;
+        let wallet_size = BigEndian::read_u32(b.get(i..i+4).ok_or(YHErrorKind::InvalidLength)?) as usize;
+        i += 4;
+        let wallet = String::from_utf8(b.get(i..i+wallet_size).ok_or(YHErrorKind::InvalidLength)?.to_vec())?;
+        i += wallet_size;
+        let count = BigEndian::read_u32(b.get(i..i+4).ok_or(YHErrorKind::InvalidLength)?);
+        i += 4;
+        let mut payments = Vec::new();
+        for _ in 0..count {
+            let size = BigEndian::read_u32(b.get(i..i+4).ok_or(YHErrorKind::InvalidLength)?) as usize;
+            i += 4;
+            payments.push(YPayment::from_bytes(b.get(i..i+size).ok_or(YHErrorKind::InvalidLength)?)?);
+            i += size;
+        }
+        let keep_data = match BigEndian::read_u32(b.get(i..i+4).ok_or(YHErrorKind::InvalidLength)?) {
+            0 => false,
+            1 => true,
+            _ => { return Err(YHErrorKind::InvalidValue.into()); },
+        };
+        i += 4;
+        let fee_size = BigEndian::read_u32(b.get(i..i+4).ok_or(YHErrorKind::InvalidLength)?) as usize;
+        i += 4;
+        let fee = YAmount::from_bytes(b.get(i..i+fee_size).ok_or(YHErrorKind::InvalidLength)?);
+        i += fee_size;
+        let max_fee_size = BigEndian::read_u32(b.get(i..i+4).ok_or(YHErrorKind::InvalidLength)?) as usize;
+        i += 4;
+        let max_fee = YAmount::from_bytes(b.get(i..i+max_fee_size).ok_or(YHErrorKind::InvalidLength)?);
+        i += max_fee_size;
+        if i != buf.len() {
+            return Err(YHErrorKind::InvalidLength.into());
+        }
+        let req = YSendBatchReq {
+            id: id,
+            version: version,
+            time: time,
+            nonce: nonce,
+            method: method,
+            wallet: wallet,
+            count: count,
+            payments: payments,
+            keep_data: keep_data,
+            fee: fee,
+            max_fee: max_fee,
+        };
+        req.check()?;
+        Ok(req)
+    }
+}
+
+#[derive(Clone, Eq, PartialEq, Debug, Default, Serialize, Deserialize)]
+pub struct YSendBatchRes {
+    pub id: YDigest64,
+    pub version: YVersion,
+    pub time: YTime,
+    pub nonce: u32,
+    pub method: YRPCMethod,
+    pub count: u32,
+    pub txs: Vec<YTransaction>,
+    pub fee_tx: Option<YTransaction>,
+}
+
+impl YSendBatchRes {
+    // a single fee transaction pays for every payment of the batch
+    pub fn new(txs: &Vec<YTransaction>, fee_tx: &Option<YTransaction>) -> YHResult<YSendBatchRes> {
+        let mut res = YSendBatchRes {
+            id: YDigest64::default(),
+            version: default_version(),
+            time: YTime::now(),
+            nonce: YRandom::u32(),
+            method: YRPCMethod::SendBatch,
+            count: txs.len() as u32,
+            txs: txs.clone(),
+            fee_tx: fee_tx.clone(),
+        };
+        res.id = res.calc_id()?;
+        Ok(res)
+    }
+
+    pub fn check(&self) -> YHResult<()> {
+        if self.id != self.calc_id()? {
+            return Err(YHErrorKind::Lib(LibErrorKind::InvalidChecksum).into());
+        }
+        if self.version.major() > default_version().major() {
+            return Err(YHErrorKind::Lib(LibErrorKind::InvalidVersion(self.version.to_string())).into());
+        }
+        if self.time > YTime::now() {
+            return Err(YHErrorKind::Lib(LibErrorKind::InvalidTime).into());
+        }
+        if self.method != YRPCMethod::SendBatch {
+            return Err(YHErrorKind::InvalidRPCMethod.into());
+        }
+        if self.txs.len() != self.count as usize {
+            return Err(YHErrorKind::InvalidLength.into());
+        }
+        for tx in self.txs.iter() {
+            tx.check()?;
+        }
+        if let Some(ref fee_tx) = self.fee_tx {
+            fee_tx.check()?;
+        }
+        Ok(())
+    }
+
+    pub fn calc_id(&self) -> YHResult<YDigest64> {
+        let mut buf = BytesMut::new();
+        buf.put(&self.version.to_bytes()?[..]);
+        buf.put(&self.time.to_bytes()[..]);
+        buf.put_u32::<BigEndian>(self.nonce);
+        buf.put(self.method.to_bytes());
+        buf.put_u32::<BigEndian>(self.count);
+        for tx in self.txs.iter() {
+            let tx_buf = tx.to_bytes()?;
+            buf.put_u32::<BigEndian>(tx_buf.len() as u32);
+            buf.put(tx_buf);
+        }
+        let fee_tx_buf = match self.fee_tx {
+            Some(ref fee_tx) => fee_tx.to_bytes()?,
+            None => Vec::new(),
+        };
+        buf.put_u32::<BigEndian>(fee_tx_buf.len() as u32);
+        buf.put(fee_tx_buf);
+        Ok(YSHA512::hash(&buf.to_vec()))
+    }
+
+    pub fn to_bytes(&self) -> YHResult<Vec<u8>> {
+        self.check()?;
+        let mut buf = BytesMut::new();
+        buf.put(self.id.to_bytes());
+        buf.put(&self.version.to_bytes()?[..]);
+        buf.put(&self.time.to_bytes()[..]);
+        buf.put_u32::<BigEndian>(self.nonce);
+        buf.put(self.method.to_bytes());
+        buf.put_u32::<BigEndian>(self.count);
+        for tx in self.txs.iter() {
+            let tx_buf = tx.to_bytes()?;
+            buf.put_u32::<BigEndian>(tx_buf.len() as u32);
+            buf.put(tx_buf);
+        }
+        let fee_tx_buf = match self.fee_tx {
+            Some(ref fee_tx) => fee_tx.to_bytes()?,
+            None => Vec::new(),
+        };
+        buf.put_u32::<BigEndian>(fee_tx_buf.len() as u32);
+        buf.put(fee_tx_buf);
+        Ok(buf.to_vec())
+    }
+
+    pub fn from_bytes(buf: &[u8]) -> YHResult<YSendBatchRes> {
+        if buf.len() < 92 {
+            return Err(YHErrorKind::InvalidLength.into());
+        }
+        let mut b = BytesMut::new();
+        b.extend_from_slice(buf);
+        let id = YDigest64::from_bytes(b.get(0..64).unwrap())?;
+        let version = YVersion::from_bytes(b.get(64..76).unwrap())?;
+        let time = YTime::from_bytes(b.get(76..84).unwrap())?;
+        let nonce = BigEndian::read_u32(b.get(84..88).unwrap());
+        let method = BigEndian::read_u32(b.get(88..92).unwrap()).into();
+        let mut i = 92;
+        let count = BigEndian::read_u32(b.get(i..i+4).ok_or(YHErrorKind::InvalidLength)?);
+        i += 4;
+        let mut txs = Vec::new();
+        for _ in 0..count {
+            let size = BigEndian::read_u32(b.get(i..i+4).ok_or(YHErrorKind::InvalidLength)?) as usize;
+            i += 4;
+            txs.push(YTransaction::from_bytes(b.get(i..i+size).ok_or(YHErrorKind::InvalidLength)?)?);
+            i += size;
+        }
+        let fee_tx_size = BigEndian::read_u32(b.get(i..i+4).ok_or(YHErrorKind::InvalidLength)?) as usize;
+        i += 4;
+        let fee_tx = if fee_tx_size == 0 {
+            None
+        } else {
+            Some(YTransaction::from_bytes(b.get(i..i+fee_tx_size).ok_or(YHErrorKind::InvalidLength)?)?)
+        };
+        i += fee_tx_size;
+        if i != buf.len() {
+            return Err(YHErrorKind::InvalidLength.into());
+        }
+        let res = YSendBatchRes {
+            id: id,
+            version: version,
+            time: time,
+            nonce: nonce,
+            method: method,
+            count: count,
+            txs: txs,
+            fee_tx: fee_tx,
+        };
+        res.check()?;
+        Ok(res)
+    }
+}
+
+#[derive(Clone, Eq, PartialEq, Debug, Default, Serialize, Deserialize)]
+pub struct YCreateUnsignedTxReq {
+    pub id: YDigest64,
+    pub version: YVersion,
+    pub time: YTime,
+    pub nonce: u32,
+    pub method: YRPCMethod,
+    pub wallet: String,
+    pub to: YPublicKey,
+    pub amount: YAmount,
+    pub keep_data: bool,
+    pub selection: YCoinSelection,
+    pub fee: YAmount,
+    pub max_fee: YAmount,
+}
+
+impl YCreateUnsignedTxReq {
+    pub fn new(wallet: &str, to: YPublicKey, amount: &YAmount, keep_data: bool, selection: YCoinSelection, fee: &YAmount, max_fee: &YAmount) -> YHResult<YCreateUnsignedTxReq> {
+        let mut req = YCreateUnsignedTxReq {
+            id: YDigest64::default(),
+            version: default_version(),
+            time: YTime::now(),
+            nonce: YRandom::u32(),
+            method: YRPCMethod::CreateUnsignedTx,
+            wallet: String::from(wallet),
+            to: to,
+            amount: amount.clone(),
+            keep_data: keep_data,
+            selection: selection,
+            fee: fee.clone(),
+            max_fee: max_fee.clone(),
+        };
+        req.id = req.calc_id()?;
+        Ok(req)
+    }
+
+    pub fn check(&self) -> YHResult<()> {
+        if self.id != self.calc_id()? {
+            return Err(YHErrorKind::Lib(LibErrorKind::InvalidChecksum).into());
+        }
+        if self.version.major() > default_version().major() {
+            return Err(YHErrorKind::Lib(LibErrorKind::InvalidVersion(self.version.to_string())).into());
+        }
+        if self.time > YTime::now() {
+            return Err(YHErrorKind::Lib(LibErrorKind::InvalidTime).into());
+        }
+        if self.method != YRPCMethod::CreateUnsignedTx {
+            return Err(YHErrorKind::InvalidRPCMethod.into());
+        }
+        Ok(())
+    }
+
+    pub fn calc_id(&self) -> YHResult<YDigest64> {
+        let mut buf = BytesMut::new();
+        buf.put(&self.version.to_bytes()?[..]);
+        buf.put(&self.time.to_bytes()[..]);
+        buf.put_u32::<BigEndian>(self.nonce);
+        buf.put(self.method.to_bytes());
+        let wallet_buf = self.wallet.as_bytes();
+        buf.put_u32::<BigEndian>(wallet_buf.len() as u32);
+        buf.put(wallet_buf);
+        buf.put(self.to.to_bytes());
+        let amount_buf = self.amount.to_bytes();
+        buf.put_u32::<BigEndian>(amount_buf.len() as u32);
+        buf.put(amount_buf);
+        buf.put_u32::<BigEndian>(self.keep_data as u32);
+        buf.put_u32::<BigEndian>(self.selection as u32);
+        let fee_buf = self.fee.to_bytes();
+        buf.put_u32::<BigEndian>(fee_buf.len() as u32);
+        buf.put(fee_buf);
+        let max_fee_buf = self.max_fee.to_bytes();
+        buf.put_u32::<BigEndian>(max_fee_buf.len() as u32);
+        buf.put(max_fee_buf);
+        Ok(YSHA512::hash(&buf.to_vec()))
+    }
+
+    pub fn to_bytes(&self) -> YHResult<Vec<u8>> {
+        self.check()?;
+        let mut buf = BytesMut::new();
+        buf.put(self.id.to_bytes());
+        buf.put(&self.version.to_bytes()?[..]);
+        buf.put(&self.time.to_bytes()[..]);
+        buf.put_u32::<BigEndian>(self.nonce);
+        buf.put(self.method.to_bytes());
+        let wallet_buf = self.wallet.as_bytes();
+        buf.put_u32::<BigEndian>(wallet_buf.len() as u32);
+        buf.put(wallet_buf);
+        buf.put(self.to.to_bytes());
+        let amount_buf = self.amount.to_bytes();
+        buf.put_u32::<BigEndian>(amount_buf.len() as u32);
+        buf.put(amount_buf);
+        buf.put_u32::<BigEndian>(self.keep_data as u32);
+        buf.put_u32::<BigEndian>(self.selection as u32);
+        let fee_buf = self.fee.to_bytes();
+        buf.put_u32::<BigEndian>(fee_buf.len() as u32);
+        buf.put(fee_buf);
+        let max_fee_buf = self.max_fee.to_bytes();
+        buf.put_u32::<BigEndian>(max_fee_buf.len() as u32);
+        buf.put(max_fee_buf);
+        Ok(buf.to_vec())
+    }
+
+    pub fn from_bytes(buf: &[u8]) -> YHResult<YCreateUnsignedTxReq> {
+        if buf.len() < 92 {
+            return Err(YHErrorKind::InvalidLength.into());
+        }
+        let mut b = BytesMut::new();
+        b.extend_from_slice(buf);
+        let id = YDigest64::from_bytes(b.get(0..64).unwrap())?;
+        let version = YVersion::from_bytes(b.get(64..76).unwrap())?;
+        let time = YTime::from_bytes(b.get(76..84).unwrap())?;
+        let nonce = BigEndian::read_u32(b.get(84..88).unwrap());
+        let method = BigEndian::read_u32(b.get(88..92).unwrap()).into();
+        let mut i = 92;
+        let wallet_size = BigEndian::read_u32(b.get(i..i+4).ok_or(YHErrorKind::InvalidLength)?) as usize;
+        i += 4;
+        let wallet = String::from_utf8(b.get(i..i+wallet_size).ok_or(YHErrorKind::InvalidLength)?.to_vec())?;
+        i += wallet_size;
+        let to = YPublicKey::from_bytes(b.get(i..i+64).ok_or(YHErrorKind::InvalidLength)?)?;
+        i += 64;
+        let amount_size = BigEndian::read_u32(b.get(i..i+4).ok_or(YHErrorKind::InvalidLength)?) as usize;
+        i += 4;
+        let amount = YAmount::from_bytes(b.get(i..i+amount_size).ok_or(YHErrorKind::InvalidLength)?);
+        i += amount_size;
+        let keep_data = match BigEndian::read_u32(b.get(i..i+4).ok_or(YHErrorKind::InvalidLength)?) {
+            0 => false,
+            1 => true,
+            _ => { return Err(YHErrorKind::InvalidValue.into()); },
+        };
+        i += 4;
+        let selection = YCoinSelection::from_u32(BigEndian::read_u32(b.get(i..i+4).ok_or(YHErrorKind::InvalidLength)?))?;
+        i += 4;
+        let fee_size = BigEndian::read_u32(b.get(i..i+4).ok_or(YHErrorKind::InvalidLength)?) as usize;
+        i += 4;
+        let fee = YAmount::from_bytes(b.get(i..i+fee_size).ok_or(YHErrorKind::InvalidLength)?);
+        i += fee_size;
+        let max_fee_size = BigEndian::read_u32(b.get(i..i+4).ok_or(YHErrorKind::InvalidLength)?) as usize;
+        i += 4;
+        let max_fee = YAmount::from_bytes(b.get(i..i+max_fee_size).ok_or(YHErrorKind::InvalidLength)?);
+        i += max_fee_size;
+        if i != buf.len() {
+            return Err(YHErrorKind::InvalidLength.into());
+        }
+        let req = YCreateUnsignedTxReq {
+            id: id,
+            version: version,
+            time: time,
+            nonce: nonce,
+            method: method,
+            wallet: wallet,
+            to: to,
+            amount: amount,
+            keep_data: keep_data,
+            selection: selection,
+            fee: fee,
+            max_fee: max_fee,
+        };
+        req.check()?;
+        Ok(req)
+    }
+}
+
+#[derive(Clone, Eq, PartialEq, Debug, Default, Serialize, Deserialize)]
+pub struct YCreateUnsignedTxRes {
+    pub id: YDigest64,
+    pub version: YVersion,
+    pub time: YTime,
+    pub nonce: u32,
+    pub method: YRPCMethod,
+    pub raw: Vec<u8>,
+}
+
+impl YCreateUnsignedTxRes {
+    pub fn new(raw: &[u8]) -> YHResult<YCreateUnsignedTxRes> {
+        let mut res = YCreateUnsignedTxRes {
+            id: YDigest64::default(),
+            version: default_version(),
+            time: YTime::now(),
+            nonce: YRandom::u32(),
+            method: YRPCMethod::CreateUnsignedTx,
+            raw: raw.to_vec(),
+        };
+        res.id = res.calc_id()?;
+        Ok(res)
+    }
+
+    pub fn check(&self) -> YHResult<()> {
+        if self.id != self.calc_id()? {
+            return Err(YHErrorKind::Lib(LibErrorKind::InvalidChecksum).into());
+        }
+        if self.version.major() > default_version().major() {
+            return Err(YHErrorKind::Lib(LibErrorKind::InvalidVersion(self.version.to_string())).into());
+        }
+        if self.time > YTime::now() {
+            return Err(YHErrorKind::Lib(LibErrorKind::InvalidTime).into());
+        }
+        if self.method != YRPCMethod::CreateUnsignedTx {
+            return Err(YHErrorKind::InvalidRPCMethod.into());
+        }
+        Ok(())
+    }
+
+    pub fn calc_id(&self) -> YHResult<YDigest64> {
+        let mut buf = BytesMut::new();
+        buf.put(&self.version.to_bytes()?[..]);
+        buf.put(&self.time.to_bytes()[..]);
+        buf.put_u32::<BigEndian>(self.nonce);
+        buf.put(self.method.to_bytes());
+        buf.put_u32::<BigEndian>(self.raw.len() as u32);
+        buf.put(&self.raw[..]);
+        Ok(YSHA512::hash(&buf.to_vec()))
+    }
+
+    pub fn to_bytes(&self) -> YHResult<Vec<u8>> {
+        self.check()?;
+        let mut buf = BytesMut::new();
+        buf.put(self.id.to_bytes());
+        buf.put(&self.version.to_bytes()?[..]);
+        buf.put(&self.time.to_bytes()[..]);
+        buf.put_u32::<BigEndian>(self.nonce);
+        buf.put(self.method.to_bytes());
+        buf.put_u32::<BigEndian>(self.raw.len() as u32);
+        buf.put(&self.raw[..]);
+        Ok(buf.to_vec())
+    }
+
+    pub fn from_bytes(buf: &[u8]) -> YHResult<YCreateUnsignedTxRes> {
+        if buf.len() < 92 {
+            return Err(YHErrorKind::InvalidLength.into());
+        }
+        let mut b = BytesMut::new();
+        b.extend_from_slice(buf);
+        let id = YDigest64::from_bytes(b.get(0..64).unwrap())?;
+        let version = YVersion::from_bytes(b.get(64..76).unwrap())?;
+        let time = YTime::from_bytes(b.get(76..84).unwrap())?;
+        let nonce = BigEndian::read_u32(b.get(84..88).unwrap());
+        let method = BigEndian::read_u32(b.get(88..92).unwrap()).into();
+        let mut i = 92;
+        let raw_size = BigEndian::read_u32(b.get(i..i+4).ok_or(YHErrorKind::InvalidLength)?) as usize;
+        i += 4;
+        let raw = b.get(i..i+raw_size).ok_or(YHErrorKind::InvalidLength)?.to_vec();
+        i += raw_size;
+        if i != buf.len() {
+            return Err(YHErrorKind::InvalidLength.into());
+        }
+        let res = YCreateUnsignedTxRes {
+            id: id,
+            version: version,
+            time: time,
+            nonce: nonce,
+            method: method,
+            raw: raw,
+        };
+        res.check()?;
+        Ok(res)
     }
 }
@@ -1,5 +1,6 @@
 pub mod rpc_method;
 pub mod message;
+pub mod frame;
 pub mod handle;
 pub mod host;
 pub mod server;
@@ -7,6 +8,7 @@ pub mod client;
 
 pub use self::rpc_method::*;
 pub use self::message::*;
+pub use self::frame::*;
 pub use self::handle::*;
 pub use self::host::*;
 pub use self::server::*;
@@ -12,7 +12,26 @@ pub enum YRPCMethod {
     GetTx=5,
     ConfirmTx=6,
     GetCb=7,
-    Unknown,
+    CreateWallet=8,
+    GetWallet=9,
+    ListWallets=10,
+    ListCoins=11,
+    GetUTXO=12,
+    ListUTXOs=13,
+    GetInfo=14,
+    SendCoins=15,
+    SendData=16,
+    PushTx=17,
+    ListTxs=18,
+    PushCb=19,
+    ListCbs=20,
+    Mine=21,
+    ListWalletData=22,
+    CreateReceiveKey=23,
+    SendBatch=24,
+    CreateUnsignedTx=25,
+    ListHistory=26,
+    Unknown=0xFFFF,
 }
 
 impl Default for YRPCMethod {
@@ -32,6 +51,25 @@ impl From<u32> for YRPCMethod {
             5 => YRPCMethod::GetTx,
             6 => YRPCMethod::ConfirmTx,
             7 => YRPCMethod::GetCb,
+            8 => YRPCMethod::CreateWallet,
+            9 => YRPCMethod::GetWallet,
+            10 => YRPCMethod::ListWallets,
+            11 => YRPCMethod::ListCoins,
+            12 => YRPCMethod::GetUTXO,
+            13 => YRPCMethod::ListUTXOs,
+            14 => YRPCMethod::GetInfo,
+            15 => YRPCMethod::SendCoins,
+            16 => YRPCMethod::SendData,
+            17 => YRPCMethod::PushTx,
+            18 => YRPCMethod::ListTxs,
+            19 => YRPCMethod::PushCb,
+            20 => YRPCMethod::ListCbs,
+            21 => YRPCMethod::Mine,
+            22 => YRPCMethod::ListWalletData,
+            23 => YRPCMethod::CreateReceiveKey,
+            24 => YRPCMethod::SendBatch,
+            25 => YRPCMethod::CreateUnsignedTx,
+            26 => YRPCMethod::ListHistory,
             _ => YRPCMethod::Unknown,
         }
     }
@@ -50,4 +88,26 @@ impl YRPCMethod {
         }
         Ok(BigEndian::read_u32(b).into())
     }
+
+    // methods reading the node wallets or spending from them
+    pub fn is_control(&self) -> bool {
+        match *self {
+            YRPCMethod::CreateWallet |
+            YRPCMethod::GetWallet |
+            YRPCMethod::ListWallets |
+            YRPCMethod::ListCoins |
+            YRPCMethod::CreateReceiveKey |
+            YRPCMethod::ListHistory |
+            YRPCMethod::ListWalletData |
+            YRPCMethod::ListTxs |
+            YRPCMethod::ListCbs |
+            YRPCMethod::SendCoins |
+            YRPCMethod::SendData |
+            YRPCMethod::SendBatch |
+            YRPCMethod::CreateUnsignedTx |
+            YRPCMethod::Mine |
+            YRPCMethod::GetInfo => true,
+            _ => false,
+        }
+    }
 }
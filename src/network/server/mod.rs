@@ -1,20 +1,59 @@
-use serde::{Serialize, Deserialize};
-use serde_json as json;
-use std::net::TcpListener;
+use libyobicash::crypto::hash::digest::YDigest64;
+use libyobicash::crypto::elliptic::keys::{YSecretKey, YPublicKey};
+use libyobicash::transaction::YTransaction as LibTransaction;
+use libyobicash::coinbase::YCoinbase as LibCoinbase;
+use bytes::{BigEndian, ByteOrder};
+use std::net::{TcpListener, TcpStream, SocketAddr};
 use std::thread;
+use std::time::{Duration, Instant};
 use std::sync::{Arc, Mutex};
-use std::io::prelude::*;
-use std::fmt::Debug;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::mpsc::{channel, Sender, Receiver, TryRecvError};
+use std::io::ErrorKind as IOErrorKind;
 use config::*;
-use store::common::*;
+use store::*;
+use models::fee::YPeerPrice;
+use models::coinbase::YCoinbase;
+use api::*;
+use network::rpc_method::*;
+use network::message::prefix::*;
+use network::host::YHost;
+use network::frame::*;
+use network::message::error::*;
+use network::message::response::*;
+use network::handle::*;
 use errors::*;
 
+pub const YSERVER_POLL_MILLIS: u64 = 10;
+
+pub const YSERVER_PRUNE_SECS: u64 = 3600;
+
+pub const YSERVER_MINE_SECS: u64 = 60;
+
+pub const YSERVER_PRICES_SECS: u64 = 300;
+
+struct YMineJob {
+    difficulty: u32,
+    miner_sk: YSecretKey,
+    recipient_pk: YPublicKey,
+    fee_pk: YPublicKey,
+}
+
+type YMineResult = (YSecretKey, YHResult<((LibCoinbase, LibTransaction), u32)>);
+
+struct YServerJob {
+    req_buf: Vec<u8>,
+    control: bool,
+    reply: Sender<Vec<u8>>,
+}
+
 #[derive(Debug)]
 pub struct YServer {
     pub config: YConfig,
     pub storage_kind: YStorageKind,
     pub storage_mode: YStorageMode,
     pub difficulty: Option<u32>,
+    pub verbose: bool,
 }
 
 impl Default for YServer {
@@ -24,6 +63,7 @@ impl Default for YServer {
             storage_kind: YStorageKind::default(),
             storage_mode: YStorageMode::default(),
             difficulty: None,
+            verbose: false,
         }
     }
 }
@@ -32,7 +72,8 @@ impl YServer {
     pub fn new(config: YConfig,
                storage_kind: YStorageKind,
                storage_mode: YStorageMode,
-               difficulty: Option<u32>) -> YHResult<YServer> {
+               difficulty: Option<u32>,
+               verbose: bool) -> YHResult<YServer> {
 
         if let Some(difficulty) = difficulty {
             if difficulty < 3 || difficulty > 63 {
@@ -40,74 +81,387 @@ impl YServer {
             }
         }
 
+        // wallet and spend methods are only served to local callers
+        if !config.control.ip()?.is_loopback() {
+            return Err(YHErrorKind::InvalidIp.into());
+        }
+
         let server = YServer {
             config: config,
             storage_kind: storage_kind,
             storage_mode: storage_mode,
             difficulty: difficulty,
+            verbose: verbose,
         };
 
         Ok(server)
     }
 
-    pub fn handle<R: Read, T>(reader: R) -> YHResult<Vec<u8>> 
-        where for <'de>T: Serialize + Deserialize<'de> + Debug
-    {
-        let req: T = json::from_reader(reader).unwrap();
-        
-        // NB: So far just an echo
+    fn log(verbose: bool, msg: &str) {
+        if verbose {
+            println!("{}", msg);
+        }
+    }
+
+    fn request_method(req_buf: &[u8]) -> YRPCMethod {
+        if req_buf.len() >= YMESSAGE_HEADER_SIZE {
+            YRPCMethod::from(BigEndian::read_u32(&req_buf[8..12]))
+        } else {
+            YRPCMethod::Unknown
+        }
+    }
 
-        let res_buf = json::to_vec(&req)?;
-        Ok(res_buf)
+    fn error_response(req_buf: &[u8], err: YHError) -> YHResult<Vec<u8>> {
+        let method = YServer::request_method(req_buf);
+        let res = YErrorRes::from_error(method, err)?;
+        YResponse::Error(res).to_bytes()
     }
 
-    pub fn run(self) {
+    fn serve_connection(mut connection: TcpStream, address: SocketAddr, control: bool, jobs: Sender<YServerJob>, verbose: bool) -> YHResult<()> {
+        connection.set_nonblocking(false)?;
 
-        let addr = self.config.host.internal();
-    
-        let listener = TcpListener::bind(addr).unwrap();
-        listener.set_nonblocking(true).unwrap();
+        loop {
+            let req_buf = match YFrame::read(&mut connection)? {
+                Some(req_buf) => req_buf,
+                None => {
+                    YServer::log(verbose, &format!("connection closed by {:?}", address));
+                    return Ok(());
+                },
+            };
 
-        let max_conns = self.config.max_conns;
-        let conns = Arc::new(Mutex::new(0u16));
+            let (reply, res_receiver) = channel();
+            let job = YServerJob {
+                req_buf: req_buf,
+                control: control,
+                reply: reply,
+            };
+            if jobs.send(job).is_err() {
+                return Err(YHErrorKind::NotConnected.into());
+            }
+
+            let res_buf = match res_receiver.recv() {
+                Ok(res_buf) => res_buf,
+                Err(_) => {
+                    return Err(YHErrorKind::NotConnected.into());
+                },
+            };
+
+            YFrame::write(&mut connection, &res_buf)?;
+        }
+    }
 
+    fn process_jobs<M: YStorage, P: YStorage>(jobs: &Receiver<YServerJob>, api: &mut YAPI<M, P>, verbose: bool) -> YHResult<usize> {
+        let mut processed = 0;
         loop {
-            match listener.accept() {
-                Ok((connection, address)) => {
-                    if *conns.lock().unwrap() == max_conns {
-                        let e: YHError = YHErrorKind::MaxConnectionsReached.into();
-                        panic!(e)
+            match jobs.try_recv() {
+                Ok(job) => {
+                    let res = if !job.control && YServer::request_method(&job.req_buf).is_control() {
+                        YServer::error_response(&job.req_buf, YHErrorKind::Unauthorized.into())
+                    } else {
+                        YHandle::handle_bytes(&job.req_buf, api)
+                    };
+                    let res = res
+                        .or_else(|err| {
+                            YServer::log(verbose, &format!("request failed: {}", err));
+                            YServer::error_response(&job.req_buf, err)
+                        });
+                    match res {
+                        Ok(res_buf) => {
+                            // NB: the worker may have gone away meanwhile
+                            let _ = job.reply.send(res_buf);
+                        },
+                        Err(err) => {
+                            // NB: dropping the reply closes the connection
+                            YServer::log(verbose, &format!("reply failed: {}", err));
+                        },
                     }
+                    processed += 1;
+                },
+                Err(TryRecvError::Empty) => {
+                    return Ok(processed);
+                },
+                Err(TryRecvError::Disconnected) => {
+                    return Err(YHErrorKind::NotConnected.into());
+                },
+            }
+        }
+    }
 
-                    let count = conns.clone();
+    // peers are pinged on their own thread, the estimator only reads the cached prices
+    fn spawn_prices(prices: Arc<Mutex<Vec<YPeerPrice>>>) -> Sender<Vec<YHost>> {
+        let (hosts_sender, requests) = channel::<Vec<YHost>>();
+        thread::spawn(move || {
+            for hosts in requests.iter() {
+                let fetched = YAPI::<YMemoryStore, YMemoryStore>::fetch_peer_prices(&hosts);
+                *prices.lock().unwrap() = fetched;
+            }
+        });
+        hosts_sender
+    }
 
-                    thread::spawn(move || {
-                        *count.lock().unwrap() += 1;
+    // relays block on every peer, so they run on their own thread too
+    fn spawn_relays(verbose: bool) -> Sender<(Vec<YHost>, Vec<LibTransaction>)> {
+        let (relays_sender, relays) = channel::<(Vec<YHost>, Vec<LibTransaction>)>();
+        thread::spawn(move || {
+            for (hosts, txs) in relays.iter() {
+                for tx in txs.iter() {
+                    let sent = YAPI::<YMemoryStore, YMemoryStore>::relay_transaction(&hosts, tx);
+                    YServer::log(verbose, &format!("relayed transaction {} to {} peers", tx.id.to_hex(), sent));
+                }
+            }
+        });
+        relays_sender
+    }
 
-                        // TODO
-                        /*
-                        let reader_conn = connection.try_clone().unwrap();
-                        let req: T = json::from_reader(reader_conn).unwrap();
+    // pruned ancestors are fetched from the peers on their own thread and stored back by the job loop
+    fn spawn_fetches(verbose: bool) -> (Sender<(Vec<YHost>, Vec<YDigest64>)>, Receiver<(Vec<LibTransaction>, Vec<LibCoinbase>)>) {
+        let (fetches_sender, fetches) = channel::<(Vec<YHost>, Vec<YDigest64>)>();
+        let (fetched_sender, fetched) = channel::<(Vec<LibTransaction>, Vec<LibCoinbase>)>();
+        thread::spawn(move || {
+            for (hosts, ids) in fetches.iter() {
+                for id in ids {
+                    match YAPI::<YMemoryStore, YMemoryStore>::fetch_transaction_ancestors(&hosts, id) {
+                        Ok(ancestors) => {
+                            if fetched_sender.send(ancestors).is_err() {
+                                return;
+                            }
+                        },
+                        Err(err) => {
+                            YServer::log(verbose, &format!("fetching ancestors of {} failed: {}", id.to_hex(), err));
+                        },
+                    }
+                }
+            }
+        });
+        (fetches_sender, fetched)
+    }
 
-                        println!("request from {:?}: req {:?}", address, req);
-                        
-                        // NB: So far just an echo
+    // the proof of work runs on its own thread, the job loop stores the mined coinbase
+    fn spawn_miner(stop: Arc<AtomicBool>) -> (Sender<YMineJob>, Receiver<YMineResult>) {
+        let (jobs_sender, jobs) = channel::<YMineJob>();
+        let (mined_sender, mined) = channel::<YMineResult>();
+        thread::spawn(move || {
+            for job in jobs.iter() {
+                if stop.load(Ordering::SeqCst) {
+                    return;
+                }
+                let res = YCoinbase::mine(job.difficulty, YMINE_INCR, job.miner_sk, job.recipient_pk, job.fee_pk);
+                if stop.load(Ordering::SeqCst) || mined_sender.send((job.miner_sk, res)).is_err() {
+                    return;
+                }
+            }
+        });
+        (jobs_sender, mined)
+    }
 
-                        let res_buf = json::to_vec(&req).unwrap();
+    // control connections don't count against max_conns so peers can't lock the operator out
+    fn accept(&self, listener: &TcpListener, control: bool, conns: &Arc<Mutex<u16>>, jobs_sender: &Sender<YServerJob>) -> bool {
+        let (connection, address) = match listener.accept() {
+            Ok(accepted) => accepted,
+            Err(ref e) if e.kind() == IOErrorKind::WouldBlock => {
+                return false;
+            },
+            Err(e) => {
+                YServer::log(self.verbose, &format!("accept failed: {}", e));
+                return false;
+            },
+        };
 
-                        connection.write(&res_buf).unwrap();
-                        connection.flush().unwrap();
+        if !control {
+            let mut count = conns.lock().unwrap();
+            if *count >= self.config.max_conns {
+                let err: YHError = YHErrorKind::MaxConnectionsReached.into();
+                YServer::log(self.verbose, &format!("refused connection from {:?}: {}", address, err));
+                return true;
+            }
+            *count += 1;
+        }
 
-                        println!("reply to {:?}: {:?}", address, res_buf);
-                        */
+        let count = conns.clone();
+        let jobs_sender = jobs_sender.clone();
+        let verbose = self.verbose;
 
-                    });
-                },
-                Err(_) => {
-                    let e: YHError = YHErrorKind::FailedConnection.into();
-                    panic!(e)
+        thread::spawn(move || {
+            if let Err(err) = YServer::serve_connection(connection, address, control, jobs_sender, verbose) {
+                YServer::log(verbose, &format!("connection with {:?} failed: {}", address, err));
+            }
+            if !control {
+                *count.lock().unwrap() -= 1;
+            }
+        });
+
+        true
+    }
+
+    pub fn serve<M: YStorage, P: YStorage>(self, mut api: YAPI<M, P>, stop: Arc<AtomicBool>) -> YHResult<()> {
+        let wallet = api.config.wallet.clone();
+        if !api.lookup_wallet(&wallet)? {
+            api.create_wallet(&wallet)?;
+        }
+        api.track_node_key(&wallet)?;
+
+        let light = self.storage_mode == YStorageMode::Light || api.config.light_mode;
+        let prune_interval = Duration::from_secs(YSERVER_PRUNE_SECS);
+        if light {
+            if let Err(err) = api.prune_store() {
+                YServer::log(self.verbose, &format!("prune failed: {}", err));
+            }
+        }
+        let mut last_prune = Instant::now();
+
+        let mine_interval = Duration::from_secs(YSERVER_MINE_SECS);
+        let mut last_mine = Instant::now();
+        let (mine_sender, mined) = YServer::spawn_miner(stop.clone());
+        let mut mining = false;
+
+        let prices_interval = Duration::from_secs(YSERVER_PRICES_SECS);
+        let prices_sender = YServer::spawn_prices(api.prices.clone());
+        let mut last_prices: Option<Instant> = None;
+
+        let relays_sender = YServer::spawn_relays(self.verbose);
+
+        let (fetches_sender, fetched) = YServer::spawn_fetches(self.verbose);
+
+        let listener = TcpListener::bind(self.config.host.internal())?;
+        listener.set_nonblocking(true)?;
+
+        let control_listener = TcpListener::bind(self.config.control.internal())?;
+        control_listener.set_nonblocking(true)?;
+
+        let conns = Arc::new(Mutex::new(0u16));
+
+        let (jobs_sender, jobs) = channel::<YServerJob>();
+
+        loop {
+            if stop.load(Ordering::SeqCst) {
+                api.close_store()?;
+                return Ok(());
+            }
+
+            let mut idle = true;
+
+            if self.accept(&listener, false, &conns, &jobs_sender) {
+                idle = false;
+            }
+
+            if self.accept(&control_listener, true, &conns, &jobs_sender) {
+                idle = false;
+            }
+
+            if YServer::process_jobs(&jobs, &mut api, self.verbose)? > 0 {
+                idle = false;
+            }
+
+            let txs = api.take_relays();
+            if !txs.is_empty() {
+                match api.peer_hosts() {
+                    Ok(hosts) => {
+                        // NB: the relays thread only ends with the server
+                        let _ = relays_sender.send((hosts, txs));
+                    },
+                    Err(err) => {
+                        YServer::log(self.verbose, &format!("listing peers failed: {}", err));
+                    },
+                }
+            }
+
+            let ids = api.take_fetches();
+            if !ids.is_empty() {
+                match api.peer_hosts() {
+                    Ok(hosts) => {
+                        // NB: the fetches thread only ends with the server
+                        let _ = fetches_sender.send((hosts, ids));
+                    },
+                    Err(err) => {
+                        YServer::log(self.verbose, &format!("listing peers failed: {}", err));
+                    },
+                }
+            }
+
+            while let Ok((txs, cbs)) = fetched.try_recv() {
+                if let Err(err) = api.store_ancestors(&txs, &cbs) {
+                    YServer::log(self.verbose, &format!("storing ancestors failed: {}", err));
+                }
+                idle = false;
+            }
+
+            if light && last_prune.elapsed() >= prune_interval {
+                if let Err(err) = api.prune_store() {
+                    YServer::log(self.verbose, &format!("prune failed: {}", err));
+                }
+                last_prune = Instant::now();
+            }
+
+            if last_prices.map_or(true, |last| last.elapsed() >= prices_interval) {
+                match api.peer_hosts() {
+                    Ok(hosts) => {
+                        // NB: the prices thread only ends with the server
+                        let _ = prices_sender.send(hosts);
+                    },
+                    Err(err) => {
+                        YServer::log(self.verbose, &format!("listing peers failed: {}", err));
+                    },
                 }
+                last_prices = Some(Instant::now());
             }
+
+            if let Some(difficulty) = self.difficulty {
+                if !mining && last_mine.elapsed() >= mine_interval {
+                    match api.genesys_keys(&wallet) {
+                        Ok((miner_sk, recipient_pk)) => {
+                            let job = YMineJob {
+                                difficulty: difficulty,
+                                miner_sk: miner_sk,
+                                recipient_pk: recipient_pk,
+                                fee_pk: api.public_key(),
+                            };
+                            // NB: the miner thread only ends with the server
+                            mining = mine_sender.send(job).is_ok();
+                        },
+                        Err(err) => {
+                            YServer::log(self.verbose, &format!("mining failed: {}", err));
+                        },
+                    }
+                    last_mine = Instant::now();
+                    idle = false;
+                }
+            }
+
+            if let Ok((miner_sk, res)) = mined.try_recv() {
+                let res = res.and_then(|((cb, tx), tries)| {
+                    api.store_genesys(&wallet, miner_sk, &cb, &tx)?;
+                    Ok((cb, tries))
+                });
+                match res {
+                    Ok((cb, tries)) => {
+                        let msg = format!("mined coinbase {} in {} tries", cb.id.to_hex(), tries);
+                        YServer::log(self.verbose, &msg);
+                    },
+                    Err(err) => {
+                        YServer::log(self.verbose, &format!("mining failed: {}", err));
+                    },
+                }
+                mining = false;
+                last_mine = Instant::now();
+                idle = false;
+            }
+
+            if idle {
+                thread::sleep(Duration::from_millis(YSERVER_POLL_MILLIS));
+            }
+        }
+    }
+
+    pub fn run(self, stop: Arc<AtomicBool>) -> YHResult<()> {
+        match self.storage_kind {
+            YStorageKind::Persistent => {
+                let api = YAPI::new(self.config.clone(), false)?;
+                self.serve(api, stop)
+            },
+            YStorageKind::Memory => {
+                let api = YAPI::new_temporary(self.config.clone())?;
+                self.serve(api, stop)
+            },
         }
     }
 }
@@ -1,6 +1,6 @@
 use errors::*;
 
-#[derive(Debug)]
+#[derive(Clone, Eq, PartialEq, Debug)]
 pub enum YStorageKind {
     Persistent=0,
     Memory=1,
@@ -12,7 +12,7 @@ impl Default for YStorageKind {
     }
 }
 
-#[derive(Debug)]
+#[derive(Clone, Eq, PartialEq, Debug)]
 pub enum YStorageMode {
     Full=0,
     Light=1,
@@ -36,6 +36,12 @@ pub struct YStoreItem {
     pub value: YStoreValue,
 }
 
+#[derive(Clone, Eq, PartialEq, Debug, Default)]
+pub struct YStorePage {
+    pub keys: Vec<YStoreKey>,
+    pub next: Option<YStoreKey>,
+}
+
 pub trait YStorage
     where Self: Sized
 {
@@ -63,5 +69,76 @@ pub trait YStorage
 
     fn list_reverse(&self, buck: &YStoreBuck, skip: u32, count: u32) -> YHResult<Vec<YStoreKey>>;
 
+    fn list_from(&self, buck: &YStoreBuck, after: &Option<YStoreKey>, limit: u32) -> YHResult<YStorePage>;
+
     fn delete(&mut self, buck: &YStoreBuck, key: &YStoreKey) -> YHResult<()>;
+
+    fn begin(&mut self) -> YHResult<()>;
+
+    fn commit(&mut self) -> YHResult<()>;
+
+    fn rollback(&mut self) -> YHResult<()>;
+}
+
+pub fn atomically<S, T, F>(store: &mut S, f: F) -> YHResult<T>
+    where S: YStorage,
+          F: FnOnce(&mut S) -> YHResult<T>
+{
+    store.begin()?;
+    match f(store) {
+        Ok(res) => {
+            store.commit()?;
+            Ok(res)
+        },
+        Err(err) => {
+            store.rollback()?;
+            Err(err)
+        },
+    }
+}
+
+pub fn store_index(buck: &YStoreBuck, key: &YStoreKey) -> Vec<u8> {
+    let mut index = Vec::new();
+    index.extend(buck.iter().cloned());
+    index.extend(key.iter().cloned());
+    index
+}
+
+pub fn prefix_end(buck: &YStoreBuck) -> Option<Vec<u8>> {
+    let mut end = buck.clone();
+    while let Some(last) = end.pop() {
+        if last < 0xff {
+            end.push(last + 1);
+            return Some(end);
+        }
+    }
+    None
+}
+
+pub fn store_page(keys: Vec<YStoreKey>, limit: u32) -> YStorePage {
+    let mut keys = keys;
+    let mut next = None;
+    if keys.len() > limit as usize {
+        keys.truncate(limit as usize);
+        next = keys.last().cloned();
+    }
+    YStorePage {
+        keys: keys,
+        next: next,
+    }
+}
+
+pub fn cursor_from_bytes(buf: &[u8]) -> Option<YStoreKey> {
+    if buf.is_empty() {
+        None
+    } else {
+        Some(buf.to_vec())
+    }
+}
+
+pub fn cursor_to_bytes(cursor: &Option<YStoreKey>) -> Vec<u8> {
+    match *cursor {
+        Some(ref key) => key.clone(),
+        None => Vec::new(),
+    }
 }
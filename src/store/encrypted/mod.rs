@@ -0,0 +1,337 @@
+use libyobicash::crypto::key::YKey32;
+use libyobicash::crypto::encryption::symmetric::YSymmetricEncryption as YSE;
+use bytes::{BytesMut, BufMut, BigEndian, ByteOrder};
+use std::collections::{BTreeMap, BTreeSet};
+use std::collections::Bound;
+use std::cell::RefCell;
+use std::sync::Arc;
+use errors::*;
+use store::common::*;
+
+pub const YENCRYPTED_BUCK_SIZE: usize = 4;
+
+pub const YENCRYPTED_CHECK_BUCK: u32 = 0x59454e43;
+
+pub const YENCRYPTED_CHECK_VALUE: &[u8] = b"yobicash";
+
+#[derive(Clone, Eq, PartialEq, Debug)]
+pub struct YEncryptedConfig<C> {
+    pub key: YKey32,
+    pub inner: C,
+}
+
+// plain keys of every raw bucket listed so far, in key order
+pub type YEncryptedIndex = BTreeMap<YStoreBuck, BTreeSet<YStoreKey>>;
+
+pub struct YEncryptedStore<S: YStorage> {
+    pub key: YKey32,
+    pub inner: S,
+    pub index: RefCell<Arc<YEncryptedIndex>>,
+    pub depth: u32,
+    pub snapshot: Option<Arc<YEncryptedIndex>>,
+}
+
+impl<S: YStorage> YEncryptedStore<S> {
+    pub fn new(key: YKey32, inner: S) -> YEncryptedStore<S> {
+        YEncryptedStore {
+            key: key,
+            inner: inner,
+            index: RefCell::new(Arc::new(BTreeMap::new())),
+            depth: 0,
+            snapshot: None,
+        }
+    }
+
+    pub fn seal(key: YKey32, buf: &[u8]) -> YHResult<Vec<u8>> {
+        let mut sealed = Vec::new();
+        sealed.put_u32::<BigEndian>(buf.len() as u32);
+        sealed.put(buf);
+        let padding = (16 - sealed.len() % 16) % 16;
+        for _ in 0..padding {
+            sealed.push(0);
+        }
+        let enc = YSE::encrypt(key, &sealed)?;
+        Ok(enc)
+    }
+
+    pub fn unseal(key: YKey32, buf: &[u8]) -> YHResult<Vec<u8>> {
+        let dec = YSE::decrypt(key, &buf.to_vec())?;
+        if dec.len() < 4 {
+            return Err(YHErrorKind::InvalidLength.into());
+        }
+        let len = BigEndian::read_u32(&dec[0..4]) as usize;
+        let buf = dec.get(4..4+len).ok_or(YHErrorKind::InvalidLength)?;
+        Ok(buf.to_vec())
+    }
+
+    pub fn check_buck() -> YStoreBuck {
+        let mut buf = BytesMut::new();
+        buf.put_u32::<BigEndian>(YENCRYPTED_CHECK_BUCK);
+        buf.to_vec()
+    }
+
+    pub fn is_sealed(&self) -> YHResult<bool> {
+        self.inner.lookup(&YEncryptedStore::<S>::check_buck(), &Vec::new())
+    }
+
+    pub fn check_key(&self) -> YHResult<()> {
+        let item = self.inner.get(&YEncryptedStore::<S>::check_buck(), &Vec::new())?;
+        match YEncryptedStore::<S>::unseal(self.key, &item.value) {
+            Ok(ref value) if value.as_slice() == YENCRYPTED_CHECK_VALUE => Ok(()),
+            _ => Err(YHErrorKind::InvalidKey.into()),
+        }
+    }
+
+    fn put_check(inner: &mut S, key: YKey32) -> YHResult<()> {
+        let value = YEncryptedStore::<S>::seal(key, YENCRYPTED_CHECK_VALUE)?;
+        inner.put(&YEncryptedStore::<S>::check_buck(), &Vec::new(), &value)
+    }
+
+    // only the bucket id is stored in clear, any owner prefix is encrypted with the key
+    fn split(buck: &YStoreBuck) -> YHResult<(YStoreBuck, Vec<u8>)> {
+        if buck.len() < YENCRYPTED_BUCK_SIZE {
+            return Err(YHErrorKind::InvalidLength.into());
+        }
+        Ok((buck[..YENCRYPTED_BUCK_SIZE].to_vec(), buck[YENCRYPTED_BUCK_SIZE..].to_vec()))
+    }
+
+    fn plain_index(buck: &YStoreBuck, key: &YStoreKey) -> YHResult<(YStoreBuck, YStoreKey)> {
+        let (raw_buck, mut plain) = YEncryptedStore::<S>::split(buck)?;
+        plain.extend(key.iter().cloned());
+        Ok((raw_buck, plain))
+    }
+
+    fn raw_index(&self, buck: &YStoreBuck, key: &YStoreKey) -> YHResult<(YStoreBuck, YStoreKey)> {
+        let (raw_buck, plain) = YEncryptedStore::<S>::plain_index(buck, key)?;
+        let raw_key = YEncryptedStore::<S>::seal(self.key, &plain)?;
+        Ok((raw_buck, raw_key))
+    }
+
+    // ciphertexts don't keep the key order, so a raw bucket is decrypted once on its first listing
+    fn load(&self, raw_buck: &YStoreBuck) -> YHResult<()> {
+        if self.index.borrow().contains_key(raw_buck) {
+            return Ok(());
+        }
+        let mut keys = BTreeSet::new();
+        for raw_key in self.inner.list(raw_buck, 0, u32::max_value())? {
+            keys.insert(YEncryptedStore::<S>::unseal(self.key, &raw_key)?);
+        }
+        Arc::make_mut(&mut *self.index.borrow_mut()).insert(raw_buck.clone(), keys);
+        Ok(())
+    }
+
+    fn keys(&self, buck: &YStoreBuck, after: &Option<YStoreKey>, reverse: bool, skip: u32, count: u32) -> YHResult<Vec<YStoreKey>> {
+        let (raw_buck, prefix) = YEncryptedStore::<S>::split(buck)?;
+        self.load(&raw_buck)?;
+        let start = match *after {
+            Some(ref key) => {
+                let mut plain = prefix.clone();
+                plain.extend(key.iter().cloned());
+                Bound::Excluded(plain)
+            },
+            None => Bound::Included(prefix.clone()),
+        };
+        let end = match prefix_end(&prefix) {
+            Some(end) => Bound::Excluded(end),
+            None => Bound::Unbounded,
+        };
+        let index = self.index.borrow();
+        let range = index[&raw_buck].range((start, end));
+        let keys: Box<Iterator<Item=&YStoreKey>> = if reverse {
+            Box::new(range.rev())
+        } else {
+            Box::new(range)
+        };
+        let keys = keys
+            .skip(skip as usize)
+            .take(count as usize)
+            .map(|plain| plain[prefix.len()..].to_vec())
+            .collect();
+        Ok(keys)
+    }
+
+    fn update_index(&mut self, raw_buck: &YStoreBuck, plain: YStoreKey, present: bool) {
+        let index = Arc::make_mut(self.index.get_mut());
+        if let Some(keys) = index.get_mut(raw_buck) {
+            if present {
+                keys.insert(plain);
+            } else {
+                keys.remove(&plain);
+            }
+        }
+    }
+
+    fn clear_index(&mut self) {
+        *self.index.get_mut() = Arc::new(BTreeMap::new());
+    }
+
+    pub fn encrypt_plain(&mut self, bucks: &Vec<YStoreBuck>) -> YHResult<()> {
+        atomically(self, |store| {
+            for buck in bucks {
+                let (raw_buck, _) = YEncryptedStore::<S>::split(buck)?;
+                let mut entries = Vec::new();
+                for key in store.inner.list(&raw_buck, 0, u32::max_value())? {
+                    let item = store.inner.get(&raw_buck, &key)?;
+                    store.inner.delete(&raw_buck, &key)?;
+                    entries.push(item);
+                }
+                for item in entries {
+                    store.put(&raw_buck, &item.key, &item.value)?;
+                }
+            }
+            YEncryptedStore::<S>::put_check(&mut store.inner, store.key)
+        })?;
+        self.clear_index();
+        Ok(())
+    }
+
+    pub fn rotate(&mut self, bucks: &Vec<YStoreBuck>, key: YKey32) -> YHResult<()> {
+        atomically(self, |store| {
+            for buck in bucks {
+                let (raw_buck, _) = YEncryptedStore::<S>::split(buck)?;
+                let mut entries = Vec::new();
+                for raw_key in store.inner.list(&raw_buck, 0, u32::max_value())? {
+                    let item = store.inner.get(&raw_buck, &raw_key)?;
+                    let plain_key = YEncryptedStore::<S>::unseal(store.key, &raw_key)?;
+                    let plain_value = YEncryptedStore::<S>::unseal(store.key, &item.value)?;
+                    store.inner.delete(&raw_buck, &raw_key)?;
+                    entries.push((plain_key, plain_value));
+                }
+                for (plain_key, plain_value) in entries {
+                    let raw_key = YEncryptedStore::<S>::seal(key, &plain_key)?;
+                    let raw_value = YEncryptedStore::<S>::seal(key, &plain_value)?;
+                    store.inner.put(&raw_buck, &raw_key, &raw_value)?;
+                }
+            }
+            YEncryptedStore::<S>::put_check(&mut store.inner, key)
+        })?;
+        self.key = key;
+        self.clear_index();
+        Ok(())
+    }
+}
+
+impl<S: YStorage> YStorage for YEncryptedStore<S> {
+    type Config = YEncryptedConfig<S::Config>;
+
+    fn create(config: Self::Config) -> YHResult<Self> {
+        let inner = S::create(config.inner)?;
+        let mut store = YEncryptedStore::new(config.key, inner);
+        YEncryptedStore::<S>::put_check(&mut store.inner, store.key)?;
+        Ok(store)
+    }
+
+    fn open(config: Self::Config) -> YHResult<Self> {
+        let inner = S::open(config.inner)?;
+        let store = YEncryptedStore::new(config.key, inner);
+        if store.is_sealed()? {
+            store.check_key()?;
+        }
+        Ok(store)
+    }
+
+    fn close(&mut self) -> YHResult<()> {
+        self.inner.close()
+    }
+
+    fn reset(&mut self) -> YHResult<Self> {
+        let inner = self.inner.reset()?;
+        let mut store = YEncryptedStore::new(self.key, inner);
+        YEncryptedStore::<S>::put_check(&mut store.inner, store.key)?;
+        Ok(store)
+    }
+
+    fn destroy(&mut self) -> YHResult<()> {
+        self.inner.destroy()
+    }
+
+    fn put(&mut self, buck: &YStoreBuck, key: &YStoreKey, value: &YStoreValue) -> YHResult<()> {
+        let (raw_buck, plain) = YEncryptedStore::<S>::plain_index(buck, key)?;
+        let raw_key = YEncryptedStore::<S>::seal(self.key, &plain)?;
+        let raw_value = YEncryptedStore::<S>::seal(self.key, value)?;
+        self.inner.put(&raw_buck, &raw_key, &raw_value)?;
+        self.update_index(&raw_buck, plain, true);
+        Ok(())
+    }
+
+    fn lookup(&self, buck: &YStoreBuck, key: &YStoreKey) -> YHResult<bool> {
+        let (raw_buck, raw_key) = self.raw_index(buck, key)?;
+        self.inner.lookup(&raw_buck, &raw_key)
+    }
+
+    fn get(&self, buck: &YStoreBuck, key: &YStoreKey) -> YHResult<YStoreItem> {
+        let (raw_buck, raw_key) = self.raw_index(buck, key)?;
+        let item = self.inner.get(&raw_buck, &raw_key)?;
+        Ok(YStoreItem {
+            key: key.clone(),
+            value: YEncryptedStore::<S>::unseal(self.key, &item.value)?,
+        })
+    }
+
+    fn count(&self, buck: &YStoreBuck) -> YHResult<u32> {
+        if buck.len() == YENCRYPTED_BUCK_SIZE {
+            self.inner.count(buck)
+        } else {
+            Ok(self.keys(buck, &None, false, 0, u32::max_value())?.len() as u32)
+        }
+    }
+
+    fn list(&self, buck: &YStoreBuck, skip: u32, count: u32) -> YHResult<Vec<YStoreKey>> {
+        self.keys(buck, &None, false, skip, count)
+    }
+
+    fn list_reverse(&self, buck: &YStoreBuck, skip: u32, count: u32) -> YHResult<Vec<YStoreKey>> {
+        self.keys(buck, &None, true, skip, count)
+    }
+
+    fn list_from(&self, buck: &YStoreBuck, after: &Option<YStoreKey>, limit: u32) -> YHResult<YStorePage> {
+        let keys = self.keys(buck, after, false, 0, limit.saturating_add(1))?;
+        Ok(store_page(keys, limit))
+    }
+
+    fn delete(&mut self, buck: &YStoreBuck, key: &YStoreKey) -> YHResult<()> {
+        let (raw_buck, plain) = YEncryptedStore::<S>::plain_index(buck, key)?;
+        let raw_key = YEncryptedStore::<S>::seal(self.key, &plain)?;
+        self.inner.delete(&raw_buck, &raw_key)?;
+        self.update_index(&raw_buck, plain, false);
+        Ok(())
+    }
+
+    fn begin(&mut self) -> YHResult<()> {
+        self.inner.begin()?;
+        if self.depth == 0 {
+            self.snapshot = Some(self.index.get_mut().clone());
+        }
+        self.depth += 1;
+        Ok(())
+    }
+
+    fn commit(&mut self) -> YHResult<()> {
+        let res = self.inner.commit();
+        if self.depth > 0 {
+            self.depth -= 1;
+            if self.depth == 0 {
+                let snapshot = self.snapshot.take();
+                if res.is_err() {
+                    if let Some(index) = snapshot {
+                        *self.index.get_mut() = index;
+                    }
+                }
+            }
+        }
+        res
+    }
+
+    fn rollback(&mut self) -> YHResult<()> {
+        let res = self.inner.rollback();
+        if self.depth > 0 {
+            self.depth -= 1;
+            if self.depth == 0 {
+                if let Some(index) = self.snapshot.take() {
+                    *self.index.get_mut() = index;
+                }
+            }
+        }
+        res
+    }
+}
@@ -1,54 +1,69 @@
-use unqlite::{UnQLite, KV, Cursor, Direction};
+use std::collections::BTreeMap;
+use std::collections::btree_map::Range;
+use std::collections::Bound;
+use std::sync::Arc;
 use std::io::Error as IOError;
 use std::io::ErrorKind as IOErrorKind;
-use std::error::Error;
 use errors::*;
 use store::common::*;
 
-#[derive(Clone, Eq, PartialEq, Debug)]
-pub struct YMemoryMode {
-    pub path: String,
-    pub read_only: bool,
-}
+pub type YMemoryMap = BTreeMap<Vec<u8>, YStoreValue>;
 
-#[derive(Clone, Eq, PartialEq, Debug)]
+#[derive(Clone, Eq, PartialEq, Debug, Default)]
 pub struct YMemoryConfig {
-    pub mode: YMemoryMode,
+    pub read_only: bool,
 }
 
+#[derive(Clone, Debug)]
 pub struct YMemoryStore {
     pub config: YMemoryConfig,
-    pub handle: UnQLite,
+    pub map: Arc<YMemoryMap>,
+    pub depth: u32,
+    pub aborted: bool,
+    pub snapshot: Option<Arc<YMemoryMap>>,
+}
+
+impl YMemoryStore {
+    pub fn from_map(config: YMemoryConfig, map: YMemoryMap) -> YMemoryStore {
+        YMemoryStore {
+            config: config,
+            map: Arc::new(map),
+            depth: 0,
+            aborted: false,
+            snapshot: None,
+        }
+    }
+
+    fn check_writable(&self) -> YHResult<()> {
+        if self.config.read_only {
+            let err = IOError::new(IOErrorKind::PermissionDenied, "read only store");
+            return Err(YHErrorKind::IO(err).into());
+        }
+        Ok(())
+    }
+
+    fn range(&self, buck: &YStoreBuck) -> Range<Vec<u8>, YStoreValue> {
+        let start = Bound::Included(buck.clone());
+        let end = match prefix_end(buck) {
+            Some(end) => Bound::Excluded(end),
+            None => Bound::Unbounded,
+        };
+        self.map.range((start, end))
+    }
 }
 
 impl YStorage for YMemoryStore {
     type Config = YMemoryConfig;
-        
+
     fn create(config: Self::Config) -> YHResult<Self> {
-        Ok(YMemoryStore {
-            config: config,
-            handle: UnQLite::create_in_memory(),
-        })
+        Ok(YMemoryStore::from_map(config, BTreeMap::new()))
     }
 
     fn open(config: Self::Config) -> YHResult<Self> {
-        if config.mode.read_only {
-            let handle = UnQLite::open_mmap(config.mode.path.as_str());
-            Ok(YMemoryStore {
-                config: config,
-                handle: handle,
-            })
-        } else {
-            let handle = UnQLite::create_in_memory();
-            Ok(YMemoryStore {
-                config: config,
-                handle: handle,
-            })
-        }
+        YMemoryStore::create(config)
     }
 
     fn close(&mut self) -> YHResult<()> {
-        let _ = self.handle;
         Ok(())
     }
 
@@ -59,114 +74,119 @@ impl YStorage for YMemoryStore {
     }
 
     fn destroy(&mut self) -> YHResult<()> {
-        let _ = self.handle;
+        self.map = Arc::new(BTreeMap::new());
+        self.depth = 0;
+        self.aborted = false;
+        self.snapshot = None;
         Ok(())
     }
 
     fn put(&mut self, buck: &YStoreBuck, key: &YStoreKey, value: &YStoreValue) -> YHResult<()> {
-        let mut index = Vec::new();
-        index.extend(buck.iter().cloned());
-        index.extend(key.iter().cloned());
-        self.handle.kv_store(index.as_slice(), value.as_slice())
-            .map_err(|err| YHErrorKind::IO(IOError::new(IOErrorKind::Other, err.description())).into())
+        self.check_writable()?;
+        let index = store_index(buck, key);
+        Arc::make_mut(&mut self.map).insert(index, value.clone());
+        Ok(())
     }
 
     fn lookup(&self, buck: &YStoreBuck, key: &YStoreKey) -> YHResult<bool> {
-        let mut index = Vec::new();
-        index.extend(buck.iter().cloned());
-        index.extend(key.iter().cloned());
-        Ok(self.handle.kv_contains(index.as_slice()))
+        let index = store_index(buck, key);
+        Ok(self.map.contains_key(&index))
     }
 
     fn get(&self, buck: &YStoreBuck, key: &YStoreKey) -> YHResult<YStoreItem> {
-        let mut index = Vec::new();
-        index.extend(buck.iter().cloned());
-        index.extend(key.iter().cloned());
-        self.handle.kv_fetch(index.as_slice())
-            .map(|value| YStoreItem { key: key.clone(), value: value })
-            .map_err(|err| YHErrorKind::IO(IOError::new(IOErrorKind::Other, err.description())).into())
+        let index = store_index(buck, key);
+        match self.map.get(&index) {
+            Some(value) => Ok(YStoreItem { key: key.clone(), value: value.clone() }),
+            None => Err(YHErrorKind::NotFound.into()),
+        }
     }
 
     fn count(&self, buck: &YStoreBuck) -> YHResult<u32> {
-        let mut entry = self.handle.seek(buck.as_slice(), Direction::Ge);
-        if entry.is_none() {
-            return Err(YHErrorKind::IO(IOError::new(IOErrorKind::NotFound, "buck not found")).into());
-        } else {
-            let mut count = 0;
-            loop {
-                count += 1;
-                entry = entry.unwrap().next();
-                if entry.is_none() {
-                    break;
-                }
-            }
-            Ok(count)
-        }
+        Ok(self.range(buck).count() as u32)
     }
 
     fn list(&self, buck: &YStoreBuck, skip: u32, count: u32) -> YHResult<Vec<YStoreKey>> {
-        let mut entry = self.handle.seek(buck.as_slice(), Direction::Ge);
-        if entry.is_none() {
-            return Err(YHErrorKind::IO(IOError::new(IOErrorKind::NotFound, "buck not found")).into());
-        } else {
-            let mut _skip = skip;
-            let mut _count = count;
-            let mut ls = Vec::new();
-            loop {
-                if _skip == 0 {
-                    break;
-                }
-                if count != 0 {
-                    let record = entry.unwrap();
-                    ls.push(record.key());
-                    entry = record.next();
-                    _count -= 1;
-                    _skip -= 1;
-                } else {
-                    break;
-                }
-                if entry.is_none() {
-                    break;
-                }
-            }
-            Ok(ls)
-        }
+        let ls = self.range(buck)
+            .skip(skip as usize)
+            .take(count as usize)
+            .map(|(index, _)| index[buck.len()..].to_vec())
+            .collect();
+        Ok(ls)
     }
 
     fn list_reverse(&self, buck: &YStoreBuck, skip: u32, count: u32) -> YHResult<Vec<YStoreKey>> {
-        let mut entry = self.handle.seek(buck.as_slice(), Direction::Le);
-        if entry.is_none() {
-            return Err(YHErrorKind::IO(IOError::new(IOErrorKind::NotFound, "buck not found")).into());
-        } else {
-            let mut _skip = skip;
-            let mut _count = count;
-            let mut ls = Vec::new();
-            loop {
-                if _skip == 0 {
-                    break;
-                }
-                if count != 0 {
-                    let record = entry.unwrap();
-                    ls.push(record.key());
-                    entry = record.next();
-                    _count -= 1;
-                    _skip -= 1;
-                } else {
-                    break;
-                }
-                if entry.is_none() {
-                    break;
+        let ls = self.range(buck)
+            .rev()
+            .skip(skip as usize)
+            .take(count as usize)
+            .map(|(index, _)| index[buck.len()..].to_vec())
+            .collect();
+        Ok(ls)
+    }
+
+    fn list_from(&self, buck: &YStoreBuck, after: &Option<YStoreKey>, limit: u32) -> YHResult<YStorePage> {
+        let start = match *after {
+            Some(ref key) => Bound::Excluded(store_index(buck, key)),
+            None => Bound::Included(buck.clone()),
+        };
+        let end = match prefix_end(buck) {
+            Some(end) => Bound::Excluded(end),
+            None => Bound::Unbounded,
+        };
+        let keys = self.map.range((start, end))
+            .take(limit as usize + 1)
+            .map(|(index, _)| index[buck.len()..].to_vec())
+            .collect();
+        Ok(store_page(keys, limit))
+    }
+
+    fn delete(&mut self, buck: &YStoreBuck, key: &YStoreKey) -> YHResult<()> {
+        self.check_writable()?;
+        let index = store_index(buck, key);
+        match Arc::make_mut(&mut self.map).remove(&index) {
+            Some(_) => Ok(()),
+            None => Err(YHErrorKind::NotFound.into()),
+        }
+    }
+
+    fn begin(&mut self) -> YHResult<()> {
+        if self.depth == 0 {
+            self.snapshot = Some(self.map.clone());
+            self.aborted = false;
+        }
+        self.depth += 1;
+        Ok(())
+    }
+
+    fn commit(&mut self) -> YHResult<()> {
+        if self.depth == 0 {
+            return Err(YHErrorKind::NoTransaction.into());
+        }
+        self.depth -= 1;
+        if self.depth == 0 {
+            let snapshot = self.snapshot.take();
+            if self.aborted {
+                if let Some(map) = snapshot {
+                    self.map = map;
                 }
+                return Err(YHErrorKind::TransactionAborted.into());
             }
-            Ok(ls)
         }
+        Ok(())
     }
 
-    fn delete(&mut self, buck: &YStoreBuck, key: &YStoreKey) -> YHResult<()> {
-        let mut index = Vec::new();
-        index.extend(buck.iter().cloned());
-        index.extend(key.iter().cloned());
-        self.handle.kv_delete(index.as_slice())
-            .map_err(|err| YHErrorKind::Store(err).into())
+    fn rollback(&mut self) -> YHResult<()> {
+        if self.depth == 0 {
+            return Err(YHErrorKind::NoTransaction.into());
+        }
+        self.depth -= 1;
+        if self.depth == 0 {
+            if let Some(map) = self.snapshot.take() {
+                self.map = map;
+            }
+        } else {
+            self.aborted = true;
+        }
+        Ok(())
     }
 }
@@ -1,7 +1,11 @@
 pub mod common;
 pub mod persistent;
 pub mod memory;
+pub mod snapshot;
+pub mod encrypted;
 
 pub use self::common::*;
 pub use self::persistent::*;
 pub use self::memory::*;
+pub use self::snapshot::*;
+pub use self::encrypted::*;
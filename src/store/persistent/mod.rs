@@ -1,4 +1,7 @@
-use unqlite::{UnQLite, KV, Cursor, Direction};
+use unqlite::{UnQLite, KV, Cursor, Transaction};
+use bytes::{BufMut, BigEndian, ByteOrder};
+use std::collections::BTreeSet;
+use std::cmp::min;
 use std::io::Error as IOError;
 use std::io::ErrorKind as IOErrorKind;
 use std::fs::remove_file;
@@ -20,9 +23,295 @@ pub struct YPersistentConfig {
     pub mode: YPersistentMode,
 }
 
+pub const YPERSISTENT_INDEX_PREFIX: &'static [u8] = b"\xff\xff\xff\xffyobicash-index";
+
+pub const YPERSISTENT_INDEX_LEVELS: usize = 16;
+
+// a node of the skip list keeping the keys ordered, a missing link standing for the head.
+// The head prev links the last node, so that listings can run backwards
+#[derive(Clone, Eq, PartialEq, Debug, Default)]
+pub struct YPersistentNode {
+    pub prev: Option<Vec<u8>>,
+    pub next: Vec<Option<Vec<u8>>>,
+}
+
+impl YPersistentNode {
+    pub fn new(levels: usize) -> YPersistentNode {
+        YPersistentNode {
+            prev: None,
+            next: vec![None; levels],
+        }
+    }
+
+    fn put_link(buf: &mut Vec<u8>, link: &Option<Vec<u8>>) {
+        match *link {
+            Some(ref index) => {
+                buf.put_u8(1);
+                buf.put_u32::<BigEndian>(index.len() as u32);
+                buf.put(index.as_slice());
+            },
+            None => buf.put_u8(0),
+        }
+    }
+
+    fn read_link(buf: &[u8], i: &mut usize) -> YHResult<Option<Vec<u8>>> {
+        let flag = *buf.get(*i).ok_or(YHErrorKind::InvalidLength)?;
+        *i += 1;
+        if flag == 0 {
+            return Ok(None);
+        }
+        let size = BigEndian::read_u32(buf.get(*i..*i+4).ok_or(YHErrorKind::InvalidLength)?) as usize;
+        *i += 4;
+        let index = buf.get(*i..*i+size).ok_or(YHErrorKind::InvalidLength)?.to_vec();
+        *i += size;
+        Ok(Some(index))
+    }
+
+    pub fn to_bytes(&self) -> Vec<u8> {
+        let mut buf = Vec::new();
+        YPersistentNode::put_link(&mut buf, &self.prev);
+        buf.put_u32::<BigEndian>(self.next.len() as u32);
+        for next in self.next.iter() {
+            YPersistentNode::put_link(&mut buf, next);
+        }
+        buf
+    }
+
+    pub fn from_bytes(buf: &[u8]) -> YHResult<YPersistentNode> {
+        let mut i = 0;
+        let prev = YPersistentNode::read_link(buf, &mut i)?;
+        let levels = BigEndian::read_u32(buf.get(i..i+4).ok_or(YHErrorKind::InvalidLength)?) as usize;
+        i += 4;
+        if levels == 0 || levels > YPERSISTENT_INDEX_LEVELS {
+            return Err(YHErrorKind::InvalidLength.into());
+        }
+        let mut next = Vec::new();
+        for _ in 0..levels {
+            next.push(YPersistentNode::read_link(buf, &mut i)?);
+        }
+        if i != buf.len() {
+            return Err(YHErrorKind::InvalidLength.into());
+        }
+        Ok(YPersistentNode {
+            prev: prev,
+            next: next,
+        })
+    }
+}
+
+// unqlite cursors follow the hash order, so the keys are kept ordered by a skip list stored
+// next to them: opening the store doesn't scan it, and an aborted transaction rolls the
+// index back with the unqlite journal like the rest of the writes
 pub struct YPersistentStore {
     pub config: YPersistentConfig,
     pub handle: UnQLite,
+    pub depth: u32,
+    pub aborted: bool,
+}
+
+impl YPersistentStore {
+    pub fn from_handle(config: YPersistentConfig, handle: UnQLite) -> YHResult<YPersistentStore> {
+        let mut store = YPersistentStore {
+            config: config,
+            handle: handle,
+            depth: 0,
+            aborted: false,
+        };
+        store.build_index()?;
+        Ok(store)
+    }
+
+    fn is_read_only(&self) -> bool {
+        match self.config.mode {
+            YPersistentMode::Persistent { read_only, .. } => read_only,
+            YPersistentMode::Temporary => false,
+        }
+    }
+
+    fn node_key(id: &Option<Vec<u8>>) -> Vec<u8> {
+        let mut key = YPERSISTENT_INDEX_PREFIX.to_vec();
+        match *id {
+            Some(ref index) => {
+                key.push(1);
+                key.extend_from_slice(index);
+            },
+            None => key.push(0),
+        }
+        key
+    }
+
+    // the levels depend on the key only, so rebuilding the index gives the same list
+    fn levels(index: &[u8]) -> usize {
+        let mut hash: u64 = 0xcbf29ce484222325;
+        for b in index.iter() {
+            hash ^= *b as u64;
+            hash = hash.wrapping_mul(0x100000001b3);
+        }
+        min(1 + hash.trailing_zeros() as usize, YPERSISTENT_INDEX_LEVELS)
+    }
+
+    // stores written before the index kept the order in memory, so they are indexed on the first open
+    fn build_index(&mut self) -> YHResult<()> {
+        if self.handle.kv_contains(YPersistentStore::node_key(&None).as_slice()) {
+            return Ok(());
+        }
+        let mut indexes = BTreeSet::new();
+        let mut entry = self.handle.first();
+        while let Some(record) = entry {
+            let key = record.key();
+            if !key.starts_with(YPERSISTENT_INDEX_PREFIX) {
+                indexes.insert(key);
+            }
+            entry = record.next();
+        }
+        if indexes.is_empty() {
+            return Ok(());
+        }
+        if self.is_read_only() {
+            return Err(YHErrorKind::InvalidSchemaVersion.into());
+        }
+        atomically(self, |store| {
+            for index in indexes.iter() {
+                store.insert_index(index)?;
+            }
+            Ok(())
+        })
+    }
+
+    fn load(&self, id: &Option<Vec<u8>>) -> YHResult<Option<YPersistentNode>> {
+        let key = YPersistentStore::node_key(id);
+        if !self.handle.kv_contains(key.as_slice()) {
+            return Ok(None);
+        }
+        let value = self.handle.kv_fetch(key.as_slice())?;
+        Ok(Some(YPersistentNode::from_bytes(&value)?))
+    }
+
+    fn node(&self, id: &Option<Vec<u8>>) -> YHResult<YPersistentNode> {
+        match self.load(id)? {
+            Some(node) => Ok(node),
+            None if id.is_none() => Ok(YPersistentNode::new(YPERSISTENT_INDEX_LEVELS)),
+            None => Err(YHErrorKind::NotFound.into()),
+        }
+    }
+
+    fn save(&mut self, id: &Option<Vec<u8>>, node: &YPersistentNode) -> YHResult<()> {
+        let key = YPersistentStore::node_key(id);
+        self.handle.kv_store(key.as_slice(), node.to_bytes().as_slice())?;
+        Ok(())
+    }
+
+    // the nodes changed by an update are loaded once, a node can precede the key on many levels
+    fn cached(&self, nodes: &mut Vec<(Option<Vec<u8>>, YPersistentNode)>, id: &Option<Vec<u8>>) -> YHResult<usize> {
+        if let Some(pos) = nodes.iter().position(|&(ref cached, _)| cached == id) {
+            return Ok(pos);
+        }
+        let node = self.node(id)?;
+        nodes.push((id.clone(), node));
+        Ok(nodes.len() - 1)
+    }
+
+    // the last node before the index on every level
+    fn predecessors(&self, index: &[u8]) -> YHResult<Vec<Option<Vec<u8>>>> {
+        let mut preds = vec![None; YPERSISTENT_INDEX_LEVELS];
+        let mut current = None;
+        let mut node = self.node(&None)?;
+        for level in (0..YPERSISTENT_INDEX_LEVELS).rev() {
+            loop {
+                let next = match node.next[level] {
+                    Some(ref next) if next.as_slice() < index => next.clone(),
+                    _ => break,
+                };
+                current = Some(next);
+                node = self.node(&current)?;
+            }
+            preds[level] = current.clone();
+        }
+        Ok(preds)
+    }
+
+    fn insert_index(&mut self, index: &[u8]) -> YHResult<()> {
+        let id = Some(index.to_vec());
+        if self.load(&id)?.is_some() {
+            return Ok(());
+        }
+        let preds = self.predecessors(index)?;
+        let mut node = YPersistentNode::new(YPersistentStore::levels(index));
+        let mut nodes = Vec::new();
+        for level in 0..node.next.len() {
+            let pos = self.cached(&mut nodes, &preds[level])?;
+            node.next[level] = nodes[pos].1.next[level].clone();
+            nodes[pos].1.next[level] = id.clone();
+        }
+        node.prev = preds[0].clone();
+        let pos = self.cached(&mut nodes, &node.next[0])?;
+        nodes[pos].1.prev = id.clone();
+        for &(ref cached, ref cached_node) in nodes.iter() {
+            self.save(cached, cached_node)?;
+        }
+        self.save(&id, &node)
+    }
+
+    fn remove_index(&mut self, index: &[u8]) -> YHResult<()> {
+        let id = Some(index.to_vec());
+        let node = match self.load(&id)? {
+            Some(node) => node,
+            None => {
+                return Ok(());
+            },
+        };
+        let preds = self.predecessors(index)?;
+        let mut nodes = Vec::new();
+        for level in 0..node.next.len() {
+            let pos = self.cached(&mut nodes, &preds[level])?;
+            if nodes[pos].1.next[level] == id {
+                nodes[pos].1.next[level] = node.next[level].clone();
+            }
+        }
+        let pos = self.cached(&mut nodes, &node.next[0])?;
+        nodes[pos].1.prev = node.prev.clone();
+        for &(ref cached, ref cached_node) in nodes.iter() {
+            self.save(cached, cached_node)?;
+        }
+        self.handle.kv_delete(YPersistentStore::node_key(&id).as_slice())?;
+        Ok(())
+    }
+
+    // the first index not before the given one
+    fn first_from(&self, index: &[u8]) -> YHResult<Option<Vec<u8>>> {
+        let preds = self.predecessors(index)?;
+        Ok(self.node(&preds[0])?.next[0].clone())
+    }
+
+    fn last_in(&self, buck: &YStoreBuck) -> YHResult<Option<Vec<u8>>> {
+        match prefix_end(buck) {
+            Some(end) => Ok(self.predecessors(&end)?[0].clone()),
+            None => Ok(self.node(&None)?.prev),
+        }
+    }
+
+    fn walk(&self, buck: &YStoreBuck, first: Option<Vec<u8>>, backwards: bool, skip: usize, take: usize) -> YHResult<Vec<Vec<u8>>> {
+        let mut indexes = Vec::new();
+        let mut skipped = 0;
+        let mut current = first;
+        while let Some(index) = current {
+            if !index.starts_with(buck) || indexes.len() >= take {
+                break;
+            }
+            let node = self.node(&Some(index.clone()))?;
+            if skipped < skip {
+                skipped += 1;
+            } else {
+                indexes.push(index);
+            }
+            current = if backwards {
+                node.prev
+            } else {
+                node.next[0].clone()
+            };
+        }
+        Ok(indexes)
+    }
 }
 
 impl YStorage for YPersistentStore {
@@ -32,17 +321,11 @@ impl YStorage for YPersistentStore {
         match config.mode.clone() {
             YPersistentMode::Temporary => {
                 let handle = UnQLite::create_temp();
-                Ok(YPersistentStore {
-                    config: config,
-                    handle: handle,
-                })
+                YPersistentStore::from_handle(config, handle)
             },
             YPersistentMode::Persistent{ path, .. } => {
                 let handle = UnQLite::create(path.as_str());
-                Ok(YPersistentStore {
-                    config: config,
-                    handle: handle,
-                })
+                YPersistentStore::from_handle(config, handle)
             },
         }
     }
@@ -51,24 +334,15 @@ impl YStorage for YPersistentStore {
         match config.mode.clone() {
             YPersistentMode::Temporary => {
                 let handle = UnQLite::create_temp();
-                Ok(YPersistentStore {
-                    config: config,
-                    handle: handle,
-                })
+                YPersistentStore::from_handle(config, handle)
             },
             YPersistentMode::Persistent{ path, read_only } => {
                 if read_only {
                     let handle = UnQLite::open_readonly(path.as_str());
-                    Ok(YPersistentStore {
-                        config: config,
-                        handle: handle,
-                    })
+                    YPersistentStore::from_handle(config, handle)
                 } else {
                     let handle = UnQLite::create(path.as_str());
-                    Ok(YPersistentStore {
-                        config: config,
-                        handle: handle,
-                    })
+                    YPersistentStore::from_handle(config, handle)
                 }
             },
         }
@@ -103,109 +377,109 @@ impl YStorage for YPersistentStore {
     }
 
     fn put(&mut self, buck: &YStoreBuck, key: &YStoreKey, value: &YStoreValue) -> YHResult<()> {
-        let mut index = Vec::new();
-        index.extend(buck.iter().cloned());
-        index.extend(key.iter().cloned());
+        let index = store_index(buck, key);
         self.handle.kv_store(index.as_slice(), value.as_slice())
-            .map_err(|err| YHErrorKind::IO(IOError::new(IOErrorKind::Other, err.description())).into())
+            .map_err(|err| YHErrorKind::IO(IOError::new(IOErrorKind::Other, err.description())))?;
+        self.insert_index(&index)
     }
 
     fn lookup(&self, buck: &YStoreBuck, key: &YStoreKey) -> YHResult<bool> {
-        let mut index = Vec::new();
-        index.extend(buck.iter().cloned());
-        index.extend(key.iter().cloned());
+        let index = store_index(buck, key);
         Ok(self.handle.kv_contains(index.as_slice()))
     }
 
     fn get(&self, buck: &YStoreBuck, key: &YStoreKey) -> YHResult<YStoreItem> {
-        let mut index = Vec::new();
-        index.extend(buck.iter().cloned());
-        index.extend(key.iter().cloned());
+        let index = store_index(buck, key);
         self.handle.kv_fetch(index.as_slice())
             .map(|value| YStoreItem { key: key.clone(), value: value })
             .map_err(|err| YHErrorKind::IO(IOError::new(IOErrorKind::Other, err.description())).into())
     }
 
     fn count(&self, buck: &YStoreBuck) -> YHResult<u32> {
-        let mut entry = self.handle.seek(buck.as_slice(), Direction::Ge);
-        if entry.is_none() {
-            return Err(YHErrorKind::IO(IOError::new(IOErrorKind::NotFound, "buck not found")).into());
-        } else {
-            let mut count = 0;
-            loop {
-                count += 1;
-                entry = entry.unwrap().next();
-                if entry.is_none() {
-                    break;
-                }
-            }
-            Ok(count)
-        }
+        let first = self.first_from(buck)?;
+        Ok(self.walk(buck, first, false, 0, usize::max_value())?.len() as u32)
     }
 
     fn list(&self, buck: &YStoreBuck, skip: u32, count: u32) -> YHResult<Vec<YStoreKey>> {
-        let mut entry = self.handle.seek(buck.as_slice(), Direction::Ge);
-        if entry.is_none() {
-            return Err(YHErrorKind::IO(IOError::new(IOErrorKind::NotFound, "buck not found")).into());
-        } else {
-            let mut _skip = skip;
-            let mut _count = count;
-            let mut ls = Vec::new();
-            loop {
-                if _skip == 0 {
-                    break;
-                }
-                if count != 0 {
-                    let record = entry.unwrap();
-                    ls.push(record.key());
-                    entry = record.next();
-                    _count -= 1;
-                    _skip -= 1;
-                } else {
-                    break;
-                }
-                if entry.is_none() {
-                    break;
-                }
-            }
-            Ok(ls)
-        }
+        let first = self.first_from(buck)?;
+        let ls = self.walk(buck, first, false, skip as usize, count as usize)?
+            .iter()
+            .map(|index| index[buck.len()..].to_vec())
+            .collect();
+        Ok(ls)
     }
 
     fn list_reverse(&self, buck: &YStoreBuck, skip: u32, count: u32) -> YHResult<Vec<YStoreKey>> {
-        let mut entry = self.handle.seek(buck.as_slice(), Direction::Le);
-        if entry.is_none() {
-            return Err(YHErrorKind::IO(IOError::new(IOErrorKind::NotFound, "buck not found")).into());
-        } else {
-            let mut _skip = skip;
-            let mut _count = count;
-            let mut ls = Vec::new();
-            loop {
-                if _skip == 0 {
-                    break;
-                }
-                if count != 0 {
-                    let record = entry.unwrap();
-                    ls.push(record.key());
-                    entry = record.next();
-                    _count -= 1;
-                    _skip -= 1;
-                } else {
-                    break;
-                }
-                if entry.is_none() {
-                    break;
+        let last = self.last_in(buck)?;
+        let ls = self.walk(buck, last, true, skip as usize, count as usize)?
+            .iter()
+            .map(|index| index[buck.len()..].to_vec())
+            .collect();
+        Ok(ls)
+    }
+
+    fn list_from(&self, buck: &YStoreBuck, after: &Option<YStoreKey>, limit: u32) -> YHResult<YStorePage> {
+        let first = match *after {
+            Some(ref key) => {
+                let after = store_index(buck, key);
+                match self.first_from(&after)? {
+                    Some(ref index) if *index == after => self.node(&Some(after))?.next[0].clone(),
+                    first => first,
                 }
+            },
+            None => self.first_from(buck)?,
+        };
+        let keys = self.walk(buck, first, false, 0, limit as usize + 1)?
+            .iter()
+            .map(|index| index[buck.len()..].to_vec())
+            .collect();
+        Ok(store_page(keys, limit))
+    }
+
+    fn delete(&mut self, buck: &YStoreBuck, key: &YStoreKey) -> YHResult<()> {
+        let index = store_index(buck, key);
+        self.handle.kv_delete(index.as_slice())?;
+        self.remove_index(&index)
+    }
+
+    fn begin(&mut self) -> YHResult<()> {
+        if self.depth == 0 {
+            self.handle.begin()?;
+            self.aborted = false;
+        }
+        self.depth += 1;
+        Ok(())
+    }
+
+    fn commit(&mut self) -> YHResult<()> {
+        if self.depth == 0 {
+            return Err(YHErrorKind::NoTransaction.into());
+        }
+        self.depth -= 1;
+        if self.depth == 0 {
+            if self.aborted {
+                self.handle.rollback()?;
+                return Err(YHErrorKind::TransactionAborted.into());
+            }
+            if let Err(err) = self.handle.commit() {
+                // NB: a failed commit leaves the journal to be rolled back
+                let _ = self.handle.rollback();
+                return Err(err.into());
             }
-            Ok(ls)
         }
+        Ok(())
     }
 
-    fn delete(&mut self, buck: &YStoreBuck, key: &YStoreKey) -> YHResult<()> {
-        let mut index = Vec::new();
-        index.extend(buck.iter().cloned());
-        index.extend(key.iter().cloned());
-        self.handle.kv_delete(index.as_slice())
-            .map_err(|err| YHErrorKind::Store(err).into())
+    fn rollback(&mut self) -> YHResult<()> {
+        if self.depth == 0 {
+            return Err(YHErrorKind::NoTransaction.into());
+        }
+        self.depth -= 1;
+        if self.depth == 0 {
+            self.handle.rollback()?;
+        } else {
+            self.aborted = true;
+        }
+        Ok(())
     }
 }
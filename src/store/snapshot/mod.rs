@@ -0,0 +1,175 @@
+use libyobicash::crypto::hash::digest::YDigest64;
+use libyobicash::crypto::hash::sha::YSHA512;
+use bytes::{BytesMut, BufMut, BigEndian, ByteOrder};
+use std::fs::OpenOptions;
+use std::io::prelude::*;
+use errors::*;
+use store::common::*;
+
+pub const YSNAPSHOT_PREFIX: u32 = 0x59534e50;
+
+pub const YSNAPSHOT_VERSION: u32 = 1;
+
+pub const YSNAPSHOT_HEADER_SIZE: usize = 76;
+
+#[derive(Clone, Eq, PartialEq, Debug)]
+pub struct YSnapshotEntry {
+    pub buck: YStoreBuck,
+    pub key: YStoreKey,
+    pub value: YStoreValue,
+}
+
+#[derive(Clone, Eq, PartialEq, Debug)]
+pub struct YSnapshot {
+    pub version: u32,
+    pub checksum: YDigest64,
+    pub entries: Vec<YSnapshotEntry>,
+}
+
+impl YSnapshot {
+    pub fn new(entries: &Vec<YSnapshotEntry>) -> YSnapshot {
+        let checksum = YSnapshot::calc_checksum(entries);
+        YSnapshot {
+            version: YSNAPSHOT_VERSION,
+            checksum: checksum,
+            entries: entries.clone(),
+        }
+    }
+
+    pub fn export<S: YStorage>(store: &S, bucks: &Vec<YStoreBuck>) -> YHResult<YSnapshot> {
+        let mut entries = Vec::new();
+        for buck in bucks {
+            let keys = store.list(buck, 0, u32::max_value())?;
+            for key in keys {
+                let item = store.get(buck, &key)?;
+                entries.push(YSnapshotEntry {
+                    buck: buck.clone(),
+                    key: item.key,
+                    value: item.value,
+                });
+            }
+        }
+        Ok(YSnapshot::new(&entries))
+    }
+
+    pub fn import<S: YStorage>(&self, store: &mut S) -> YHResult<()> {
+        self.check()?;
+        for entry in self.entries.iter() {
+            if store.count(&entry.buck)? != 0 {
+                return Err(YHErrorKind::AlreadyFound.into());
+            }
+        }
+        atomically(store, |store| {
+            for entry in self.entries.iter() {
+                store.put(&entry.buck, &entry.key, &entry.value)?;
+            }
+            Ok(())
+        })
+    }
+
+    fn entries_to_bytes(entries: &Vec<YSnapshotEntry>) -> Vec<u8> {
+        let mut buf = BytesMut::new();
+        for entry in entries {
+            buf.put_u32::<BigEndian>(entry.buck.len() as u32);
+            buf.put(entry.buck.as_slice());
+            buf.put_u32::<BigEndian>(entry.key.len() as u32);
+            buf.put(entry.key.as_slice());
+            buf.put_u32::<BigEndian>(entry.value.len() as u32);
+            buf.put(entry.value.as_slice());
+        }
+        buf.to_vec()
+    }
+
+    pub fn calc_checksum(entries: &Vec<YSnapshotEntry>) -> YDigest64 {
+        YSHA512::hash(YSnapshot::entries_to_bytes(entries).as_slice())
+    }
+
+    pub fn check(&self) -> YHResult<()> {
+        if self.version != YSNAPSHOT_VERSION {
+            return Err(YHErrorKind::InvalidValue.into());
+        }
+        if self.checksum != YSnapshot::calc_checksum(&self.entries) {
+            return Err(YHErrorKind::InvalidChecksum.into());
+        }
+        Ok(())
+    }
+
+    pub fn to_bytes(&self) -> YHResult<Vec<u8>> {
+        self.check()?;
+        let mut buf = BytesMut::new();
+        buf.put_u32::<BigEndian>(YSNAPSHOT_PREFIX);
+        buf.put_u32::<BigEndian>(self.version);
+        buf.put_u32::<BigEndian>(self.entries.len() as u32);
+        buf.put(self.checksum.to_bytes());
+        buf.put(YSnapshot::entries_to_bytes(&self.entries));
+        Ok(buf.to_vec())
+    }
+
+    fn read_field(buf: &[u8], i: &mut usize) -> YHResult<Vec<u8>> {
+        let len_buf = buf.get(*i..*i+4).ok_or(YHErrorKind::InvalidLength)?;
+        let len = BigEndian::read_u32(len_buf) as usize;
+        *i += 4;
+        let field = buf.get(*i..*i+len).ok_or(YHErrorKind::InvalidLength)?;
+        *i += len;
+        Ok(field.to_vec())
+    }
+
+    pub fn from_bytes(buf: &[u8]) -> YHResult<YSnapshot> {
+        if buf.len() < YSNAPSHOT_HEADER_SIZE {
+            return Err(YHErrorKind::InvalidLength.into());
+        }
+
+        if BigEndian::read_u32(&buf[0..4]) != YSNAPSHOT_PREFIX {
+            return Err(YHErrorKind::InvalidValue.into());
+        }
+
+        let version = BigEndian::read_u32(&buf[4..8]);
+        let count = BigEndian::read_u32(&buf[8..12]);
+        let checksum = YDigest64::from_bytes(&buf[12..76])?;
+
+        let mut i = YSNAPSHOT_HEADER_SIZE;
+        let mut entries = Vec::new();
+        for _ in 0..count {
+            let buck = YSnapshot::read_field(buf, &mut i)?;
+            let key = YSnapshot::read_field(buf, &mut i)?;
+            let value = YSnapshot::read_field(buf, &mut i)?;
+            entries.push(YSnapshotEntry {
+                buck: buck,
+                key: key,
+                value: value,
+            });
+        }
+
+        if i != buf.len() {
+            return Err(YHErrorKind::InvalidLength.into());
+        }
+
+        let snapshot = YSnapshot {
+            version: version,
+            checksum: checksum,
+            entries: entries,
+        };
+        snapshot.check()?;
+        Ok(snapshot)
+    }
+
+    pub fn write(&self, path: &str) -> YHResult<()> {
+        let buf = self.to_bytes()?;
+        let mut file = OpenOptions::new()
+            .write(true)
+            .create_new(true)
+            .open(path)?;
+        file.write_all(&buf)?;
+        file.sync_all()?;
+        Ok(())
+    }
+
+    pub fn read(path: &str) -> YHResult<YSnapshot> {
+        let mut file = OpenOptions::new()
+            .read(true)
+            .open(path)?;
+        let mut buf = Vec::new();
+        file.read_to_end(&mut buf)?;
+        YSnapshot::from_bytes(&buf)
+    }
+}
@@ -0,0 +1,32 @@
+use libyobicash::amount::YAmount;
+use errors::*;
+
+// YAmount has no multiplication nor conversion to integers, so both go bit by bit
+
+pub fn amount_mul(amount: &YAmount, n: u64) -> YAmount {
+    let mut res = YAmount::zero();
+    for i in (0..64).rev() {
+        res = res.clone() + res;
+        if (n >> i) & 1 == 1 {
+            res = res + amount.clone();
+        }
+    }
+    res
+}
+
+// amounts are only built from u64 values, larger ones are refused
+pub fn amount_to_u64(amount: &YAmount) -> YHResult<u64> {
+    let mut n = 0u64;
+    let mut acc = YAmount::zero();
+    for i in (0..64).rev() {
+        let bit = YAmount::from_u64(1 << i)?;
+        if acc.clone() + bit.clone() <= *amount {
+            acc += bit;
+            n |= 1 << i;
+        }
+    }
+    if acc != *amount {
+        return Err(YHErrorKind::InvalidValue.into());
+    }
+    Ok(n)
+}
@@ -0,0 +1,22 @@
+use errors::*;
+
+pub fn to_hex(buf: &[u8]) -> String {
+    buf.iter()
+        .map(|b| format!("{:02x}", b))
+        .collect()
+}
+
+pub fn from_hex(s: &str) -> YHResult<Vec<u8>> {
+    if s.len() % 2 != 0 {
+        return Err(YHErrorKind::InvalidLength.into());
+    }
+    let mut buf = Vec::new();
+    for i in 0..s.len() / 2 {
+        let b = s.get(2*i..2*i+2)
+            .ok_or(YHErrorKind::ParsingFailure)?;
+        let n = u8::from_str_radix(b, 16)
+            .map_err(|_| YHErrorKind::ParsingFailure)?;
+        buf.push(n);
+    }
+    Ok(buf)
+}
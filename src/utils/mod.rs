@@ -0,0 +1,5 @@
+pub mod hex;
+pub mod amount;
+
+pub use self::hex::*;
+pub use self::amount::*;
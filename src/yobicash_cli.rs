@@ -1,10 +1,11 @@
-extern crate structopt;
 extern crate yobicash;
 
-use structopt::StructOpt;
 use yobicash::cli::client::*;
+use std::process::exit;
 
 fn main() {
-  let opt = YClientOpt::from_args();
-  println!("yobicash client opt: {:?}", opt) 
+    if let Err(err) = YClientCli::from_args() {
+        eprintln!("yobicash-cli error: {}", err);
+        exit(1);
+    }
 }
@@ -1,27 +1,233 @@
 extern crate structopt;
 extern crate yobicash;
 
-//use structopt::StructOpt;
-//use yobicash::cli::node::*;
-//use yobicash::config::*;
-//use yobicash::network::server::*;
+use structopt::StructOpt;
+use yobicash::cli::node::*;
+use yobicash::cli::prompt::YPrompt;
+use yobicash::config::*;
+use yobicash::store::*;
+use yobicash::api::*;
+use yobicash::models::export::YWalletExport;
+use yobicash::errors::*;
+use std::path::Path;
+use std::process::exit;
 
-fn main() {
-    /*
-    let opt = YNodeOpt::from_args();
-    println!("yobicashd opt: {:?}", opt);
-    */
+fn read_config() -> YHResult<YConfig> {
+    if !Path::new(&YConfig::path()?).exists() {
+        let pswd = YPrompt::read_secret("YOBICASH_PASSWORD", "new node password: ")?;
+        YConfig::create_default(&pswd)?;
+    }
+    if Path::new(&YConfig::tmp_path()?).exists() {
+        let msg = format!("{} was left by an interrupted change-password", YConfig::tmp_path()?);
+        return Err(YHErrorKind::Other(msg).into());
+    }
+    let mut config = YConfig::read()?;
+    // legacy configs keep working until change-password moves them to the kdf
+    if config.legacy_password.is_some() {
+        eprintln!("yobicashd warning: run change-password with the node stopped to upgrade the password");
+        return Ok(config);
+    }
+    config.password = YPrompt::read_secret("YOBICASH_PASSWORD", "node password: ")?;
+    config.master_key()?;
+    Ok(config)
+}
 
-    /*
-    YConfig::create_default().unwrap();
-    println!("default config written");
-    */
+fn run(opt: YNodeOpt) -> YHResult<()> {
+    match opt {
+        YNodeOpt::Start { light, temporary, difficulty, verbose } => {
+            let mut config = read_config()?;
+            let storage_mode = if light || config.light_mode {
+                config.light_mode = true;
+                YStorageMode::Light
+            } else {
+                YStorageMode::Full
+            };
+            let storage_kind = if temporary {
+                YStorageKind::Memory
+            } else {
+                YStorageKind::Persistent
+            };
+            if verbose {
+                println!("yobicashd starting at {:?}, control at {:?}", config.host.internal(), config.control.internal());
+                println!("storage kind: {:?}, storage mode: {:?}, mining difficulty: {:?}",
+                         storage_kind, storage_mode, difficulty);
+            }
+            let mut node = YNode::new(config, storage_kind, storage_mode);
+            node.start(difficulty, verbose)?;
+            if verbose {
+                println!("yobicashd stopped");
+            }
+            Ok(())
+        },
+        YNodeOpt::Status { verbose } => {
+            let status = YNode::status()?;
+            if verbose {
+                if let Some(pid) = YNode::read_pid()? {
+                    println!("yobicashd pid: {}", pid);
+                }
+            }
+            println!("{:?}", status);
+            Ok(())
+        },
+        YNodeOpt::Stop { verbose } => {
+            if verbose {
+                println!("yobicashd stopping");
+            }
+            YNode::stop()?;
+            println!("{:?}", YNode::status()?);
+            Ok(())
+        },
+        YNodeOpt::Check { repair, verbose } => {
+            if repair && YNode::status()? == YNodeStatus::Running {
+                return Err(YHErrorKind::Other("stop yobicashd before repairing".to_string()).into());
+            }
+            let config = read_config()?;
+            if verbose {
+                println!("yobicashd checking {}", YConfig::db_path()?);
+            }
+            let mut api = YAPI::new(config, !repair)?;
+            let report = api.check_store(repair)?;
+            api.close_store()?;
+            println!("{}", report.to_json()?);
+            if !report.is_ok() {
+                let msg = format!("{} inconsistencies left unrepaired", report.unrepaired());
+                return Err(YHErrorKind::Other(msg).into());
+            }
+            Ok(())
+        },
+        YNodeOpt::Backup { path, verbose } => {
+            let config = read_config()?;
+            if verbose {
+                println!("yobicashd backing up {} to {}", YConfig::db_path()?, path);
+            }
+            let api = YAPI::new(config, true)?;
+            let count = api.backup_store(&path)?;
+            println!("backed up {} entries to {}", count, path);
+            Ok(())
+        },
+        YNodeOpt::Reindex { verbose } => {
+            if YNode::status()? == YNodeStatus::Running {
+                return Err(YHErrorKind::Other("stop yobicashd before reindexing".to_string()).into());
+            }
+            let config = read_config()?;
+            if verbose {
+                println!("yobicashd reindexing {}", YConfig::db_path()?);
+            }
+            let mut api = YAPI::new(config, false)?;
+            api.reindex_store()?;
+            api.close_store()?;
+            println!("reindexed {}", YConfig::db_path()?);
+            Ok(())
+        },
+        YNodeOpt::Restore { path, verbose } => {
+            if YNode::status()? == YNodeStatus::Running {
+                return Err(YHErrorKind::Other("stop yobicashd before restoring".to_string()).into());
+            }
+            let config = read_config()?;
+            if verbose {
+                println!("yobicashd restoring {} into {}", path, YConfig::db_path()?);
+            }
+            let mut api = YAPI::new(config, false)?;
+            let count = api.restore_store(&path)?;
+            api.close_store()?;
+            println!("restored {} entries from {}", count, path);
+            Ok(())
+        },
+        YNodeOpt::Prune { verbose } => {
+            if YNode::status()? == YNodeStatus::Running {
+                return Err(YHErrorKind::Other("stop yobicashd before pruning".to_string()).into());
+            }
+            let config = read_config()?;
+            if verbose {
+                println!("yobicashd pruning {}", YConfig::db_path()?);
+            }
+            let mut api = YAPI::new(config, false)?;
+            let report = api.prune_store()?;
+            api.close_store()?;
+            println!("{}", report.to_json()?);
+            Ok(())
+        },
+        YNodeOpt::ChangePassword { verbose } => {
+            if YNode::status()? == YNodeStatus::Running {
+                return Err(YHErrorKind::Other("stop yobicashd before changing the password".to_string()).into());
+            }
+            let config = read_config()?;
+            let password = YPrompt::read_secret("YOBICASH_NEW_PASSWORD", "new node password: ")?;
+            if verbose {
+                println!("yobicashd re-encrypting {}", YConfig::db_path()?);
+            }
+            let mut api = YAPI::new(config, false)?;
+            api.change_password(&password)?;
+            api.close_store()?;
+            println!("re-encrypted {}", YConfig::db_path()?);
+            Ok(())
+        },
+        YNodeOpt::Mnemonic { wallet, verbose } => {
+            let config = read_config()?;
+            if verbose {
+                println!("yobicashd reading wallet {}", wallet);
+            }
+            let api = YAPI::new(config, true)?;
+            println!("{}", api.wallet_mnemonic(&wallet)?);
+            Ok(())
+        },
+        YNodeOpt::Export { verbose, cmd } => {
+            match cmd {
+                ExportCommands::Wallet { wallet, out } => {
+                    let config = read_config()?;
+                    let passphrase = YPrompt::read_secret("YOBICASH_WALLET_PASSPHRASE", "wallet passphrase: ")?;
+                    if verbose {
+                        println!("yobicashd exporting wallet {} to {}", wallet, out);
+                    }
+                    let api = YAPI::new(config, true)?;
+                    api.export_wallet(&wallet, &passphrase)?.write(&out)?;
+                    println!("exported wallet {} to {}", wallet, out);
+                    Ok(())
+                },
+            }
+        },
+        YNodeOpt::Import { verbose, cmd } => {
+            match cmd {
+                ImportCommands::Wallet { input } => {
+                    if YNode::status()? == YNodeStatus::Running {
+                        return Err(YHErrorKind::Other("stop yobicashd before importing a wallet".to_string()).into());
+                    }
+                    let config = read_config()?;
+                    let export = YWalletExport::read(&input)?;
+                    let passphrase = YPrompt::read_secret("YOBICASH_WALLET_PASSPHRASE", "wallet passphrase: ")?;
+                    if verbose {
+                        println!("yobicashd importing wallet from {}", input);
+                    }
+                    let mut api = YAPI::new(config, false)?;
+                    let imported = api.import_wallet(&export, &passphrase)?;
+                    api.close_store()?;
+                    println!("imported wallet {} with {} unspent coins", imported.name, imported.ucoins.len());
+                    Ok(())
+                },
+            }
+        },
+        YNodeOpt::RestoreWallet { wallet, verbose } => {
+            if YNode::status()? == YNodeStatus::Running {
+                return Err(YHErrorKind::Other("stop yobicashd before restoring a wallet".to_string()).into());
+            }
+            let config = read_config()?;
+            let phrase = YPrompt::read_secret("YOBICASH_MNEMONIC", "seed phrase: ")?;
+            if verbose {
+                println!("yobicashd restoring wallet {}", wallet);
+            }
+            let mut api = YAPI::new(config, false)?;
+            let restored = api.restore_wallet(&wallet, &phrase)?;
+            api.close_store()?;
+            println!("restored wallet {} with {} unspent coins", wallet, restored.ucoins.len());
+            Ok(())
+        },
+    }
+}
 
-    // TODO
-    /*
-    let server = YServer::default();
-    println!("default server created");
-    server.run();
-    println!("default server started ad 127.0.0.1:2112");
-    */
+fn main() {
+    let opt = YNodeOpt::from_args();
+    if let Err(err) = run(opt) {
+        eprintln!("yobicashd error: {}", err);
+        exit(1);
+    }
 }
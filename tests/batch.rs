@@ -0,0 +1,79 @@
+extern crate libyobicash;
+extern crate yobicash;
+
+mod common;
+
+use libyobicash::crypto::elliptic::keys::*;
+use libyobicash::amount::YAmount;
+use yobicash::models::*;
+use yobicash::network::message::transaction::YSendBatchReq;
+use yobicash::network::handle::YTransactionHandle;
+use common::*;
+
+#[test]
+fn batch_pays_every_recipient() {
+    let mut api = api();
+    let key = api.get_key();
+    let fee_pk = api.public_key();
+    let balance = api.get_wallet("node").unwrap().balance;
+    let alice_pk = api.new_receive_key("alice").unwrap();
+    let bob_pk = YSecretKey::random().to_public();
+    let payments = vec![(alice_pk, amount(1)), (bob_pk, amount(2))];
+
+    let (txs, fee_tx) = api.create_batch_transaction(key, "node", &payments, true, &YFirstFit,
+                                                     YSELECTION_MAX_INPUTS, &YFee::new(fee_pk, &amount(1))).unwrap();
+    assert_eq!(txs.len(), 2);
+    for (tx, &(to, ref amount)) in txs.iter().zip(payments.iter()) {
+        assert!(tx.0.outputs.iter().any(|output| output.recipient == to && output.amount == *amount));
+    }
+    assert!(fee_tx.is_some());
+    assert_eq!(api.get_wallet("alice").unwrap().balance, amount(1));
+    assert_eq!(api.get_wallet("node").unwrap().balance + amount(4), balance);
+}
+
+#[test]
+fn failed_batch_creates_nothing() {
+    let mut api = api();
+    let key = api.get_key();
+    let fee_pk = api.public_key();
+    let wallet = api.get_wallet("node").unwrap();
+    let txs = api.list_transactions_by_wallet("node").unwrap().len();
+    let to = YSecretKey::random().to_public();
+    let mut fee = YFee::new(fee_pk, &amount(3));
+    fee.max_amount = Some(amount(2));
+
+    let payments = vec![(to, amount(1)), (to, amount(2))];
+    assert!(api.create_batch_transaction(key, "node", &payments, true, &YFirstFit,
+                                         YSELECTION_MAX_INPUTS, &fee).is_err());
+    let payments = vec![(to, amount(1)), (to, wallet.balance.clone())];
+    assert!(api.create_batch_transaction(key, "node", &payments, true, &YFirstFit,
+                                         YSELECTION_MAX_INPUTS, &YFee::zero()).is_err());
+    assert!(api.create_batch_transaction(key, "node", &Vec::new(), true, &YFirstFit,
+                                         YSELECTION_MAX_INPUTS, &YFee::zero()).is_err());
+    assert_eq!(api.get_wallet("node").unwrap(), wallet);
+    assert_eq!(api.list_transactions_by_wallet("node").unwrap().len(), txs);
+}
+
+#[test]
+fn payment_bytes_roundtrip() {
+    let payment = YPayment::new(YSecretKey::random().to_public(), &amount(7)).unwrap();
+    assert_eq!(YPayment::from_bytes(&payment.to_bytes().unwrap()).unwrap(), payment);
+    assert!(YPayment::new(payment.to, &YAmount::zero()).is_err());
+    assert_eq!(YPayment::total(&vec![payment.clone(), payment]), amount(14));
+}
+
+#[test]
+fn batch_returns_and_relays_the_fee() {
+    let mut api = api();
+    let to = api.new_receive_key("alice").unwrap();
+    let payments = vec![YPayment::new(to, &amount(1)).unwrap(), YPayment::new(to, &amount(2)).unwrap()];
+    let req = YSendBatchReq::new("node", &payments, true, &amount(1), &YAmount::zero()).unwrap();
+    let res = YTransactionHandle::send_batch(req, &mut api).unwrap();
+    assert_eq!(res.txs.len(), 2);
+    let fee_tx = res.fee_tx.clone().unwrap();
+
+    let mut relays = res.txs.clone();
+    relays.push(fee_tx);
+    assert_eq!(api.take_relays(), relays);
+    assert_eq!(api.get_wallet("alice").unwrap().balance, amount(3));
+}
@@ -0,0 +1,28 @@
+#![allow(dead_code)]
+
+use libyobicash::amount::YAmount;
+use yobicash::config::*;
+use yobicash::api::YAPI;
+use yobicash::store::*;
+
+pub fn amount(n: u64) -> YAmount {
+    YAmount::from_u64(n).unwrap()
+}
+
+// the default scrypt parameters make every fixture take seconds
+pub fn config() -> YConfig {
+    YConfig::with_kdf(YKdfParams::new(4, 8, 1).unwrap()).unwrap()
+}
+
+pub fn api_with_config(config: YConfig) -> YAPI<YMemoryStore, YMemoryStore> {
+    let mut api = YAPI::new_temporary(config).unwrap();
+    api.create_wallet("node").unwrap();
+    api.create_wallet("alice").unwrap();
+    let fee_pk = api.public_key();
+    api.mine_genesys("node", 3, 1, fee_pk).unwrap();
+    api
+}
+
+pub fn api() -> YAPI<YMemoryStore, YMemoryStore> {
+    api_with_config(config())
+}
@@ -0,0 +1,111 @@
+extern crate libyobicash;
+extern crate yobicash;
+
+mod common;
+
+use libyobicash::crypto::elliptic::keys::*;
+use libyobicash::amount::YAmount;
+use yobicash::errors::*;
+use yobicash::api::YAPI;
+use yobicash::store::*;
+use yobicash::models::*;
+use yobicash::network::message::transaction::YSendCoinsReq;
+use yobicash::network::handle::YTransactionHandle;
+use common::*;
+
+fn api(price: u64) -> YAPI<YMemoryStore, YMemoryStore> {
+    let mut config = config();
+    config.price = price;
+    api_with_config(config)
+}
+
+#[test]
+fn estimator_uses_the_median_price() {
+    assert_eq!(YFeeEstimator::default().price(), YAmount::zero());
+    assert_eq!(YFeeEstimator::default().estimate(100, 100), YAmount::zero());
+
+    let mut estimator = YFeeEstimator::new(&vec![amount(1), amount(50)]);
+    estimator.add_price(&amount(3));
+    assert_eq!(estimator.price(), amount(3));
+    assert_eq!(estimator.estimate(10, 5), amount(45));
+    assert_eq!(estimator.estimate(0, 0), YAmount::zero());
+}
+
+#[test]
+fn explicit_fee_is_paid_to_the_confirming_node() {
+    let mut api = api(0);
+    let key = api.get_key();
+    api.track_node_key("alice").unwrap();
+    let fee_pk = api.fee_public_key().unwrap();
+    assert_eq!(fee_pk, api.public_key());
+    let balance = api.get_wallet("node").unwrap().balance;
+    let to = YSecretKey::random().to_public();
+    let fee = YFee::new(fee_pk, &amount(1));
+
+    let (_, fee_tx) = api.create_coin_transaction(key, "node", to, amount(1), true,
+                                                  &YFirstFit, YSELECTION_MAX_INPUTS, &fee).unwrap();
+    let fee_tx = fee_tx.unwrap();
+    assert!(fee_tx.0.outputs.iter().any(|output| output.recipient == fee_pk && output.amount == amount(1)));
+    assert_eq!(api.get_wallet("node").unwrap().balance + amount(2), balance);
+    assert_eq!(api.get_wallet("alice").unwrap().balance, amount(1));
+
+    let (_, fee_tx) = api.create_coin_transaction(key, "node", to, amount(1), true,
+                                                  &YFirstFit, YSELECTION_MAX_INPUTS, &YFee::zero()).unwrap();
+    assert!(fee_tx.is_none());
+}
+
+#[test]
+fn fee_goes_to_the_node_quoting_the_median_price() {
+    let api = api(2);
+    let low = YSecretKey::random().to_public();
+    let mid = YSecretKey::random().to_public();
+    let high = YSecretKey::random().to_public();
+
+    *api.prices.lock().unwrap() = vec![YPeerPrice::new(high, &amount(3)), YPeerPrice::new(low, &amount(1))];
+    assert_eq!(api.fee_public_key().unwrap(), api.public_key());
+    assert_eq!(api.fee_estimator().unwrap().price(), amount(2));
+
+    *api.prices.lock().unwrap() = vec![YPeerPrice::new(high, &amount(9)), YPeerPrice::new(low, &amount(1)),
+                                       YPeerPrice::new(mid, &amount(5))];
+    assert_eq!(api.fee_public_key().unwrap(), mid);
+    assert_eq!(api.fee_estimator().unwrap().price(), amount(5));
+}
+
+#[test]
+fn estimated_fee_respects_the_max_fee() {
+    let mut api = api(2);
+    let key = api.get_key();
+    let fee_pk = api.fee_public_key().unwrap();
+    let to = YSecretKey::random().to_public();
+    let estimator = api.fee_estimator().unwrap();
+    assert_eq!(estimator.price(), amount(2));
+    assert!(api.estimate_fee(10, 0).unwrap() == amount(20));
+
+    let wallet = api.get_wallet("node").unwrap();
+    let fee = YFee::estimated(fee_pk, &estimator, Some(amount(1)));
+    let res = api.create_coin_transaction(key, "node", to, amount(1), true,
+                                          &YFirstFit, YSELECTION_MAX_INPUTS, &fee);
+    match res {
+        Err(YHError(YHErrorKind::FeeTooHigh, _)) => {},
+        _ => panic!("expected a fee too high error"),
+    }
+    assert_eq!(api.get_wallet("node").unwrap(), wallet);
+
+    let fee = YFee::new(fee_pk, &amount(3));
+    let mut capped = fee.clone();
+    capped.max_amount = Some(amount(2));
+    assert!(api.create_coin_transaction(key, "node", to, amount(1), true,
+                                        &YFirstFit, YSELECTION_MAX_INPUTS, &capped).is_err());
+}
+
+#[test]
+fn sent_transactions_return_and_relay_the_fee() {
+    let mut api = api(1);
+    let to = api.new_receive_key("alice").unwrap();
+    let req = YSendCoinsReq::new("node", to, &amount(1), true, YCoinSelection::FirstFit,
+                                 &amount(1), &YAmount::zero()).unwrap();
+    let res = YTransactionHandle::send_coins(req, &mut api).unwrap();
+    let fee_tx = res.fee_tx.clone().unwrap();
+    assert!(fee_tx.inputs.iter().all(|input| input.id == res.tx.id));
+    assert_eq!(api.take_relays(), vec![res.tx.clone(), fee_tx]);
+}
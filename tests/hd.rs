@@ -0,0 +1,88 @@
+extern crate libyobicash;
+extern crate yobicash;
+
+mod common;
+
+use yobicash::utils::to_hex;
+use yobicash::models::*;
+use common::*;
+
+#[test]
+fn mnemonic_matches_bip39_vectors() {
+    let phrase = "abandon abandon abandon abandon abandon abandon abandon abandon abandon abandon abandon about";
+    assert_eq!(YMnemonic::from_entropy(&[0u8; 16]).unwrap(), phrase);
+    assert_eq!(YMnemonic::to_entropy(phrase).unwrap(), vec![0u8; 16]);
+    assert_eq!(to_hex(&YMnemonic::to_seed(phrase).unwrap()),
+               "5eb00bbddcf069084889a8ab9155568165f5c453ccb85e70811aaed6f6da5fc1\
+                9a5ac40b389cd370d086206dec8aa6c43daea6690f20ad3d8d48b2d2ce9e38e4");
+
+    let phrase = "letter advice cage absurd amount doctor acoustic avoid letter advice cage above";
+    assert_eq!(YMnemonic::from_entropy(&[0x80u8; 16]).unwrap(), phrase);
+
+    let entropy = YMnemonic::gen_entropy();
+    let phrase = YMnemonic::from_entropy(&entropy).unwrap();
+    assert_eq!(phrase.split_whitespace().count(), 24);
+    assert_eq!(YMnemonic::to_entropy(&phrase).unwrap(), entropy);
+}
+
+#[test]
+fn mnemonic_rejects_invalid_phrases() {
+    let bad_checksum = "abandon abandon abandon abandon abandon abandon abandon abandon abandon abandon abandon abandon";
+    assert!(YMnemonic::to_entropy(bad_checksum).is_err());
+    assert!(YMnemonic::to_entropy("abandon abandon abandon").is_err());
+    assert!(YMnemonic::to_entropy("yobicash abandon abandon abandon abandon abandon abandon abandon abandon abandon abandon about").is_err());
+}
+
+#[test]
+fn wallet_keys_are_deterministic() {
+    let wallet = YWallet::new("a");
+    let phrase = wallet.mnemonic().unwrap();
+    let mut restored = YWallet::from_mnemonic("b", &phrase).unwrap();
+    assert_eq!(restored.seed, wallet.seed);
+    assert_eq!(wallet.derive_key(YHD_RECEIVE_BRANCH, 0).unwrap(),
+               restored.next_key(YHD_RECEIVE_BRANCH).unwrap());
+    assert_eq!(wallet.derive_key(YHD_CHANGE_BRANCH, 0).unwrap(),
+               restored.next_key(YHD_CHANGE_BRANCH).unwrap());
+    assert_eq!(restored.receive_idx, 1);
+    assert_eq!(restored.change_idx, 1);
+    assert!(wallet.derive_key(YHD_RECEIVE_BRANCH, 0).unwrap() != wallet.derive_key(YHD_RECEIVE_BRANCH, 1).unwrap());
+    assert!(wallet.derive_key(YHD_RECEIVE_BRANCH, 0).unwrap() != wallet.derive_key(YHD_CHANGE_BRANCH, 0).unwrap());
+
+    let decoded = YWallet::from_bytes(&restored.to_bytes().unwrap()).unwrap();
+    assert_eq!(decoded, restored);
+}
+
+#[test]
+fn restore_wallet_rescans_utxos() {
+    let mut api = api();
+    let wallet = api.get_wallet("node").unwrap();
+    assert_eq!(wallet.ucoins.len(), 1);
+
+    let phrase = api.wallet_mnemonic("node").unwrap();
+    let restored = api.restore_wallet("restored", &phrase).unwrap();
+    let coin = wallet.ucoins[0].clone();
+    assert!(restored.ucoins.iter().any(|c| c.id == coin.id && c.idx == coin.idx && c.sk == coin.sk));
+    assert_eq!(restored.balance, wallet.balance);
+    assert!(restored.receive_idx >= 1);
+    assert!(api.restore_wallet("restored", &phrase).is_err());
+    assert_eq!(api.rescan_wallet("restored").unwrap(), 0);
+}
+
+#[test]
+fn restore_wallet_keeps_spent_coins_spent() {
+    let mut api = api();
+    let key = api.get_key();
+    let to = api.new_receive_key("alice").unwrap();
+    api.create_coin_transaction(key, "node", to, amount(1), true,
+                                &YFirstFit, YSELECTION_MAX_INPUTS, &YFee::zero()).unwrap();
+    let wallet = api.get_wallet("node").unwrap();
+    assert_eq!(wallet.scoins.len(), 1);
+
+    let phrase = api.wallet_mnemonic("node").unwrap();
+    let restored = api.restore_wallet("restored", &phrase).unwrap();
+    assert_eq!(restored.balance, wallet.balance);
+    let spent = wallet.scoins[0].clone();
+    assert!(restored.scoins.iter().any(|c| c.id == spent.id && c.idx == spent.idx));
+    assert!(!restored.ucoins.iter().any(|c| c.id == spent.id && c.idx == spent.idx));
+    assert!(restored.change_idx >= 1);
+}
@@ -0,0 +1,56 @@
+extern crate libyobicash;
+extern crate yobicash;
+
+mod common;
+
+use yobicash::models::*;
+use common::*;
+
+#[test]
+fn history_tracks_direction_and_balance() {
+    let mut api = api();
+    let key = api.get_key();
+    let alice_pk = api.new_receive_key("alice").unwrap();
+    let (tx, _) = api.create_coin_transaction(key, "node", alice_pk, amount(2), true, &YFirstFit,
+                                              YSELECTION_MAX_INPUTS, &YFee::zero()).unwrap();
+
+    let node = api.wallet_history("node").unwrap();
+    assert!(node.entries.iter().any(|entry| entry.direction == YHistoryDirection::Mined));
+    let sent = node.entries.last().unwrap();
+    assert_eq!(sent.id, tx.0.id);
+    assert_eq!(sent.direction, YHistoryDirection::Sent);
+    assert_eq!(sent.amount, amount(2));
+    assert_eq!(sent.counterparty, Some(alice_pk));
+    assert!(!sent.has_data);
+    assert_eq!(sent.balance, api.get_wallet("node").unwrap().balance);
+
+    let alice = api.wallet_history("alice").unwrap();
+    assert_eq!(alice.entries.len(), 1);
+    let received = &alice.entries[0];
+    assert_eq!(received.id, tx.0.id);
+    assert_eq!(received.direction, YHistoryDirection::Received);
+    assert_eq!(received.amount, amount(2));
+    assert_eq!(received.balance, amount(2));
+}
+
+#[test]
+fn history_exports_csv_and_json() {
+    let api = api();
+    let history = api.wallet_history("node").unwrap();
+    assert!(!history.entries.is_empty());
+
+    let csv = history.format("csv".parse::<YHistoryFormat>().unwrap()).unwrap();
+    let lines: Vec<&str> = csv.lines().collect();
+    assert_eq!(lines[0], YHISTORY_CSV_HEADER);
+    assert_eq!(lines.len(), history.entries.len() + 1);
+    assert!(lines.iter().any(|line| line.contains(",coinbase,mined,")));
+
+    let json = history.format(YHistoryFormat::Json).unwrap();
+    assert_eq!(YHistory::from_json(&json).unwrap(), history);
+    assert!("xml".parse::<YHistoryFormat>().is_err());
+
+    for entry in history.entries.iter() {
+        let buf = entry.to_bytes().unwrap();
+        assert_eq!(&YHistoryEntry::from_bytes(&buf).unwrap(), entry);
+    }
+}
@@ -0,0 +1,52 @@
+extern crate serde_json;
+extern crate yobicash;
+
+use serde_json::Value;
+use yobicash::config::*;
+
+#[test]
+fn kdf_verifies_the_password() {
+    let kdf = YKdfParams::new(4, 8, 1).unwrap();
+    let key = kdf.derive_key("a long enough password").unwrap();
+    let verifier = YKdfParams::verifier(key);
+    assert!(kdf.verify("a long enough password", &verifier).unwrap() == key);
+    assert!(kdf.verify("another long password", &verifier).is_err());
+
+    let other = YKdfParams::new(4, 8, 1).unwrap();
+    assert!(other.salt != kdf.salt);
+    assert!(other.derive_key("a long enough password").unwrap() != key);
+    assert!(YKdfParams::new(0, 8, 1).is_err());
+}
+
+#[test]
+fn config_stores_a_verifier_instead_of_the_password() {
+    let mut config = YConfig::default();
+    config.kdf = YKdfParams::new(4, 8, 1).unwrap();
+    config.password = String::from("a long enough password");
+    config.verifier = YKdfParams::verifier(config.kdf.derive_key(&config.password).unwrap());
+    let json = config.to_json().unwrap();
+    assert!(!json.contains(&config.password));
+
+    let mut read = YConfig::from_json(&json).unwrap();
+    assert!(read.password.is_empty());
+    assert!(read.legacy_password.is_none());
+    read.password = config.password.clone();
+    assert!(read.master_key().unwrap() == config.master_key().unwrap());
+    read.password = String::from("another long password");
+    assert!(read.master_key().is_err());
+}
+
+#[test]
+fn config_reads_legacy_passwords() {
+    let mut legacy: Value = serde_json::from_str(&YConfig::default().to_json().unwrap()).unwrap();
+    {
+        let obj = legacy.as_object_mut().unwrap();
+        obj.remove("kdf");
+        obj.remove("verifier");
+        obj.insert("password".to_string(), Value::String("a long enough password".to_string()));
+    }
+    let config = YConfig::from_json(&legacy.to_string()).unwrap();
+    assert_eq!(config.legacy_password, Some("a long enough password".to_string()));
+    assert!(config.password.is_empty());
+    assert!(config.kdf.check().is_ok());
+}
@@ -0,0 +1,319 @@
+extern crate libyobicash;
+extern crate yobicash;
+#[macro_use]
+extern crate quickcheck;
+
+use libyobicash::utils::random::YRandom;
+use libyobicash::utils::time::YTime;
+use libyobicash::crypto::hash::digest::YDigest64;
+use libyobicash::crypto::hash::sha::YSHA512;
+use libyobicash::crypto::elliptic::keys::*;
+use libyobicash::amount::YAmount;
+use libyobicash::transaction::YTransaction as LibTransaction;
+use libyobicash::coinbase::YCoinbase as LibCoinbase;
+use libyobicash::utxo::YUTXO as LibUTXO;
+use yobicash::models::wallet::YWallet;
+use yobicash::models::coin::*;
+use yobicash::models::peer::YPeer;
+use yobicash::models::payment::YPayment;
+use yobicash::models::selection::YCoinSelection;
+use yobicash::models::history::*;
+use yobicash::network::host::YHost;
+use yobicash::network::rpc_method::YRPCMethod;
+use yobicash::network::message::*;
+use yobicash::network::frame::YFrame;
+use std::io::Cursor;
+
+fn digest(seed: &[u8]) -> YDigest64 {
+    YSHA512::hash(seed)
+}
+
+fn public_key() -> YPublicKey {
+    YSecretKey::random().to_public()
+}
+
+fn genesys() -> (LibCoinbase, LibTransaction) {
+    let chunks = YRandom::bytes(3);
+    let ((cb, tx), _) = LibCoinbase::mine_genesys(1, &chunks,
+                                                  YSecretKey::random(),
+                                                  public_key(),
+                                                  public_key()).unwrap();
+    (cb, tx)
+}
+
+fn wallet(name: &str, cb: &LibCoinbase) -> YWallet {
+    let amount = cb.outputs[0].amount.clone();
+    let coin = YCoin::new(cb.time.clone(), YSecretKey::random(),
+                          YCoinKind::Coinbase, cb.id, 0, 0,
+                          false, None, &amount).unwrap();
+    let mut wallet = YWallet::new(name);
+    wallet.balance = amount;
+    wallet.ucoins.push(coin);
+    wallet
+}
+
+fn req_roundtrip(req: YRequest) -> bool {
+    let buf = req.to_bytes().unwrap();
+    let dec = YRequest::from_bytes(&buf).unwrap();
+    dec == req && dec.method() == req.method()
+}
+
+fn res_roundtrip(res: YResponse) -> bool {
+    let buf = res.to_bytes().unwrap();
+    let dec = YResponse::from_bytes(&buf).unwrap();
+    dec == res && dec.method() == res.method()
+}
+
+quickcheck! {
+    fn ping_req_roundtrip() -> bool {
+        req_roundtrip(YRequest::Ping(YPingReq::new().unwrap()))
+    }
+
+    fn ping_res_roundtrip(price: u32) -> bool {
+        let price = YAmount::from_u64(price as u64).unwrap();
+        res_roundtrip(YResponse::Ping(YPingRes::new(public_key(), &price).unwrap()))
+    }
+
+    fn list_peers_req_roundtrip(cursor: Vec<u8>, limit: u32) -> bool {
+        req_roundtrip(YRequest::ListPeers(YListPeersReq::new(&cursor, limit).unwrap()))
+    }
+
+    fn list_peers_res_roundtrip(hosts: Vec<([u8; 4], u16)>, next: Vec<u8>) -> bool {
+        let peers = hosts.into_iter()
+            .map(|(addr, port)| YPeer::new(YHost::new(addr, port)))
+            .collect();
+        res_roundtrip(YResponse::ListPeers(YListPeersRes::new(&peers, &next).unwrap()))
+    }
+
+    fn get_data_req_roundtrip(seed: Vec<u8>) -> bool {
+        req_roundtrip(YRequest::GetData(YGetDataReq::new(digest(&seed)).unwrap()))
+    }
+
+    fn list_data_req_roundtrip(seed: Vec<u8>) -> bool {
+        req_roundtrip(YRequest::ListData(YListDataReq::new(digest(&seed)).unwrap()))
+    }
+
+    fn get_tx_req_roundtrip(seed: Vec<u8>) -> bool {
+        req_roundtrip(YRequest::GetTx(YGetTxReq::new(digest(&seed)).unwrap()))
+    }
+
+    fn confirm_tx_req_roundtrip(seed: Vec<u8>) -> bool {
+        req_roundtrip(YRequest::ConfirmTx(YConfirmTxReq::new(digest(&seed)).unwrap()))
+    }
+
+    fn list_tx_ancestors_req_roundtrip(seed: Vec<u8>) -> bool {
+        req_roundtrip(YRequest::ListTxAncestors(YListTxAncestorsReq::new(digest(&seed)).unwrap()))
+    }
+
+    fn get_cb_req_roundtrip(seed: Vec<u8>) -> bool {
+        req_roundtrip(YRequest::GetCb(YGetCbReq::new(digest(&seed)).unwrap()))
+    }
+
+    fn create_wallet_req_roundtrip(name: String) -> bool {
+        req_roundtrip(YRequest::CreateWallet(YCreateWalletReq::new(&name).unwrap()))
+    }
+
+    fn get_wallet_req_roundtrip(name: String) -> bool {
+        req_roundtrip(YRequest::GetWallet(YGetWalletReq::new(&name).unwrap()))
+    }
+
+    fn list_wallets_req_roundtrip(cursor: Vec<u8>, limit: u32) -> bool {
+        req_roundtrip(YRequest::ListWallets(YListWalletsReq::new(&cursor, limit).unwrap()))
+    }
+
+    fn list_coins_req_roundtrip(name: String, kind: u8) -> bool {
+        let kind = match kind % 3 {
+            0 => YCoinsKind::All,
+            1 => YCoinsKind::Unspent,
+            _ => YCoinsKind::Spent,
+        };
+        req_roundtrip(YRequest::ListCoins(YListCoinsReq::new(&name, kind).unwrap()))
+    }
+
+    fn create_receive_key_req_roundtrip(name: String) -> bool {
+        req_roundtrip(YRequest::CreateReceiveKey(YCreateReceiveKeyReq::new(&name).unwrap()))
+    }
+
+    fn list_history_req_roundtrip(name: String) -> bool {
+        req_roundtrip(YRequest::ListHistory(YListHistoryReq::new(&name).unwrap()))
+    }
+
+    fn list_history_res_roundtrip(seeds: Vec<Vec<u8>>, has_data: bool) -> bool {
+        let mut entries = Vec::new();
+        for (i, seed) in seeds.iter().enumerate() {
+            let amount = YAmount::from_u64(i as u64 + 1).unwrap();
+            let (kind, direction, counterparty) = if i % 2 == 0 {
+                (YCoinKind::Coinbase, YHistoryDirection::Mined, None)
+            } else {
+                (YCoinKind::Transaction, YHistoryDirection::Sent, Some(public_key()))
+            };
+            let mut entry = YHistoryEntry::new(YTime::now(), digest(seed), kind, direction, &amount, counterparty, has_data);
+            entry.balance = amount;
+            entries.push(entry);
+        }
+        res_roundtrip(YResponse::ListHistory(YListHistoryRes::new(&entries).unwrap()))
+    }
+
+    fn get_utxo_req_roundtrip(seed: Vec<u8>, idx: u32) -> bool {
+        req_roundtrip(YRequest::GetUTXO(YGetUTXOReq::new(digest(&seed), idx).unwrap()))
+    }
+
+    fn list_utxos_req_roundtrip(seed: Vec<u8>) -> bool {
+        req_roundtrip(YRequest::ListUTXOs(YListUTXOsReq::new(digest(&seed)).unwrap()))
+    }
+
+    fn get_info_req_roundtrip() -> bool {
+        req_roundtrip(YRequest::GetInfo(YGetInfoReq::new().unwrap()))
+    }
+
+    fn get_info_res_roundtrip(balance: u32, wallets: u32, ucoins: u32, scoins: u32,
+                              data: u32, txs: u32, cbs: u32) -> bool {
+        let balance = YAmount::from_u64(balance as u64).unwrap();
+        let res = YGetInfoRes::new(&balance, wallets, ucoins, scoins, data, txs, cbs).unwrap();
+        res_roundtrip(YResponse::GetInfo(res))
+    }
+
+    fn send_coins_req_roundtrip(name: String, amount: u32, keep_data: bool, fee: u32, max_fee: u32) -> bool {
+        let amount = YAmount::from_u64(amount as u64).unwrap();
+        let fee = YAmount::from_u64(fee as u64).unwrap();
+        let max_fee = YAmount::from_u64(max_fee as u64).unwrap();
+        let req = YSendCoinsReq::new(&name, public_key(), &amount, keep_data, YCoinSelection::LargestFirst, &fee, &max_fee).unwrap();
+        req_roundtrip(YRequest::SendCoins(req))
+    }
+
+    fn send_data_req_roundtrip(name: String, data: Vec<u8>, keep_data: bool, fee: u32) -> bool {
+        let fee = YAmount::from_u64(fee as u64).unwrap();
+        let req = YSendDataReq::new(&name, public_key(), &data, keep_data, YCoinSelection::Random, &fee, &YAmount::zero()).unwrap();
+        req_roundtrip(YRequest::SendData(req))
+    }
+
+    fn send_batch_req_roundtrip(name: String, amounts: Vec<u32>, keep_data: bool, fee: u32) -> bool {
+        let mut payments = Vec::new();
+        for amount in amounts {
+            let amount = YAmount::from_u64(amount as u64 + 1).unwrap();
+            payments.push(YPayment::new(public_key(), &amount).unwrap());
+        }
+        let fee = YAmount::from_u64(fee as u64).unwrap();
+        let req = YSendBatchReq::new(&name, &payments, keep_data, &fee, &YAmount::zero()).unwrap();
+        req_roundtrip(YRequest::SendBatch(req))
+    }
+
+    fn create_unsigned_tx_req_roundtrip(name: String, amount: u32, keep_data: bool, fee: u32) -> bool {
+        let amount = YAmount::from_u64(amount as u64).unwrap();
+        let fee = YAmount::from_u64(fee as u64).unwrap();
+        let req = YCreateUnsignedTxReq::new(&name, public_key(), &amount, keep_data, YCoinSelection::BranchAndBound, &fee, &YAmount::zero()).unwrap();
+        req_roundtrip(YRequest::CreateUnsignedTx(req))
+    }
+
+    fn create_unsigned_tx_res_roundtrip(raw: Vec<u8>) -> bool {
+        res_roundtrip(YResponse::CreateUnsignedTx(YCreateUnsignedTxRes::new(&raw).unwrap()))
+    }
+
+    fn push_tx_res_roundtrip(seed: Vec<u8>) -> bool {
+        res_roundtrip(YResponse::PushTx(YPushTxRes::new(digest(&seed)).unwrap()))
+    }
+
+    fn list_txs_req_roundtrip(name: String, cursor: Vec<u8>, limit: u32) -> bool {
+        req_roundtrip(YRequest::ListTxs(YListTxsReq::new(&name, &cursor, limit).unwrap()))
+    }
+
+    fn push_cb_res_roundtrip(seed: Vec<u8>) -> bool {
+        res_roundtrip(YResponse::PushCb(YPushCbRes::new(digest(&seed)).unwrap()))
+    }
+
+    fn list_cbs_req_roundtrip(name: String, cursor: Vec<u8>, limit: u32) -> bool {
+        req_roundtrip(YRequest::ListCbs(YListCbsReq::new(&name, &cursor, limit).unwrap()))
+    }
+
+    fn mine_req_roundtrip(name: String, difficulty: u32) -> bool {
+        req_roundtrip(YRequest::Mine(YMineReq::new(&name, difficulty).unwrap()))
+    }
+
+    fn list_wallet_data_req_roundtrip(name: String, cursor: Vec<u8>, limit: u32) -> bool {
+        req_roundtrip(YRequest::ListWalletData(YListWalletDataReq::new(&name, &cursor, limit).unwrap()))
+    }
+
+    fn error_res_roundtrip(method: u32, message: String) -> bool {
+        let res = YErrorRes::new(YRPCMethod::from(method), message).unwrap();
+        res_roundtrip(YResponse::Error(res))
+    }
+}
+
+#[test]
+fn data_res_roundtrip() {
+    let data = Vec::new();
+    assert!(res_roundtrip(YResponse::ListData(YListDataRes::new(&data).unwrap())));
+    assert!(res_roundtrip(YResponse::ListWalletData(YListWalletDataRes::new(&data, &[]).unwrap())));
+}
+
+#[test]
+fn transaction_msgs_roundtrip() {
+    let (cb, tx) = genesys();
+    let txs = vec![tx.clone()];
+    assert!(res_roundtrip(YResponse::GetTx(YGetTxRes::new(&tx).unwrap())));
+    assert!(res_roundtrip(YResponse::ListTxAncestors(YListTxAncestorsRes::new(&txs).unwrap())));
+    assert!(res_roundtrip(YResponse::ListTxs(YListTxsRes::new(&txs, &tx.id.to_bytes()).unwrap())));
+    assert!(res_roundtrip(YResponse::SendCoins(YSendCoinsRes::new(&tx, &None).unwrap())));
+    assert!(res_roundtrip(YResponse::SendCoins(YSendCoinsRes::new(&tx, &Some(tx.clone())).unwrap())));
+    assert!(res_roundtrip(YResponse::SendData(YSendDataRes::new(&tx, &None).unwrap())));
+    assert!(res_roundtrip(YResponse::SendData(YSendDataRes::new(&tx, &Some(tx.clone())).unwrap())));
+    assert!(res_roundtrip(YResponse::SendBatch(YSendBatchRes::new(&txs, &None).unwrap())));
+    assert!(res_roundtrip(YResponse::SendBatch(YSendBatchRes::new(&txs, &Some(tx.clone())).unwrap())));
+    assert!(res_roundtrip(YResponse::ConfirmTx(YConfirmTxRes::new(true, &cb).unwrap())));
+    assert!(req_roundtrip(YRequest::PushTx(YPushTxReq::new(&tx).unwrap())));
+}
+
+#[test]
+fn coinbase_msgs_roundtrip() {
+    let (cb, _) = genesys();
+    let cbs = vec![cb.clone()];
+    assert!(res_roundtrip(YResponse::GetCb(YGetCbRes::new(&cb).unwrap())));
+    assert!(res_roundtrip(YResponse::Mine(YMineRes::new(&cb).unwrap())));
+    assert!(res_roundtrip(YResponse::ListCbs(YListCbsRes::new(&cbs, &[]).unwrap())));
+    assert!(req_roundtrip(YRequest::PushCb(YPushCbReq::new(&cb).unwrap())));
+}
+
+#[test]
+fn utxo_msgs_roundtrip() {
+    let (cb, _) = genesys();
+    let utxo = LibUTXO::from_output(&cb.outputs[0], cb.id, 0);
+    let utxos = vec![utxo.clone()];
+    assert!(res_roundtrip(YResponse::GetUTXO(YGetUTXORes::new(&utxo).unwrap())));
+    assert!(res_roundtrip(YResponse::ListUTXOs(YListUTXOsRes::new(&utxos).unwrap())));
+}
+
+#[test]
+fn wallet_msgs_roundtrip() {
+    let (cb, _) = genesys();
+    let wallet = wallet("wallet", &cb);
+    let wallets = vec![wallet.to_public(), YWallet::new("empty").to_public()];
+    let coins = wallet.ucoins.iter().map(|coin| coin.to_public()).collect();
+    assert!(res_roundtrip(YResponse::CreateWallet(YCreateWalletRes::new(&wallet.to_public()).unwrap())));
+    assert!(res_roundtrip(YResponse::GetWallet(YGetWalletRes::new(&wallet.to_public()).unwrap())));
+    assert!(res_roundtrip(YResponse::ListWallets(YListWalletsRes::new(&wallets, b"wallet").unwrap())));
+    assert!(res_roundtrip(YResponse::ListCoins(YListCoinsRes::new(&coins).unwrap())));
+    assert!(res_roundtrip(YResponse::CreateReceiveKey(YCreateReceiveKeyRes::new(public_key()).unwrap())));
+}
+
+#[test]
+fn truncated_frames_are_rejected() {
+    let req = YRequest::Ping(YPingReq::new().unwrap());
+    let buf = req.to_bytes().unwrap();
+    for i in 0..buf.len() {
+        assert!(YRequest::from_bytes(&buf[..i]).is_err());
+    }
+}
+
+#[test]
+fn frames_roundtrip_through_a_stream() {
+    let req = YRequest::Ping(YPingReq::new().unwrap());
+    let buf = req.to_bytes().unwrap();
+    let mut stream = Cursor::new(Vec::new());
+    YFrame::write(&mut stream, &buf).unwrap();
+    YFrame::write(&mut stream, &buf).unwrap();
+    stream.set_position(0);
+    assert_eq!(YFrame::read(&mut stream).unwrap(), Some(buf.clone()));
+    assert_eq!(YFrame::read(&mut stream).unwrap(), Some(buf));
+    assert_eq!(YFrame::read(&mut stream).unwrap(), None);
+}
+
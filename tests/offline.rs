@@ -0,0 +1,140 @@
+extern crate libyobicash;
+extern crate yobicash;
+
+mod common;
+
+use libyobicash::crypto::elliptic::keys::*;
+use libyobicash::amount::YAmount;
+use yobicash::errors::*;
+use yobicash::models::*;
+use common::*;
+
+#[test]
+fn unsigned_transaction_has_no_secrets() {
+    let mut api = api();
+    let key = api.get_key();
+    let wallet = api.get_wallet("node").unwrap();
+    let to = YSecretKey::random().to_public();
+
+    let unsigned = api.create_unsigned_transaction(key, "node", to, amount(1), true,
+                                                   &YFirstFit, YSELECTION_MAX_INPUTS, &YFee::zero()).unwrap();
+    assert_eq!(YUnsignedTransaction::from_bytes(&unsigned.to_bytes().unwrap()).unwrap(), unsigned);
+    assert_eq!(unsigned.change_idx, wallet.change_idx);
+    assert_eq!(unsigned.fee, YAmount::zero());
+    assert!(unsigned.inputs.iter().all(|input| wallet.ucoins.iter().any(|ucoin| ucoin.id == input.utxo.0.id)));
+
+    let after = api.get_wallet("node").unwrap();
+    assert_eq!(after.balance, wallet.balance);
+    assert_eq!(after.ucoins, wallet.ucoins);
+    assert_eq!(after.change_idx, wallet.change_idx + 1);
+
+    let mut truncated = unsigned.to_bytes().unwrap();
+    truncated.pop();
+    assert!(YUnsignedTransaction::from_bytes(&truncated).is_err());
+}
+
+#[test]
+fn unsigned_inputs_are_reserved() {
+    let mut api = api();
+    let key = api.get_key();
+    let to = YSecretKey::random().to_public();
+    let unsigned = api.create_unsigned_transaction(key, "node", to, amount(1), true,
+                                                   &YFirstFit, YSELECTION_MAX_INPUTS, &YFee::zero()).unwrap();
+    for input in unsigned.inputs.iter() {
+        assert!(YReservation::is_reserved(&api.store.persistent, input.utxo.0.id, input.utxo.0.idx).unwrap());
+    }
+
+    let wallet = api.get_wallet("node").unwrap();
+    match wallet.select_unreserved(&api.store.persistent, &YFirstFit, amount(1), YSELECTION_MAX_INPUTS, true) {
+        Ok(coins) => assert!(coins.iter().all(|coin| {
+            !unsigned.inputs.iter().any(|input| input.utxo.0.id == coin.id && input.utxo.0.idx == coin.idx)
+        })),
+        Err(YHError(YHErrorKind::NotEnoughFunds, _)) => {},
+        Err(err) => panic!("unexpected error: {}", err),
+    }
+
+    let root = YHDKey::from_mnemonic(&api.wallet_mnemonic("node").unwrap()).unwrap();
+    let (signed, _) = unsigned.sign(&root).unwrap();
+    api.push_transaction(&signed).unwrap();
+    for input in unsigned.inputs.iter() {
+        assert!(!YReservation::is_reserved(&api.store.persistent, input.utxo.0.id, input.utxo.0.idx).unwrap());
+    }
+}
+
+#[test]
+fn offline_signed_transaction_is_pushed() {
+    let mut api = api();
+    let key = api.get_key();
+    let balance = api.get_wallet("node").unwrap().balance;
+    let to = api.new_receive_key("alice").unwrap();
+    let unsigned = api.create_unsigned_transaction(key, "node", to, amount(1), true,
+                                                   &YFirstFit, YSELECTION_MAX_INPUTS, &YFee::zero()).unwrap();
+
+    let alice_root = api.get_wallet("alice").unwrap().root_key().unwrap();
+    match unsigned.sign(&alice_root) {
+        Err(YHError(YHErrorKind::InvalidValue, _)) => {},
+        _ => panic!("expected an invalid value error"),
+    }
+
+    let root = YHDKey::from_mnemonic(&api.wallet_mnemonic("node").unwrap()).unwrap();
+    let (tx, fee_tx) = unsigned.sign(&root).unwrap();
+    assert!(fee_tx.is_none());
+    assert!(tx.outputs.iter().any(|output| output.recipient == to && output.amount == amount(1)));
+
+    api.push_transaction(&tx).unwrap();
+    let node = api.get_wallet("node").unwrap();
+    assert_eq!(node.balance + amount(1), balance);
+    assert!(unsigned.inputs.iter().all(|input| node.scoins.iter().any(|scoin| scoin.id == input.utxo.0.id && scoin.idx == input.utxo.0.idx)));
+    assert_eq!(api.get_wallet("alice").unwrap().balance, amount(1));
+    assert!(api.push_transaction(&tx).is_err());
+}
+
+#[test]
+fn unsigned_transaction_carries_the_fee() {
+    let mut api = api();
+    api.track_node_key("node").unwrap();
+    let key = api.get_key();
+    let fee_pk = api.public_key();
+    let balance = api.get_wallet("node").unwrap().balance;
+    let to = api.new_receive_key("alice").unwrap();
+    let unsigned = api.create_unsigned_transaction(key, "node", to, amount(1), true,
+                                                   &YFirstFit, YSELECTION_MAX_INPUTS, &YFee::new(fee_pk, &amount(1))).unwrap();
+    assert_eq!(unsigned.fee_pk, fee_pk);
+    assert_eq!(unsigned.fee, amount(1));
+
+    let export = api.export_wallet("node", "passphrase").unwrap();
+    let (tx, fee_tx) = unsigned.sign_with_wallet(&export.wallet("passphrase").unwrap()).unwrap();
+    let fee_tx = fee_tx.unwrap();
+    assert!(fee_tx.inputs.iter().all(|input| input.id == tx.id));
+    assert!(fee_tx.outputs.iter().any(|output| output.recipient == fee_pk && output.amount == amount(1)));
+
+    api.push_transaction(&tx).unwrap();
+    api.push_transaction(&fee_tx).unwrap();
+    assert_eq!(api.get_wallet("node").unwrap().balance + amount(1), balance);
+    assert_eq!(api.get_wallet("alice").unwrap().balance, amount(1));
+
+    let alice = api.get_wallet("alice").unwrap();
+    match unsigned.sign_with_wallet(&YWallet::new(&alice.name)) {
+        Err(YHError(YHErrorKind::NotHDWallet, _)) => {},
+        _ => panic!("expected a not hd wallet error"),
+    }
+}
+
+#[test]
+fn unsigned_transaction_skips_node_key_coins() {
+    let mut api = api();
+    api.track_node_key("node").unwrap();
+    let key = api.get_key();
+    let fee_pk = api.public_key();
+    let to = api.new_receive_key("alice").unwrap();
+    api.create_coin_transaction(key, "node", to, amount(1), true,
+                                &YFirstFit, YSELECTION_MAX_INPUTS, &YFee::new(fee_pk, &amount(1))).unwrap();
+    let node = api.get_wallet("node").unwrap();
+    let fee_coin = node.ucoins.iter().find(|coin| coin.sk.to_public() == fee_pk).unwrap().clone();
+
+    let unsigned = api.create_unsigned_transaction(key, "node", to, amount(1), true,
+                                                   &YSmallestFirst, YSELECTION_MAX_INPUTS, &YFee::zero()).unwrap();
+    assert!(!unsigned.inputs.iter().any(|input| input.utxo.0.id == fee_coin.id && input.utxo.0.idx == fee_coin.idx));
+    let root = YHDKey::from_mnemonic(&api.wallet_mnemonic("node").unwrap()).unwrap();
+    assert!(unsigned.sign(&root).is_ok());
+}
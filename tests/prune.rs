@@ -0,0 +1,97 @@
+extern crate libyobicash;
+extern crate yobicash;
+
+use libyobicash::utils::random::YRandom;
+use libyobicash::crypto::elliptic::keys::*;
+use libyobicash::coinbase::YCoinbase as LibCoinbase;
+use std::thread;
+use std::time::Duration;
+use yobicash::errors::*;
+use yobicash::config::YConfig;
+use yobicash::api::YAPI;
+use yobicash::store::*;
+use yobicash::models::*;
+
+mod common;
+
+fn api(retention: u64) -> YAPI<YMemoryStore, YMemoryStore> {
+    let mut config = common::config();
+    config.data_retention = retention;
+    let mut api = YAPI::new_temporary(config).unwrap();
+    api.create_wallet("node").unwrap();
+    api
+}
+
+fn foreign_coinbase() -> LibCoinbase {
+    let chunks = YRandom::bytes(3);
+    let pk = YSecretKey::random().to_public();
+    let ((cb, _), _) = LibCoinbase::mine_genesys(1, &chunks, YSecretKey::random(), pk, pk).unwrap();
+    cb
+}
+
+#[test]
+fn prune_keeps_records_within_the_retention_window() {
+    let mut api = api(YConfig::default_data_retention());
+    let fee_pk = api.public_key();
+    let ((cb, tx), _) = api.mine_genesys("node", 3, 1, fee_pk).unwrap();
+    let foreign = api.push_coinbase(&foreign_coinbase()).unwrap();
+
+    let report = api.prune_store().unwrap();
+    assert_eq!(report, YPruneReport::default());
+    assert!(api.get_coinbase(cb.0.id).is_ok());
+    assert!(api.get_transaction(tx.0.id).is_ok());
+    assert!(api.get_coinbase(foreign.0.id).is_ok());
+    assert!(api.config.light_mode);
+    assert_eq!(YPrune::mode(&api.store.persistent).unwrap(), YStorageMode::Light);
+    assert!(YPrune::set_mode(&mut api.store.persistent, YStorageMode::Full).is_err());
+}
+
+#[test]
+fn prune_drops_expired_foreign_records() {
+    let mut api = api(0);
+    let fee_pk = api.public_key();
+    let ((cb, tx), _) = api.mine_genesys("node", 3, 1, fee_pk).unwrap();
+    let foreign = api.push_coinbase(&foreign_coinbase()).unwrap();
+    let utxos_count = YUTXO::count(&api.store.persistent).unwrap();
+
+    thread::sleep(Duration::from_secs(2));
+
+    let report = api.prune_store().unwrap();
+    assert_eq!(report.coinbases, 1);
+    assert_eq!(report.transactions, 1);
+    assert!(api.get_coinbase(cb.0.id).is_ok());
+    assert!(api.get_transaction(tx.0.id).is_err());
+    assert!(api.get_coinbase(foreign.0.id).is_err());
+    assert_eq!(YUTXO::count(&api.store.persistent).unwrap(), utxos_count);
+    assert!(api.check_store(false).unwrap().is_ok());
+}
+
+#[test]
+fn reindex_skips_missing_data_transactions() {
+    let mut api = common::api();
+    let key = api.get_key();
+    let alice_pk = api.new_receive_key("alice").unwrap();
+    let (tx, _) = api.create_data_transaction(key, "node", alice_pk, &[1, 2, 3], true,
+                                              &YFirstFit, YSELECTION_MAX_INPUTS, &YFee::zero()).unwrap();
+    assert!(api.get_wallet("alice").unwrap().ucoins.iter().any(|coin| coin.has_data));
+
+    tx.delete(&mut api.store.persistent).unwrap();
+    api.reindex_store().unwrap();
+    assert!(YIndex::list_wallet_txs(&api.store.persistent, key, "alice").unwrap().contains(&tx.0.id));
+}
+
+#[test]
+fn light_store_queues_pruned_ancestors() {
+    let mut config = common::config();
+    config.light_mode = true;
+    let mut api = common::api_with_config(config);
+    let id = foreign_coinbase().id;
+
+    match api.list_transaction_ancestors(id) {
+        Err(YHError(YHErrorKind::Pruned, _)) => {},
+        _ => panic!("expected a pruned error"),
+    }
+    api.list_transaction_ancestors(id).unwrap_err();
+    assert_eq!(api.take_fetches(), vec![id]);
+    assert!(api.take_fetches().is_empty());
+}
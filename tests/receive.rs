@@ -0,0 +1,79 @@
+extern crate libyobicash;
+extern crate yobicash;
+
+mod common;
+
+use libyobicash::utils::random::YRandom;
+use libyobicash::crypto::elliptic::keys::*;
+use libyobicash::coinbase::YCoinbase as LibCoinbase;
+use yobicash::models::*;
+use common::*;
+
+#[test]
+fn receive_keys_are_linked_to_the_wallet() {
+    let mut api = api();
+    let first = api.new_receive_key("alice").unwrap();
+    let second = api.new_receive_key("alice").unwrap();
+    assert!(first != second);
+
+    let keys = api.list_receive_keys("alice").unwrap();
+    assert_eq!(keys.len(), 2);
+    assert!(keys.contains(&first) && keys.contains(&second));
+    assert!(api.list_receive_keys("node").unwrap().is_empty());
+    assert!(api.new_receive_key("bob").is_err());
+
+    let stored = YKeys::get(&api.store.persistent, first).unwrap();
+    assert!(stored.is_linked());
+    assert_eq!(YKeys::from_bytes(&stored.to_bytes().unwrap()).unwrap(), stored);
+    assert_eq!(api.get_wallet("alice").unwrap().receive_idx, 2);
+}
+
+#[test]
+fn wallet_detects_incoming_transactions() {
+    let mut api = api();
+    let pk = api.new_receive_key("alice").unwrap();
+    let amount = amount(1);
+    let key = api.get_key();
+    let (tx, _) = api.create_coin_transaction(key, "node", pk, amount.clone(), true,
+                                              &YFirstFit, YSELECTION_MAX_INPUTS, &YFee::zero()).unwrap();
+
+    let alice = api.get_wallet("alice").unwrap();
+    assert_eq!(alice.ucoins.len(), 1);
+    assert_eq!(alice.ucoins[0].id, tx.0.id);
+    assert_eq!(alice.ucoins[0].sk.to_public(), pk);
+    assert_eq!(alice.balance, amount);
+    assert_eq!(api.list_transactions_by_wallet("alice").unwrap().len(), 1);
+}
+
+#[test]
+fn wallet_detects_incoming_coinbases() {
+    let mut api = api();
+    let pk = api.new_receive_key("alice").unwrap();
+    let chunks = YRandom::bytes(3);
+    let ((cb, _), _) = LibCoinbase::mine_genesys(1, &chunks, YSecretKey::random(), pk, pk).unwrap();
+    let cb = api.push_coinbase(&cb).unwrap();
+
+    let alice = api.get_wallet("alice").unwrap();
+    let paid = cb.0.outputs.iter().filter(|output| output.recipient == pk).count();
+    assert!(paid > 0);
+    assert_eq!(alice.ucoins.len(), paid);
+    assert!(alice.ucoins.iter().all(|coin| coin.id == cb.0.id && coin.kind == YCoinKind::Coinbase));
+    assert_eq!(api.list_coinbases_by_wallet("alice").unwrap().len(), 1);
+}
+
+#[test]
+fn coinbase_mined_off_the_store_is_credited() {
+    let mut api = api();
+    let balance = api.get_wallet("node").unwrap().balance;
+    let (miner_sk, recipient_pk) = api.genesys_keys("node").unwrap();
+    assert!(YCoinbase::mine(2, 1, miner_sk, recipient_pk, api.public_key()).is_err());
+
+    let ((cb, tx), _) = YCoinbase::mine(3, 1, miner_sk, recipient_pk, api.public_key()).unwrap();
+    assert!(api.get_coinbase(cb.id).is_err());
+    api.store_genesys("node", miner_sk, &cb, &tx).unwrap();
+    assert!(api.get_coinbase(cb.id).is_ok());
+    assert!(api.get_transaction(tx.id).is_ok());
+    let node = api.get_wallet("node").unwrap();
+    assert_eq!(node.balance, balance + cb.outputs[0].amount.clone());
+    assert!(node.ucoins.iter().any(|coin| coin.id == cb.id));
+}
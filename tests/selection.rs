@@ -0,0 +1,97 @@
+extern crate libyobicash;
+extern crate yobicash;
+
+mod common;
+
+use libyobicash::utils::time::YTime;
+use libyobicash::crypto::hash::digest::YDigest64;
+use libyobicash::crypto::elliptic::keys::YSecretKey;
+use libyobicash::amount::YAmount;
+use yobicash::errors::*;
+use yobicash::models::*;
+use common::*;
+
+fn coin(idx: u32, value: u64, has_data: bool) -> YCoin {
+    YCoin::new(YTime::now(), YSecretKey::random(), YCoinKind::Transaction,
+               YDigest64::default(), idx, 1, has_data, None, &amount(value)).unwrap()
+}
+
+fn coins() -> Vec<YCoin> {
+    vec![coin(0, 5, false), coin(1, 1, false), coin(2, 8, false), coin(3, 3, false), coin(4, 2, false)]
+}
+
+fn values(coins: &Vec<YCoin>) -> Vec<YAmount> {
+    coins.iter().map(|coin| coin.amount.clone()).collect()
+}
+
+fn total(coins: &Vec<YCoin>) -> YAmount {
+    coins.iter().fold(YAmount::zero(), |tot, coin| tot + coin.amount.clone())
+}
+
+fn is_too_many_inputs(res: YHResult<Vec<YCoin>>) -> bool {
+    match res {
+        Err(YHError(YHErrorKind::TooManyInputs, _)) => true,
+        _ => false,
+    }
+}
+
+#[test]
+fn ordered_selectors() {
+    let coins = coins();
+    let max = YSELECTION_MAX_INPUTS;
+    assert_eq!(values(&YFirstFit.select(&coins, &amount(6), max).unwrap()), vec![amount(5), amount(1)]);
+    assert_eq!(values(&YLargestFirst.select(&coins, &amount(6), max).unwrap()), vec![amount(8)]);
+    assert_eq!(values(&YSmallestFirst.select(&coins, &amount(6), max).unwrap()),
+               vec![amount(1), amount(2), amount(3)]);
+    assert_eq!(values(&YSmallestFirst.select(&coins, &amount(6), 2).unwrap()),
+               vec![amount(3), amount(5)]);
+}
+
+#[test]
+fn branch_and_bound_finds_changeless_selections() {
+    let coins = coins();
+    let selected = YBranchAndBound.select(&coins, &amount(10), YSELECTION_MAX_INPUTS).unwrap();
+    assert_eq!(total(&selected), amount(10));
+    let selected = YBranchAndBound.select(&coins, &amount(16), 3).unwrap();
+    assert_eq!(total(&selected), amount(16));
+    assert!(selected.len() <= 3);
+    assert!(YBranchAndBound::exact_match(&coins, &amount(7), 1).is_none());
+    assert_eq!(values(&YBranchAndBound.select(&coins, &amount(7), 1).unwrap()), vec![amount(8)]);
+}
+
+#[test]
+fn random_selection_covers_the_amount() {
+    let coins = coins();
+    for _ in 0..20 {
+        let selected = YRandomSelect.select(&coins, &amount(9), 3).unwrap();
+        assert!(total(&selected) >= amount(9));
+        assert!(selected.len() <= 3);
+    }
+}
+
+#[test]
+fn selectors_respect_funds_and_max_inputs() {
+    let coins = coins();
+    for selection in vec![YCoinSelection::FirstFit, YCoinSelection::LargestFirst,
+                          YCoinSelection::SmallestFirst, YCoinSelection::BranchAndBound,
+                          YCoinSelection::Random] {
+        assert!(selection.select(&coins, &amount(20), YSELECTION_MAX_INPUTS).is_err());
+        assert!(is_too_many_inputs(selection.select(&coins, &amount(19), 4)));
+        assert!(is_too_many_inputs(selection.select(&coins, &amount(1), 0)));
+        assert_eq!(total(&selection.select(&coins, &amount(19), 5).unwrap()), amount(19));
+    }
+    assert_eq!("branch-and-bound".parse::<YCoinSelection>().unwrap(), YCoinSelection::BranchAndBound);
+    assert!("best".parse::<YCoinSelection>().is_err());
+}
+
+#[test]
+fn wallet_skips_data_coins_when_keeping_data() {
+    let mut wallet = YWallet::new("wallet");
+    wallet.ucoins = vec![coin(0, 8, true), coin(1, 5, false), coin(2, 3, false)];
+    wallet.balance = amount(16);
+    let selected = wallet.select_ucoins(&YLargestFirst, amount(6), YSELECTION_MAX_INPUTS, true).unwrap();
+    assert_eq!(values(&selected), vec![amount(5), amount(3)]);
+    let selected = wallet.select_ucoins(&YLargestFirst, amount(6), YSELECTION_MAX_INPUTS, false).unwrap();
+    assert_eq!(values(&selected), vec![amount(8)]);
+    assert!(wallet.select_ucoins(&YLargestFirst, amount(9), YSELECTION_MAX_INPUTS, true).is_err());
+}
@@ -0,0 +1,343 @@
+extern crate libyobicash;
+extern crate yobicash;
+
+use libyobicash::crypto::hash::sha::YSHA256;
+use libyobicash::crypto::key::YKey32;
+use yobicash::store::*;
+use yobicash::errors::*;
+use std::env;
+use std::process;
+
+fn buck(n: u32) -> YStoreBuck {
+    vec![(n >> 24) as u8, (n >> 16) as u8, (n >> 8) as u8, n as u8]
+}
+
+fn key(n: u8) -> YStoreKey {
+    vec![n, n]
+}
+
+fn fill<S: YStorage>(store: &mut S, b: &YStoreBuck, n: u8) {
+    for i in 0..n {
+        store.put(b, &key(i), &vec![i]).unwrap();
+    }
+}
+
+pub fn put_get_delete<S: YStorage>(store: &mut S) {
+    let b = buck(1);
+    assert!(!store.lookup(&b, &key(0)).unwrap());
+    store.put(&b, &key(0), &vec![42]).unwrap();
+    assert!(store.lookup(&b, &key(0)).unwrap());
+    assert!(!store.lookup(&buck(2), &key(0)).unwrap());
+    let item = store.get(&b, &key(0)).unwrap();
+    assert_eq!(item.key, key(0));
+    assert_eq!(item.value, vec![42]);
+    store.put(&b, &key(0), &vec![43]).unwrap();
+    assert_eq!(store.get(&b, &key(0)).unwrap().value, vec![43]);
+    store.delete(&b, &key(0)).unwrap();
+    assert!(!store.lookup(&b, &key(0)).unwrap());
+    assert!(store.get(&b, &key(0)).is_err());
+}
+
+pub fn empty_bucket<S: YStorage>(store: &mut S) {
+    let b = buck(3);
+    assert_eq!(store.count(&b).unwrap(), 0);
+    assert!(store.list(&b, 0, 10).unwrap().is_empty());
+    assert!(store.list_reverse(&b, 0, 10).unwrap().is_empty());
+}
+
+pub fn count_is_bucket_bounded<S: YStorage>(store: &mut S) {
+    fill(store, &buck(4), 3);
+    fill(store, &buck(5), 5);
+    fill(store, &buck(6), 7);
+    assert_eq!(store.count(&buck(4)).unwrap(), 3);
+    assert_eq!(store.count(&buck(5)).unwrap(), 5);
+    assert_eq!(store.count(&buck(6)).unwrap(), 7);
+}
+
+pub fn list_is_ordered_and_paged<S: YStorage>(store: &mut S) {
+    let b = buck(8);
+    fill(store, &buck(7), 4);
+    fill(store, &b, 10);
+    fill(store, &buck(9), 4);
+
+    let all: Vec<YStoreKey> = (0..10).map(key).collect();
+    assert_eq!(store.list(&b, 0, 100).unwrap(), all);
+    assert_eq!(store.list(&b, 0, 3).unwrap(), all[0..3].to_vec());
+    assert_eq!(store.list(&b, 4, 3).unwrap(), all[4..7].to_vec());
+    assert_eq!(store.list(&b, 8, 5).unwrap(), all[8..10].to_vec());
+    assert!(store.list(&b, 10, 5).unwrap().is_empty());
+    assert!(store.list(&b, 0, 0).unwrap().is_empty());
+
+    let mut rev = all.clone();
+    rev.reverse();
+    assert_eq!(store.list_reverse(&b, 0, 100).unwrap(), rev);
+    assert_eq!(store.list_reverse(&b, 2, 3).unwrap(), rev[2..5].to_vec());
+    assert!(store.list_reverse(&b, 10, 5).unwrap().is_empty());
+
+    for k in store.list(&b, 0, 100).unwrap() {
+        assert_eq!(store.get(&b, &k).unwrap().key, k);
+    }
+}
+
+pub fn list_from_pages_by_cursor<S: YStorage>(store: &mut S) {
+    let b = buck(13);
+    fill(store, &buck(12), 4);
+    fill(store, &b, 10);
+    fill(store, &buck(14), 4);
+
+    let all: Vec<YStoreKey> = (0..10).map(key).collect();
+    let mut keys = Vec::new();
+    let mut cursor = None;
+    loop {
+        let page = store.list_from(&b, &cursor, 3).unwrap();
+        assert!(page.keys.len() <= 3);
+        keys.extend(page.keys.clone());
+        if page.next.is_none() {
+            break;
+        }
+        assert_eq!(page.next, page.keys.last().cloned());
+        cursor = page.next;
+    }
+    assert_eq!(keys, all);
+
+    let page = store.list_from(&b, &None, 10).unwrap();
+    assert_eq!(page.keys, all);
+    assert_eq!(page.next, None);
+
+    let page = store.list_from(&b, &Some(key(4)), 2).unwrap();
+    assert_eq!(page.keys, all[5..7].to_vec());
+    assert_eq!(page.next, Some(key(6)));
+
+    store.delete(&b, &key(6)).unwrap();
+    store.put(&b, &key(1), &vec![1]).unwrap();
+    let page = store.list_from(&b, &Some(key(6)), 100).unwrap();
+    assert_eq!(page.keys, all[7..10].to_vec());
+
+    let page = store.list_from(&b, &Some(vec![4]), 1).unwrap();
+    assert_eq!(page.keys, vec![key(4)]);
+
+    assert!(store.list_from(&b, &Some(key(9)), 10).unwrap().keys.is_empty());
+    assert!(store.list_from(&buck(15), &None, 10).unwrap().keys.is_empty());
+    assert!(store.list_from(&b, &None, 0).unwrap().keys.is_empty());
+}
+
+pub fn last_bucket_is_bounded<S: YStorage>(store: &mut S) {
+    let b = buck(0xffff_ffff);
+    fill(store, &buck(0xffff_fffe), 2);
+    fill(store, &b, 3);
+    assert_eq!(store.count(&b).unwrap(), 3);
+    assert_eq!(store.list_reverse(&b, 0, 1).unwrap(), vec![key(2)]);
+    assert_eq!(store.list_reverse(&buck(0xffff_fffe), 0, 1).unwrap(), vec![key(1)]);
+    assert_eq!(store.list_from(&b, &Some(key(0)), 10).unwrap().keys, vec![key(1), key(2)]);
+}
+
+pub fn transactions<S: YStorage>(store: &mut S) {
+    let b = buck(10);
+    store.put(&b, &key(0), &vec![0]).unwrap();
+
+    store.begin().unwrap();
+    store.put(&b, &key(0), &vec![1]).unwrap();
+    store.put(&b, &key(1), &vec![1]).unwrap();
+    assert_eq!(store.get(&b, &key(0)).unwrap().value, vec![1]);
+    store.rollback().unwrap();
+    assert_eq!(store.get(&b, &key(0)).unwrap().value, vec![0]);
+    assert!(!store.lookup(&b, &key(1)).unwrap());
+
+    store.begin().unwrap();
+    store.delete(&b, &key(0)).unwrap();
+    store.put(&b, &key(2), &vec![2]).unwrap();
+    store.commit().unwrap();
+    assert!(!store.lookup(&b, &key(0)).unwrap());
+    assert!(store.lookup(&b, &key(2)).unwrap());
+
+    let res: YHResult<()> = atomically(store, |store| {
+        store.put(&b, &key(3), &vec![3])?;
+        atomically(store, |store| {
+            store.put(&b, &key(4), &vec![4])?;
+            Err(YHErrorKind::InvalidValue.into())
+        })
+    });
+    assert!(res.is_err());
+    assert!(!store.lookup(&b, &key(3)).unwrap());
+    assert!(!store.lookup(&b, &key(4)).unwrap());
+
+    store.begin().unwrap();
+    store.begin().unwrap();
+    store.put(&b, &key(5), &vec![5]).unwrap();
+    store.rollback().unwrap();
+    assert!(store.commit().is_err());
+    assert!(!store.lookup(&b, &key(5)).unwrap());
+
+    assert!(store.commit().is_err());
+    assert!(store.rollback().is_err());
+}
+
+pub fn conformance<S: YStorage, F: Fn() -> S>(new_store: F) {
+    put_get_delete(&mut new_store());
+    empty_bucket(&mut new_store());
+    count_is_bucket_bounded(&mut new_store());
+    list_is_ordered_and_paged(&mut new_store());
+    list_from_pages_by_cursor(&mut new_store());
+    last_bucket_is_bounded(&mut new_store());
+    transactions(&mut new_store());
+}
+
+#[test]
+fn persistent_store_conformance() {
+    conformance(|| {
+        let config = YPersistentConfig { mode: YPersistentMode::Temporary };
+        YPersistentStore::create(config).unwrap()
+    });
+}
+
+#[test]
+fn persistent_store_keeps_key_order_when_reopened() {
+    let mut path = env::temp_dir();
+    path.push(format!("yobicash-conformance-{}", process::id()));
+    let path = path.to_str().unwrap().to_string();
+    let config = YPersistentConfig {
+        mode: YPersistentMode::Persistent {
+            path: path.clone(),
+            read_only: false,
+        },
+    };
+    let b = buck(1);
+    {
+        let mut store = YPersistentStore::create(config.clone()).unwrap();
+        for i in (0..16).rev() {
+            store.put(&b, &key(i), &vec![i]).unwrap();
+        }
+        store.put(&buck(2), &key(0), &vec![0]).unwrap();
+        store.close().unwrap();
+    }
+    let mut store = YPersistentStore::open(config).unwrap();
+    let expected: Vec<YStoreKey> = (0..16).map(key).collect();
+    assert_eq!(store.count(&b).unwrap(), 16);
+    assert_eq!(store.list(&b, 0, 100).unwrap(), expected);
+    let page = store.list_from(&b, &Some(key(7)), 4).unwrap();
+    assert_eq!(page.keys, (8..12).map(key).collect::<Vec<YStoreKey>>());
+    store.destroy().unwrap();
+}
+
+#[test]
+fn persistent_store_rolls_back_the_key_order() {
+    let config = YPersistentConfig { mode: YPersistentMode::Temporary };
+    let mut store = YPersistentStore::create(config).unwrap();
+    let b = buck(1);
+    for i in (0..64).filter(|i| i % 2 == 0) {
+        store.put(&b, &key(i), &vec![i]).unwrap();
+    }
+    let expected = store.list(&b, 0, 100).unwrap();
+
+    store.begin().unwrap();
+    for i in (0..64).filter(|i| i % 2 == 1) {
+        store.put(&b, &key(i), &vec![i]).unwrap();
+    }
+    for i in (0..64).filter(|i| i % 4 == 0) {
+        store.delete(&b, &key(i)).unwrap();
+    }
+    assert_eq!(store.count(&b).unwrap(), 48);
+    store.rollback().unwrap();
+
+    assert_eq!(store.list(&b, 0, 100).unwrap(), expected);
+    let mut rev = expected.clone();
+    rev.reverse();
+    assert_eq!(store.list_reverse(&b, 0, 100).unwrap(), rev);
+}
+
+#[test]
+fn memory_store_conformance() {
+    conformance(|| {
+        YMemoryStore::create(YMemoryConfig::default()).unwrap()
+    });
+}
+
+fn ekey(pswd: &str) -> YKey32 {
+    YKey32(YSHA256::hash(pswd.as_bytes()).0)
+}
+
+fn encrypted_store(pswd: &str) -> YEncryptedStore<YMemoryStore> {
+    let config = YEncryptedConfig {
+        key: ekey(pswd),
+        inner: YMemoryConfig::default(),
+    };
+    YEncryptedStore::create(config).unwrap()
+}
+
+#[test]
+fn encrypted_store_conformance() {
+    conformance(|| encrypted_store("password"));
+}
+
+#[test]
+fn encrypted_store_hides_keys_and_values() {
+    let b = buck(16);
+    let mut owner = b.clone();
+    owner.extend(vec![7, 7, 7]);
+    let mut store = encrypted_store("password");
+    fill(&mut store, &b, 3);
+    fill(&mut store, &owner, 2);
+
+    let raw_keys = store.inner.list(&b, 0, 100).unwrap();
+    assert_eq!(raw_keys.len(), 5);
+    for raw_key in raw_keys {
+        assert!(!raw_key.starts_with(&[7, 7, 7]));
+        assert!((0..3).all(|i| raw_key != key(i)));
+        assert_eq!(store.inner.get(&b, &raw_key).unwrap().value.len() % 16, 0);
+    }
+
+    assert_eq!(store.count(&b).unwrap(), 5);
+    assert_eq!(store.count(&owner).unwrap(), 2);
+    assert_eq!(store.list(&owner, 0, 10).unwrap(), vec![key(0), key(1)]);
+    let mut joined = vec![7, 7, 7];
+    joined.extend(key(1));
+    assert_eq!(store.get(&b, &joined).unwrap().value, vec![1]);
+    store.delete(&b, &joined).unwrap();
+    assert_eq!(store.list(&owner, 0, 10).unwrap(), vec![key(0)]);
+}
+
+#[test]
+fn encrypted_store_rotates_keys() {
+    let b = buck(17);
+    let mut store = encrypted_store("password");
+    fill(&mut store, &b, 4);
+    let wrong_store = YEncryptedStore::new(ekey("wrong password"), store.inner.clone());
+    assert!(wrong_store.check_key().is_err());
+
+    store.rotate(&vec![b.clone()], ekey("new password")).unwrap();
+    assert!(store.check_key().is_ok());
+    assert_eq!(store.list(&b, 0, 10).unwrap(), (0..4).map(key).collect::<Vec<YStoreKey>>());
+    assert_eq!(store.get(&b, &key(3)).unwrap().value, vec![3]);
+    assert!(YEncryptedStore::new(ekey("password"), store.inner.clone()).check_key().is_err());
+
+    let mut plain = YMemoryStore::create(YMemoryConfig::default()).unwrap();
+    fill(&mut plain, &b, 2);
+    let mut store = YEncryptedStore::new(ekey("password"), plain);
+    assert!(!store.is_sealed().unwrap());
+    store.encrypt_plain(&vec![b.clone()]).unwrap();
+    assert!(store.is_sealed().unwrap());
+    assert_eq!(store.get(&b, &key(1)).unwrap().value, vec![1]);
+    assert!(!store.inner.lookup(&b, &key(1)).unwrap());
+}
+
+#[test]
+fn memory_store_clones_are_snapshots() {
+    let b = buck(11);
+    let mut store = YMemoryStore::create(YMemoryConfig::default()).unwrap();
+    fill(&mut store, &b, 3);
+    let snapshot = store.clone();
+    store.put(&b, &key(3), &vec![3]).unwrap();
+    store.delete(&b, &key(0)).unwrap();
+    assert_eq!(snapshot.count(&b).unwrap(), 3);
+    assert!(snapshot.lookup(&b, &key(0)).unwrap());
+    assert!(!snapshot.lookup(&b, &key(3)).unwrap());
+    assert_eq!(store.count(&b).unwrap(), 3);
+}
+
+#[test]
+fn read_only_memory_store_rejects_writes() {
+    let config = YMemoryConfig { read_only: true };
+    let mut store = YMemoryStore::create(config).unwrap();
+    assert!(store.put(&buck(12), &key(0), &vec![0]).is_err());
+    assert_eq!(store.count(&buck(12)).unwrap(), 0);
+}
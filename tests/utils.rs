@@ -0,0 +1,23 @@
+extern crate libyobicash;
+extern crate yobicash;
+
+use libyobicash::amount::YAmount;
+use yobicash::utils::*;
+
+#[test]
+fn hex_roundtrip() {
+    let buf = vec![0u8, 1, 0x7f, 0x80, 0xff];
+    assert_eq!(to_hex(&buf), "00017f80ff");
+    assert_eq!(from_hex(&to_hex(&buf)).unwrap(), buf);
+    assert!(from_hex("abc").is_err());
+    assert!(from_hex("zz").is_err());
+}
+
+#[test]
+fn amount_arithmetic() {
+    let price = YAmount::from_u64(3).unwrap();
+    assert_eq!(amount_mul(&price, 5), YAmount::from_u64(15).unwrap());
+    assert_eq!(amount_mul(&price, 0), YAmount::zero());
+    assert_eq!(amount_to_u64(&YAmount::from_u64(u64::max_value()).unwrap()).unwrap(), u64::max_value());
+    assert_eq!(amount_to_u64(&amount_mul(&price, 1 << 40)).unwrap(), 3 << 40);
+}
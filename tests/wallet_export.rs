@@ -0,0 +1,74 @@
+extern crate libyobicash;
+extern crate yobicash;
+
+mod common;
+
+use libyobicash::amount::YAmount;
+use yobicash::errors::*;
+use yobicash::api::YAPI;
+use yobicash::models::*;
+use common::*;
+
+#[test]
+fn export_is_encrypted_under_the_passphrase() {
+    let api = api();
+    let wallet = api.get_wallet("node").unwrap();
+    let export = api.export_wallet("node", "passphrase").unwrap();
+
+    let export = YWalletExport::from_bytes(&export.to_bytes().unwrap()).unwrap();
+    assert_eq!(export.kdf.log_n, api.config.kdf.log_n);
+    assert_ne!(export.kdf.salt, api.config.kdf.salt);
+    assert_eq!(export.wallet("passphrase").unwrap(), wallet);
+    match export.wallet("wrong passphrase") {
+        Err(YHError(YHErrorKind::InvalidPassword, _)) => {},
+        _ => panic!("expected an invalid password error"),
+    }
+
+    let mut buf = export.to_bytes().unwrap();
+    buf[4] += 1;
+    assert!(YWalletExport::from_bytes(&buf).is_err());
+    let mut tampered = export.clone();
+    let kdf = config().kdf;
+    tampered.checksum = YWalletExport::with_kdf(&YWallet::new("other"), "passphrase", kdf).unwrap().checksum;
+    assert!(tampered.wallet("passphrase").is_err());
+}
+
+#[test]
+fn import_revalidates_the_coins() {
+    let mut api = api();
+    let key = api.get_key();
+    let wallet = api.get_wallet("node").unwrap();
+    let export = api.export_wallet("node", "passphrase").unwrap();
+    assert!(api.import_wallet(&export, "passphrase").is_err());
+
+    wallet.delete(&mut api.store.persistent, key).unwrap();
+    let imported = api.import_wallet(&export, "passphrase").unwrap();
+    assert_eq!(imported.ucoins.len(), wallet.ucoins.len());
+    assert_eq!(imported.balance, wallet.balance);
+    assert_eq!(imported.mnemonic().unwrap(), wallet.mnemonic().unwrap());
+    assert_eq!(api.get_wallet("node").unwrap(), imported);
+
+    let mut other = YAPI::new_temporary(config()).unwrap();
+    let imported = other.import_wallet(&export, "passphrase").unwrap();
+    assert!(imported.ucoins.is_empty());
+    assert_eq!(imported.balance, YAmount::zero());
+    assert_eq!(imported.receive_idx, wallet.receive_idx);
+}
+
+#[test]
+fn import_marks_coins_spent_since_the_export() {
+    let mut api = api();
+    let key = api.get_key();
+    let wallet = api.get_wallet("node").unwrap();
+    let export = api.export_wallet("node", "passphrase").unwrap();
+
+    let to = api.new_receive_key("alice").unwrap();
+    api.create_coin_transaction(key, "node", to, amount(1), true,
+                                &YFirstFit, YSELECTION_MAX_INPUTS, &YFee::zero()).unwrap();
+    api.get_wallet("node").unwrap().delete(&mut api.store.persistent, key).unwrap();
+
+    let imported = api.import_wallet(&export, "passphrase").unwrap();
+    assert!(imported.ucoins.is_empty());
+    assert_eq!(imported.balance, YAmount::zero());
+    assert!(wallet.ucoins.iter().all(|ucoin| imported.scoins.iter().any(|scoin| scoin.id == ucoin.id && scoin.idx == ucoin.idx)));
+}